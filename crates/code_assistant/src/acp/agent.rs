@@ -19,6 +19,7 @@ use crate::ui::UserInterface;
 use command_executor::{CommandExecutor, DefaultCommandExecutor};
 use llm::factory::create_llm_client_from_model;
 use llm::provider_config::ConfigurationSystem;
+use llm::recording::PlaybackControl;
 
 /// Pending session that hasn't been persisted yet (deferred until first prompt).
 #[derive(Clone)]
@@ -585,6 +586,7 @@ impl acp::Agent for ACPAgentImpl {
                 &model_name_for_prompt,
                 playback_path,
                 fast_playback,
+                PlaybackControl::default(),
                 None,
             )
             .await
@@ -588,6 +588,7 @@ impl UserInterface for ACPUserUI {
                 status,
                 message,
                 output,
+                ..
             } => {
                 let tool_status = map_tool_status(status);
                 let message_clone = message.clone();
@@ -703,6 +704,7 @@ impl UserInterface for ACPUserUI {
             | UiEvent::DisplayCompactionSummary { .. }
             | UiEvent::StreamingStarted(_)
             | UiEvent::StreamingStopped { .. }
+            | UiEvent::MessageUsage { .. }
             | UiEvent::RefreshChatList
             | UiEvent::UpdateChatList { .. }
             | UiEvent::ClearMessages
@@ -713,9 +715,13 @@ impl UserInterface for ACPUserUI {
             | UiEvent::RequestPendingMessageEdit { .. }
             | UiEvent::UpdatePendingMessage { .. }
             | UiEvent::ClearError
+            | UiEvent::RetryAttempt { .. }
+            | UiEvent::Phase { .. }
+            | UiEvent::ToolProgress { .. }
             | UiEvent::UpdateCurrentModel { .. }
             | UiEvent::UpdateSandboxPolicy { .. }
             | UiEvent::CancelSubAgent { .. }
+            | UiEvent::RequestToolApproval { .. }
             | UiEvent::HiddenToolCompleted
             | UiEvent::StartMessageEdit { .. }
             | UiEvent::SwitchBranch { .. }
@@ -730,6 +736,15 @@ impl UserInterface for ACPUserUI {
                     *last_error = Some(message);
                 }
             }
+            UiEvent::DisplayWarning { message } => {
+                tracing::warn!("ACPUserUI: Received DisplayWarning event: {}", message);
+            }
+            UiEvent::DisplayErrorDetailed { info } => {
+                tracing::error!("ACPUserUI: Received DisplayErrorDetailed event: {:?}", info);
+                if let Ok(mut last_error) = self.last_error.lock() {
+                    *last_error = Some(info.to_string());
+                }
+            }
         }
         Ok(())
     }
@@ -898,6 +913,15 @@ impl UserInterface for ACPUserUI {
         // No action needed
     }
 
+    fn notify_retry_attempt(&self, _attempt: u32, _max: u32) {
+        // No action needed
+    }
+
+    fn requires_tool_approval(&self, _tool_name: &str) -> bool {
+        // ACP clients are asked through the permission mediator instead
+        false
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -4,10 +4,12 @@ mod tests;
 pub mod persistence;
 pub mod runner;
 pub mod sub_agent;
+pub mod tool_approval;
 pub mod types;
 
 pub use crate::types::ToolSyntax;
 // pub use persistence::FileStatePersistence;
 pub use runner::{Agent, AgentComponents};
 pub use sub_agent::{DefaultSubAgentRunner, SubAgentCancellationRegistry, SubAgentRunner};
+pub use tool_approval::ToolApprovalRegistry;
 pub use types::ToolExecution;
@@ -1,4 +1,5 @@
 use crate::agent::persistence::AgentStatePersistence;
+use crate::agent::tool_approval::ToolApprovalRegistry;
 use crate::agent::types::ToolExecution;
 use crate::config::ProjectManager;
 use crate::permissions::PermissionMediator;
@@ -18,7 +19,7 @@ use llm::{
 use std::collections::HashMap;
 use std::fs;
 use std::sync::{Arc, Mutex};
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use tracing::{debug, trace, warn};
 
 /// Runtime components required to construct an `Agent`.
@@ -96,6 +97,10 @@ pub struct Agent {
     enable_naming_reminders: bool,
     // Shared pending message with SessionInstance
     pending_message_ref: Option<Arc<Mutex<Option<String>>>>,
+    // Shared steering message with SessionInstance
+    steering_message_ref: Option<Arc<Mutex<Option<String>>>>,
+    // Pending tool approvals shared with SessionInstance
+    tool_approvals: Option<Arc<ToolApprovalRegistry>>,
     // File trees for projects (used in system prompt)
     file_trees: HashMap<String, String>,
     // Available project names (used in system prompt)
@@ -160,6 +165,8 @@ impl Agent {
             session_name: String::new(),
             enable_naming_reminders: true, // Enabled by default
             pending_message_ref: None,
+            steering_message_ref: None,
+            tool_approvals: None,
             model_hint: None,
             file_trees: HashMap::new(),
             available_projects: Vec::new(),
@@ -187,6 +194,16 @@ impl Agent {
         self.pending_message_ref = Some(pending_ref);
     }
 
+    /// Set the shared steering message reference from SessionInstance
+    pub fn set_steering_message_ref(&mut self, steering_ref: Arc<Mutex<Option<String>>>) {
+        self.steering_message_ref = Some(steering_ref);
+    }
+
+    /// Set the registry through which the UI approves gated tool calls
+    pub fn set_tool_approval_registry(&mut self, registry: Arc<ToolApprovalRegistry>) {
+        self.tool_approvals = Some(registry);
+    }
+
     /// Update the model hint used for selecting system prompts
     pub fn set_model_hint(&mut self, model_hint: Option<String>) {
         let normalized = model_hint.and_then(|hint| {
@@ -257,6 +274,57 @@ impl Agent {
         }
     }
 
+    /// Get and clear the steering message from shared state
+    fn take_steering_message(&self) -> Option<String> {
+        self.steering_message_ref
+            .as_ref()
+            .and_then(|steering_ref| steering_ref.lock().unwrap().take())
+    }
+
+    /// Check if steering text is waiting for the next tool call
+    fn has_steering_message(&self) -> bool {
+        self.steering_message_ref
+            .as_ref()
+            .is_some_and(|steering_ref| steering_ref.lock().unwrap().is_some())
+    }
+
+    /// Steering that arrives after the last tool call of a turn has no tool
+    /// boundary left to land on, so it is delivered like a queued message
+    fn queue_steering_message(&self) {
+        let (Some(steering), Some(pending_ref)) = (
+            self.take_steering_message(),
+            self.pending_message_ref.as_ref(),
+        ) else {
+            return;
+        };
+        let mut pending = pending_ref.lock().unwrap();
+        match pending.as_mut() {
+            Some(existing) => {
+                existing.push('\n');
+                existing.push_str(&steering);
+            }
+            None => *pending = Some(steering),
+        }
+    }
+
+    /// Steering still waiting when the turn ends (completed, failed or
+    /// cancelled before a tool boundary) becomes a queued message, so it
+    /// isn't injected at the first tool call of a later, unrelated turn
+    pub async fn release_steering_message(&self) -> Result<()> {
+        if !self.has_steering_message() {
+            return Ok(());
+        }
+        self.queue_steering_message();
+        let message = self
+            .pending_message_ref
+            .as_ref()
+            .and_then(|pending_ref| pending_ref.lock().unwrap().clone());
+        self.ui
+            .send_event(UiEvent::UpdatePendingMessage { message })
+            .await?;
+        Ok(())
+    }
+
     async fn update_activity_state(&self, new_state: SessionActivityState) -> Result<()> {
         if let Some(session_id) = &self.session_id {
             self.ui
@@ -404,6 +472,13 @@ impl Agent {
     /// Run a single iteration of the agent loop without waiting for user input
     /// This is used in the new on-demand agent architecture
     pub async fn run_single_iteration(&mut self) -> Result<()> {
+        let result = self.run_agent_loop().await;
+        // However the turn ended, steering must not outlive it
+        let released = self.release_steering_message().await;
+        result.and(released)
+    }
+
+    async fn run_agent_loop(&mut self) -> Result<()> {
         loop {
             // Check for pending user message and add it to history at start of each iteration
             if let Some(pending_message) = self.get_and_clear_pending_message() {
@@ -443,6 +518,8 @@ impl Agent {
                                     status: crate::ui::ToolStatus::Error,
                                     message: Some("Prompt Too Long".to_string()),
                                     output: Some(error_message.clone()),
+                                    duration: None,
+                                    unchanged: false,
                                 })
                                 .await;
                         }
@@ -459,6 +536,14 @@ impl Agent {
                     // force context compaction so we have a chance to continue.
                     warn!("No large tool results to replace — dropping last exchange and forcing compaction");
                     self.drop_last_tool_exchange();
+                    let message = "Prompt too long: dropped the last tool call and compacted \
+                                   the conversation";
+                    let _ = self
+                        .ui
+                        .send_event(UiEvent::DisplayWarning {
+                            message: message.to_string(),
+                        })
+                        .await;
                     self.perform_compaction().await?;
                     continue;
                 }
@@ -721,6 +806,9 @@ impl Agent {
         let parser = ParserRegistry::get(self.tool_syntax());
         match parser.extract_requests(llm_response, request_counter, 0) {
             Ok((requests, truncated_response)) => {
+                if requests.is_empty() {
+                    self.queue_steering_message();
+                }
                 if requests.is_empty() && !self.has_pending_message() {
                     Ok((requests, LoopFlow::GetUserInput, truncated_response))
                 } else {
@@ -816,6 +904,18 @@ impl Agent {
                             end_time: Some(SystemTime::now()),
                         }
                     })
+                } else if self.has_steering_message() {
+                    // The user redirected the turn; the remaining tools wait
+                    // for the model to decide again
+                    let now = Some(SystemTime::now());
+                    ContentBlock::ToolResult {
+                        tool_use_id: tool_request.id.clone(),
+                        content: "Tool call skipped: the user sent new instructions before it ran."
+                            .to_string(),
+                        is_error: Some(true),
+                        start_time: now,
+                        end_time: now,
+                    }
                 } else {
                     // Sequential execution
                     let start_time = Some(SystemTime::now());
@@ -848,6 +948,19 @@ impl Agent {
             let result_message = Message::new_user_content(final_blocks);
             self.append_message(result_message)?;
         }
+
+        // Steering follows the tool results it interrupted
+        if let Some(steering) = self.take_steering_message() {
+            debug!("Delivering steering message: {}", steering);
+            self.append_message(Message::new_user(steering.clone()))?;
+            self.ui
+                .send_event(UiEvent::DisplayUserInput {
+                    content: steering,
+                    attachments: Vec::new(),
+                    node_id: None,
+                })
+                .await?;
+        }
         Ok(LoopFlow::Continue)
     }
 
@@ -946,6 +1059,8 @@ impl Agent {
                 status: crate::ui::ToolStatus::Running,
                 message: None,
                 output: None,
+                duration: None,
+                unchanged: false,
             })
             .await;
 
@@ -961,6 +1076,8 @@ impl Agent {
                         status: crate::ui::ToolStatus::Error,
                         message: Some(error_msg.clone()),
                         output: Some(error_msg.clone()),
+                        duration: None,
+                        unchanged: false,
                     })
                     .await;
                 return (
@@ -986,6 +1103,7 @@ impl Agent {
         };
 
         let tool = SpawnAgentTool;
+        let started = std::time::Instant::now();
         match tool.execute(&mut context, &mut parsed_input).await {
             Ok(output) => {
                 let success = output.is_success();
@@ -1006,6 +1124,8 @@ impl Agent {
                         status,
                         message: Some(status_msg),
                         output: Some(ui_output),
+                        duration: Some(started.elapsed()),
+                        unchanged: false,
                     })
                     .await;
 
@@ -1030,6 +1150,8 @@ impl Agent {
                         status: crate::ui::ToolStatus::Error,
                         message: Some(error_msg.clone()),
                         output: Some(error_msg.clone()),
+                        duration: Some(started.elapsed()),
+                        unchanged: false,
                     })
                     .await;
 
@@ -1439,6 +1561,18 @@ impl Agent {
             response.usage.cache_read_input_tokens
         );
 
+        let usage = &response.usage;
+        let _ = self
+            .ui
+            .send_event(UiEvent::MessageUsage {
+                prompt_tokens: usage.input_tokens
+                    + usage.cache_creation_input_tokens
+                    + usage.cache_read_input_tokens,
+                completion_tokens: usage.output_tokens,
+                cost: self.estimated_cost(usage),
+            })
+            .await;
+
         // Inform UI that the LLM request has completed (normal completion)
         let _ = self
             .ui
@@ -1557,6 +1691,14 @@ impl Agent {
         &self.message_history
     }
 
+    /// Cost of a response per the pricing in models.json, if the model has any.
+    fn estimated_cost(&self, usage: &llm::Usage) -> Option<f64> {
+        let model_name = &self.session_model_config.as_ref()?.model_name;
+        let config_system = llm::provider_config::ConfigurationSystem::load().ok()?;
+        let pricing = config_system.get_model(model_name)?.pricing?;
+        Some(pricing.cost(usage))
+    }
+
     fn context_usage_ratio(&mut self) -> Result<Option<f32>> {
         let model_name = match self.session_model_config.as_ref() {
             Some(config) => config.model_name.clone(),
@@ -2004,6 +2146,35 @@ impl Agent {
         messages
     }
 
+    /// Ask the UI to approve a tool call and wait for the decision. Without an
+    /// approval registry the call proceeds unprompted.
+    async fn request_tool_approval(&self, tool_id: &str) -> Result<bool> {
+        let Some(registry) = &self.tool_approvals else {
+            return Ok(true);
+        };
+        let mut decision = registry.register(tool_id.to_string());
+        self.ui
+            .send_event(UiEvent::RequestToolApproval {
+                tool_id: tool_id.to_string(),
+            })
+            .await?;
+
+        // Cancelling the turn skips the call instead of waiting on the prompt
+        let mut cancel_check = tokio::time::interval(Duration::from_millis(100));
+        loop {
+            tokio::select! {
+                // A dropped request (e.g. the session was torn down) counts as a skip
+                result = &mut decision => return Ok(result.unwrap_or(false)),
+                _ = cancel_check.tick() => {
+                    if !self.ui.should_streaming_continue() {
+                        debug!("Tool {} skipped: turn cancelled while awaiting approval", tool_id);
+                        registry.resolve(tool_id, false);
+                    }
+                }
+            }
+        }
+    }
+
     /// Executes a tool and catches all errors, returning them as Results
     async fn execute_tool(&mut self, tool_request: &ToolRequest) -> Result<bool> {
         debug!(
@@ -2043,14 +2214,22 @@ impl Agent {
             return Err(anyhow::anyhow!("Invalid session title provided"));
         }
 
+        // Gated tools wait for the user to approve or skip them
+        let approved = is_hidden
+            || !self.ui.requires_tool_approval(&tool_request.name)
+            || self.request_tool_approval(&tool_request.id).await?;
+
         // Update status to Running before execution (skip for hidden tools)
-        if !is_hidden {
+        let show_running = approved && !is_hidden;
+        if show_running {
             self.ui
                 .send_event(UiEvent::UpdateToolStatus {
                     tool_id: tool_request.id.clone(),
                     status: crate::ui::ToolStatus::Running,
                     message: None,
                     output: None,
+                    duration: None,
+                    unchanged: false,
                 })
                 .await?;
         }
@@ -2075,7 +2254,24 @@ impl Agent {
 
         // Execute the tool - could fail with ParseError or other errors
         let mut input = tool_request.input.clone();
-        let result = match tool.invoke(&mut context, &mut input).await {
+        let started = std::time::Instant::now();
+        let outcome = if approved {
+            if show_running {
+                self.ui
+                    .send_event(UiEvent::Phase {
+                        label: Some(format!("Running {}", tool_request.name)),
+                    })
+                    .await?;
+            }
+            let outcome = tool.invoke(&mut context, &mut input).await;
+            if show_running {
+                self.ui.send_event(UiEvent::Phase { label: None }).await?;
+            }
+            outcome
+        } else {
+            Err(anyhow::anyhow!("the user skipped this tool call"))
+        };
+        let result = match outcome {
             Ok(result) => {
                 // Tool executed successfully (but may have failed functionally)
                 let success = result.is_success();
@@ -2105,6 +2301,8 @@ impl Agent {
                             status,
                             message: Some(short_output),
                             output: Some(ui_output),
+                            duration: Some(started.elapsed()),
+                            unchanged: result.is_unchanged(),
                         })
                         .await?;
                 }
@@ -2173,6 +2371,8 @@ impl Agent {
                             status: crate::ui::ToolStatus::Error,
                             message: Some(error_text.clone()),
                             output: Some(error_text.clone()),
+                            duration: Some(started.elapsed()),
+                            unchanged: false,
                         })
                         .await?;
                 }
@@ -122,8 +122,14 @@ impl DefaultSubAgentRunner {
         permission_handler: Option<Arc<dyn PermissionMediator>>,
     ) -> Result<Agent> {
         // Create a fresh LLM provider (avoid requiring Clone).
-        let llm_provider =
-            llm::factory::create_llm_client_from_model(&self.model_name, None, false, None).await?;
+        let llm_provider = llm::factory::create_llm_client_from_model(
+            &self.model_name,
+            None,
+            false,
+            llm::recording::PlaybackControl::default(),
+            None,
+        )
+        .await?;
 
         // Create a fresh project manager, copying init_path if set.
         let mut project_manager: Box<dyn crate::config::ProjectManager> =
@@ -457,6 +463,8 @@ impl SubAgentUiAdapter {
                 status: ToolStatus::Running,
                 message: Some("Sub-agent running".to_string()),
                 output: Some(json),
+                duration: None,
+                unchanged: false,
             })
             .await;
     }
@@ -682,6 +690,12 @@ impl UserInterface for SubAgentUiAdapter {
 
     fn clear_rate_limit(&self) {}
 
+    fn notify_retry_attempt(&self, _attempt: u32, _max: u32) {}
+
+    fn requires_tool_approval(&self, _tool_name: &str) -> bool {
+        false
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -487,6 +487,184 @@ async fn test_unknown_tool_error_handling() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_steering_skips_pending_tools_and_follows_results() -> Result<()> {
+    let mock_llm = MockLLMProvider::new(vec![Ok(create_test_response(
+        "read-files-id",
+        "read_files",
+        serde_json::json!({
+            "project": "test",
+            "paths": ["test.txt"]
+        }),
+        "Reading the file",
+    ))]);
+    let mock_llm_ref = mock_llm.clone();
+
+    let components = AgentComponents {
+        llm_provider: Box::new(mock_llm),
+        project_manager: Box::new(MockProjectManager::new()),
+        command_executor: Box::new(create_command_executor_mock()),
+        ui: Arc::new(MockUI::default()),
+        state_persistence: Box::new(MockStatePersistence::new()),
+        permission_handler: None,
+        sub_agent_runner: None,
+    };
+
+    let session_config = SessionConfig {
+        init_path: Some(PathBuf::from("./test_path")),
+        initial_project: String::new(),
+        tool_syntax: ToolSyntax::Native,
+        use_diff_blocks: false,
+        sandbox_policy: SandboxPolicy::DangerFullAccess,
+    };
+
+    let mut agent = Agent::new(components, session_config);
+    agent.disable_naming_reminders();
+    // Steering sent while the first response streams
+    let steering = Arc::new(std::sync::Mutex::new(Some("Use the v2 API".to_string())));
+    agent.set_steering_message_ref(steering.clone());
+
+    agent.start_with_task("Test task".to_string()).await?;
+
+    let requests = mock_llm_ref.get_requests();
+    assert_eq!(requests.len(), 2);
+    let messages = &requests[1].messages;
+    assert_eq!(messages.len(), 4);
+
+    // The tool didn't run, and the model is told why
+    if let MessageContent::Structured(blocks) = &messages[2].content {
+        match &blocks[0] {
+            ContentBlock::ToolResult {
+                tool_use_id,
+                content,
+                is_error,
+                ..
+            } => {
+                assert_eq!(tool_use_id, "read-files-id");
+                assert_eq!(*is_error, Some(true));
+                assert!(content.contains("skipped"));
+            }
+            other => panic!("Expected ToolResult, got {other:?}"),
+        }
+    } else {
+        panic!("Expected Structured content in tool result message");
+    }
+
+    // The steering text follows the results
+    assert_eq!(messages[3].role, MessageRole::User);
+    match &messages[3].content {
+        MessageContent::Text(content) => assert_eq!(content, "Use the v2 API"),
+        other => panic!("Expected Text content, got {other:?}"),
+    }
+    assert!(steering.lock().unwrap().is_none());
+
+    Ok(())
+}
+
+type SharedMessage = Arc<std::sync::Mutex<Option<String>>>;
+
+/// An agent whose steering slot already holds text, as if it arrived while
+/// the first response streamed.
+fn agent_with_pending_steering(
+    mock_llm: MockLLMProvider,
+    ui: MockUI,
+) -> (Agent, SharedMessage, SharedMessage) {
+    let components = AgentComponents {
+        llm_provider: Box::new(mock_llm),
+        project_manager: Box::new(MockProjectManager::new()),
+        command_executor: Box::new(create_command_executor_mock()),
+        ui: Arc::new(ui),
+        state_persistence: Box::new(MockStatePersistence::new()),
+        permission_handler: None,
+        sub_agent_runner: None,
+    };
+
+    let session_config = SessionConfig {
+        init_path: Some(PathBuf::from("./test_path")),
+        initial_project: String::new(),
+        tool_syntax: ToolSyntax::Native,
+        use_diff_blocks: false,
+        sandbox_policy: SandboxPolicy::DangerFullAccess,
+    };
+
+    let mut agent = Agent::new(components, session_config);
+    agent.disable_naming_reminders();
+    let steering = Arc::new(std::sync::Mutex::new(Some("Use the v2 API".to_string())));
+    let pending = Arc::new(std::sync::Mutex::new(None));
+    agent.set_steering_message_ref(steering.clone());
+    agent.set_pending_message_ref(pending.clone());
+    (agent, steering, pending)
+}
+
+#[tokio::test]
+async fn test_steering_left_by_complete_task_is_queued() -> Result<()> {
+    // complete_task ends the turn before any tool boundary reads steering
+    let mock_llm = MockLLMProvider::new(vec![Ok(create_test_response(
+        "complete-task-id",
+        "complete_task",
+        serde_json::json!({ "message": "Done" }),
+        "Completing the task",
+    ))]);
+    let ui = MockUI::default();
+    let (mut agent, steering, pending) = agent_with_pending_steering(mock_llm.clone(), ui.clone());
+
+    agent.start_with_task("Test task".to_string()).await?;
+
+    assert_eq!(mock_llm.get_requests().len(), 1);
+    assert!(steering.lock().unwrap().is_none());
+    assert_eq!(pending.lock().unwrap().as_deref(), Some("Use the v2 API"));
+    assert!(ui.events().iter().any(|event| matches!(
+        event,
+        UiEvent::UpdatePendingMessage { message: Some(message) } if message == "Use the v2 API"
+    )));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_steering_left_by_failed_turn_is_queued() -> Result<()> {
+    let mock_llm = MockLLMProvider::new(vec![Err(anyhow::anyhow!("Connection reset"))]);
+    let (mut agent, steering, pending) =
+        agent_with_pending_steering(mock_llm.clone(), MockUI::default());
+
+    assert!(agent
+        .start_with_task("Test task".to_string())
+        .await
+        .is_err());
+
+    assert!(steering.lock().unwrap().is_none());
+    assert_eq!(pending.lock().unwrap().as_deref(), Some("Use the v2 API"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_steering_after_cancelled_stream_is_not_left_behind() -> Result<()> {
+    let mock_llm = MockLLMProvider::new(vec![
+        Ok(create_test_response_text("Switching to the v2 API.")),
+        Err(anyhow::anyhow!("Streaming cancelled by user")),
+    ]);
+    let (mut agent, steering, pending) =
+        agent_with_pending_steering(mock_llm.clone(), MockUI::default());
+
+    agent.start_with_task("Test task".to_string()).await?;
+
+    // The cancelled response has no tool boundary, so the steering is
+    // delivered as the next user message
+    let requests = mock_llm.get_requests();
+    assert_eq!(requests.len(), 2);
+    let last = requests[1].messages.last().unwrap();
+    assert_eq!(last.role, MessageRole::User);
+    match &last.content {
+        MessageContent::Text(content) => assert_eq!(content, "Use the v2 API"),
+        other => panic!("Expected Text content, got {other:?}"),
+    }
+    assert!(steering.lock().unwrap().is_none());
+    assert!(pending.lock().unwrap().is_none());
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_invalid_xml_tool_error_handling() -> Result<()> {
     let mock_llm = MockLLMProvider::new(vec![
@@ -2721,5 +2899,14 @@ async fn test_prompt_too_long_fallback_drops_exchange_and_compacts() -> Result<(
         "Expected compaction divider in UI streaming output"
     );
 
+    // The user is told that context was lost
+    let warned = ui.events().iter().any(|event| {
+        matches!(
+            event,
+            UiEvent::DisplayWarning { message } if message.contains("dropped the last tool call")
+        )
+    });
+    assert!(warned, "Expected a warning about the dropped exchange");
+
     Ok(())
 }
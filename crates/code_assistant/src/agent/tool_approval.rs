@@ -0,0 +1,45 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio::sync::oneshot;
+
+/// Tool calls waiting for the user to approve or skip them, keyed by tool id.
+#[derive(Default)]
+pub struct ToolApprovalRegistry {
+    pending: Mutex<HashMap<String, oneshot::Sender<bool>>>,
+}
+
+impl ToolApprovalRegistry {
+    /// Register a pending approval. The receiver yields `true` once approved
+    /// and `false` once rejected; it errors if the registry is dropped first.
+    pub fn register(&self, tool_id: String) -> oneshot::Receiver<bool> {
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(tool_id, tx);
+        rx
+    }
+
+    /// Deliver the user's decision. Returns false if nothing was waiting.
+    pub fn resolve(&self, tool_id: &str, approved: bool) -> bool {
+        let sender = self.pending.lock().unwrap().remove(tool_id);
+        sender.is_some_and(|tx| tx.send(approved).is_ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_resolve_delivers_decision_once() {
+        let registry = ToolApprovalRegistry::default();
+        let approved = registry.register("tool-1".to_string());
+        let rejected = registry.register("tool-2".to_string());
+
+        assert!(registry.resolve("tool-1", true));
+        assert!(registry.resolve("tool-2", false));
+        assert!(!registry.resolve("tool-1", true));
+        assert!(!registry.resolve("unknown", true));
+
+        assert!(approved.await.unwrap());
+        assert!(!rejected.await.unwrap());
+    }
+}
@@ -5,6 +5,7 @@ use crate::ui::{self, UserInterface};
 use anyhow::Result;
 use command_executor::DefaultCommandExecutor;
 use llm::factory::create_llm_client_from_model;
+use llm::recording::PlaybackControl;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tracing::{debug, error, info};
@@ -97,6 +98,7 @@ pub fn run(config: AgentRunConfig) -> Result<()> {
                     &model,
                     playback.clone(),
                     fast_playback,
+                    PlaybackControl::default(),
                     record.clone(),
                 )
                 .await
@@ -195,6 +197,7 @@ pub fn run(config: AgentRunConfig) -> Result<()> {
                     record_path: record.clone(),
                     playback_path: playback.clone(),
                     fast_playback,
+                    playback_control: PlaybackControl::default(),
                 }),
                 Arc::new(gui_for_thread) as Arc<dyn crate::ui::UserInterface>,
             )
@@ -14,6 +14,8 @@ pub struct AgentRunConfig {
     pub path: PathBuf,
     pub task: Option<String>,
     pub continue_task: bool,
+    /// Session to show read-only instead of running an agent (terminal UI only)
+    pub view: Option<String>,
     pub model: String,
     pub tool_syntax: ToolSyntax,
     pub use_diff_format: bool,
@@ -78,6 +78,10 @@ pub struct Args {
     #[arg(long)]
     pub continue_task: bool,
 
+    /// Open a saved session read-only in the terminal UI, without starting an agent
+    #[arg(long, value_name = "SESSION_ID", conflicts_with_all = ["task", "continue_task", "ui"])]
+    pub view: Option<String>,
+
     /// Enable verbose logging (use multiple times for more verbosity)
     #[arg(short, long, action = clap::ArgAction::Count)]
     pub verbose: u8,
@@ -253,6 +257,7 @@ mod tests {
         assert_eq!(args.verbose, 0);
         assert!(!args.ui);
         assert!(!args.continue_task);
+        assert!(args.view.is_none());
         assert!(!args.fast_playback);
         assert!(!args.use_diff_format);
         assert!(!args.list_models);
@@ -272,6 +277,17 @@ mod tests {
         assert_eq!(args.verbose, 3);
     }
 
+    #[test]
+    fn test_view_flag() {
+        let args = Args::try_parse_from(["test", "--view", "chat_123"])
+            .expect("Failed to parse view args");
+        assert_eq!(args.view.as_deref(), Some("chat_123"));
+
+        // A viewed session cannot also run a task
+        assert!(Args::try_parse_from(["test", "--view", "chat_123", "-t", "fix it"]).is_err());
+        assert!(Args::try_parse_from(["test", "--view", "chat_123", "--continue-task"]).is_err());
+    }
+
     #[test]
     fn test_server_mode() {
         let args = Args::try_parse_from(["test", "server", "--verbose"])
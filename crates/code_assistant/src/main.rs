@@ -51,6 +51,7 @@ async fn main() -> Result<()> {
                 path,
                 task: None,
                 continue_task: false,
+                view: None,
                 model: model_name.clone(),
                 tool_syntax,
                 use_diff_format,
@@ -83,6 +84,7 @@ async fn main() -> Result<()> {
                 path: args.path,
                 task: args.task,
                 continue_task: args.continue_task,
+                view: args.view,
                 model: model_name,
                 tool_syntax: args.tool_syntax,
                 use_diff_format: args.use_diff_format,
@@ -554,6 +554,12 @@ impl FileSessionPersistence {
         Ok(chats_dir.join(format!("{session_id}.json")))
     }
 
+    /// Path of the human-readable markdown transcript kept alongside a session.
+    pub fn transcript_file_path(&self, session_id: &str) -> Result<PathBuf> {
+        let chats_dir = self.ensure_chats_dir()?;
+        Ok(chats_dir.join(format!("{session_id}.md")))
+    }
+
     fn metadata_file_path(&self) -> Result<PathBuf> {
         let chats_dir = self.ensure_chats_dir()?;
         Ok(chats_dir.join("metadata.json"))
@@ -968,6 +974,15 @@ impl DraftStorage {
         })
     }
 
+    /// Draft storage in the default config directory, shared by the UIs
+    pub fn in_config_dir() -> Result<Self> {
+        Self::new(
+            dirs::config_dir()
+                .unwrap_or_else(|| std::env::current_dir().unwrap())
+                .join("code-assistant"),
+        )
+    }
+
     /// Get the path for a draft file for a given session
     fn draft_file_path(&self, session_id: &str) -> PathBuf {
         self.drafts_dir.join(format!("{session_id}.json"))
@@ -5,7 +5,7 @@ use std::sync::{Arc, Mutex};
 use tokio::task::JoinHandle;
 
 // Agent instances are created on-demand, no need to import
-use crate::agent::SubAgentCancellationRegistry;
+use crate::agent::{SubAgentCancellationRegistry, ToolApprovalRegistry};
 use crate::persistence::{ChatMetadata, ChatSession, NodeId};
 use crate::ui::gpui::elements::MessageRole;
 use crate::ui::streaming::create_stream_processor;
@@ -52,11 +52,17 @@ pub struct SessionInstance {
     /// Pending user message that will be processed by the next agent iteration
     pub pending_message: Arc<Mutex<Option<String>>>,
 
+    /// Steering text the running agent picks up before its next tool call
+    pub steering_message: Arc<Mutex<Option<String>>>,
+
     /// Tracks sandbox-approved roots for this session
     pub sandbox_context: Arc<SandboxContext>,
 
     /// Cancellation registry for sub-agents running in agent tasks
     pub sub_agent_cancellation_registry: Arc<SubAgentCancellationRegistry>,
+
+    /// Tool calls waiting for the user to approve them
+    pub tool_approval_registry: Arc<ToolApprovalRegistry>,
 }
 
 impl SessionInstance {
@@ -74,8 +80,10 @@ impl SessionInstance {
             is_ui_connected: Arc::new(Mutex::new(false)),
             activity_state: Arc::new(Mutex::new(SessionActivityState::Idle)),
             pending_message: Arc::new(Mutex::new(None)),
+            steering_message: Arc::new(Mutex::new(None)),
             sandbox_context,
             sub_agent_cancellation_registry: Arc::new(SubAgentCancellationRegistry::default()),
+            tool_approval_registry: Arc::new(ToolApprovalRegistry::default()),
         }
     }
 
@@ -85,6 +93,12 @@ impl SessionInstance {
         self.sub_agent_cancellation_registry.cancel(tool_id)
     }
 
+    /// Approve or reject a tool call waiting for the user
+    /// Returns true if the tool was waiting for a decision, false otherwise
+    pub fn resolve_tool_approval(&self, tool_id: &str, approved: bool) -> bool {
+        self.tool_approval_registry.resolve(tool_id, approved)
+    }
+
     /// Get the current activity state
     pub fn get_activity_state(&self) -> SessionActivityState {
         self.activity_state.lock().unwrap().clone()
@@ -326,6 +340,10 @@ impl SessionInstance {
             }
             fn notify_rate_limit(&self, _seconds_remaining: u64) {}
             fn clear_rate_limit(&self) {}
+            fn notify_retry_attempt(&self, _attempt: u32, _max: u32) {}
+            fn requires_tool_approval(&self, _tool_name: &str) -> bool {
+                false
+            }
             fn as_any(&self) -> &dyn std::any::Any {
                 self
             }
@@ -457,6 +475,7 @@ impl SessionInstance {
                 status,
                 message: Some(short_output),
                 output: Some(output),
+                unchanged: execution.result.is_unchanged(),
             });
         }
 
@@ -651,6 +670,17 @@ impl UserInterface for ProxyUI {
         // No-op if session not connected
     }
 
+    fn notify_retry_attempt(&self, attempt: u32, max: u32) {
+        if self.is_connected() {
+            self.real_ui.notify_retry_attempt(attempt, max);
+        }
+    }
+
+    fn requires_tool_approval(&self, tool_name: &str) -> bool {
+        // Nobody sees the prompt of a session in the background
+        self.is_connected() && self.real_ui.requires_tool_approval(tool_name)
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
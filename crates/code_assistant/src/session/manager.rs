@@ -13,7 +13,7 @@ use crate::persistence::{
 };
 use crate::session::instance::SessionInstance;
 use crate::session::{SessionConfig, SessionState};
-use crate::ui::ui_events::UiEvent;
+use crate::ui::ui_events::{ErrorInfo, UiEvent};
 use crate::ui::UserInterface;
 use command_executor::{CommandExecutor, SandboxedCommandExecutor};
 use llm::LLMProvider;
@@ -348,6 +348,7 @@ impl SessionManager {
             session_state,
             activity_state_ref,
             pending_message_ref,
+            steering_message_ref,
             sandbox_context,
         ) = {
             let session_instance = self
@@ -366,6 +367,7 @@ impl SessionManager {
             let proxy_ui = session_instance.create_proxy_ui(ui.clone());
             let activity_state_ref = session_instance.activity_state.clone();
             let pending_message_ref = session_instance.pending_message.clone();
+            let steering_message_ref = session_instance.steering_message.clone();
 
             let session_state = crate::session::SessionState {
                 session_id: session_id.to_string(),
@@ -396,6 +398,7 @@ impl SessionManager {
                 session_state,
                 activity_state_ref,
                 pending_message_ref,
+                steering_message_ref,
                 session_instance.sandbox_context.clone(),
             )
         };
@@ -478,6 +481,10 @@ impl SessionManager {
 
         // Set the shared pending message reference
         agent.set_pending_message_ref(pending_message_ref);
+        agent.set_steering_message_ref(steering_message_ref);
+        if let Some(session_instance) = self.active_sessions.get(session_id) {
+            agent.set_tool_approval_registry(session_instance.tool_approval_registry.clone());
+        }
 
         // Load the session state into the agent
         agent.load_from_session_state(session_state).await?;
@@ -485,6 +492,11 @@ impl SessionManager {
         // Spawn the agent task
         let session_id_clone = session_id.to_string();
         let ui_clone = ui.clone();
+        let model_name = self
+            .get_session_model_config(session_id)
+            .ok()
+            .flatten()
+            .map(|config| config.model_name);
 
         let task_handle = tokio::spawn(async move {
             debug!("Starting agent for session {}", session_id_clone);
@@ -498,6 +510,14 @@ impl SessionManager {
             if let Ok(mut state) = activity_state_ref.lock() {
                 *state = crate::session::instance::SessionActivityState::Idle;
             }
+            // Steering stored after the loop released it but before the
+            // session went idle would have no agent left to read it
+            if let Err(e) = agent.release_steering_message().await {
+                debug!(
+                    "Failed to release steering for session {}: {}",
+                    session_id_clone, e
+                );
+            }
 
             // Always broadcast the state change to UI
             let send_result = ui_clone
@@ -536,11 +556,12 @@ impl SessionManager {
                     );
 
                     // Send error to UI for user notification
-                    let error_message = format!("Agent error: {e}");
+                    let mut info = ErrorInfo::from_error("Agent error", e);
+                    if let Some(api) = info.api.as_mut() {
+                        api.provider = model_name.as_deref().and_then(provider_label);
+                    }
                     if let Err(ui_error) = ui_clone
-                        .send_event(crate::ui::UiEvent::DisplayError {
-                            message: error_message,
-                        })
+                        .send_event(crate::ui::UiEvent::DisplayErrorDetailed { info })
                         .await
                     {
                         error!(
@@ -595,6 +616,21 @@ impl SessionManager {
         }
     }
 
+    /// Deliver the user's decision for a tool call awaiting approval
+    /// Returns Ok(false) if the tool was not waiting (e.g. already resolved)
+    pub fn resolve_tool_approval(
+        &self,
+        session_id: &str,
+        tool_id: &str,
+        approved: bool,
+    ) -> Result<bool> {
+        if let Some(session_instance) = self.active_sessions.get(session_id) {
+            Ok(session_instance.resolve_tool_approval(tool_id, approved))
+        } else {
+            Err(anyhow::anyhow!("Session not found: {}", session_id))
+        }
+    }
+
     /// Get a session instance by ID
     pub fn get_session(&self, session_id: &str) -> Option<&SessionInstance> {
         self.active_sessions.get(session_id)
@@ -761,6 +797,37 @@ impl SessionManager {
         }
     }
 
+    /// Steer a running agent session: the text reaches the agent before its
+    /// next tool call instead of after the turn like a queued message.
+    /// Returns `false` when no agent was running and the text was queued
+    /// instead.
+    pub fn steer_agent(&mut self, session_id: &str, message: String) -> Result<bool> {
+        let session_instance = self
+            .active_sessions
+            .get(session_id)
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {session_id}"))?;
+
+        // The agent task goes idle before releasing leftover steering, so
+        // checking the state under the steering lock can't strand the text
+        let steering_ref = session_instance.steering_message.clone();
+        let mut steering = steering_ref.lock().unwrap();
+        if session_instance.get_activity_state()
+            == crate::session::instance::SessionActivityState::Idle
+        {
+            drop(steering);
+            self.queue_user_message(session_id, message)?;
+            return Ok(false);
+        }
+        match steering.as_mut() {
+            Some(existing) => {
+                existing.push('\n');
+                existing.push_str(&message);
+            }
+            None => *steering = Some(message),
+        }
+        Ok(true)
+    }
+
     /// Queue a user message for a running agent session
     pub fn queue_user_message(&mut self, session_id: &str, message: String) -> Result<()> {
         // Get the active session instance and update shared pending message
@@ -890,3 +957,10 @@ impl SessionManager {
         Ok(pending.clone())
     }
 }
+
+/// Label of the provider configured for `model_name`, for error reports.
+fn provider_label(model_name: &str) -> Option<String> {
+    let config_system = llm::provider_config::ConfigurationSystem::load().ok()?;
+    let (_, provider) = config_system.get_model_with_provider(model_name).ok()?;
+    Some(provider.label.clone())
+}
@@ -325,6 +325,14 @@ impl UserInterface for MockUI {
         // Mock implementation does nothing with rate limit clearing
     }
 
+    fn notify_retry_attempt(&self, _attempt: u32, _max: u32) {
+        // Mock implementation does nothing with retry notifications
+    }
+
+    fn requires_tool_approval(&self, _tool_name: &str) -> bool {
+        false
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -48,7 +48,7 @@ impl ToolsConfig {
     }
 
     /// Get the configuration directory.
-    fn config_directory() -> Result<PathBuf> {
+    pub(crate) fn config_directory() -> Result<PathBuf> {
         // Check for custom config directory first
         if let Ok(custom_dir) = std::env::var("CODE_ASSISTANT_CONFIG_DIR") {
             return Ok(PathBuf::from(custom_dir));
@@ -17,6 +17,9 @@ pub trait AnyOutput: Send + Sync {
     /// Determine if the tool execution was successful
     fn is_success(&self) -> bool;
 
+    /// Determine if the tool succeeded without changing anything
+    fn is_unchanged(&self) -> bool;
+
     /// Serialize this output to a JSON value
     #[allow(dead_code)]
     fn to_json(&self) -> Result<serde_json::Value>;
@@ -32,6 +35,10 @@ impl<T: Render + ToolResult + Serialize + Send + Sync + 'static> AnyOutput for T
         ToolResult::is_success(self)
     }
 
+    fn is_unchanged(&self) -> bool {
+        ToolResult::is_unchanged(self)
+    }
+
     fn to_json(&self) -> Result<serde_json::Value> {
         serde_json::to_value(self).map_err(|e| anyhow::anyhow!("Failed to serialize output: {e}"))
     }
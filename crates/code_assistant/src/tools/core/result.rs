@@ -3,4 +3,10 @@ pub trait ToolResult: Send + Sync + 'static {
     /// Returns whether the tool execution was successful
     /// This is used for status reporting and can affect how the result is displayed
     fn is_success(&self) -> bool;
+
+    /// Whether the tool succeeded without changing anything, e.g. a write of
+    /// the content a file already had
+    fn is_unchanged(&self) -> bool {
+        false
+    }
 }
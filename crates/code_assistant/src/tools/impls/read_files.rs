@@ -287,8 +287,17 @@ impl Tool for ReadFilesTool {
         let mut loaded_files = HashMap::new();
         let mut failed_files = Vec::new();
 
-        // Process each path
-        for path_str in input.paths.clone() {
+        // Process each path, reporting progress when reading several
+        let total = input.paths.len();
+        for (index, path_str) in input.paths.clone().into_iter().enumerate() {
+            if let (Some(ui), Some(tool_id), true) = (context.ui, &context.tool_id, total > 1) {
+                let _ = ui
+                    .send_event(crate::ui::UiEvent::ToolProgress {
+                        tool_id: tool_id.clone(),
+                        fraction: index as f32 / total as f32,
+                    })
+                    .await;
+            }
             // Parse the path string to extract line range information
             let parsed_path = match PathWithLineRange::parse(&path_str) {
                 Ok(parsed) => parsed,
@@ -497,7 +506,8 @@ mod tests {
             ),
             ("test2.txt".to_string(), "Another file content".to_string()),
         ])
-        .with_ui();
+        .with_ui()
+        .with_tool_id("tool-1".to_string());
         let mut context = fixture.context();
 
         // Parameters for read_files
@@ -541,6 +551,18 @@ mod tests {
         assert!(has_test_txt, "Expected ResourceLoaded event for test.txt");
         assert!(has_test2_txt, "Expected ResourceLoaded event for test2.txt");
 
+        // Reading several files reports progress before each one
+        let progress: Vec<f32> = events
+            .iter()
+            .filter_map(|e| match e {
+                UiEvent::ToolProgress { tool_id, fraction } if tool_id == "tool-1" => {
+                    Some(*fraction)
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(progress, [0.0, 0.5]);
+
         Ok(())
     }
 
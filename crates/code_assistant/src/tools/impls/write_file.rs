@@ -23,6 +23,9 @@ pub struct WriteFileOutput {
     pub path: PathBuf,
     pub content: String,
     pub error: Option<String>,
+    /// The file already had exactly this content
+    #[serde(default)]
+    pub unchanged: bool,
 }
 
 // Render implementation for output formatting
@@ -30,6 +33,8 @@ impl Render for WriteFileOutput {
     fn status(&self) -> String {
         if let Some(error) = &self.error {
             format!("Failed to write to file {}: {}", self.path.display(), error)
+        } else if self.unchanged {
+            format!("File unchanged: {}", self.path.display())
         } else {
             format!("Successfully wrote to file: {}", self.path.display())
         }
@@ -53,6 +58,10 @@ impl ToolResult for WriteFileOutput {
     fn is_success(&self) -> bool {
         self.error.is_none()
     }
+
+    fn is_unchanged(&self) -> bool {
+        self.error.is_none() && self.unchanged
+    }
 }
 
 // Tool implementation
@@ -132,6 +141,7 @@ impl Tool for WriteFileTool {
                         "Failed to get explorer for project {}: {}",
                         input.project, e
                     )),
+                    unchanged: false,
                 });
             }
         };
@@ -149,6 +159,7 @@ impl Tool for WriteFileTool {
                 path,
                 content: String::new(),
                 error: Some("Absolute paths are not allowed".to_string()),
+                unchanged: false,
             });
         }
 
@@ -157,6 +168,13 @@ impl Tool for WriteFileTool {
         // Join with root_dir to get full path
         let full_path = project_root.join(&path);
 
+        // Note whether the write is a no-op before overwriting the file
+        let unchanged = !input.append
+            && explorer
+                .read_file(&full_path)
+                .await
+                .is_ok_and(|existing| existing == input.content);
+
         // Write the file first
         match explorer
             .write_file(&full_path, &input.content, input.append)
@@ -193,12 +211,14 @@ impl Tool for WriteFileTool {
                     path,
                     content: input.content.clone(),
                     error: None,
+                    unchanged,
                 })
             }
             Err(e) => Ok(WriteFileOutput {
                 path,
                 content: String::new(), // Empty content on error
                 error: Some(e.to_string()),
+                unchanged: false,
             }),
         }
     }
@@ -217,6 +237,7 @@ mod tests {
             path: PathBuf::from("test.txt"),
             content: "Test content".to_string(),
             error: None,
+            unchanged: false,
         };
 
         let mut tracker = ResourcesTracker::new();
@@ -229,6 +250,7 @@ mod tests {
             path: PathBuf::from("test.txt"),
             content: String::new(),
             error: Some("File not writable".to_string()),
+            unchanged: false,
         };
 
         let rendered_error = output_error.render(&mut tracker);
@@ -313,4 +335,27 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_write_file_reports_identical_content_as_unchanged() -> Result<()> {
+        let mut fixture =
+            ToolTestFixture::with_files(vec![("test.txt".to_string(), "Same content".to_string())]);
+        let mut context = fixture.context();
+
+        let mut input = WriteFileInput {
+            project: "test-project".to_string(),
+            path: "test.txt".to_string(),
+            content: "Same content".to_string(),
+            append: false,
+        };
+        let result = WriteFileTool.execute(&mut context, &mut input).await?;
+        assert!(result.is_unchanged());
+        assert!(result.status().starts_with("File unchanged"));
+
+        input.content = "New content".to_string();
+        let result = WriteFileTool.execute(&mut context, &mut input).await?;
+        assert!(!result.is_unchanged());
+
+        Ok(())
+    }
 }
@@ -6,6 +6,7 @@ use crate::utils::content::content_blocks_from;
 use command_executor::DefaultCommandExecutor;
 use llm::factory::create_llm_client_from_model;
 use llm::provider_config::ConfigurationSystem;
+use llm::recording::{PlaybackAction, PlaybackControl, PlaybackStatus};
 use sandbox::SandboxPolicy;
 
 use std::path::PathBuf;
@@ -43,11 +44,27 @@ pub enum BackendEvent {
         message: String,
         attachments: Vec<DraftAttachment>,
     },
+    /// Redirect a running turn: the message is handed to the agent at the
+    /// next tool boundary instead of cancelling the turn
+    SteerMessage {
+        session_id: String,
+        message: String,
+    },
     RequestPendingMessageEdit {
         session_id: String,
     },
+    /// Run the agent again on the session's history, e.g. after a
+    /// retryable error ended the turn
+    RetryLastTurn {
+        session_id: String,
+    },
+    /// Write the session's current state to disk (e.g. idle auto-save)
+    PersistSession {
+        session_id: String,
+    },
 
     // Model management
+    ListModels,
     SwitchModel {
         session_id: String,
         model_name: String,
@@ -63,6 +80,16 @@ pub enum BackendEvent {
         tool_id: String,
     },
 
+    // Tool approval
+    ApproveTool {
+        session_id: String,
+        tool_id: String,
+    },
+    RejectTool {
+        session_id: String,
+        tool_id: String,
+    },
+
     // Session branching
     StartMessageEdit {
         session_id: String,
@@ -75,6 +102,11 @@ pub enum BackendEvent {
     CancelMessageEdit {
         session_id: String,
     },
+
+    // Recorded session playback
+    PlaybackControl {
+        action: PlaybackAction,
+    },
 }
 
 // Response from backend to UI
@@ -106,6 +138,9 @@ pub enum BackendResponse {
         session_id: String,
         model_name: String,
     },
+    ModelsListed {
+        models: Vec<String>,
+    },
 
     SandboxPolicyChanged {
         session_id: String,
@@ -117,6 +152,10 @@ pub enum BackendResponse {
         tool_id: String,
     },
 
+    PlaybackStatusChanged {
+        status: PlaybackStatus,
+    },
+
     // Session branching responses
     MessageEditReady {
         session_id: String,
@@ -146,6 +185,8 @@ pub struct BackendRuntimeOptions {
     pub record_path: Option<PathBuf>,
     pub playback_path: Option<PathBuf>,
     pub fast_playback: bool,
+    /// Pause, step and speed of the playback, shared by every client created
+    pub playback_control: PlaybackControl,
 }
 
 pub async fn handle_backend_events(
@@ -208,10 +249,32 @@ pub async fn handle_backend_events(
                 .await,
             ),
 
+            BackendEvent::SteerMessage {
+                session_id,
+                message,
+            } => handle_steer_message(&multi_session_manager, &session_id, message).await,
+
             BackendEvent::RequestPendingMessageEdit { session_id } => {
                 Some(handle_request_pending_message_edit(&multi_session_manager, &session_id).await)
             }
 
+            BackendEvent::RetryLastTurn { session_id } => {
+                debug!("Retrying last turn for session {}", session_id);
+                start_agent(
+                    &multi_session_manager,
+                    &session_id,
+                    runtime_options.as_ref(),
+                    &ui,
+                )
+                .await
+            }
+
+            BackendEvent::PersistSession { session_id } => {
+                handle_persist_session(&multi_session_manager, &session_id).await
+            }
+
+            BackendEvent::ListModels => Some(handle_list_models()),
+
             BackendEvent::SwitchModel {
                 session_id,
                 model_name,
@@ -225,6 +288,15 @@ pub async fn handle_backend_events(
                 tool_id,
             } => Some(handle_cancel_sub_agent(&multi_session_manager, &session_id, &tool_id).await),
 
+            BackendEvent::ApproveTool {
+                session_id,
+                tool_id,
+            } => handle_tool_approval(&multi_session_manager, &session_id, &tool_id, true).await,
+            BackendEvent::RejectTool {
+                session_id,
+                tool_id,
+            } => handle_tool_approval(&multi_session_manager, &session_id, &tool_id, false).await,
+
             BackendEvent::StartMessageEdit {
                 session_id,
                 node_id,
@@ -240,6 +312,10 @@ pub async fn handle_backend_events(
             BackendEvent::CancelMessageEdit { session_id } => {
                 Some(handle_cancel_message_edit(&multi_session_manager, &session_id).await)
             }
+
+            BackendEvent::PlaybackControl { action } => {
+                Some(handle_playback_control(&runtime_options, action))
+            }
         };
 
         // Send response back to UI only if there is one
@@ -435,6 +511,16 @@ async fn handle_send_user_message(
     }
 
     // Start the agent (message already added)
+    start_agent(multi_session_manager, session_id, runtime_options, ui).await
+}
+
+/// Start the agent on the session's current history.
+async fn start_agent(
+    multi_session_manager: &Arc<Mutex<SessionManager>>,
+    session_id: &str,
+    runtime_options: &BackendRuntimeOptions,
+    ui: &Arc<dyn UserInterface>,
+) -> Option<BackendResponse> {
     let result = {
         let project_manager = Box::new(DefaultProjectManager::new());
         let command_executor = Box::new(DefaultCommandExecutor);
@@ -453,6 +539,7 @@ async fn handle_send_user_message(
                 &session_config.model_name,
                 runtime_options.playback_path.clone(),
                 runtime_options.fast_playback,
+                runtime_options.playback_control.clone(),
                 runtime_options.record_path.clone(),
             )
             .await
@@ -554,6 +641,49 @@ async fn handle_queue_user_message(
     }
 }
 
+async fn handle_steer_message(
+    multi_session_manager: &Arc<Mutex<SessionManager>>,
+    session_id: &str,
+    message: String,
+) -> Option<BackendResponse> {
+    debug!("Steer message for session {}: {}", session_id, message);
+
+    // The agent picks the steering text up before its next tool call; tools
+    // it hasn't started yet are skipped so the model can react to it. If the
+    // turn already ended, the text is queued for the next one instead.
+    let mut manager = multi_session_manager.lock().await;
+    match manager.steer_agent(session_id, message) {
+        Ok(true) => None,
+        Ok(false) => Some(BackendResponse::PendingMessageUpdated {
+            session_id: session_id.to_string(),
+            message: manager.get_pending_message(session_id).unwrap_or(None),
+        }),
+        Err(e) => {
+            error!("Failed to steer session {}: {}", session_id, e);
+            Some(BackendResponse::Error {
+                message: format!("Failed to steer: {e}"),
+            })
+        }
+    }
+}
+
+async fn handle_persist_session(
+    multi_session_manager: &Arc<Mutex<SessionManager>>,
+    session_id: &str,
+) -> Option<BackendResponse> {
+    debug!("Persisting session {}", session_id);
+    let result = multi_session_manager.lock().await.save_session(session_id);
+    match result {
+        Ok(()) => None,
+        Err(e) => {
+            error!("Failed to persist session {}: {}", session_id, e);
+            Some(BackendResponse::Error {
+                message: format!("Failed to save session: {e}"),
+            })
+        }
+    }
+}
+
 async fn handle_request_pending_message_edit(
     multi_session_manager: &Arc<Mutex<SessionManager>>,
     session_id: &str,
@@ -592,6 +722,35 @@ async fn handle_request_pending_message_edit(
     }
 }
 
+fn handle_playback_control(
+    runtime_options: &BackendRuntimeOptions,
+    action: PlaybackAction,
+) -> BackendResponse {
+    if runtime_options.playback_path.is_none() {
+        return BackendResponse::Error {
+            message: "Not playing back a recording".to_string(),
+        };
+    }
+    let status = runtime_options.playback_control.apply(action);
+    BackendResponse::PlaybackStatusChanged { status }
+}
+
+fn handle_list_models() -> BackendResponse {
+    match ConfigurationSystem::load() {
+        Ok(config_system) => {
+            let mut models: Vec<String> = config_system.models.keys().cloned().collect();
+            models.sort();
+            BackendResponse::ModelsListed { models }
+        }
+        Err(e) => {
+            error!("Failed to load model configuration: {}", e);
+            BackendResponse::Error {
+                message: format!("Failed to load model configuration: {e}"),
+            }
+        }
+    }
+}
+
 async fn handle_switch_model(
     multi_session_manager: &Arc<Mutex<SessionManager>>,
     session_id: &str,
@@ -718,6 +877,46 @@ async fn handle_cancel_sub_agent(
     }
 }
 
+async fn handle_tool_approval(
+    multi_session_manager: &Arc<Mutex<SessionManager>>,
+    session_id: &str,
+    tool_id: &str,
+    approved: bool,
+) -> Option<BackendResponse> {
+    debug!(
+        "Tool {} in session {} {}",
+        tool_id,
+        session_id,
+        if approved { "approved" } else { "rejected" }
+    );
+
+    let result = {
+        let manager = multi_session_manager.lock().await;
+        manager.resolve_tool_approval(session_id, tool_id, approved)
+    };
+
+    match result {
+        Ok(true) => None,
+        Ok(false) => {
+            // Not an error - the agent may have been cancelled meanwhile
+            debug!(
+                "Tool {} was not awaiting approval in session {}",
+                tool_id, session_id
+            );
+            None
+        }
+        Err(e) => {
+            error!(
+                "Failed to resolve approval for tool {} in session {}: {}",
+                tool_id, session_id, e
+            );
+            Some(BackendResponse::Error {
+                message: format!("Failed to resolve tool approval: {e}"),
+            })
+        }
+    }
+}
+
 // ============================================================================
 // Session Branching Handlers
 // ============================================================================
@@ -271,18 +271,11 @@ impl Gpui {
         let event_receiver = Arc::new(Mutex::new(rx));
 
         // Initialize draft storage (using default config directory)
-        let draft_storage = Arc::new(
-            DraftStorage::new(
-                dirs::config_dir()
-                    .unwrap_or_else(|| std::env::current_dir().unwrap())
-                    .join("code-assistant"),
-            )
-            .unwrap_or_else(|e| {
-                warn!("Failed to initialize draft storage: {}, using fallback", e);
-                DraftStorage::new(std::env::temp_dir().join("code-assistant-drafts"))
-                    .expect("Failed to create fallback draft storage")
-            }),
-        );
+        let draft_storage = Arc::new(DraftStorage::in_config_dir().unwrap_or_else(|e| {
+            warn!("Failed to initialize draft storage: {}, using fallback", e);
+            DraftStorage::new(std::env::temp_dir().join("code-assistant-drafts"))
+                .expect("Failed to create fallback draft storage")
+        }));
 
         Self {
             message_queue,
@@ -582,6 +575,7 @@ impl Gpui {
                 status,
                 message,
                 output,
+                ..
             } => {
                 self.update_all_messages(cx, |message_container, cx| {
                     message_container.update_tool_status(
@@ -994,6 +988,15 @@ impl Gpui {
                 // Refresh UI to show the error popover
                 cx.refresh().expect("Failed to refresh windows");
             }
+            UiEvent::DisplayErrorDetailed { info } => {
+                debug!("UI: DisplayErrorDetailed event: {:?}", info);
+                *self.current_error.lock().unwrap() = Some(info.to_string());
+                cx.refresh().expect("Failed to refresh windows");
+            }
+            UiEvent::DisplayWarning { message } => {
+                // No warning surface in the GPUI yet
+                warn!("UI: DisplayWarning event: {}", message);
+            }
             UiEvent::ClearError => {
                 debug!("UI: ClearError event");
                 // Clear the error message from state
@@ -1001,6 +1004,30 @@ impl Gpui {
                 // Refresh UI to hide the error popover
                 cx.refresh().expect("Failed to refresh windows");
             }
+            UiEvent::RequestToolApproval { tool_id } => {
+                // requires_tool_approval() is always false for the GPUI
+                warn!("UI: unexpected approval request for tool_id: {}", tool_id);
+            }
+            UiEvent::ToolProgress { tool_id, fraction } => {
+                // Progress bars are only drawn by the terminal UI
+                trace!("UI: ToolProgress {tool_id} {fraction}");
+            }
+            UiEvent::MessageUsage {
+                prompt_tokens,
+                completion_tokens,
+                ..
+            } => {
+                // Session totals are shown from the session metadata instead
+                trace!("UI: MessageUsage {prompt_tokens} in, {completion_tokens} out");
+            }
+            UiEvent::RetryAttempt { attempt, max } => {
+                // Retries are not surfaced in the GPUI; see notify_retry_attempt()
+                trace!("UI: RetryAttempt {attempt}/{max}");
+            }
+            UiEvent::Phase { label } => {
+                // Tool progress is shown on the tool cards in the GPUI
+                trace!("UI: Phase {label:?}");
+            }
             UiEvent::StartReasoningSummaryItem => {
                 self.update_last_message(cx, |message, cx| {
                     message.start_reasoning_summary_item(cx);
@@ -1640,6 +1667,9 @@ impl Gpui {
                 }
             }
 
+            BackendResponse::ModelsListed { .. } => {
+                // The model selector loads the configuration directly
+            }
             BackendResponse::SandboxPolicyChanged { session_id, policy } => {
                 let current_session_id = self.current_session_id.lock().unwrap().clone();
                 if current_session_id.as_deref() == Some(session_id.as_str()) {
@@ -1668,6 +1698,10 @@ impl Gpui {
                 // No additional UI update needed here
             }
 
+            BackendResponse::PlaybackStatusChanged { .. } => {
+                // Playback controls are only offered by the terminal UI
+            }
+
             // Session branching responses
             BackendResponse::MessageEditReady {
                 session_id,
@@ -1911,6 +1945,14 @@ impl UserInterface for Gpui {
         // See notify_rate_limit()
     }
 
+    fn notify_retry_attempt(&self, _attempt: u32, _max: u32) {
+        // See notify_rate_limit()
+    }
+
+    fn requires_tool_approval(&self, _tool_name: &str) -> bool {
+        false
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -8,7 +8,8 @@ pub use streaming::DisplayFragment;
 use thiserror::Error;
 pub use ui_events::UiEvent;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ToolStatus {
     Pending, // Default status when a tool appears in the stream
     Running, // Tool is currently being executed
@@ -39,6 +40,12 @@ pub trait UserInterface: Send + Sync {
     /// Clear rate limit notification
     fn clear_rate_limit(&self);
 
+    /// Notify the UI that a failed request is being retried
+    fn notify_retry_attempt(&self, attempt: u32, max: u32);
+
+    /// Whether calls to this tool must be approved by the user before running
+    fn requires_tool_approval(&self, tool_name: &str) -> bool;
+
     /// Downcast to Any for accessing concrete type methods
     #[allow(dead_code)]
     fn as_any(&self) -> &dyn std::any::Any;
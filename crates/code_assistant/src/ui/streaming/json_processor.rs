@@ -134,6 +134,11 @@ impl StreamProcessorTrait for JsonStreamProcessor {
                 Ok(())
             }
 
+            StreamingChunk::RetryAttempt { attempt, max } => {
+                self.ui.notify_retry_attempt(*attempt, *max);
+                Ok(())
+            }
+
             StreamingChunk::InputJson {
                 content,
                 tool_name,
@@ -113,6 +113,14 @@ impl UserInterface for TestUI {
         // Test implementation does nothing with rate limit clearing
     }
 
+    fn notify_retry_attempt(&self, _attempt: u32, _max: u32) {
+        // Test implementation does nothing with retry notifications
+    }
+
+    fn requires_tool_approval(&self, _tool_name: &str) -> bool {
+        false
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
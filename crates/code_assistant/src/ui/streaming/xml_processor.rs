@@ -100,6 +100,11 @@ impl StreamProcessorTrait for XmlStreamProcessor {
                 Ok(())
             }
 
+            StreamingChunk::RetryAttempt { attempt, max } => {
+                self.ui.notify_retry_attempt(*attempt, *max);
+                Ok(())
+            }
+
             // For native JSON input, handle based on tool information
             StreamingChunk::InputJson {
                 content,
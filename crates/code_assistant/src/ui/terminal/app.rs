@@ -1,33 +1,318 @@
 use crate::app::AgentRunConfig;
 use crate::config;
-use crate::persistence::FileSessionPersistence;
+use crate::persistence::{DraftAttachment, DraftStorage, FileSessionPersistence};
 use crate::session::manager::SessionManager;
 use crate::session::SessionConfig;
 use crate::ui::backend::{
     handle_backend_events, BackendEvent, BackendResponse, BackendRuntimeOptions,
 };
 use crate::ui::terminal::{
+    command_palette::{CommandPalette, PaletteAction},
+    composer::StatusBarInfo,
+    config::TerminalConfig,
+    copy::copy_to_clipboard,
+    diff_pane,
+    export::default_export_path,
     input::{InputManager, KeyEventResult},
+    model_picker::{ModelPicker, PickerAction},
+    path_links::{editor_command, preferred_editor, resolve_path, PathRef},
     renderer::ProductionTerminalRenderer,
-    state::AppState,
+    state::{AppState, PendingToolApproval},
+    theme,
+    tool_renderers::ToolDetail,
+    transcript_writer::TranscriptWriter,
     tui,
     ui::TerminalUI,
+    viewer,
 };
 use crate::ui::UserInterface;
 use anyhow::Result;
+use llm::recording::{PlaybackControl, PlaybackStatus};
 
 use crossterm::cursor::MoveTo;
-use crossterm::event::{Event, EventStream};
+use crossterm::event::{Event, EventStream, KeyCode, KeyModifiers};
 use futures::StreamExt;
+use ratatui::text::Line;
+use std::path::{Path, PathBuf};
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc,
 };
 use tokio::sync::Mutex;
-use tokio::time::Duration;
-use tracing::debug;
+use tokio::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Window in which a second Escape confirms cancelling the running turn.
+const CANCEL_CONFIRM_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Shown after Ctrl+C cancels a turn, while a second press would quit.
+const QUIT_HINT: &str = "Cancellation requested... Press Ctrl+C again to quit";
+
+/// Forward an event to the backend. A failed send means the backend task
+/// has stopped, which the renderer then reports as a persistent error.
+async fn send_to_backend(
+    backend_event_tx: &async_channel::Sender<BackendEvent>,
+    renderer: &Arc<Mutex<ProductionTerminalRenderer>>,
+    event: BackendEvent,
+) -> bool {
+    match backend_event_tx.send(event).await {
+        Ok(()) => true,
+        Err(e) => {
+            warn!("Backend channel closed: {e}");
+            renderer.lock().await.mark_backend_disconnected();
+            false
+        }
+    }
+}
+
+/// Approve or skip the tool call waiting for the user.
+async fn resolve_tool_approval(
+    app_state: &Arc<Mutex<AppState>>,
+    backend_event_tx: &async_channel::Sender<BackendEvent>,
+    renderer: &Arc<Mutex<ProductionTerminalRenderer>>,
+    approved: bool,
+) {
+    let Some(PendingToolApproval {
+        session_id,
+        tool_id,
+    }) = app_state.lock().await.pending_tool_approval.take()
+    else {
+        return;
+    };
+
+    renderer
+        .lock()
+        .await
+        .set_tool_awaiting_approval(&tool_id, false);
+    let event = if approved {
+        BackendEvent::ApproveTool {
+            session_id,
+            tool_id,
+        }
+    } else {
+        BackendEvent::RejectTool {
+            session_id,
+            tool_id,
+        }
+    };
+    send_to_backend(backend_event_tx, renderer, event).await;
+}
+
+/// Send a message for the current session, queueing it if the agent is busy.
+async fn send_user_message(
+    app_state: &Arc<Mutex<AppState>>,
+    cancel_flag: &Arc<AtomicBool>,
+    backend_event_tx: &async_channel::Sender<BackendEvent>,
+    renderer: &Arc<Mutex<ProductionTerminalRenderer>>,
+    message: String,
+    attachments: Vec<DraftAttachment>,
+) {
+    let (current_session_id, activity_state) = {
+        let state = app_state.lock().await;
+        (
+            state.current_session_id.clone(),
+            state.activity_state.clone(),
+        )
+    };
+
+    let Some(session_id) = current_session_id else {
+        return;
+    };
+
+    let event = match activity_state {
+        Some(crate::session::instance::SessionActivityState::Idle) | None => {
+            cancel_flag.store(false, Ordering::SeqCst);
+            BackendEvent::SendUserMessage {
+                session_id,
+                message,
+                attachments,
+                branch_parent_id: None, // Terminal UI doesn't support branching yet
+            }
+        }
+        _ => {
+            app_state.lock().await.push_queued_message(message.clone());
+            BackendEvent::QueueUserMessage {
+                session_id,
+                message,
+                attachments,
+            }
+        }
+    };
+
+    send_to_backend(backend_event_tx, renderer, event).await;
+}
+
+/// Run the last turn again after a retryable error, unless the agent has
+/// started again in the meantime.
+async fn retry_last_turn(
+    app_state: &Arc<Mutex<AppState>>,
+    cancel_flag: &Arc<AtomicBool>,
+    backend_event_tx: &async_channel::Sender<BackendEvent>,
+    renderer: &Arc<Mutex<ProductionTerminalRenderer>>,
+) {
+    let session_id = {
+        let state = app_state.lock().await;
+        let idle = matches!(
+            state.activity_state,
+            Some(crate::session::instance::SessionActivityState::Idle) | None
+        );
+        state.current_session_id.clone().filter(|_| idle)
+    };
+    renderer.lock().await.clear_error();
+    let Some(session_id) = session_id else {
+        return;
+    };
+
+    cancel_flag.store(false, Ordering::SeqCst);
+    send_to_backend(
+        backend_event_tx,
+        renderer,
+        BackendEvent::RetryLastTurn { session_id },
+    )
+    .await;
+}
+
+/// Steer the running turn with `message`. When the agent is idle, or the
+/// message carries attachments (steering is text only), it is sent like a
+/// regular message instead.
+async fn steer_or_send_message(
+    app_state: &Arc<Mutex<AppState>>,
+    cancel_flag: &Arc<AtomicBool>,
+    backend_event_tx: &async_channel::Sender<BackendEvent>,
+    renderer: &Arc<Mutex<ProductionTerminalRenderer>>,
+    message: String,
+    attachments: Vec<DraftAttachment>,
+) {
+    let steer_session = {
+        let mut state = app_state.lock().await;
+        let busy = !matches!(
+            state.activity_state,
+            Some(crate::session::instance::SessionActivityState::Idle) | None
+        );
+        match state.current_session_id.clone() {
+            Some(session_id) if busy && attachments.is_empty() => {
+                state.push_steering_message(&message);
+                Some(session_id)
+            }
+            _ => None,
+        }
+    };
+
+    match steer_session {
+        Some(session_id) => {
+            send_to_backend(
+                backend_event_tx,
+                renderer,
+                BackendEvent::SteerMessage {
+                    session_id,
+                    message,
+                },
+            )
+            .await;
+        }
+        None => {
+            send_user_message(
+                app_state,
+                cancel_flag,
+                backend_event_tx,
+                renderer,
+                message,
+                attachments,
+            )
+            .await;
+        }
+    }
+}
+
+/// Save the session and the composer draft once changes have been idle for
+/// the auto-save interval. The session is written by the backend task and
+/// the draft on a blocking thread, so drawing isn't held up.
+async fn autosave(
+    app_state: &Arc<Mutex<AppState>>,
+    input_manager: &mut InputManager,
+    draft_storage: Option<&Arc<DraftStorage>>,
+    backend_event_tx: &async_channel::Sender<BackendEvent>,
+) {
+    let Some(interval) = TerminalConfig::global().autosave_interval() else {
+        return;
+    };
+    let session_id = {
+        let mut state = app_state.lock().await;
+        if !state.take_autosave(interval, std::time::Instant::now()) {
+            return;
+        }
+        state.current_session_id.clone()
+    };
+    let Some(session_id) = session_id else {
+        return;
+    };
+
+    debug!("Auto-saving session {}", session_id);
+    let _ = backend_event_tx.try_send(BackendEvent::PersistSession {
+        session_id: session_id.clone(),
+    });
+    if let Some(storage) = draft_storage.cloned() {
+        let (draft, attachments) = input_manager.draft();
+        tokio::task::spawn_blocking(move || {
+            if let Err(e) = storage.save_draft(&session_id, &draft, &attachments) {
+                warn!(
+                    "Failed to auto-save draft for session {}: {}",
+                    session_id, e
+                );
+            }
+        });
+    }
+}
+
+/// Build the welcome banner for the project at `root_path`.
+fn project_welcome_banner(root_path: &Path) -> Vec<Line<'static>> {
+    // Determine if this is a configured (persistent) project
+    let is_configured_project = config::load_projects()
+        .map(|projects| projects.values().any(|p| p.path == root_path))
+        .unwrap_or(false);
+
+    // Shorten path by replacing home directory with ~
+    let display_path = if let Some(home) = dirs::home_dir() {
+        if let Ok(suffix) = root_path.strip_prefix(&home) {
+            format!("~/{}", suffix.display())
+        } else {
+            root_path.display().to_string()
+        }
+    } else {
+        root_path.display().to_string()
+    };
+
+    super::welcome_banner::welcome_banner_lines(&display_path, !is_configured_project)
+}
+
+/// Open `path_ref` in the user's editor, suspending the TUI until the
+/// editor exits. Only failing to take the terminal back is fatal.
+fn open_in_editor(
+    tui: &mut tui::Tui,
+    event_stream: &mut EventStream,
+    path_ref: &PathRef,
+    root_path: &Path,
+) -> Result<Result<String, String>> {
+    let path = resolve_path(&path_ref.path, root_path);
+    let editor = preferred_editor();
+    let Some((program, args)) = editor_command(&editor, &path, path_ref.line) else {
+        return Ok(Err(format!("Cannot run editor `{editor}`")));
+    };
+    // Dropping the stream stops its reader thread, which would otherwise race
+    // the editor for keystrokes; the replacement only reads once polled
+    *event_stream = EventStream::new();
+    let status = tui.run_external(|| {
+        tokio::task::block_in_place(|| std::process::Command::new(&program).args(&args).status())
+    })?;
+    Ok(match status {
+        Ok(status) if status.success() => Ok(format!("Opened {}", path.display())),
+        Ok(status) => Err(format!("{program} exited with {status}")),
+        Err(e) => Err(format!("Failed to start {program}: {e}")),
+    })
+}
 
 /// Main event loop for handling terminal events
+#[allow(clippy::too_many_arguments)]
 async fn event_loop(
     mut input_manager: InputManager,
     renderer: Arc<Mutex<ProductionTerminalRenderer>>,
@@ -36,9 +321,27 @@ async fn event_loop(
     backend_event_tx: async_channel::Sender<BackendEvent>,
     mut tui: tui::Tui,
     mut redraw_rx: tokio::sync::watch::Receiver<()>,
+    root_path: PathBuf,
 ) -> Result<()> {
     let mut event_stream = EventStream::new();
     let mut needs_redraw = true; // Draw initial frame
+    if TerminalConfig::global().highlight_paths {
+        tui.set_path_link_root(root_path.clone());
+    }
+
+    // When an Escape asked to confirm cancelling the turn; a second Escape
+    // within CANCEL_CONFIRM_TIMEOUT confirms it
+    let mut cancel_armed_at: Option<Instant> = None;
+    let draft_storage =
+        TerminalConfig::global().autosave_interval().and_then(
+            |_| match DraftStorage::in_config_dir() {
+                Ok(storage) => Some(Arc::new(storage)),
+                Err(e) => {
+                    warn!("Drafts won't be auto-saved: {e}");
+                    None
+                }
+            },
+        );
 
     loop {
         // === PHASE 1: Draw if needed ===
@@ -54,22 +357,66 @@ async fn event_loop(
                     renderer_guard.clear_info();
                 }
 
+                renderer_guard.set_popup_text(
+                    state
+                        .model_picker
+                        .as_ref()
+                        .map(ModelPicker::to_status_text)
+                        .or_else(|| {
+                            state
+                                .command_palette
+                                .as_ref()
+                                .map(CommandPalette::to_status_text)
+                        })
+                        .or_else(|| input_manager.path_completion_text()),
+                );
+
+                renderer_guard.set_queued_messages(state.queued_messages.clone());
+                renderer_guard.set_steering_message(state.steering_message.clone());
+
+                renderer_guard.set_status_bar(StatusBarInfo {
+                    model: state.current_model.clone(),
+                    session_name: state.current_session_name().map(str::to_string),
+                    sandbox_policy: state.current_sandbox_policy.clone(),
+                    tool_syntax: state.tool_syntax,
+                    playback: state.playback,
+                    held_lines: None,
+                    usage: state
+                        .session_usage
+                        .filter(|_| TerminalConfig::global().show_usage),
+                });
+
                 if state.plan_dirty {
                     renderer_guard.set_plan_state(state.plan.clone());
                     state.plan_dirty = false;
                 }
                 renderer_guard.set_plan_expanded(state.plan_expanded);
                 renderer_guard.set_overlay_active(state.is_overlay_active());
+                renderer_guard.set_following(state.following);
+                renderer_guard.set_focused(state.focused);
+
+                let notify_turn = state.take_turn_notification(std::time::Instant::now());
 
                 drop(state); // Release the lock before rendering
 
+                if notify_turn {
+                    if let Err(err) =
+                        tui::notify_turn_complete(TerminalConfig::global().turn_notification)
+                    {
+                        warn!("Failed to send turn notification: {err}");
+                    }
+                }
+
                 let screen_size = tui.size()?;
 
+                // Drop the rate limit countdown once it has run out
+                renderer_guard.expire_rate_limit_spinner();
+
                 // Prepare renderer state (streaming tick, flush finalized messages)
                 renderer_guard.prepare(screen_size.width, screen_size.height);
 
                 // Drain pending history lines and insert them into scrollback
-                let pending_lines = renderer_guard.drain_pending_history_lines();
+                let pending_lines = renderer_guard.drain_pending_history_rows();
                 if !pending_lines.is_empty() {
                     tui.insert_history_lines(pending_lines);
                 }
@@ -94,6 +441,15 @@ async fn event_loop(
                 Duration::from_secs(86400)
             }
         };
+        let autosave_delay = match TerminalConfig::global().autosave_interval() {
+            Some(interval) => app_state
+                .lock()
+                .await
+                .autosave_delay(interval, std::time::Instant::now()),
+            None => None,
+        };
+
+        let quit_confirm_delay = input_manager.quit_confirm_remaining(std::time::Instant::now());
 
         // === PHASE 3: Wait for any wake source ===
         tokio::select! {
@@ -101,17 +457,140 @@ async fn event_loop(
                 match maybe_event {
                     Some(Ok(event)) => match event {
                         Event::Key(key_event) => {
-                            let key_result = input_manager.handle_key_event(key_event);
+                            // The model picker and command palette are modal: keys go to
+                            // them while they are open
+                            let (picker_action, palette_action, plan_expanded, awaiting_approval) = {
+                                let mut state = app_state.lock().await;
+                                let action = state
+                                    .model_picker
+                                    .as_mut()
+                                    .map(|picker| picker.handle_key(key_event));
+                                let palette_action = if action.is_none() {
+                                    state
+                                        .command_palette
+                                        .as_mut()
+                                        .map(|palette| palette.handle_key(key_event))
+                                } else {
+                                    None
+                                };
+                                (
+                                    action,
+                                    palette_action,
+                                    state.plan_expanded,
+                                    state.pending_tool_approval.is_some(),
+                                )
+                            };
+                            // Enter approves and Escape skips a gated tool call
+                            let approval = match key_event.code {
+                                KeyCode::Enter if key_event.modifiers == KeyModifiers::NONE => {
+                                    Some(true)
+                                }
+                                KeyCode::Esc => Some(false),
+                                _ => None,
+                            }
+                            .filter(|_| awaiting_approval);
+                            // Enter on an empty composer retries after a retryable error
+                            let retry = key_event.code == KeyCode::Enter
+                                && key_event.modifiers == KeyModifiers::NONE
+                                && input_manager.textarea.is_empty()
+                                && renderer.lock().await.has_retryable_error();
+                            // PageUp/PageDown scroll the expanded plan
+                            let plan_scroll = match key_event.code {
+                                KeyCode::PageUp => Some(-1),
+                                KeyCode::PageDown => Some(1),
+                                _ => None,
+                            }
+                            .filter(|_| plan_expanded);
+                            let key_result = match (picker_action, palette_action) {
+                                (Some(PickerAction::Select(model_name)), _) => {
+                                    app_state.lock().await.close_model_picker();
+                                    KeyEventResult::SwitchModel(model_name)
+                                }
+                                (Some(PickerAction::Cancel), _) => {
+                                    app_state.lock().await.close_model_picker();
+                                    KeyEventResult::Continue
+                                }
+                                (Some(PickerAction::None), _) => KeyEventResult::Continue,
+                                (None, Some(PaletteAction::Run(result))) => {
+                                    app_state.lock().await.close_command_palette();
+                                    result
+                                }
+                                (None, Some(PaletteAction::Cancel)) => {
+                                    app_state.lock().await.close_command_palette();
+                                    KeyEventResult::Continue
+                                }
+                                (None, Some(PaletteAction::None)) => KeyEventResult::Continue,
+                                (None, None) if approval.is_some() => {
+                                    resolve_tool_approval(
+                                        &app_state,
+                                        &backend_event_tx,
+                                        &renderer,
+                                        approval == Some(true),
+                                    )
+                                    .await;
+                                    KeyEventResult::Continue
+                                }
+                                (None, None) if retry => {
+                                    retry_last_turn(
+                                        &app_state,
+                                        &cancel_flag,
+                                        &backend_event_tx,
+                                        &renderer,
+                                    )
+                                    .await;
+                                    KeyEventResult::Continue
+                                }
+                                (None, None) => match plan_scroll {
+                                    Some(delta) => {
+                                        renderer.lock().await.scroll_plan(delta);
+                                        KeyEventResult::Continue
+                                    }
+                                    None => {
+                                        let turn_active = {
+                                            let state = app_state.lock().await;
+                                            state.current_session_id.is_some()
+                                                && !matches!(
+                                                    state.activity_state,
+                                                    Some(crate::session::instance::SessionActivityState::Idle)
+                                                        | None
+                                                )
+                                        };
+                                        input_manager.set_turn_active(turn_active);
+                                        // Only edits to the draft need saving, not
+                                        // cursor movement or scrolling
+                                        let draft = input_manager.draft_snapshot();
+                                        let result = input_manager.handle_key_event(key_event);
+                                        if input_manager.draft_snapshot() != draft {
+                                            app_state.lock().await.note_change(std::time::Instant::now());
+                                        }
+                                        result
+                                    }
+                                },
+                            };
+
+                            if !matches!(key_result, KeyEventResult::RerunLastCommand) {
+                                app_state.lock().await.pending_rerun = None;
+                            }
 
                             match key_result {
                                 KeyEventResult::Quit => {
                                     break;
                                 }
+                                KeyEventResult::CancelTurn => {
+                                    // Ctrl+C needs no confirmation; a pending Esc one is moot
+                                    cancel_armed_at = None;
+                                    cancel_flag.store(true, Ordering::SeqCst);
+                                    debug!("Ctrl+C pressed - cancellation flag set");
+                                    app_state
+                                        .lock()
+                                        .await
+                                        .set_info_message(Some(QUIT_HINT.to_string()));
+                                }
                                 KeyEventResult::Escape => {
-                                    // Check if there's an error to dismiss first
-                                    let has_error = {
+                                    // Check if there's an error or warning to dismiss first
+                                    let (has_error, has_warning) = {
                                         let renderer_guard = renderer.lock().await;
-                                        renderer_guard.has_error()
+                                        (renderer_guard.has_error(), renderer_guard.has_warning())
                                     };
 
                                     let has_info = {
@@ -119,11 +598,20 @@ async fn event_loop(
                                         state.info_message.is_some()
                                     };
 
+                                    // A second Escape shortly after the first confirms
+                                    // the cancellation, even though the prompt is an info
+                                    let cancel_confirmed = cancel_armed_at
+                                        .take()
+                                        .is_some_and(|armed| armed.elapsed() < CANCEL_CONFIRM_TIMEOUT);
+
                                     if has_error {
                                         // Clear the error
                                         let mut renderer_guard = renderer.lock().await;
                                         renderer_guard.clear_error();
-                                    } else if has_info {
+                                    } else if has_warning {
+                                        let mut renderer_guard = renderer.lock().await;
+                                        renderer_guard.clear_warning();
+                                    } else if has_info && !cancel_confirmed {
                                         // Clear the info message
                                         let mut state = app_state.lock().await;
                                         state.set_info_message(None);
@@ -138,21 +626,28 @@ async fn event_loop(
                                         };
 
                                         if let Some(session_id) = current_session_id {
-                                            cancel_flag.store(true, Ordering::SeqCst);
-                                            debug!(
-                                                "Escape pressed - cancellation flag set for session {} (state: {:?})",
-                                                session_id, activity_state
-                                            );
-
-                                            let mut state = app_state.lock().await;
-                                            if matches!(
+                                            let is_idle = matches!(
                                                 activity_state,
                                                 Some(crate::session::instance::SessionActivityState::Idle)
-                                            ) {
+                                            );
+                                            let mut state = app_state.lock().await;
+                                            if is_idle {
                                                 state.set_info_message(Some(
                                                     "No agent is currently running.".to_string(),
                                                 ));
+                                            } else if TerminalConfig::global().confirm_cancel
+                                                && !cancel_confirmed
+                                            {
+                                                cancel_armed_at = Some(Instant::now());
+                                                state.set_info_message(Some(
+                                                    "Press Esc again to cancel".to_string(),
+                                                ));
                                             } else {
+                                                cancel_flag.store(true, Ordering::SeqCst);
+                                                debug!(
+                                                    "Escape pressed - cancellation flag set for session {} (state: {:?})",
+                                                    session_id, activity_state
+                                                );
                                                 state.set_info_message(Some(
                                                     "Cancellation requested...".to_string(),
                                                 ));
@@ -165,36 +660,70 @@ async fn event_loop(
                                     message,
                                     attachments,
                                 } => {
-                                    let current_session_id = {
-                                        let state = app_state.lock().await;
-                                        state.current_session_id.clone()
-                                    };
-
-                                    if let Some(session_id) = current_session_id {
-                                        let activity_state = {
-                                            let state = app_state.lock().await;
-                                            state.activity_state.clone()
-                                        };
-
-                                        let event = match activity_state {
-                                            Some(crate::session::instance::SessionActivityState::Idle)
-                                            | None => {
-                                                cancel_flag.store(false, Ordering::SeqCst);
-                                                BackendEvent::SendUserMessage {
-                                                    session_id,
-                                                    message,
-                                                    attachments,
-                                                    branch_parent_id: None, // Terminal UI doesn't support branching yet
-                                                }
-                                            }
-                                            _ => BackendEvent::QueueUserMessage {
-                                                session_id,
-                                                message,
-                                                attachments,
-                                            },
-                                        };
-
-                                        let _ = backend_event_tx.send(event).await;
+                                    app_state
+                                        .lock()
+                                        .await
+                                        .note_message_sent(TerminalConfig::global().auto_scroll);
+                                    send_user_message(
+                                        &app_state,
+                                        &cancel_flag,
+                                        &backend_event_tx,
+                                        &renderer,
+                                        message,
+                                        attachments,
+                                    )
+                                    .await;
+                                }
+                                KeyEventResult::SteerMessage {
+                                    message,
+                                    attachments,
+                                } => {
+                                    steer_or_send_message(
+                                        &app_state,
+                                        &cancel_flag,
+                                        &backend_event_tx,
+                                        &renderer,
+                                        message,
+                                        attachments,
+                                    )
+                                    .await;
+                                }
+                                KeyEventResult::RerunLastCommand => {
+                                    let command = renderer.lock().await.last_executed_command();
+                                    let mut state = app_state.lock().await;
+                                    match command {
+                                        None => {
+                                            state.set_info_message(Some(
+                                                "No command to re-run".to_string(),
+                                            ));
+                                        }
+                                        Some(command)
+                                            if TerminalConfig::global().confirm_rerun
+                                                && state.pending_rerun.as_ref() != Some(&command) =>
+                                        {
+                                            state.set_info_message(Some(format!(
+                                                "Press Ctrl+R again to re-run: `{command}`"
+                                            )));
+                                            state.pending_rerun = Some(command);
+                                        }
+                                        Some(command) => {
+                                            state.pending_rerun = None;
+                                            state.set_info_message(Some(format!(
+                                                "Re-running: `{command}`"
+                                            )));
+                                            drop(state);
+                                            send_user_message(
+                                                &app_state,
+                                                &cancel_flag,
+                                                &backend_event_tx,
+                                                &renderer,
+                                                format!(
+                                                    "Run this command again and report the result:\n\n```\n{command}\n```"
+                                                ),
+                                                Vec::new(),
+                                            )
+                                            .await;
+                                        }
                                     }
                                 }
                                 KeyEventResult::Continue => {
@@ -218,14 +747,14 @@ async fn event_loop(
                                             model_name: model_name.clone(),
                                         };
 
-                                        let _ = backend_event_tx.send(event).await;
-
-                                        // Update state
-                                        let mut state = app_state.lock().await;
-                                        state.update_current_model(Some(model_name.clone()));
-                                        state.set_info_message(Some(format!(
-                                            "Switched to model: {model_name}",
-                                        )));
+                                        if send_to_backend(&backend_event_tx, &renderer, event).await
+                                        {
+                                            let mut state = app_state.lock().await;
+                                            state.update_current_model(Some(model_name.clone()));
+                                            state.set_info_message(Some(format!(
+                                                "Switched to model: {model_name}",
+                                            )));
+                                        }
                                     } else {
                                         let mut state = app_state.lock().await;
                                         state.set_info_message(Some(
@@ -233,6 +762,26 @@ async fn event_loop(
                                         ));
                                     }
                                 }
+                                KeyEventResult::OpenCommandPalette => {
+                                    app_state.lock().await.open_command_palette();
+                                }
+                                KeyEventResult::StopFollowing => {
+                                    app_state
+                                        .lock()
+                                        .await
+                                        .stop_following(TerminalConfig::global().auto_scroll);
+                                }
+                                KeyEventResult::JumpToLatest => {
+                                    app_state.lock().await.resume_following();
+                                }
+                                KeyEventResult::OpenModelPicker => {
+                                    send_to_backend(
+                                        &backend_event_tx,
+                                        &renderer,
+                                        BackendEvent::ListModels,
+                                    )
+                                    .await;
+                                }
                                 KeyEventResult::ShowCurrentModel => {
                                     let current_model = {
                                         let state = app_state.lock().await;
@@ -263,19 +812,210 @@ async fn event_loop(
                                     renderer_guard.set_plan_expanded(expanded);
                                     renderer_guard.set_overlay_active(overlay_active);
                                 }
+                                KeyEventResult::ToggleThinking => {
+                                    let toggled =
+                                        renderer.lock().await.toggle_last_thinking_block();
+                                    if !toggled {
+                                        let mut state = app_state.lock().await;
+                                        state.set_info_message(Some(
+                                            "No thinking block to expand".to_string(),
+                                        ));
+                                    }
+                                }
+                                KeyEventResult::FocusTool { forward } => {
+                                    if !renderer.lock().await.focus_tool(forward) {
+                                        let mut state = app_state.lock().await;
+                                        state.set_info_message(Some(
+                                            "No tools in the current response".to_string(),
+                                        ));
+                                    }
+                                }
+                                KeyEventResult::ToggleFocusedTool => {
+                                    if !renderer.lock().await.toggle_focused_tool() {
+                                        let mut state = app_state.lock().await;
+                                        state.set_info_message(Some(
+                                            "Highlight a tool with Alt+Up/Alt+Down first"
+                                                .to_string(),
+                                        ));
+                                    }
+                                }
+                                KeyEventResult::InspectTool => {
+                                    if !renderer.lock().await.inspect_tool() {
+                                        let mut state = app_state.lock().await;
+                                        state.set_info_message(Some(
+                                            "No tool to inspect".to_string(),
+                                        ));
+                                    }
+                                }
+                                KeyEventResult::ToggleToolDetail => {
+                                    let detail = renderer.lock().await.toggle_tool_detail();
+                                    let mut state = app_state.lock().await;
+                                    let shown = match detail {
+                                        ToolDetail::Compact => "compact",
+                                        ToolDetail::Full => "in full",
+                                    };
+                                    state.set_info_message(Some(format!(
+                                        "Tools shown {shown} (scrollback above is unchanged)"
+                                    )));
+                                }
+                                KeyEventResult::ToggleLineNumbers => {
+                                    let shown = renderer.lock().await.toggle_line_numbers();
+                                    let state = if shown { "on" } else { "off" };
+                                    app_state
+                                        .lock()
+                                        .await
+                                        .set_info_message(Some(format!("Line numbers {state}")));
+                                }
+                                KeyEventResult::ToggleDiffPane { path } => {
+                                    let shown = renderer.lock().await.toggle_diff_pane(path);
+                                    if shown && tui.size()?.width < diff_pane::MIN_SPLIT_WIDTH {
+                                        app_state.lock().await.set_info_message(Some(format!(
+                                            "Diff pane needs a terminal at least {} columns wide",
+                                            diff_pane::MIN_SPLIT_WIDTH
+                                        )));
+                                    }
+                                }
+                                KeyEventResult::OpenLastPath => {
+                                    let path_ref = renderer.lock().await.last_path_ref();
+                                    let result = match path_ref {
+                                        Some(path_ref) => {
+                                            open_in_editor(&mut tui, &mut event_stream, &path_ref, &root_path)?
+                                        }
+                                        None => Err("No file path mentioned yet".to_string()),
+                                    };
+                                    match result {
+                                        Ok(message) => {
+                                            app_state.lock().await.set_info_message(Some(message))
+                                        }
+                                        Err(error) => renderer.lock().await.set_error(error),
+                                    }
+                                }
+                                KeyEventResult::ExportTranscript { path, format } => {
+                                    let path = path.unwrap_or_else(|| default_export_path(format));
+                                    let path = std::path::absolute(&path).unwrap_or(path);
+                                    let rendered = renderer.lock().await.export_transcript(format);
+                                    let result = match rendered {
+                                        Ok(content) => tokio::fs::write(&path, content)
+                                            .await
+                                            .map_err(anyhow::Error::from),
+                                        Err(e) => Err(e),
+                                    };
+                                    match result {
+                                        Ok(()) => {
+                                            let mut state = app_state.lock().await;
+                                            state.set_info_message(Some(format!(
+                                                "Transcript exported to {}",
+                                                path.display()
+                                            )));
+                                        }
+                                        Err(e) => {
+                                            renderer.lock().await.set_error(format!(
+                                                "Failed to export transcript to {}: {e}",
+                                                path.display()
+                                            ));
+                                        }
+                                    }
+                                }
+                                KeyEventResult::ClearScreen { new_session } => {
+                                    let is_idle = matches!(
+                                        app_state.lock().await.activity_state,
+                                        Some(crate::session::instance::SessionActivityState::Idle)
+                                            | None
+                                    );
+                                    if !is_idle {
+                                        app_state.lock().await.set_info_message(Some(
+                                            "Can't clear while the agent is running (Esc to cancel)"
+                                                .to_string(),
+                                        ));
+                                    } else {
+                                        renderer.lock().await.clear_all_messages();
+                                        if let Err(e) = tui.clear_scrollback() {
+                                            warn!("Failed to clear scrollback: {e}");
+                                        }
+                                        let message = if !new_session {
+                                            Some("Cleared")
+                                        } else if send_to_backend(
+                                            &backend_event_tx,
+                                            &renderer,
+                                            BackendEvent::CreateNewSession {
+                                                name: None,
+                                                initial_project: None,
+                                            },
+                                        )
+                                        .await
+                                        {
+                                            app_state.lock().await.awaiting_new_session = true;
+                                            Some("Cleared. Starting a new session...")
+                                        } else {
+                                            None
+                                        };
+                                        if let Some(message) = message {
+                                            app_state
+                                                .lock()
+                                                .await
+                                                .set_info_message(Some(message.to_string()));
+                                        }
+                                    }
+                                }
+                                KeyEventResult::Copy { selector } => {
+                                    let text = renderer.lock().await.copy_text(selector);
+                                    match text.and_then(|text| {
+                                        copy_to_clipboard(&text)
+                                            .map(|()| text.chars().count())
+                                            .map_err(|e| format!("Failed to copy: {e}"))
+                                    }) {
+                                        Ok(chars) => {
+                                            app_state.lock().await.set_info_message(Some(
+                                                format!("Copied {chars} characters"),
+                                            ));
+                                        }
+                                        Err(error) => renderer.lock().await.set_error(error),
+                                    }
+                                }
+                                KeyEventResult::Playback(action) => {
+                                    send_to_backend(
+                                        &backend_event_tx,
+                                        &renderer,
+                                        BackendEvent::PlaybackControl { action },
+                                    )
+                                    .await;
+                                }
+                                KeyEventResult::ShowWelcome => {
+                                    renderer
+                                        .lock()
+                                        .await
+                                        .add_styled_history_lines(project_welcome_banner(&root_path));
+                                }
+                                KeyEventResult::ToggleNoColor => {
+                                    let enabled = theme::toggle_monochrome();
+                                    let mut state = app_state.lock().await;
+                                    state.set_info_message(Some(if enabled {
+                                        "No-color mode enabled".to_string()
+                                    } else {
+                                        "No-color mode disabled".to_string()
+                                    }));
+                                }
                             }
                             needs_redraw = true;
                         }
                         Event::Paste(pasted) => {
-                            // Many terminals convert newlines to \r when pasting;
-                            // normalize before processing.
-                            let pasted = pasted.replace('\r', "\n");
-                            input_manager.handle_paste(pasted);
+                            app_state.lock().await.note_change(std::time::Instant::now());
+                            if let Err(error) = input_manager.handle_paste(pasted) {
+                                renderer.lock().await.set_error(error);
+                            }
                             needs_redraw = true;
                         }
                         Event::Resize(_, _) => {
                             needs_redraw = true;
                         }
+                        Event::FocusGained => {
+                            app_state.lock().await.focused = true;
+                            needs_redraw = true;
+                        }
+                        Event::FocusLost => {
+                            app_state.lock().await.focused = false;
+                            needs_redraw = true;
+                        }
                         _ => {}
                     },
                     Some(Err(e)) => {
@@ -295,6 +1035,26 @@ async fn event_loop(
             _ = tokio::time::sleep(animation_delay) => {
                 needs_redraw = true;
             }
+
+            _ = tokio::time::sleep(quit_confirm_delay.unwrap_or_default()), if quit_confirm_delay.is_some() => {
+                // A later Ctrl+C cancels again, so stop offering to quit
+                input_manager.expire_quit_confirm();
+                let mut state = app_state.lock().await;
+                if state.info_message.as_deref() == Some(QUIT_HINT) {
+                    state.set_info_message(Some("Cancellation requested...".to_string()));
+                    needs_redraw = true;
+                }
+            }
+
+            _ = tokio::time::sleep(autosave_delay.unwrap_or_default()), if autosave_delay.is_some() => {
+                autosave(
+                    &app_state,
+                    &mut input_manager,
+                    draft_storage.as_ref(),
+                    &backend_event_tx,
+                )
+                .await;
+            }
         }
     }
 
@@ -314,6 +1074,11 @@ impl TerminalTuiApp {
     }
 
     pub async fn run(&self, config: &AgentRunConfig) -> Result<()> {
+        // A viewed session needs neither a backend nor an agent
+        if let Some(session_id) = &config.view {
+            return viewer::run(session_id).await;
+        }
+
         let app_state = Arc::new(Mutex::new(AppState::new()));
         let root_path = config.path.canonicalize()?;
 
@@ -351,6 +1116,7 @@ impl TerminalTuiApp {
 
         // Setup backend communication channels
         let (backend_event_tx, backend_event_rx) = async_channel::unbounded::<BackendEvent>();
+        terminal_ui.set_backend_event_sender(backend_event_tx.clone());
         let (backend_response_tx, backend_response_rx) =
             async_channel::unbounded::<BackendResponse>();
 
@@ -361,6 +1127,7 @@ impl TerminalTuiApp {
                 record_path: config.record.clone(),
                 playback_path: config.playback.clone(),
                 fast_playback: config.fast_playback,
+                playback_control: PlaybackControl::default(),
             });
             let ui = ui.clone();
 
@@ -439,6 +1206,12 @@ impl TerminalTuiApp {
         {
             let mut state = app_state.lock().await;
             state.current_session_id = Some(session_id.clone());
+            state.update_current_model(Some(config.model.clone()));
+            state.update_sandbox_policy(Some(config.sandbox_policy.clone()));
+            state.update_tool_syntax(Some(config.tool_syntax));
+            if config.playback.is_some() {
+                state.playback = Some(PlaybackStatus::default());
+            }
         }
 
         // Kick off a session list refresh (optional but useful)
@@ -457,7 +1230,9 @@ impl TerminalTuiApp {
         // Spawn a background task to translate backend responses into UiEvents
         {
             let ui_clone = ui.clone();
+            let terminal_ui_clone = terminal_ui.clone();
             let app_state_clone = app_state.clone();
+            let backend_event_tx = backend_event_tx.clone();
             tokio::spawn(async move {
                 while let Ok(resp) = backend_response_rx.recv().await {
                     match resp {
@@ -491,7 +1266,23 @@ impl TerminalTuiApp {
                                 .send_event(crate::ui::UiEvent::DisplayError { message })
                                 .await;
                         }
-                        BackendResponse::SessionCreated { .. } => {}
+                        // Switch only to the session our own `/clear new` asked
+                        // for; the session's messages (SetMessages) make it current
+                        BackendResponse::SessionCreated { session_id } => {
+                            let requested = std::mem::take(
+                                &mut app_state_clone.lock().await.awaiting_new_session,
+                            );
+                            if requested {
+                                let _ = backend_event_tx
+                                    .send(BackendEvent::LoadSession { session_id })
+                                    .await;
+                                let _ = backend_event_tx.send(BackendEvent::ListSessions).await;
+                                app_state_clone
+                                    .lock()
+                                    .await
+                                    .set_info_message(Some("Started a new session".to_string()));
+                            }
+                        }
                         BackendResponse::SessionDeleted { .. } => {}
                         BackendResponse::ModelSwitched {
                             session_id: _,
@@ -505,6 +1296,19 @@ impl TerminalTuiApp {
                             )));
                         }
 
+                        BackendResponse::ModelsListed { models } => {
+                            {
+                                let mut state = app_state_clone.lock().await;
+                                if models.is_empty() {
+                                    state
+                                        .set_info_message(Some("No models configured".to_string()));
+                                } else {
+                                    state.open_model_picker(models);
+                                }
+                            }
+                            terminal_ui_clone.trigger_redraw().await;
+                        }
+
                         BackendResponse::SandboxPolicyChanged {
                             session_id: _,
                             policy,
@@ -517,6 +1321,11 @@ impl TerminalTuiApp {
                             )));
                         }
 
+                        BackendResponse::PlaybackStatusChanged { status } => {
+                            app_state_clone.lock().await.playback = Some(status);
+                            terminal_ui_clone.trigger_redraw().await;
+                        }
+
                         BackendResponse::SubAgentCancelled {
                             session_id: _,
                             tool_id: _,
@@ -539,8 +1348,26 @@ impl TerminalTuiApp {
         std::io::Write::flush(&mut std::io::stdout())?;
 
         // Initialize components
-        let input_manager = InputManager::new();
-        let renderer = ProductionTerminalRenderer::new()?;
+        let mut input_manager = InputManager::new();
+        // A draft left by a previous run (auto-saved or from another UI) moves
+        // into the composer. It is only removed from disk when auto-save will
+        // write it again while it is unsent; otherwise quitting would lose it.
+        if config.continue_task {
+            let restored = DraftStorage::in_config_dir().and_then(|storage| {
+                let draft = storage.load_draft(&session_id)?;
+                if TerminalConfig::global().autosave_interval().is_some() {
+                    storage.clear_draft(&session_id)?;
+                }
+                Ok(draft)
+            });
+            match restored {
+                Ok(Some((draft, attachments))) => input_manager.restore_draft(&draft, attachments),
+                Ok(None) => {}
+                Err(e) => warn!("Failed to restore draft for session {}: {}", session_id, e),
+            }
+        }
+        let mut renderer = ProductionTerminalRenderer::new()?;
+        renderer.set_transcript_writer(TranscriptWriter::for_session(&session_id));
 
         // Initialize the Tui (raw mode, custom terminal, panic hook)
         let tui = tui::init()?;
@@ -553,30 +1380,17 @@ impl TerminalTuiApp {
         // Create redraw notification channel
         let (redraw_tx, redraw_rx) = tokio::sync::watch::channel::<()>(());
         terminal_ui.set_redraw_sender(redraw_tx.clone());
+        input_manager.enable_path_completion(root_path.clone(), redraw_tx.clone());
+        if config.playback.is_some() {
+            input_manager.enable_playback_controls();
+        }
 
         // Display welcome banner with project info
-        {
-            let mut renderer_guard = renderer.lock().await;
-
-            // Determine if this is a configured (persistent) project
-            let is_configured_project = config::load_projects()
-                .map(|projects| projects.values().any(|p| p.path == root_path))
-                .unwrap_or(false);
-
-            // Shorten path by replacing home directory with ~
-            let display_path = if let Some(home) = dirs::home_dir() {
-                if let Ok(suffix) = root_path.strip_prefix(&home) {
-                    format!("~/{}", suffix.display())
-                } else {
-                    root_path.display().to_string()
-                }
-            } else {
-                root_path.display().to_string()
-            };
-
-            let banner_lines =
-                super::welcome_banner::welcome_banner_lines(&display_path, !is_configured_project);
-            renderer_guard.add_styled_history_lines(banner_lines);
+        if TerminalConfig::global().show_welcome_banner {
+            renderer
+                .lock()
+                .await
+                .add_styled_history_lines(project_welcome_banner(&root_path));
         }
 
         // Send initial task if provided
@@ -598,6 +1412,7 @@ impl TerminalTuiApp {
             backend_event_tx,
             tui,
             redraw_rx,
+            root_path,
         ));
 
         // Wait for the event loop to finish (Ctrl+C or event stream end)
@@ -0,0 +1,270 @@
+//! Color capabilities of the terminal, read once from the environment.
+//!
+//! `NO_COLOR` (any non-empty value, see <https://no-color.org>) switches the
+//! UI to monochrome. `COLORTERM` and `TERM` decide how many colors are
+//! available; RGB colors are mapped to the nearest palette entry on
+//! terminals without truecolor support.
+
+use ratatui::style::Color;
+use std::sync::OnceLock;
+
+/// How many colors the terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    /// The 16 named ANSI colors
+    Ansi16,
+    /// The xterm 256-color palette
+    Ansi256,
+    /// 24-bit RGB
+    TrueColor,
+}
+
+/// What the terminal (and the user) allow in terms of color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColorSupport {
+    /// `NO_COLOR` is set: render without colors
+    pub no_color: bool,
+    pub depth: ColorDepth,
+}
+
+impl Default for ColorSupport {
+    fn default() -> Self {
+        Self {
+            no_color: false,
+            depth: ColorDepth::TrueColor,
+        }
+    }
+}
+
+static COLOR_SUPPORT: OnceLock<ColorSupport> = OnceLock::new();
+
+impl ColorSupport {
+    /// Resolve support from the values of `NO_COLOR`, `COLORTERM` and `TERM`.
+    /// An unset `TERM` (e.g. Windows consoles) is assumed to be truecolor.
+    pub fn from_env_values(
+        no_color: Option<&str>,
+        colorterm: Option<&str>,
+        term: Option<&str>,
+    ) -> Self {
+        let no_color = no_color.is_some_and(|value| !value.is_empty());
+        let colorterm = colorterm.unwrap_or_default().to_lowercase();
+        let depth = if colorterm == "truecolor" || colorterm == "24bit" {
+            ColorDepth::TrueColor
+        } else {
+            match term {
+                None | Some("") => ColorDepth::TrueColor,
+                Some(term) if term.contains("direct") || term.contains("truecolor") => {
+                    ColorDepth::TrueColor
+                }
+                Some(term) if term.contains("256color") => ColorDepth::Ansi256,
+                Some(_) => ColorDepth::Ansi16,
+            }
+        };
+        Self { no_color, depth }
+    }
+
+    /// The support detected at startup.
+    pub fn global() -> &'static Self {
+        COLOR_SUPPORT.get_or_init(detect)
+    }
+
+    /// `color` as the terminal can show it.
+    pub fn adapt(&self, color: Color) -> Color {
+        downgrade(color, self.depth)
+    }
+}
+
+/// Read and cache the color support. Call once at startup.
+pub fn init() {
+    COLOR_SUPPORT.get_or_init(detect);
+}
+
+/// [`ColorSupport::adapt`] with the detected support.
+pub fn adapt(color: Color) -> Color {
+    ColorSupport::global().adapt(color)
+}
+
+#[cfg(not(test))]
+fn detect() -> ColorSupport {
+    let var = |name| std::env::var(name).ok();
+    ColorSupport::from_env_values(
+        var("NO_COLOR").as_deref(),
+        var("COLORTERM").as_deref(),
+        var("TERM").as_deref(),
+    )
+}
+
+// Tests must not depend on the environment they run in
+#[cfg(test)]
+fn detect() -> ColorSupport {
+    ColorSupport::default()
+}
+
+/// Map `color` to the nearest color available at `depth`. Named colors are
+/// left alone; they exist at every depth.
+pub fn downgrade(color: Color, depth: ColorDepth) -> Color {
+    match (color, depth) {
+        (_, ColorDepth::TrueColor) => color,
+        (Color::Rgb(r, g, b), ColorDepth::Ansi256) => Color::Indexed(rgb_to_ansi256(r, g, b)),
+        (Color::Rgb(r, g, b), ColorDepth::Ansi16) => rgb_to_ansi16((r, g, b)),
+        (Color::Indexed(index), ColorDepth::Ansi16) => match ANSI_16.get(usize::from(index)) {
+            Some((named, _)) => *named,
+            None => rgb_to_ansi16(ansi256_to_rgb(index)),
+        },
+        _ => color,
+    }
+}
+
+/// The named colors with their xterm default values, in palette order.
+const ANSI_16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Channel values of the 6x6x6 color cube (indices 16-231).
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).unsigned_abs().pow(2);
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+fn rgb_to_ansi16(rgb: (u8, u8, u8)) -> Color {
+    ANSI_16
+        .iter()
+        .min_by_key(|(_, value)| distance(rgb, *value))
+        .map(|(named, _)| *named)
+        .unwrap_or(Color::Reset)
+}
+
+/// Nearest entry of the 256-color palette, from the color cube or the
+/// grayscale ramp (indices 232-255).
+fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let level = |value: u8| -> u8 {
+        CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, level)| value.abs_diff(**level))
+            .map(|(idx, _)| idx as u8)
+            .unwrap_or(0)
+    };
+    let (cr, cg, cb) = (level(r), level(g), level(b));
+    let cube_index = 16 + 36 * cr + 6 * cg + cb;
+    let cube_rgb = (
+        CUBE_LEVELS[usize::from(cr)],
+        CUBE_LEVELS[usize::from(cg)],
+        CUBE_LEVELS[usize::from(cb)],
+    );
+
+    let average = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+    let gray_step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 8 + 10 * gray_step;
+    let gray_index = 232 + gray_step;
+
+    if distance((r, g, b), (gray, gray, gray)) < distance((r, g, b), cube_rgb) {
+        gray_index
+    } else {
+        cube_index
+    }
+}
+
+/// RGB value of a 256-color palette entry (16 and above).
+fn ansi256_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI_16[usize::from(index)].1,
+        16..=231 => {
+            let offset = index - 16;
+            (
+                CUBE_LEVELS[usize::from(offset / 36)],
+                CUBE_LEVELS[usize::from(offset / 6 % 6)],
+                CUBE_LEVELS[usize::from(offset % 6)],
+            )
+        }
+        _ => {
+            let gray = 8 + 10 * (index - 232);
+            (gray, gray, gray)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_depth_and_no_color() {
+        let support =
+            |no_color, colorterm, term| ColorSupport::from_env_values(no_color, colorterm, term);
+        assert_eq!(
+            support(None, Some("truecolor"), Some("xterm-256color")).depth,
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            support(None, Some("24bit"), Some("screen")).depth,
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            support(None, None, Some("xterm-256color")).depth,
+            ColorDepth::Ansi256
+        );
+        assert_eq!(support(None, None, Some("linux")).depth, ColorDepth::Ansi16);
+        assert_eq!(support(None, None, None).depth, ColorDepth::TrueColor);
+
+        assert!(support(Some("1"), None, None).no_color);
+        // An empty NO_COLOR doesn't count
+        assert!(!support(Some(""), None, None).no_color);
+    }
+
+    #[test]
+    fn test_downgrade_to_256_colors() {
+        let to_256 = |r, g, b| downgrade(Color::Rgb(r, g, b), ColorDepth::Ansi256);
+        // Exact cube entries
+        assert_eq!(to_256(255, 0, 0), Color::Indexed(196));
+        assert_eq!(to_256(0, 95, 135), Color::Indexed(24));
+        // Near-grays go to the grayscale ramp
+        assert_eq!(to_256(40, 40, 40), Color::Indexed(235));
+        assert_eq!(to_256(128, 128, 130), Color::Indexed(244));
+        // Named colors are kept
+        assert_eq!(downgrade(Color::Cyan, ColorDepth::Ansi256), Color::Cyan);
+    }
+
+    #[test]
+    fn test_downgrade_to_16_colors() {
+        let to_16 = |r, g, b| downgrade(Color::Rgb(r, g, b), ColorDepth::Ansi16);
+        assert_eq!(to_16(250, 10, 10), Color::LightRed);
+        assert_eq!(to_16(190, 0, 0), Color::Red);
+        assert_eq!(to_16(0, 130, 0), Color::Green);
+        assert_eq!(to_16(100, 140, 255), Color::LightBlue);
+        assert_eq!(to_16(30, 30, 30), Color::Black);
+        assert_eq!(to_16(120, 120, 120), Color::DarkGray);
+        assert_eq!(
+            downgrade(Color::Indexed(9), ColorDepth::Ansi16),
+            Color::LightRed
+        );
+        assert_eq!(
+            downgrade(Color::Indexed(231), ColorDepth::Ansi16),
+            Color::White
+        );
+    }
+
+    #[test]
+    fn test_truecolor_keeps_rgb() {
+        let color = Color::Rgb(12, 34, 56);
+        assert_eq!(downgrade(color, ColorDepth::TrueColor), color);
+        assert_eq!(ColorSupport::default().adapt(color), color);
+    }
+}
@@ -0,0 +1,329 @@
+//! Command palette opened with Ctrl+P: every slash command and key action
+//! in one fuzzy-filtered list.
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::commands::help_text;
+use super::copy::CopySelector;
+use super::export::ExportFormat;
+use super::fuzzy::fuzzy_filter;
+use super::input::KeyEventResult;
+use super::tool_renderers::tools_info;
+
+/// Maximum number of commands listed in the palette at once.
+const MAX_VISIBLE_COMMANDS: usize = 8;
+
+/// An entry in the palette. Running it produces the same result as the
+/// slash command or key binding it stands for.
+#[derive(Debug, Clone, Copy)]
+pub struct Command {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub action: fn() -> KeyEventResult,
+}
+
+/// All palette commands, in display order.
+pub fn registry() -> &'static [Command] {
+    const COMMANDS: &[Command] = &[
+        Command {
+            name: "Switch model",
+            description: "Pick a model (/model)",
+            action: || KeyEventResult::OpenModelPicker,
+        },
+        Command {
+            name: "Show current model",
+            description: "Name the model in use (/current)",
+            action: || KeyEventResult::ShowCurrentModel,
+        },
+        Command {
+            name: "New session",
+            description: "Clear the transcript and start a new session (/clear new)",
+            action: || KeyEventResult::ClearScreen { new_session: true },
+        },
+        Command {
+            name: "Clear transcript",
+            description: "Clear the screen, keep the session (/clear)",
+            action: || KeyEventResult::ClearScreen { new_session: false },
+        },
+        Command {
+            name: "Export transcript",
+            description: "Save the transcript as Markdown (/export)",
+            action: || KeyEventResult::ExportTranscript {
+                path: None,
+                format: ExportFormat::Markdown,
+            },
+        },
+        Command {
+            name: "Copy last message",
+            description: "Copy the latest message to the clipboard (/copy)",
+            action: || KeyEventResult::Copy {
+                selector: CopySelector::Last,
+            },
+        },
+        Command {
+            name: "Copy tool output",
+            description: "Copy the latest tool output to the clipboard (/copy tool)",
+            action: || KeyEventResult::Copy {
+                selector: CopySelector::Tool,
+            },
+        },
+        Command {
+            name: "Toggle plan",
+            description: "Expand or collapse the plan (/plan)",
+            action: || KeyEventResult::TogglePlan,
+        },
+        Command {
+            name: "Toggle thinking",
+            description: "Expand or collapse the latest thinking block (Ctrl+T)",
+            action: || KeyEventResult::ToggleThinking,
+        },
+        Command {
+            name: "Toggle tool detail",
+            description: "Switch tools between full and compact view (Ctrl+O)",
+            action: || KeyEventResult::ToggleToolDetail,
+        },
+        Command {
+            name: "Toggle line numbers",
+            description: "Number the lines of the draft in the composer (Ctrl+L)",
+            action: || KeyEventResult::ToggleLineNumbers,
+        },
+        Command {
+            name: "Toggle diff pane",
+            description:
+                "Show the turn's diff of the latest edited file beside the transcript (Ctrl+X)",
+            action: || KeyEventResult::ToggleDiffPane { path: None },
+        },
+        Command {
+            name: "Rerun last command",
+            description: "Ask the agent to run the last shell command again (Ctrl+R)",
+            action: || KeyEventResult::RerunLastCommand,
+        },
+        Command {
+            name: "Open last path",
+            description: "Open the latest mentioned file in your editor (Ctrl+G)",
+            action: || KeyEventResult::OpenLastPath,
+        },
+        Command {
+            name: "Jump to latest",
+            description: "Write held output and follow new output again (End)",
+            action: || KeyEventResult::JumpToLatest,
+        },
+        Command {
+            name: "Toggle no-color",
+            description: "Switch monochrome output on or off (/no-color)",
+            action: || KeyEventResult::ToggleNoColor,
+        },
+        Command {
+            name: "Show welcome",
+            description: "Print the welcome banner (/welcome)",
+            action: || KeyEventResult::ShowWelcome,
+        },
+        Command {
+            name: "List tool renderers",
+            description: "Show which renderer draws each tool (/tools)",
+            action: || KeyEventResult::ShowInfo(tools_info()),
+        },
+        Command {
+            name: "Inspect tool",
+            description: "Print the highlighted or latest tool's full parameters (/inspect)",
+            action: || KeyEventResult::InspectTool,
+        },
+        Command {
+            name: "Help",
+            description: "List the slash commands (/help)",
+            action: || KeyEventResult::ShowInfo(help_text()),
+        },
+        Command {
+            name: "Quit",
+            description: "Leave the application (Ctrl+C)",
+            action: || KeyEventResult::Quit,
+        },
+    ];
+    COMMANDS
+}
+
+/// Result of a key press while the palette is open.
+#[derive(Debug)]
+pub enum PaletteAction {
+    /// Keep the palette open
+    None,
+    /// A command was chosen; dispatch its result
+    Run(KeyEventResult),
+    /// The palette was dismissed
+    Cancel,
+}
+
+/// Interactive command list, narrowed by typing a fuzzy query.
+#[derive(Debug, Clone)]
+pub struct CommandPalette {
+    commands: &'static [Command],
+    names: Vec<String>,
+    query: String,
+    selected: usize,
+}
+
+impl CommandPalette {
+    pub fn new() -> Self {
+        let commands = registry();
+        Self {
+            commands,
+            names: commands
+                .iter()
+                .map(|command| command.name.to_string())
+                .collect(),
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    /// Commands matching the current query, best matches first.
+    pub fn filtered(&self) -> Vec<&'static Command> {
+        fuzzy_filter(&self.names, &self.query)
+            .into_iter()
+            .filter_map(|name| self.commands.iter().find(|command| command.name == name))
+            .collect()
+    }
+
+    pub fn selected_command(&self) -> Option<&'static Command> {
+        self.filtered().get(self.selected).copied()
+    }
+
+    pub fn handle_key(&mut self, key_event: KeyEvent) -> PaletteAction {
+        let match_count = self.filtered().len();
+        match key_event.code {
+            KeyCode::Esc => PaletteAction::Cancel,
+            KeyCode::Char('c' | 'p') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                PaletteAction::Cancel
+            }
+            KeyCode::Enter => match self.selected_command() {
+                Some(command) => PaletteAction::Run((command.action)()),
+                None => PaletteAction::None,
+            },
+            KeyCode::Up => {
+                self.selected = self.selected.saturating_sub(1);
+                PaletteAction::None
+            }
+            KeyCode::Down => {
+                if self.selected + 1 < match_count {
+                    self.selected += 1;
+                }
+                PaletteAction::None
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.selected = 0;
+                PaletteAction::None
+            }
+            KeyCode::Char(c) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.query.push(c);
+                self.selected = 0;
+                PaletteAction::None
+            }
+            _ => PaletteAction::None,
+        }
+    }
+
+    /// Status-area text for the palette: a header with the query and a
+    /// window of matching commands with the selection marked.
+    pub fn to_status_text(&self) -> String {
+        let matches = self.filtered();
+        let mut text = if self.query.is_empty() {
+            String::from("Commands (type to filter, Enter to run, Esc to cancel)")
+        } else {
+            format!("Commands: {}", self.query)
+        };
+
+        if matches.is_empty() {
+            text.push_str("\nNo matching commands");
+            return text;
+        }
+
+        let start = self
+            .selected
+            .saturating_sub(MAX_VISIBLE_COMMANDS - 1)
+            .min(matches.len().saturating_sub(MAX_VISIBLE_COMMANDS));
+        let end = (start + MAX_VISIBLE_COMMANDS).min(matches.len());
+        for (idx, command) in matches[start..end].iter().enumerate() {
+            text.push('\n');
+            if start + idx == self.selected {
+                text.push_str(&format!("› **{}**  {}", command.name, command.description));
+            } else {
+                text.push_str(&format!("  {}  {}", command.name, command.description));
+            }
+        }
+        if end < matches.len() {
+            text.push_str(&format!("\n  (+{} more)", matches.len() - end));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_registry_names_are_unique() {
+        let commands = registry();
+        for (idx, command) in commands.iter().enumerate() {
+            assert!(
+                commands[idx + 1..]
+                    .iter()
+                    .all(|other| other.name != command.name),
+                "duplicate command {}",
+                command.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_palette_filters_and_runs() {
+        let mut palette = CommandPalette::new();
+        assert_eq!(palette.filtered().len(), registry().len());
+
+        for c in "newses".chars() {
+            palette.handle_key(key(KeyCode::Char(c)));
+        }
+        assert_eq!(
+            palette.selected_command().map(|command| command.name),
+            Some("New session")
+        );
+        assert!(palette.to_status_text().contains("› **New session**"));
+        assert!(matches!(
+            palette.handle_key(key(KeyCode::Enter)),
+            PaletteAction::Run(KeyEventResult::ClearScreen { new_session: true })
+        ));
+    }
+
+    #[test]
+    fn test_palette_navigation_and_cancel() {
+        let mut palette = CommandPalette::new();
+        palette.handle_key(key(KeyCode::Up));
+        assert_eq!(
+            palette.selected_command().map(|command| command.name),
+            Some(registry()[0].name)
+        );
+        palette.handle_key(key(KeyCode::Down));
+        assert_eq!(
+            palette.selected_command().map(|command| command.name),
+            Some(registry()[1].name)
+        );
+
+        for c in "zzz".chars() {
+            palette.handle_key(key(KeyCode::Char(c)));
+        }
+        assert!(palette.to_status_text().ends_with("No matching commands"));
+        assert!(matches!(
+            palette.handle_key(key(KeyCode::Enter)),
+            PaletteAction::None
+        ));
+        assert!(matches!(
+            palette.handle_key(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::CONTROL)),
+            PaletteAction::Cancel
+        ));
+    }
+}
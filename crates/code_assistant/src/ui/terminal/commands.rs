@@ -1,6 +1,12 @@
 use anyhow::Result;
 use llm::provider_config::ConfigurationSystem;
 
+use std::path::PathBuf;
+
+use super::copy::{parse_copy_args, CopySelector};
+use super::export::{parse_export_args, ExportFormat};
+use super::model_picker::{resolve_model, ModelMatch};
+
 /// Result of processing a slash command
 #[derive(Debug, Clone)]
 pub enum CommandResult {
@@ -10,6 +16,8 @@ pub enum CommandResult {
     Help(String),
     /// List available models
     ListModels,
+    /// Open the interactive model picker
+    OpenModelPicker,
     /// List available providers
     ListProviders,
     /// Switch to a specific model
@@ -20,6 +28,23 @@ pub enum CommandResult {
     InvalidCommand(String),
     /// Toggle plan rendering mode
     TogglePlan,
+    /// Toggle no-color (monochrome) mode
+    ToggleNoColor,
+    /// Write the transcript to a file
+    ExportTranscript {
+        path: Option<PathBuf>,
+        format: ExportFormat,
+    },
+    /// Clear the transcript, optionally starting a new session
+    ClearScreen { new_session: bool },
+    /// Show the welcome banner again
+    ShowWelcome,
+    /// Copy part of the transcript to the clipboard
+    Copy(CopySelector),
+    /// List the tool renderers and the tools they handle
+    ListTools,
+    /// Print the full parameters of the highlighted or latest tool
+    InspectTool,
 }
 
 /// Process slash commands in terminal UI
@@ -43,33 +68,58 @@ impl CommandProcessor {
 
         let parts: Vec<&str> = input[1..].split_whitespace().collect();
         if parts.is_empty() {
-            return CommandResult::Help(self.get_help_text());
+            return CommandResult::Help(help_text());
         }
 
         match parts[0].to_lowercase().as_str() {
-            "help" | "h" => CommandResult::Help(self.get_help_text()),
+            "help" | "h" => CommandResult::Help(help_text()),
             "model" | "m" => self.process_model_command(&parts[1..]),
+            "models" => CommandResult::ListModels,
             "provider" | "p" => self.process_provider_command(&parts[1..]),
             "current" | "c" => CommandResult::ShowCurrentModel,
             "plan" => CommandResult::TogglePlan,
+            "welcome" => CommandResult::ShowWelcome,
+            "tools" => CommandResult::ListTools,
+            "inspect" => CommandResult::InspectTool,
+            "no-color" | "nocolor" => CommandResult::ToggleNoColor,
+            "export" => match parse_export_args(&parts[1..]) {
+                Ok((path, format)) => CommandResult::ExportTranscript { path, format },
+                Err(error) => CommandResult::InvalidCommand(error),
+            },
+            "copy" => match parse_copy_args(&parts[1..]) {
+                Ok(selector) => CommandResult::Copy(selector),
+                Err(error) => CommandResult::InvalidCommand(error),
+            },
+            "clear" => match parts.get(1).copied() {
+                None => CommandResult::ClearScreen { new_session: false },
+                Some("new") => CommandResult::ClearScreen { new_session: true },
+                Some(arg) => CommandResult::InvalidCommand(format!(
+                    "Unknown argument for /clear: {arg} (use '/clear new' for a new session)"
+                )),
+            },
             _ => CommandResult::InvalidCommand(format!("Unknown command: /{}", parts[0])),
         }
     }
 
     fn process_model_command(&self, args: &[&str]) -> CommandResult {
         if args.is_empty() {
-            return CommandResult::ListModels;
+            return CommandResult::OpenModelPicker;
         }
 
-        let model_name = args.join(" ");
+        let query = args.join(" ");
+        let mut models: Vec<String> = self.config.models.keys().cloned().collect();
+        models.sort();
 
-        // Check if the model exists
-        if self.config.models.contains_key(&model_name) {
-            CommandResult::SwitchModel(model_name)
-        } else {
-            CommandResult::InvalidCommand(format!(
-                "Model '{model_name}' not found. Use '/model' to list available models.",
-            ))
+        match resolve_model(&models, &query) {
+            ModelMatch::Found(model_name) => CommandResult::SwitchModel(model_name),
+            ModelMatch::Ambiguous(candidates) => CommandResult::InvalidCommand(format!(
+                "Model '{query}' is ambiguous. Candidates: {}",
+                candidates.join(", ")
+            )),
+            ModelMatch::NoMatch => CommandResult::InvalidCommand(format!(
+                "No model matches '{query}'. Available models: {}",
+                models.join(", ")
+            )),
         }
     }
 
@@ -83,23 +133,6 @@ impl CommandProcessor {
         )
     }
 
-    fn get_help_text(&self) -> String {
-        concat!(
-            "Available commands:\n",
-            "/help, /h          - Show this help\n",
-            "/model, /m         - List available models\n",
-            "/model <name>      - Switch to model\n",
-            "/provider, /p      - List available providers\n",
-            "/current, /c       - Show current model\n",
-            "/plan              - Toggle plan view\n",
-            "\n",
-            "Examples:\n",
-            "/model Claude Sonnet 4.5\n",
-            "/model GPT-5",
-        )
-        .to_string()
-    }
-
     /// Get formatted list of available models
     pub fn get_models_list(&self) -> String {
         let mut models: Vec<_> = self.config.models.keys().cloned().collect();
@@ -130,3 +163,69 @@ impl CommandProcessor {
         output
     }
 }
+
+/// Help text listing the slash commands.
+pub fn help_text() -> String {
+    concat!(
+        "Available commands:\n",
+        "/help, /h          - Show this help\n",
+        "/model, /m         - Pick a model\n",
+        "/model <name>      - Switch to model (partial names allowed)\n",
+        "/models            - List available models\n",
+        "/provider, /p      - List available providers\n",
+        "/current, /c       - Show current model\n",
+        "/plan              - Toggle plan view\n",
+        "/no-color          - Toggle monochrome output\n",
+        "/export [path]     - Save transcript (--format json for JSON)\n",
+        "/copy [last|N|tool] - Copy a message, N messages or tool output\n",
+        "/clear             - Clear the transcript\n",
+        "/clear new         - Clear and start a new session\n",
+        "/welcome           - Show the welcome banner\n",
+        "/tools             - List tool renderers and their tools\n",
+        "/inspect           - Print the highlighted (or latest) tool's full parameters\n",
+        "\n",
+        "Ctrl+P (empty composer) opens the command palette.\n",
+        "Ctrl+C cancels a running turn; pressed again (or when idle) it quits.\n",
+        "Ctrl+S sends the draft to steer a running turn at its next tool call.\n",
+        "Ctrl+L numbers the lines of the draft.\n",
+        "Up/Down on the first/last line recall sent messages; the draft comes back after the newest.\n",
+        "Alt+Up/Alt+Down highlight the live turn's tools; Alt+O expands or collapses the highlighted one.\n",
+        "Ctrl+X shows this turn's diff of a file beside the transcript (@path to pick one).\n",
+        "\n",
+        "Examples:\n",
+        "/model Claude Sonnet 4.5\n",
+        "/model GPT-5",
+    )
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn processor() -> CommandProcessor {
+        CommandProcessor {
+            config: ConfigurationSystem {
+                providers: Default::default(),
+                models: Default::default(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_welcome_command() {
+        let processor = processor();
+        assert!(matches!(
+            processor.process_command("/welcome"),
+            CommandResult::ShowWelcome
+        ));
+        assert!(matches!(
+            processor.process_command("  /WELCOME  "),
+            CommandResult::ShowWelcome
+        ));
+        assert!(matches!(
+            processor.process_command("/welcomes"),
+            CommandResult::InvalidCommand(_)
+        ));
+    }
+}
@@ -5,9 +5,15 @@ use ratatui::{
     widgets::{Block, Widget, WidgetRef},
 };
 
+use super::config::TerminalConfig;
 use super::custom_terminal;
+use super::message::MessageUsage;
 use super::terminal_color;
 use super::textarea::TextArea;
+use super::theme::Theme;
+use crate::types::ToolSyntax;
+use llm::recording::PlaybackStatus;
+use sandbox::SandboxPolicy;
 
 /// Width reserved for the "› " prefix to the left of the textarea.
 const PREFIX_COLS: u16 = 2;
@@ -17,13 +23,135 @@ fn composer_bg() -> Color {
     terminal_color::composer_bg()
 }
 
+/// Height of the persistent status bar drawn below the composer.
+pub const STATUS_BAR_HEIGHT: u16 = 1;
+
+/// Session details shown in the status bar below the composer footer.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatusBarInfo {
+    pub model: Option<String>,
+    pub session_name: Option<String>,
+    pub sandbox_policy: Option<SandboxPolicy>,
+    pub tool_syntax: Option<ToolSyntax>,
+    pub playback: Option<PlaybackStatus>,
+    /// Lines held back from scrollback while not following new output
+    pub held_lines: Option<usize>,
+    /// Token and cost total of the session so far
+    pub usage: Option<MessageUsage>,
+}
+
+impl StatusBarInfo {
+    fn sandbox_label(policy: &SandboxPolicy) -> &'static str {
+        match policy {
+            SandboxPolicy::DangerFullAccess => "full access",
+            SandboxPolicy::ReadOnly => "read-only",
+            SandboxPolicy::WorkspaceWrite { .. } => "workspace-write",
+        }
+    }
+
+    fn tool_syntax_label(tool_syntax: ToolSyntax) -> &'static str {
+        match tool_syntax {
+            ToolSyntax::Native => "native",
+            ToolSyntax::Xml => "xml",
+            ToolSyntax::Caret => "caret",
+        }
+    }
+
+    fn playback_label(status: PlaybackStatus) -> String {
+        let state = if status.paused {
+            "⏸ paused"
+        } else {
+            "▶ playback"
+        };
+        format!("{state} {}x", status.speed)
+    }
+
+    /// Build the status bar line; fields that are not known yet are skipped.
+    pub fn to_line(&self) -> Line<'static> {
+        let value_style = Style::default()
+            .fg(Theme::global().muted_fg)
+            .add_modifier(Modifier::DIM);
+        let separator_style = Style::default()
+            .fg(Theme::global().secondary_fg)
+            .add_modifier(Modifier::DIM);
+
+        let mut values: Vec<String> = Vec::new();
+        if let Some(model) = &self.model {
+            values.push(model.clone());
+        }
+        if let Some(session_name) = &self.session_name {
+            values.push(session_name.clone());
+        }
+        if let Some(policy) = &self.sandbox_policy {
+            values.push(Self::sandbox_label(policy).to_string());
+        }
+        if let Some(tool_syntax) = self.tool_syntax {
+            values.push(Self::tool_syntax_label(tool_syntax).to_string());
+        }
+        if let Some(playback) = self.playback {
+            values.push(Self::playback_label(playback));
+        }
+        if let Some(usage) = &self.usage {
+            values.push(usage.summary());
+        }
+        if let Some(held_lines) = self.held_lines {
+            values.push(format!("⇣ {held_lines} new lines (End)"));
+        }
+
+        let mut spans = vec![Span::raw("  ")];
+        for (idx, value) in values.into_iter().enumerate() {
+            if idx > 0 {
+                spans.push(Span::styled(" · ", separator_style));
+            }
+            spans.push(Span::styled(value, value_style));
+        }
+        Line::from(spans)
+    }
+}
+
 pub struct Composer {
     max_input_rows: u16,
+    /// Whether the terminal has focus; the prompt and draft dim without it
+    focused: bool,
+    /// Viewing a saved session: no draft, no cursor, only the quit hint
+    read_only: bool,
+    /// Number the draft's lines in a gutter left of the text
+    line_numbers: bool,
 }
 
+/// Shown in place of the draft while the composer is read-only.
+const READ_ONLY_PLACEHOLDER: &str = "Read-only session view";
+
 impl Composer {
     pub fn new(max_input_rows: u16) -> Self {
-        Self { max_input_rows }
+        Self {
+            max_input_rows,
+            focused: true,
+            read_only: false,
+            line_numbers: false,
+        }
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    /// Show or hide line numbers. Returns whether they are now shown.
+    pub fn toggle_line_numbers(&mut self) -> bool {
+        self.line_numbers = !self.line_numbers;
+        self.line_numbers
+    }
+
+    /// Columns of the line-number gutter: the widest number and a space.
+    fn gutter_cols(&self, textarea: &TextArea) -> u16 {
+        if !self.line_numbers || self.read_only {
+            return 0;
+        }
+        textarea.line_count().to_string().len() as u16 + 1
     }
 
     #[cfg(test)]
@@ -34,7 +162,8 @@ impl Composer {
     /// Calculate total height:
     ///   1 (top padding) + textarea lines + 1 (bottom padding) + 1 (footer hints).
     pub fn calculate_input_height(&self, textarea: &TextArea, width: u16) -> u16 {
-        let textarea_width = width.saturating_sub(PREFIX_COLS + 1); // prefix + 1 right margin
+        // prefix + gutter + 1 right margin
+        let textarea_width = width.saturating_sub(PREFIX_COLS + self.gutter_cols(textarea) + 1);
         let lines = textarea.desired_height(textarea_width);
         let total = lines + 3; // 1 top + textarea + 1 bottom padding + 1 footer
         total.clamp(4, self.max_input_rows + 3)
@@ -66,26 +195,62 @@ impl Composer {
             .style(bg_style)
             .render(bg_rect, f.buffer_mut());
 
-        // Textarea area: inset from left by PREFIX_COLS, from right by 1
+        // Textarea area: inset from left by PREFIX_COLS and the gutter, from right by 1
+        let gutter_cols = self.gutter_cols(textarea);
         let textarea_rect = Rect {
-            x: area.x + PREFIX_COLS,
+            x: area.x + PREFIX_COLS + gutter_cols,
             y: area.y + 1,
-            width: area.width.saturating_sub(PREFIX_COLS + 1),
+            width: area.width.saturating_sub(PREFIX_COLS + gutter_cols + 1),
             height: textarea_height,
         };
 
+        // Line numbers, only on the first row of each wrapped line
+        if gutter_cols > 0 {
+            let number_style = Style::default()
+                .fg(Theme::global().muted_fg)
+                .bg(composer_bg());
+            let rows = textarea.row_line_numbers(textarea_rect.width);
+            for (row, number) in rows.iter().enumerate().take(textarea_height as usize) {
+                if let Some(number) = number {
+                    f.buffer_mut().set_string(
+                        area.x + PREFIX_COLS,
+                        textarea_rect.y + row as u16,
+                        format!("{number:>width$}", width = (gutter_cols - 1) as usize),
+                        number_style,
+                    );
+                }
+            }
+        }
+
         // Render "› " prefix on the first textarea row
-        let prompt = Span::styled(
-            "›",
+        let prompt_style = if self.focused && !self.read_only {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
             Style::default()
-                .add_modifier(Modifier::BOLD)
-                .bg(composer_bg()),
-        );
+                .fg(Theme::global().muted_fg)
+                .add_modifier(Modifier::DIM)
+        };
+        let prompt = Span::styled("›", prompt_style.bg(composer_bg()));
         f.buffer_mut()
             .set_span(area.x, area.y + 1, &prompt, PREFIX_COLS);
 
-        // Render textarea
-        (&textarea).render_ref(textarea_rect, f.buffer_mut());
+        // Render textarea, or the placeholder when there is nothing to edit
+        if self.read_only {
+            let placeholder = Span::styled(
+                READ_ONLY_PLACEHOLDER,
+                Style::default()
+                    .fg(Theme::global().muted_fg)
+                    .add_modifier(Modifier::ITALIC),
+            );
+            f.buffer_mut().set_span(
+                textarea_rect.x,
+                textarea_rect.y,
+                &placeholder,
+                textarea_rect.width,
+            );
+        } else {
+            (&textarea).render_ref(textarea_rect, f.buffer_mut());
+        }
 
         // Apply background to textarea cells (textarea renders with default bg)
         for row in 0..textarea_rect.height {
@@ -97,25 +262,44 @@ impl Composer {
                     if cell.bg == Color::Reset {
                         cell.set_style(Style::default().bg(composer_bg()));
                     }
+                    if !self.focused {
+                        cell.modifier.insert(Modifier::DIM);
+                    }
                 }
             }
         }
 
         // Render footer hints below the background area (dimmed, no bg)
         let action_style = Style::default()
-            .fg(Color::DarkGray)
+            .fg(Theme::global().muted_fg)
             .add_modifier(Modifier::DIM);
-        let mapping_style = Style::default().fg(Color::Gray).add_modifier(Modifier::DIM);
-        let footer_line = Line::from(vec![
-            Span::styled("  Enter", action_style),
-            Span::styled(" send  ", mapping_style),
-            Span::styled("Shift+Enter", action_style),
-            Span::styled(" newline  ", mapping_style),
-            Span::styled("Esc", action_style),
-            Span::styled(" dismiss  ", mapping_style),
-            Span::styled("/help", action_style),
-            Span::styled(" commands", mapping_style),
-        ]);
+        let mapping_style = Style::default()
+            .fg(Theme::global().secondary_fg)
+            .add_modifier(Modifier::DIM);
+        let (send_key, newline_key) = if TerminalConfig::global().submit_on_enter {
+            ("Enter", "Shift+Enter")
+        } else {
+            ("Shift+Enter", "Enter")
+        };
+        let footer_line = if self.read_only {
+            Line::from(vec![
+                Span::styled("  q/Esc", action_style),
+                Span::styled(" quit  ", mapping_style),
+                Span::styled("scroll the terminal", action_style),
+                Span::styled(" to read back", mapping_style),
+            ])
+        } else {
+            Line::from(vec![
+                Span::styled(format!("  {send_key}"), action_style),
+                Span::styled(" send  ", mapping_style),
+                Span::styled(newline_key, action_style),
+                Span::styled(" newline  ", mapping_style),
+                Span::styled("Esc", action_style),
+                Span::styled(" dismiss  ", mapping_style),
+                Span::styled("/help", action_style),
+                Span::styled(" commands", mapping_style),
+            ])
+        };
         let footer_rect = Rect {
             x: area.x,
             y: footer_y,
@@ -125,8 +309,24 @@ impl Composer {
         footer_line.render(footer_rect, f.buffer_mut());
 
         // Set cursor position (relative to textarea_rect)
+        if self.read_only {
+            return;
+        }
         if let Some((cursor_x, cursor_y)) = textarea.cursor_position(textarea_rect) {
             f.set_cursor_position(Position::new(cursor_x, cursor_y));
         }
     }
+
+    /// Render the persistent status bar (model, session, sandbox, tool syntax).
+    pub fn render_status_bar(
+        &self,
+        f: &mut custom_terminal::Frame,
+        area: Rect,
+        info: &StatusBarInfo,
+    ) {
+        if area.height == 0 || area.width == 0 {
+            return;
+        }
+        info.to_line().render(area, f.buffer_mut());
+    }
 }
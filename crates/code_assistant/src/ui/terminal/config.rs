@@ -0,0 +1,296 @@
+//! Configuration for the terminal UI, loaded from `terminal.json`.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use super::input::PastePlaceholderFormat;
+use super::markdown::MarkdownRenderer;
+use super::spinner::SpinnerStyle;
+use super::state::AutoScroll;
+use super::terminal_color::BackgroundMode;
+use super::textarea::{WordSeparators, WrapMode};
+use super::theme::{DiffStyleConfig, ThemeName};
+use super::tool_renderers::ProjectSuffixStyle;
+use super::transcript::{ThinkingVisibility, TurnSeparator};
+use super::tui::TurnNotification;
+use crate::tools::core::ToolsConfig;
+
+/// User-facing settings for the terminal UI.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TerminalConfig {
+    /// Color theme used for status, diff, thinking and error styling
+    pub theme: ThemeName,
+    /// Whether the terminal background is light or dark (auto-detected by default)
+    pub background: BackgroundMode,
+    /// Diff colors: a `preset` (`default` or `github`) and optional
+    /// `insert_fg`/`delete_fg`/`insert_bg`/`delete_bg`/`use_bg_highlight`
+    pub diff_style: DiffStyleConfig,
+    /// Start in no-color mode (styling limited to bold/dim/italic/underline)
+    pub no_color: bool,
+    /// Show thinking blocks in full instead of a collapsed one-line summary
+    pub expand_thinking: bool,
+    /// `hidden`, `collapsed` or `full` thinking; overrides `expand_thinking`
+    pub thinking_visibility: Option<ThinkingVisibility>,
+    /// Between an assistant turn and the next user message in scrollback:
+    /// `blank-line`, `rule` (a dim full-width line) or `none`
+    pub turn_separator: TurnSeparator,
+    /// Marker in front of user messages in scrollback, e.g. `❯`, `$` or
+    /// `>>>`; assistant content is indented to line up after it
+    pub user_prompt: Option<String>,
+    /// Color of the user marker as a color name or `#rrggbb`
+    pub user_prompt_color: Option<String>,
+    /// Append committed messages to a markdown transcript next to the session file
+    pub save_transcript: bool,
+    /// Require a second Ctrl+R before re-running the last command
+    pub confirm_rerun: bool,
+    /// Require a second Escape within two seconds to cancel a running turn
+    pub confirm_cancel: bool,
+    /// Streaming tool output kept per block; older output is dropped first
+    pub tool_output_max_bytes: usize,
+    pub tool_output_max_lines: usize,
+    /// Spinner animation; `auto` falls back to ASCII where braille may not render
+    pub spinner_style: SpinnerStyle,
+    /// Pastes longer than this many characters, or spanning more than this
+    /// many lines, are shown as a placeholder in the composer
+    pub paste_placeholder_chars: usize,
+    pub paste_placeholder_lines: usize,
+    /// Label for collapsed pastes: `chars`, `lines` or `preview` (the start
+    /// of the first line)
+    pub paste_placeholder_format: PastePlaceholderFormat,
+    /// Enter sends the message and Shift/Alt+Enter inserts a newline; when
+    /// disabled the two are swapped
+    pub submit_on_enter: bool,
+    /// Trim trailing whitespace from each line of a sent message and drop
+    /// trailing blank lines; fenced code blocks are left as they are
+    pub trim_trailing_whitespace: bool,
+    /// Bell or desktop notification when a turn finishes while the terminal
+    /// is unfocused
+    pub turn_notification: TurnNotification,
+    /// Background for tool content (diffs, command output) as a color name
+    /// or `#rrggbb`; unset derives a subtle tint from the terminal background
+    pub tool_content_bg: Option<String>,
+    /// Per-tool backgrounds keyed by tool name, e.g. `{"execute_command": "#1e2430"}`
+    pub tool_content_bg_overrides: HashMap<String, String>,
+    /// Tools whose calls wait for Enter (approve) or Esc (skip) before
+    /// running, e.g. `["execute_command", "write_file"]`
+    pub approve_tools: Vec<String>,
+    /// Show the welcome banner on startup; `/welcome` shows it either way
+    pub show_welcome_banner: bool,
+    /// Note in scrollback when tool calls move to a different project
+    pub project_breadcrumbs: bool,
+    /// Tool header project suffix: `hidden` values, `format` (`[{}]`) and
+    /// `basename` to shorten paths
+    pub project_suffix: ProjectSuffixStyle,
+    /// Word boundaries for Alt+B/F and word deletion in the composer:
+    /// `code` keeps `snake_case` together, `prose` keeps `kebab-case` together
+    pub word_separators: WordSeparators,
+    /// How the composer breaks long lines: `first-fit`, `optimal-fit`
+    /// (balanced rows for prose) or `no-wrap` (scroll sideways, for code)
+    pub composer_wrap: WrapMode,
+    /// Rows a single status message (error, info, plan) may take above the
+    /// composer; longer ones are cut off with `…`
+    pub max_status_height: u16,
+    /// Underline file paths and `path:line` references in assistant text
+    pub highlight_paths: bool,
+    /// Whether new output goes to scrollback right away (`always`), or is
+    /// held after PageUp until End or the next message (`at-bottom`) or
+    /// until End only (`never`)
+    pub auto_scroll: AutoScroll,
+    /// Show token usage and estimated cost under each response, and the
+    /// session total in the status bar
+    pub show_usage: bool,
+    /// Save the session and composer draft after this many idle seconds
+    /// with unsaved changes; 0 turns auto-save off
+    pub autosave_idle_secs: u64,
+    /// Committed messages kept in memory once they are in the terminal
+    /// scrollback; older ones are dropped (the scrollback itself keeps
+    /// them, but `/export` and `/copy` no longer see them). 0 keeps all
+    pub max_retained_messages: usize,
+    /// Mask tokens, keys and passwords in displayed command lines and
+    /// output, the saved transcript, `/copy` and `/export`; what the agent
+    /// sees is unchanged
+    pub redact_secrets: bool,
+    /// Extra regexes for `redact_secrets`; with a capture group only the
+    /// group is masked
+    pub redact_patterns: Vec<String>,
+    /// Draw images in the scrollback on terminals with Kitty or iTerm2
+    /// graphics support instead of showing a placeholder
+    pub inline_images: bool,
+    /// Renderer for message markdown: `tui-markdown`, or `builtin` for
+    /// output that stays the same across dependency upgrades
+    pub markdown_renderer: MarkdownRenderer,
+}
+
+impl Default for TerminalConfig {
+    fn default() -> Self {
+        Self {
+            theme: ThemeName::default(),
+            background: BackgroundMode::default(),
+            diff_style: DiffStyleConfig::default(),
+            no_color: false,
+            expand_thinking: false,
+            thinking_visibility: None,
+            turn_separator: TurnSeparator::default(),
+            user_prompt: None,
+            user_prompt_color: None,
+            save_transcript: false,
+            confirm_rerun: false,
+            confirm_cancel: false,
+            tool_output_max_bytes: 64 * 1024,
+            tool_output_max_lines: 2000,
+            spinner_style: SpinnerStyle::default(),
+            paste_placeholder_chars: 200,
+            paste_placeholder_lines: 20,
+            paste_placeholder_format: PastePlaceholderFormat::default(),
+            submit_on_enter: true,
+            trim_trailing_whitespace: false,
+            turn_notification: TurnNotification::default(),
+            tool_content_bg: None,
+            tool_content_bg_overrides: HashMap::new(),
+            approve_tools: Vec::new(),
+            show_welcome_banner: true,
+            project_breadcrumbs: true,
+            project_suffix: ProjectSuffixStyle::default(),
+            word_separators: WordSeparators::default(),
+            composer_wrap: WrapMode::default(),
+            max_status_height: 10,
+            highlight_paths: true,
+            auto_scroll: AutoScroll::default(),
+            show_usage: false,
+            autosave_idle_secs: 0,
+            max_retained_messages: 1000,
+            redact_secrets: false,
+            redact_patterns: Vec::new(),
+            inline_images: true,
+            markdown_renderer: MarkdownRenderer::default(),
+        }
+    }
+}
+
+impl TerminalConfig {
+    /// Idle time after which unsaved changes are auto-saved, if enabled.
+    pub fn autosave_interval(&self) -> Option<Duration> {
+        (self.autosave_idle_secs > 0).then(|| Duration::from_secs(self.autosave_idle_secs))
+    }
+
+    /// Get the global singleton instance of the terminal configuration.
+    /// Returns a default config if no configuration file exists.
+    pub fn global() -> &'static Self {
+        static INSTANCE: OnceLock<TerminalConfig> = OnceLock::new();
+        INSTANCE.get_or_init(|| {
+            Self::load().unwrap_or_else(|err| {
+                tracing::warn!("Failed to load terminal config, using defaults: {err:#}");
+                Self::default()
+            })
+        })
+    }
+
+    /// Load the terminal configuration from disk.
+    /// Returns Ok with default config if the file doesn't exist.
+    pub fn load() -> Result<Self> {
+        let config_path = Self::config_path()?;
+
+        if !config_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(&config_path).with_context(|| {
+            format!("Failed to read terminal config: {}", config_path.display())
+        })?;
+
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse terminal config: {}", config_path.display()))
+    }
+
+    /// How thinking is shown, falling back to `expand_thinking`.
+    pub fn thinking_visibility(&self) -> ThinkingVisibility {
+        self.thinking_visibility.unwrap_or(if self.expand_thinking {
+            ThinkingVisibility::Full
+        } else {
+            ThinkingVisibility::Collapsed
+        })
+    }
+
+    /// Get the path to the terminal configuration file.
+    pub fn config_path() -> Result<PathBuf> {
+        Ok(ToolsConfig::config_directory()?.join("terminal.json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> TerminalConfig {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_parse_partial_config_uses_defaults() {
+        let config = parse("{}");
+        assert_eq!(config.theme, ThemeName::Default);
+        assert_eq!(config.background, BackgroundMode::Auto);
+
+        let config = parse(r#"{"theme": "high-contrast"}"#);
+        assert_eq!(config.theme, ThemeName::HighContrast);
+        assert_eq!(config.background, BackgroundMode::Auto);
+
+        let config = parse(r#"{"background": "light"}"#);
+        assert_eq!(config.background, BackgroundMode::Light);
+    }
+
+    #[test]
+    fn test_thinking_visibility_falls_back_to_expand_thinking() {
+        assert_eq!(
+            parse("{}").thinking_visibility(),
+            ThinkingVisibility::Collapsed
+        );
+        assert_eq!(
+            parse(r#"{"expand_thinking": true}"#).thinking_visibility(),
+            ThinkingVisibility::Full
+        );
+        assert_eq!(
+            parse(r#"{"expand_thinking": true, "thinking_visibility": "hidden"}"#)
+                .thinking_visibility(),
+            ThinkingVisibility::Hidden
+        );
+    }
+
+    #[test]
+    fn test_settings_default_and_parse() {
+        // (setting, default, a non-default value), all as JSON
+        let cases = [
+            ("turn_separator", r#""blank-line""#, r#""rule""#),
+            ("user_prompt", "null", r#""$""#),
+            ("paste_placeholder_format", r#""chars""#, r#""preview""#),
+            ("trim_trailing_whitespace", "false", "true"),
+            ("turn_notification", r#""off""#, r#""desktop""#),
+            ("show_welcome_banner", "true", "false"),
+            ("composer_wrap", r#""first-fit""#, r#""no-wrap""#),
+            ("max_status_height", "10", "4"),
+            ("highlight_paths", "true", "false"),
+            ("auto_scroll", r#""always""#, r#""at-bottom""#),
+            ("show_usage", "false", "true"),
+            ("autosave_idle_secs", "0", "30"),
+            ("max_retained_messages", "1000", "0"),
+            ("redact_secrets", "false", "true"),
+            ("redact_patterns", "[]", r#"["corp-\\w+"]"#),
+            ("inline_images", "true", "false"),
+            ("markdown_renderer", r#""tui-markdown""#, r#""builtin""#),
+        ];
+
+        let json = |text: &str| serde_json::from_str::<serde_json::Value>(text).unwrap();
+        let defaults = serde_json::to_value(parse("{}")).unwrap();
+        for (setting, default, value) in cases {
+            assert_eq!(defaults[setting], json(default), "default {setting}");
+            let config = parse(&format!(r#"{{"{setting}": {value}}}"#));
+            let parsed = serde_json::to_value(config).unwrap();
+            assert_eq!(parsed[setting], json(value), "parsed {setting}");
+        }
+    }
+}
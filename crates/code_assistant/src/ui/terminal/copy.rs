@@ -0,0 +1,174 @@
+//! `/copy`: put part of the committed transcript on the clipboard.
+
+use std::sync::Mutex;
+
+use super::message::{LiveMessage, MessageBlock};
+use super::redact::redact;
+use super::tui;
+
+/// Which part of the transcript `/copy` takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopySelector {
+    /// The most recent message
+    Last,
+    /// The last N messages, oldest first
+    Messages(usize),
+    /// Output of the most recent tool that produced any
+    Tool,
+}
+
+/// Parse `/copy` arguments: nothing or `last`, a message count, or `tool`.
+pub fn parse_copy_args(args: &[&str]) -> Result<CopySelector, String> {
+    match args {
+        [] | ["last"] => Ok(CopySelector::Last),
+        ["tool"] => Ok(CopySelector::Tool),
+        [count] => match count.parse::<usize>() {
+            Ok(0) => Err("/copy needs at least one message".to_string()),
+            Ok(count) => Ok(CopySelector::Messages(count)),
+            Err(_) => Err(format!(
+                "Unknown argument for /copy: {count} (use last, a number, or tool)"
+            )),
+        },
+        _ => Err("Usage: /copy [last|N|tool]".to_string()),
+    }
+}
+
+/// Text of the selected messages or tool output, or why there is none.
+/// Secrets are masked as on screen.
+pub fn extract_copy_text(
+    messages: &[LiveMessage],
+    selector: CopySelector,
+) -> Result<String, String> {
+    let with_text: Vec<String> = messages
+        .iter()
+        .map(message_text)
+        .filter(|text| !text.is_empty())
+        .collect();
+
+    let text = match selector {
+        CopySelector::Last => with_text
+            .last()
+            .cloned()
+            .ok_or_else(|| "Nothing to copy yet".to_string()),
+        CopySelector::Messages(count) => {
+            if count > with_text.len() {
+                return Err(format!(
+                    "Only {} message(s) in the transcript",
+                    with_text.len()
+                ));
+            }
+            Ok(with_text[with_text.len() - count..].join("\n\n"))
+        }
+        CopySelector::Tool => messages
+            .iter()
+            .rev()
+            .flat_map(|message| message.blocks.iter().rev())
+            .find_map(|block| match block {
+                MessageBlock::ToolUse(tool) => tool
+                    .output
+                    .as_deref()
+                    .filter(|output| !output.trim().is_empty())
+                    .map(|output| output.trim_end().to_string()),
+                _ => None,
+            })
+            .ok_or_else(|| "No tool output to copy".to_string()),
+    }?;
+    Ok(redact(&text))
+}
+
+/// Visible text of a message: its text blocks and tool output, thinking
+/// left out.
+fn message_text(message: &LiveMessage) -> String {
+    message
+        .blocks
+        .iter()
+        .filter_map(|block| match block {
+            MessageBlock::UserText(text) => Some(text.content.as_str()),
+            MessageBlock::PlainText(text) => Some(text.content.as_str()),
+            MessageBlock::SystemText(text) => Some(text.content.as_str()),
+            MessageBlock::ToolUse(tool) => tool.output.as_deref(),
+            MessageBlock::Thinking(_) | MessageBlock::Image(_) => None,
+        })
+        .map(|text| text.trim_matches('\n').trim_end())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Kept open for the life of the process: on X11 and Wayland the copied
+/// text is served by its owner and disappears once the handle is dropped.
+static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+
+/// Place `text` on the system clipboard, falling back to an OSC 52 request
+/// to the terminal (e.g. over SSH, where there is no local clipboard).
+pub fn copy_to_clipboard(text: &str) -> std::io::Result<()> {
+    let mut clipboard = CLIPBOARD.lock().unwrap_or_else(|e| e.into_inner());
+    if clipboard.is_none() {
+        *clipboard = arboard::Clipboard::new().ok();
+    }
+    if let Some(handle) = clipboard.as_mut() {
+        if handle.set_text(text).is_ok() {
+            return Ok(());
+        }
+    }
+    tui::copy_via_osc52(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::terminal::message::{PlainTextBlock, ThinkingBlock, ToolUseBlock};
+
+    fn text_message(content: &str) -> LiveMessage {
+        let mut message = LiveMessage::new();
+        let mut text = PlainTextBlock::new();
+        text.content = content.to_string();
+        message.add_block(MessageBlock::PlainText(text));
+        message
+    }
+
+    #[test]
+    fn test_parse_copy_args() {
+        assert_eq!(parse_copy_args(&[]), Ok(CopySelector::Last));
+        assert_eq!(parse_copy_args(&["last"]), Ok(CopySelector::Last));
+        assert_eq!(parse_copy_args(&["3"]), Ok(CopySelector::Messages(3)));
+        assert_eq!(parse_copy_args(&["tool"]), Ok(CopySelector::Tool));
+        assert!(parse_copy_args(&["0"]).is_err());
+        assert!(parse_copy_args(&["-1"]).is_err());
+        assert!(parse_copy_args(&["last", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_extract_messages_and_tool_output() {
+        let mut user = LiveMessage::new();
+        let mut prompt = PlainTextBlock::new();
+        prompt.content = "Run the tests".to_string();
+        user.add_block(MessageBlock::UserText(prompt));
+
+        let mut reply = LiveMessage::new();
+        let mut thinking = ThinkingBlock::new();
+        thinking.content = "hidden reasoning".to_string();
+        reply.add_block(MessageBlock::Thinking(thinking));
+        let mut tool = ToolUseBlock::new("execute_command".to_string(), "tool-1".to_string());
+        tool.output = Some("test result: ok\n".to_string());
+        reply.add_block(MessageBlock::ToolUse(tool));
+
+        let messages = vec![user, reply, text_message("All tests pass.\n")];
+
+        assert_eq!(
+            extract_copy_text(&messages, CopySelector::Last),
+            Ok("All tests pass.".to_string())
+        );
+        assert_eq!(
+            extract_copy_text(&messages, CopySelector::Messages(3)),
+            Ok("Run the tests\n\ntest result: ok\n\nAll tests pass.".to_string())
+        );
+        assert_eq!(
+            extract_copy_text(&messages, CopySelector::Tool),
+            Ok("test result: ok".to_string())
+        );
+        assert!(extract_copy_text(&messages, CopySelector::Messages(4)).is_err());
+        assert!(extract_copy_text(&[], CopySelector::Last).is_err());
+        assert!(extract_copy_text(&messages[2..], CopySelector::Tool).is_err());
+    }
+}
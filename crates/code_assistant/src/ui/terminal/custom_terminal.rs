@@ -47,6 +47,8 @@ use ratatui::style::Modifier;
 use ratatui::widgets::Widget;
 use ratatui::widgets::WidgetRef;
 
+use super::theme::output_color;
+
 #[derive(Debug, Hash)]
 pub struct Frame<'a> {
     /// Where should the cursor be after drawing this frame?
@@ -399,13 +401,14 @@ where
                     diff.queue(writer)?;
                     modifier = cell.modifier;
                 }
-                if cell.fg != fg || cell.bg != bg {
+                let (cell_fg, cell_bg) = (output_color(cell.fg), output_color(cell.bg));
+                if cell_fg != fg || cell_bg != bg {
                     queue!(
                         writer,
-                        SetColors(Colors::new(cell.fg.into(), cell.bg.into()))
+                        SetColors(Colors::new(cell_fg.into(), cell_bg.into()))
                     )?;
-                    fg = cell.fg;
-                    bg = cell.bg;
+                    fg = cell_fg;
+                    bg = cell_bg;
                 }
 
                 queue!(writer, Print(cell.symbol()))?;
@@ -413,6 +416,7 @@ where
             DrawCommand::ClearToEnd { bg: clear_bg, .. } => {
                 queue!(writer, SetAttribute(crossterm::style::Attribute::Reset))?;
                 modifier = Modifier::empty();
+                let clear_bg = output_color(clear_bg);
                 queue!(writer, SetBackgroundColor(clear_bg.into()))?;
                 bg = clear_bg;
                 queue!(writer, Clear(crossterm::terminal::ClearType::UntilNewLine))?;
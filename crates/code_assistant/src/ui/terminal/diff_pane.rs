@@ -0,0 +1,251 @@
+//! Side pane showing the changes made to one file during the current turn,
+//! next to the live transcript.
+//!
+//! The edits are collected from the transcript's edit/write tool blocks since
+//! the last user message, so the pane updates as tools stream in. Each tool's
+//! diff is shown in order, separated like hunks.
+
+use ratatui::prelude::*;
+use ratatui::style::Style;
+
+use super::message::{LiveMessage, MessageBlock, ToolUseBlock};
+use super::terminal_color;
+use super::theme::Theme;
+use super::tool_renderers::diff_renderer::{
+    file_path, generate_tool_diff_lines, render_diff_to_buffer, DiffLine,
+};
+use super::tool_renderers::truncate_to_width;
+
+/// Narrowest terminal that gets the split; below it the pane is hidden.
+pub const MIN_SPLIT_WIDTH: u16 = 100;
+
+/// Rows the pane may take, including its header. Longer diffs show their end.
+pub const MAX_PANE_HEIGHT: u16 = 20;
+
+/// Tools whose changes the pane collects.
+const EDIT_TOOLS: [&str; 3] = ["edit", "write_file", "replace_in_file"];
+
+/// Which file the pane shows.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DiffPane {
+    /// A file picked by the user; `None` follows the most recently edited file
+    pub path: Option<String>,
+}
+
+/// The file and its diff lines for the current turn.
+pub struct PaneContent {
+    pub path: Option<String>,
+    pub edits: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+impl DiffPane {
+    /// Collect the pane's content from the transcript messages, oldest first.
+    pub fn content<'a>(&self, messages: impl IntoIterator<Item = &'a LiveMessage>) -> PaneContent {
+        let edits = turn_edits(messages);
+        let path = self.path.clone().or_else(|| {
+            edits
+                .iter()
+                .rev()
+                .find_map(|tool_block| file_path(tool_block))
+        });
+        let Some(target) = path.as_deref() else {
+            return PaneContent {
+                path: None,
+                edits: 0,
+                lines: Vec::new(),
+            };
+        };
+
+        let mut lines = Vec::new();
+        let mut count = 0;
+        for tool_block in edits.iter().filter(|tool_block| {
+            file_path(tool_block).is_some_and(|edited| same_file(&edited, target))
+        }) {
+            let diff = generate_tool_diff_lines(tool_block);
+            if diff.is_empty() {
+                continue;
+            }
+            if count > 0 {
+                lines.push(DiffLine::HunkSeparator);
+            }
+            lines.extend(diff);
+            count += 1;
+        }
+        PaneContent {
+            path,
+            edits: count,
+            lines,
+        }
+    }
+}
+
+impl PaneContent {
+    /// Rows the pane needs: the header plus the diff, capped.
+    pub fn height(&self) -> u16 {
+        (self.lines.len().max(1) as u16)
+            .saturating_add(1)
+            .min(MAX_PANE_HEIGHT)
+    }
+
+    /// Draw the header and the end of the diff into `area`.
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        if area.height == 0 || area.width == 0 {
+            return;
+        }
+        let theme = Theme::global();
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                if let Some(cell) = buf.cell_mut((x, y)) {
+                    cell.reset();
+                }
+            }
+        }
+
+        let header = match &self.path {
+            Some(path) if self.edits == 1 => format!("± {path} (1 edit this turn)"),
+            Some(path) => format!("± {path} ({} edits this turn)", self.edits),
+            None => "± No files edited this turn".to_string(),
+        };
+        buf.set_string(
+            area.x,
+            area.y,
+            truncate_to_width(&header, area.width as usize),
+            Style::default().fg(theme.path_fg),
+        );
+
+        let body_height = area.height.saturating_sub(1) as usize;
+        let skip = self.lines.len().saturating_sub(body_height);
+        render_diff_to_buffer(
+            &self.lines[skip..],
+            area,
+            buf,
+            area.x,
+            area.y + 1,
+            terminal_color::tool_content_bg(),
+        );
+    }
+}
+
+/// Split `area` into the transcript and the pane, or `None` when it is too
+/// narrow for both.
+pub fn split(area: Rect) -> Option<(Rect, Rect)> {
+    if area.width < MIN_SPLIT_WIDTH {
+        return None;
+    }
+    let pane_width = area.width * 2 / 5;
+    let main_width = area.width - pane_width - 1;
+    Some((
+        Rect::new(area.x, area.y, main_width, area.height),
+        Rect::new(area.x + main_width + 1, area.y, pane_width, area.height),
+    ))
+}
+
+/// Edit tool blocks since the last user message.
+fn turn_edits<'a>(messages: impl IntoIterator<Item = &'a LiveMessage>) -> Vec<&'a ToolUseBlock> {
+    let mut edits = Vec::new();
+    for block in messages.into_iter().flat_map(|message| &message.blocks) {
+        match block {
+            MessageBlock::UserText(_) => edits.clear(),
+            MessageBlock::ToolUse(tool_block) if EDIT_TOOLS.contains(&tool_block.name.as_str()) => {
+                edits.push(tool_block)
+            }
+            _ => {}
+        }
+    }
+    edits
+}
+
+/// Whether two paths name the same file, allowing for one being relative
+/// to a directory the other includes (`src/main.rs` vs `app/src/main.rs`).
+fn same_file(a: &str, b: &str) -> bool {
+    let a = a.trim_start_matches("./");
+    let b = b.trim_start_matches("./");
+    a == b || a.ends_with(&format!("/{b}")) || b.ends_with(&format!("/{a}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::terminal::message::{ParameterValue, PlainTextBlock};
+
+    fn edit(path: &str, old: &str, new: &str) -> MessageBlock {
+        let mut tool_block = ToolUseBlock::new("edit".to_string(), format!("tool-{path}-{new}"));
+        for (name, value) in [("path", path), ("old_text", old), ("new_text", new)] {
+            tool_block
+                .parameters
+                .insert(name.to_string(), ParameterValue::new(value.to_string()));
+        }
+        MessageBlock::ToolUse(tool_block)
+    }
+
+    fn message(blocks: Vec<MessageBlock>) -> LiveMessage {
+        let mut message = LiveMessage::new();
+        message.blocks = blocks;
+        message
+    }
+
+    #[test]
+    fn test_collects_edits_of_one_file_in_the_turn() {
+        let user = |text: &str| {
+            let mut block = PlainTextBlock::new();
+            block.content = text.to_string();
+            MessageBlock::UserText(block)
+        };
+        let messages = vec![
+            message(vec![edit("src/lib.rs", "a", "old turn")]),
+            message(vec![user("go")]),
+            message(vec![
+                edit("src/lib.rs", "a", "b"),
+                edit("src/main.rs", "x", "y"),
+            ]),
+            message(vec![edit("./src/lib.rs", "b", "c")]),
+        ];
+
+        // Follows the latest edited file
+        let content = DiffPane::default().content(&messages);
+        assert_eq!(content.path.as_deref(), Some("./src/lib.rs"));
+        assert_eq!(content.edits, 2);
+        assert!(content
+            .lines
+            .iter()
+            .any(|line| matches!(line, DiffLine::HunkSeparator)));
+        assert!(!content
+            .lines
+            .iter()
+            .any(|line| matches!(line, DiffLine::Insert { text, .. } if text == "old turn")));
+
+        let pane = DiffPane {
+            path: Some("src/main.rs".to_string()),
+        };
+        assert_eq!(pane.content(&messages).edits, 1);
+    }
+
+    #[test]
+    fn test_split_collapses_on_narrow_terminals() {
+        assert_eq!(split(Rect::new(0, 0, MIN_SPLIT_WIDTH - 1, 10)), None);
+        let (main, pane) = split(Rect::new(0, 0, 120, 10)).unwrap();
+        assert_eq!((main.width, pane.x, pane.width), (71, 72, 48));
+    }
+
+    #[test]
+    fn test_pane_shows_the_end_of_long_diffs() {
+        let new_text: Vec<String> = (1..=40).map(|i| format!("line {i}")).collect();
+        let messages = vec![message(vec![edit("notes.txt", "", &new_text.join("\n"))])];
+        let content = DiffPane::default().content(&messages);
+        assert_eq!(content.height(), MAX_PANE_HEIGHT);
+
+        let area = Rect::new(0, 0, 40, 5);
+        let mut buf = Buffer::empty(area);
+        content.render(area, &mut buf);
+        let row = |y: u16| -> String {
+            (0..area.width)
+                .map(|x| buf[(x, y)].symbol().to_string())
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        };
+        assert_eq!(row(0), "± notes.txt (1 edit this turn)");
+        assert!(row(4).ends_with("+line 40"), "{}", row(4));
+    }
+}
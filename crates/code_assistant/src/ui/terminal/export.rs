@@ -0,0 +1,258 @@
+//! `/export`: write the committed transcript to a markdown or JSON file.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use super::message::{LiveMessage, MessageBlock};
+use super::redact::redact;
+use super::transcript_writer::message_to_markdown;
+use crate::ui::ToolStatus;
+
+/// Output format for `/export`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    Json,
+}
+
+impl ExportFormat {
+    fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "markdown" | "md" => Some(Self::Markdown),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// Parse `/export` arguments: an optional path and `--format <md|json>`.
+pub fn parse_export_args(args: &[&str]) -> Result<(Option<PathBuf>, ExportFormat), String> {
+    let mut format = ExportFormat::default();
+    let mut path_parts = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let value = if let Some(value) = arg.strip_prefix("--format=") {
+            value
+        } else if *arg == "--format" {
+            iter.next()
+                .copied()
+                .ok_or_else(|| "Missing value for --format".to_string())?
+        } else {
+            path_parts.push(*arg);
+            continue;
+        };
+        format = ExportFormat::parse(value)
+            .ok_or_else(|| format!("Unknown export format '{value}' (use markdown or json)"))?;
+    }
+
+    let path = (!path_parts.is_empty()).then(|| PathBuf::from(path_parts.join(" ")));
+    Ok((path, format))
+}
+
+/// Default export file name in the working directory.
+pub fn default_export_path(format: ExportFormat) -> PathBuf {
+    PathBuf::from(format!("transcript.{}", format.extension()))
+}
+
+/// Serializable snapshot of a committed message.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedMessage {
+    pub blocks: Vec<ExportedBlock>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExportedBlock {
+    UserText {
+        content: String,
+    },
+    Text {
+        content: String,
+    },
+    Thinking {
+        content: String,
+    },
+    System {
+        content: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        status: ToolStatus,
+        parameters: Vec<ExportedParameter>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        status_message: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+    },
+    Image {
+        media_type: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        width: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        height: Option<u32>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedParameter {
+    pub name: String,
+    pub value: String,
+}
+
+impl From<&LiveMessage> for ExportedMessage {
+    fn from(message: &LiveMessage) -> Self {
+        let blocks = message
+            .blocks
+            .iter()
+            .map(|block| match block {
+                MessageBlock::UserText(text) => ExportedBlock::UserText {
+                    content: text.content.clone(),
+                },
+                MessageBlock::PlainText(text) => ExportedBlock::Text {
+                    content: text.content.clone(),
+                },
+                MessageBlock::Thinking(thinking) => ExportedBlock::Thinking {
+                    content: thinking.content.clone(),
+                },
+                MessageBlock::SystemText(text) => ExportedBlock::System {
+                    content: text.content.clone(),
+                },
+                MessageBlock::Image(image) => ExportedBlock::Image {
+                    media_type: image.media_type.clone(),
+                    width: image.width,
+                    height: image.height,
+                },
+                MessageBlock::ToolUse(tool) => ExportedBlock::ToolUse {
+                    id: tool.id.clone(),
+                    name: tool.name.clone(),
+                    status: tool.status,
+                    parameters: tool
+                        .parameters
+                        .iter()
+                        .map(|(name, param)| ExportedParameter {
+                            name: name.clone(),
+                            value: param.value.clone(),
+                        })
+                        .collect(),
+                    status_message: tool.status_message.clone(),
+                    output: tool.output.clone(),
+                },
+            })
+            .collect();
+        Self { blocks }
+    }
+}
+
+impl ExportedMessage {
+    /// The message with secrets masked in every text field.
+    fn redacted(mut self) -> Self {
+        for block in &mut self.blocks {
+            match block {
+                ExportedBlock::UserText { content }
+                | ExportedBlock::Text { content }
+                | ExportedBlock::Thinking { content }
+                | ExportedBlock::System { content } => *content = redact(content),
+                ExportedBlock::ToolUse {
+                    parameters,
+                    status_message,
+                    output,
+                    ..
+                } => {
+                    for parameter in parameters {
+                        parameter.value = redact(&parameter.value);
+                    }
+                    for text in [status_message, output].into_iter().flatten() {
+                        *text = redact(text);
+                    }
+                }
+                ExportedBlock::Image { .. } => {}
+            }
+        }
+        self
+    }
+}
+
+/// Render committed messages in the requested format, secrets masked as
+/// on screen.
+pub fn render_export(messages: &[LiveMessage], format: ExportFormat) -> Result<String> {
+    match format {
+        ExportFormat::Markdown => Ok(messages
+            .iter()
+            .map(|message| redact(&message_to_markdown(message)))
+            .collect()),
+        ExportFormat::Json => {
+            let exported: Vec<ExportedMessage> = messages
+                .iter()
+                .map(|message| ExportedMessage::from(message).redacted())
+                .collect();
+            Ok(serde_json::to_string_pretty(&exported)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::terminal::message::{PlainTextBlock, ToolUseBlock};
+
+    #[test]
+    fn test_parse_export_args() {
+        assert_eq!(parse_export_args(&[]), Ok((None, ExportFormat::Markdown)));
+        assert_eq!(
+            parse_export_args(&["out/session.json", "--format", "json"]),
+            Ok((Some(PathBuf::from("out/session.json")), ExportFormat::Json))
+        );
+        assert_eq!(
+            parse_export_args(&["--format=md", "my", "notes.md"]),
+            Ok((Some(PathBuf::from("my notes.md")), ExportFormat::Markdown))
+        );
+        assert!(parse_export_args(&["--format", "html"]).is_err());
+        assert!(parse_export_args(&["--format"]).is_err());
+    }
+
+    #[test]
+    fn test_json_export_round_trips_blocks() {
+        let mut message = LiveMessage::new();
+        let mut text = PlainTextBlock::new();
+        text.content = "Reading the file".to_string();
+        message.add_block(MessageBlock::PlainText(text));
+        let mut tool = ToolUseBlock::new("read_files".to_string(), "tool-1".to_string());
+        tool.add_or_update_parameter("paths".to_string(), "src/lib.rs".to_string());
+        tool.status = ToolStatus::Success;
+        message.add_block(MessageBlock::ToolUse(tool));
+
+        let json = render_export(&[message], ExportFormat::Json).unwrap();
+        let parsed: Vec<ExportedMessage> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            parsed,
+            vec![ExportedMessage {
+                blocks: vec![
+                    ExportedBlock::Text {
+                        content: "Reading the file".to_string()
+                    },
+                    ExportedBlock::ToolUse {
+                        id: "tool-1".to_string(),
+                        name: "read_files".to_string(),
+                        status: ToolStatus::Success,
+                        parameters: vec![ExportedParameter {
+                            name: "paths".to_string(),
+                            value: "src/lib.rs".to_string()
+                        }],
+                        status_message: None,
+                        output: None,
+                    },
+                ],
+            }]
+        );
+    }
+}
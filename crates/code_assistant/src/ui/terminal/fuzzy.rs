@@ -0,0 +1,185 @@
+//! Fuzzy matching shared by the pickers: the model picker, the command
+//! palette and `@` path completion.
+//!
+//! A query matches when its characters appear in the candidate in order,
+//! ignoring case (whitespace in the query is ignored). Hits at the start of
+//! a word and runs of consecutive hits score higher; each candidate
+//! character skipped between two hits costs a little.
+
+use std::cmp::Reverse;
+
+/// Every matched character.
+const SCORE_MATCH: i32 = 16;
+/// A match on the first character of the candidate.
+const BONUS_FIRST_CHAR: i32 = 12;
+/// A match at the start of a word: after a separator or a lower-to-upper
+/// case change (`gpt-4o`, `src/main`, `ToolDetail`).
+const BONUS_BOUNDARY: i32 = 8;
+/// A match right after the previous one.
+const BONUS_CONSECUTIVE: i32 = 12;
+/// Per candidate character skipped between two matches.
+const PENALTY_GAP: i32 = 1;
+
+/// Score `candidate` against `query`: `None` if it doesn't match, otherwise
+/// the score (higher is better) and the char indices of the matched
+/// characters in `candidate`, for highlighting. An empty query matches
+/// everything with a score of 0.
+pub fn match_score(query: &str, candidate: &str) -> Option<(i32, Vec<usize>)> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+    let chars: Vec<char> = candidate.chars().collect();
+    let lower: Vec<char> = chars.iter().map(|&c| fold_case(c)).collect();
+    if query.len() > chars.len() {
+        return None;
+    }
+
+    // scores[i][j]: best score with query[..=i] matched and query[i] at
+    // candidate char j; parents[i][j] is where query[i - 1] matched then.
+    let mut scores: Vec<Vec<Option<i32>>> = vec![vec![None; chars.len()]; query.len()];
+    let mut parents: Vec<Vec<usize>> = vec![vec![0; chars.len()]; query.len()];
+    for (j, &c) in lower.iter().enumerate() {
+        if c == query[0] {
+            scores[0][j] = Some(SCORE_MATCH + bonus(&chars, j));
+        }
+    }
+    for i in 1..query.len() {
+        // Best earlier match k <= j - 2, as score + k * PENALTY_GAP so the
+        // gap penalty to any later j follows by subtraction
+        let mut best_before: Option<(i32, usize)> = None;
+        for j in 1..chars.len() {
+            if j >= 2 {
+                if let Some(score) = scores[i - 1][j - 2] {
+                    let value = score + (j as i32 - 2) * PENALTY_GAP;
+                    if best_before.is_none_or(|(best, _)| value > best) {
+                        best_before = Some((value, j - 2));
+                    }
+                }
+            }
+            if lower[j] != query[i] {
+                continue;
+            }
+            let consecutive = scores[i - 1][j - 1].map(|score| (score + BONUS_CONSECUTIVE, j - 1));
+            let gapped = best_before.map(|(value, k)| (value - (j as i32 - 1) * PENALTY_GAP, k));
+            let Some((previous, parent)) = [consecutive, gapped]
+                .into_iter()
+                .flatten()
+                .max_by_key(|(score, k)| (*score, *k))
+            else {
+                continue;
+            };
+            scores[i][j] = Some(previous + SCORE_MATCH + bonus(&chars, j));
+            parents[i][j] = parent;
+        }
+    }
+
+    let last = query.len() - 1;
+    let (mut j, score) = scores[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, score)| score.map(|score| (j, score)))
+        .max_by_key(|&(j, score)| (score, Reverse(j)))?;
+    let mut indices = vec![j; query.len()];
+    for i in (1..query.len()).rev() {
+        j = parents[i][j];
+        indices[i - 1] = j;
+    }
+    Some((score, indices))
+}
+
+/// Filter candidates by a fuzzy query, best matches first; equal scores
+/// keep their original order. An empty query keeps all candidates.
+pub fn fuzzy_filter<'a>(candidates: &'a [String], query: &str) -> Vec<&'a str> {
+    let query = query.trim();
+    if query.is_empty() {
+        return candidates.iter().map(String::as_str).collect();
+    }
+
+    let mut matches: Vec<(i32, &str)> = candidates
+        .iter()
+        .filter_map(|candidate| {
+            match_score(query, candidate).map(|(score, _)| (score, candidate.as_str()))
+        })
+        .collect();
+    matches.sort_by_key(|(score, _)| Reverse(*score));
+    matches
+        .into_iter()
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// Lowercase `c`, keeping one char per char so indices line up.
+fn fold_case(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+fn bonus(chars: &[char], j: usize) -> i32 {
+    let Some(&previous) = j.checked_sub(1).and_then(|k| chars.get(k)) else {
+        return BONUS_FIRST_CHAR;
+    };
+    let current = chars[j];
+    if !previous.is_alphanumeric() || (previous.is_lowercase() && current.is_uppercase()) {
+        BONUS_BOUNDARY
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranked<'a>(candidates: &'a [String], query: &str) -> Vec<&'a str> {
+        fuzzy_filter(candidates, query)
+    }
+
+    #[test]
+    fn test_word_boundaries_and_runs_rank_higher() {
+        let candidates = vec![
+            "configured-gadget".to_string(),
+            "great-output-4".to_string(),
+            "gpt-4o".to_string(),
+            "Claude Sonnet 4".to_string(),
+        ];
+        assert_eq!(
+            ranked(&candidates, "gpt4"),
+            vec!["gpt-4o", "great-output-4"]
+        );
+
+        let (gpt, _) = match_score("gpt4", "gpt-4o").unwrap();
+        let (great, _) = match_score("gpt4", "great-output-4").unwrap();
+        assert!(gpt > great, "{gpt} <= {great}");
+
+        let files = vec![
+            "src/ui/terminal/main.rs".to_string(),
+            "src/main.rs".to_string(),
+        ];
+        // Fewer skipped characters wins
+        assert_eq!(ranked(&files, "smain")[0], "src/main.rs");
+    }
+
+    #[test]
+    fn test_matching_ignores_case() {
+        assert_eq!(
+            match_score("SONNET", "Claude Sonnet 4").unwrap().1,
+            (7..13).collect::<Vec<_>>()
+        );
+        assert!(match_score("claude", "CLAUDE").is_some());
+        assert_eq!(match_score("td", "ToolDetail").unwrap().1, vec![0, 4]);
+        assert_eq!(match_score("gpt 5", "GPT-5").unwrap().1, vec![0, 1, 2, 4]);
+    }
+
+    #[test]
+    fn test_match_indices_prefer_boundaries() {
+        // `m` at the word start rather than inside `command`
+        assert_eq!(match_score("mr", "command/main.rs").unwrap().1, vec![8, 13]);
+        assert_eq!(match_score("", "anything"), Some((0, Vec::new())));
+        assert_eq!(match_score("xyz", "gpt-4o"), None);
+        assert_eq!(match_score("gpt-4o-mini", "gpt-4o"), None);
+    }
+}
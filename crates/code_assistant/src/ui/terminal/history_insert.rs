@@ -5,6 +5,7 @@
 use std::fmt;
 use std::io;
 use std::io::Write;
+use std::path::Path;
 
 use crossterm::cursor::MoveTo;
 use crossterm::queue;
@@ -26,12 +27,18 @@ use ratatui::text::Line;
 use ratatui::text::Span;
 use unicode_width::UnicodeWidthChar;
 
+use super::inline_image::{image_indent, ImageRow};
+use super::path_links::{path_links, PathLink};
+use super::theme::output_color;
+
 /// Insert `lines` above the viewport using ANSI scroll regions (DECSTBM).
 /// This pushes completed content into the native terminal scrollback without
-/// disturbing the viewport content below.
+/// disturbing the viewport content below. With a `link_root`, highlighted
+/// path references become OSC 8 hyperlinks to files under it.
 pub fn insert_history_lines<B>(
     terminal: &mut crate::ui::terminal::custom_terminal::Terminal<B>,
-    lines: Vec<Line>,
+    rows: Vec<HistoryRow>,
+    link_root: Option<&Path>,
 ) -> io::Result<()>
 where
     B: Backend + Write,
@@ -44,8 +51,27 @@ where
     let writer = terminal.backend_mut();
 
     // Pre-wrap lines so terminal scrollback sees properly formatted text.
-    let wrapped = wrap_lines_for_width_styled(&lines, area.width.max(1) as usize);
-    let wrapped_lines = wrapped.len() as u16;
+    // Images pass through whole and cover their own rows.
+    let width = area.width.max(1) as usize;
+    let wrapped: Vec<(HistoryRow, Vec<PathLink>)> = rows
+        .into_iter()
+        .flat_map(|row| match row {
+            HistoryRow::Text(line) => {
+                let links = link_root.map_or_else(Vec::new, |root| path_links(&line, root));
+                let rows = wrap_lines_for_width_styled(std::slice::from_ref(&line), width);
+                let row_links = split_links(&line, &rows, &links);
+                rows.into_iter()
+                    .map(HistoryRow::Text)
+                    .zip(row_links)
+                    .collect()
+            }
+            image => vec![(image, Vec::new())],
+        })
+        .collect();
+    let wrapped_lines = wrapped
+        .iter()
+        .map(|(row, _)| row.rows())
+        .fold(0u16, u16::saturating_add);
     let cursor_top = if area.bottom() < screen_size.height {
         // If the viewport is not at the bottom of the screen, scroll it down to make room.
         let scroll_amount = wrapped_lines.min(screen_size.height - area.bottom());
@@ -88,18 +114,30 @@ where
 
     queue!(writer, MoveTo(0, cursor_top))?;
 
-    for line in wrapped {
+    // The cursor moves down with each line until it reaches the bottom of
+    // the scroll region, where further lines scroll the region instead.
+    let region_bottom = area.top().saturating_sub(1);
+    let mut cursor_row = cursor_top;
+    for (row, links) in wrapped {
+        let line = match row {
+            HistoryRow::Text(line) => line,
+            HistoryRow::Image(image) => {
+                cursor_row = insert_image(writer, &image, cursor_row, region_bottom)?;
+                continue;
+            }
+        };
         queue!(writer, Print("\r\n"))?;
+        cursor_row = (cursor_row + 1).min(region_bottom);
         queue!(
             writer,
             SetColors(Colors::new(
                 line.style
                     .fg
-                    .map(std::convert::Into::into)
+                    .map(|color| output_color(color).into())
                     .unwrap_or(CColor::Reset),
                 line.style
                     .bg
-                    .map(std::convert::Into::into)
+                    .map(|color| output_color(color).into())
                     .unwrap_or(CColor::Reset)
             ))
         )?;
@@ -114,7 +152,7 @@ where
                 content: s.content.clone(),
             })
             .collect();
-        write_spans(writer, merged_spans.iter())?;
+        write_spans(writer, merged_spans.iter(), &links)?;
     }
 
     queue!(writer, ResetScrollRegion)?;
@@ -130,14 +168,123 @@ where
     Ok(())
 }
 
-fn write_spans<'a, I>(mut writer: &mut impl Write, content: I) -> io::Result<()>
+/// A row of history output: a line of text or an image.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HistoryRow {
+    Text(Line<'static>),
+    Image(ImageRow),
+}
+
+impl HistoryRow {
+    /// Screen rows this takes before wrapping.
+    fn rows(&self) -> u16 {
+        match self {
+            HistoryRow::Text(_) => 1,
+            HistoryRow::Image(image) => image.rows,
+        }
+    }
+
+    /// The text line, unless this is an image.
+    pub fn as_line(&self) -> Option<&Line<'static>> {
+        match self {
+            HistoryRow::Text(line) => Some(line),
+            HistoryRow::Image(_) => None,
+        }
+    }
+}
+
+impl From<Line<'static>> for HistoryRow {
+    fn from(line: Line<'static>) -> Self {
+        HistoryRow::Text(line)
+    }
+}
+
+/// Reserve the image's rows below `cursor_row` and draw it into them, or
+/// show its placeholder when the scroll region is too short. Returns the
+/// cursor row afterwards.
+fn insert_image(
+    writer: &mut impl Write,
+    image: &ImageRow,
+    mut cursor_row: u16,
+    region_bottom: u16,
+) -> io::Result<u16> {
+    for _ in 0..image.rows {
+        queue!(writer, Print("\r\n"), Clear(ClearType::UntilNewLine))?;
+        cursor_row = (cursor_row + 1).min(region_bottom);
+    }
+    let top = (cursor_row + 1).saturating_sub(image.rows);
+    if cursor_row + 1 >= image.rows {
+        queue!(writer, MoveTo(image_indent(), top), Print(&image.sequence))?;
+    } else {
+        queue!(
+            writer,
+            MoveTo(image_indent(), top),
+            Print(&image.placeholder)
+        )?;
+    }
+    queue!(writer, MoveTo(0, cursor_row))?;
+    Ok(cursor_row)
+}
+
+/// The links of `line` for each of its wrapped `rows`, relative to the
+/// row. Wrapping keeps every character except the newlines ending rows.
+fn split_links(line: &Line<'_>, rows: &[Line<'_>], links: &[PathLink]) -> Vec<Vec<PathLink>> {
+    if links.is_empty() {
+        return vec![Vec::new(); rows.len()];
+    }
+    let text: String = line
+        .spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect();
+    let mut offset = 0;
+    rows.iter()
+        .map(|row| {
+            let start = offset;
+            offset += row
+                .spans
+                .iter()
+                .map(|span| span.content.len())
+                .sum::<usize>();
+            let end = offset;
+            if text[offset..].starts_with('\n') {
+                offset += 1;
+            }
+            links
+                .iter()
+                .filter(|(range, _)| range.start < end && range.end > start)
+                .map(|(range, uri)| {
+                    let range = range.start.max(start) - start..range.end.min(end) - start;
+                    (range, uri.clone())
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn write_spans<'a, I>(mut writer: &mut impl Write, content: I, links: &[PathLink]) -> io::Result<()>
 where
     I: IntoIterator<Item = &'a Span<'a>>,
 {
     let mut fg = Color::Reset;
     let mut bg = Color::Reset;
     let mut last_modifier = Modifier::empty();
+    let mut offset = 0;
+    let mut open_link: Option<&str> = None;
     for span in content {
+        let start = offset;
+        offset += span.content.len();
+        let link = links
+            .iter()
+            .find(|(range, _)| range.contains(&start))
+            .map(|(_, uri)| uri.as_str());
+        if link != open_link {
+            queue!(
+                writer,
+                Print(format!("\x1b]8;;{}\x1b\\", link.unwrap_or("")))
+            )?;
+            open_link = link;
+        }
         let mut modifier = Modifier::empty();
         modifier.insert(span.style.add_modifier);
         modifier.remove(span.style.sub_modifier);
@@ -149,8 +296,8 @@ where
             diff.queue(&mut writer)?;
             last_modifier = modifier;
         }
-        let next_fg = span.style.fg.unwrap_or(Color::Reset);
-        let next_bg = span.style.bg.unwrap_or(Color::Reset);
+        let next_fg = output_color(span.style.fg.unwrap_or(Color::Reset));
+        let next_bg = output_color(span.style.bg.unwrap_or(Color::Reset));
         if next_fg != fg || next_bg != bg {
             queue!(
                 writer,
@@ -162,6 +309,9 @@ where
 
         queue!(writer, Print(span.content.clone()))?;
     }
+    if open_link.is_some() {
+        queue!(writer, Print("\x1b]8;;\x1b\\"))?;
+    }
 
     queue!(
         writer,
@@ -411,6 +561,83 @@ fn line_to_plain(line: &Line<'_>) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn image_rows_are_reserved_before_drawing() {
+        let image = ImageRow {
+            rows: 3,
+            placeholder: "[image 1x1 (image/png)]".to_string(),
+            sequence: "<img>".to_string(),
+        };
+        let mut out = Vec::new();
+        // Region rows 0..=9, cursor at its bottom: the image takes rows 7..=9
+        assert_eq!(insert_image(&mut out, &image, 9, 9).unwrap(), 9);
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.matches("\r\n").count(), 3);
+        assert!(out.ends_with("\x1b[8;3H<img>\x1b[10;1H"), "{out:?}");
+
+        // A region shorter than the image shows the placeholder
+        let mut out = Vec::new();
+        insert_image(&mut out, &image, 0, 1).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("[image 1x1 (image/png)]"));
+        assert!(!out.contains("<img>"));
+    }
+
+    #[test]
+    fn no_color_mode_writes_no_colors() {
+        let spans = [Span::styled(
+            "code",
+            ratatui::style::Style::default()
+                .fg(Color::Cyan)
+                .bg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )];
+        crate::ui::terminal::theme::set_monochrome(true);
+        let mut out = Vec::new();
+        write_spans(&mut out, spans.iter(), &[]).unwrap();
+        crate::ui::terminal::theme::set_monochrome(false);
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("\x1b[1m"), "{out:?}");
+        assert!(
+            !out.contains("\x1b[38;5;") && !out.contains("\x1b[36m"),
+            "{out:?}"
+        );
+        assert!(
+            !out.contains("\x1b[40m") && !out.contains("\x1b[48;5;"),
+            "{out:?}"
+        );
+    }
+
+    #[test]
+    fn links_follow_wrapped_rows() {
+        let line = Line::from(vec![
+            Span::raw("see "),
+            Span::raw("src/a.rs"),
+            Span::raw("\nok"),
+        ]);
+        let links = vec![(4..12, "file:///w/src/a.rs".to_string())];
+        let rows = wrap_lines_for_width_styled(std::slice::from_ref(&line), 8);
+        assert_eq!(
+            split_links(&line, &rows, &links),
+            vec![
+                vec![(4..8, "file:///w/src/a.rs".to_string())],
+                vec![(0..4, "file:///w/src/a.rs".to_string())],
+                vec![],
+            ]
+        );
+
+        let mut out = Vec::new();
+        let spans = [Span::raw("see "), Span::raw("src/"), Span::raw(" ")];
+        let row_links = vec![(4..8, "file:///w/src/a.rs".to_string())];
+        write_spans(&mut out, spans.iter(), &row_links).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(
+            out.contains("see \x1b]8;;file:///w/src/a.rs\x1b\\src/\x1b]8;;\x1b\\ "),
+            "{out:?}"
+        );
+    }
+
     #[test]
     fn styled_wrap_preserves_input_line_boundaries() {
         let lines = vec![Line::from("ab"), Line::from("cd")];
@@ -0,0 +1,339 @@
+//! Images drawn inline in the scrollback on terminals that speak the Kitty
+//! or iTerm2 graphics protocol.
+//!
+//! The protocol is detected once at startup from the environment. An image
+//! travels through the history stream as its own row carrying its escape
+//! sequence and the number of rows it covers; history insertion reserves
+//! those rows and draws the image into them. The live viewport,
+//! exports and terminals without graphics support show a placeholder instead.
+
+use std::io::Cursor;
+use std::sync::OnceLock;
+
+use base64::Engine;
+use ratatui::prelude::*;
+
+use super::config::TerminalConfig;
+use super::history_insert::HistoryRow;
+use super::message::ImageBlock;
+use super::theme::Theme;
+
+/// Rows an image may cover in the scrollback; taller images are scaled down.
+pub const MAX_IMAGE_ROWS: u16 = 20;
+
+/// Columns left of an image, matching the indent of message text.
+pub fn image_indent() -> u16 {
+    Theme::global().gutter_width() as u16
+}
+
+/// Cell size assumed when the terminal doesn't report its pixel size.
+#[cfg(not(test))]
+const DEFAULT_CELL_PIXELS: (u16, u16) = (8, 16);
+
+/// Base64 bytes per Kitty graphics escape sequence.
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+/// Graphics protocols images can be drawn with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    /// Kitty graphics protocol (kitty, Ghostty)
+    Kitty,
+    /// iTerm2 inline images (iTerm2, WezTerm)
+    Iterm2,
+}
+
+impl GraphicsProtocol {
+    /// The protocol the terminal supports, from the values of `TERM`,
+    /// `TERM_PROGRAM`, `KITTY_WINDOW_ID` and `TMUX`. Inside tmux images
+    /// would need passthrough, so none is used.
+    pub fn from_env_values(
+        term: Option<&str>,
+        term_program: Option<&str>,
+        kitty_window_id: Option<&str>,
+        tmux: Option<&str>,
+    ) -> Option<Self> {
+        if tmux.is_some_and(|value| !value.is_empty()) {
+            return None;
+        }
+        let term = term.unwrap_or_default();
+        let term_program = term_program.unwrap_or_default().to_lowercase();
+        if term.contains("kitty")
+            || term.contains("ghostty")
+            || term_program == "ghostty"
+            || kitty_window_id.is_some_and(|value| !value.is_empty())
+        {
+            return Some(GraphicsProtocol::Kitty);
+        }
+        match term_program.as_str() {
+            "iterm.app" | "wezterm" => Some(GraphicsProtocol::Iterm2),
+            _ => None,
+        }
+    }
+}
+
+/// How images are drawn on this terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Graphics {
+    pub protocol: GraphicsProtocol,
+    /// Width and height of a cell in pixels
+    pub cell_pixels: (u16, u16),
+}
+
+static GRAPHICS: OnceLock<Option<Graphics>> = OnceLock::new();
+
+/// Read and cache the graphics support. Call once at startup.
+pub fn init() {
+    graphics();
+}
+
+/// The graphics support detected at startup; `None` shows placeholders,
+/// as does turning `inline_images` off.
+pub fn graphics() -> Option<Graphics> {
+    *GRAPHICS.get_or_init(|| {
+        if TerminalConfig::global().inline_images {
+            detect()
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(not(test))]
+fn detect() -> Option<Graphics> {
+    let var = |name| std::env::var(name).ok();
+    let protocol = GraphicsProtocol::from_env_values(
+        var("TERM").as_deref(),
+        var("TERM_PROGRAM").as_deref(),
+        var("KITTY_WINDOW_ID").as_deref(),
+        var("TMUX").as_deref(),
+    )?;
+    let cell_pixels = ratatui::crossterm::terminal::window_size()
+        .ok()
+        .filter(|size| size.columns > 0 && size.rows > 0 && size.width > 0 && size.height > 0)
+        .map_or(DEFAULT_CELL_PIXELS, |size| {
+            (size.width / size.columns, size.height / size.rows)
+        });
+    Some(Graphics {
+        protocol,
+        cell_pixels,
+    })
+}
+
+// Tests must not depend on the terminal they run in
+#[cfg(test)]
+fn detect() -> Option<Graphics> {
+    None
+}
+
+/// An image ready to be drawn into the scrollback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageRow {
+    /// Rows the image covers
+    pub rows: u16,
+    /// Shown instead when the rows don't fit on screen
+    pub placeholder: String,
+    /// Escape sequence drawing the image at the cursor
+    pub sequence: String,
+}
+
+/// The scrollback row for `image`: the image itself when the terminal can
+/// draw it, otherwise its placeholder.
+pub fn history_row(image: &ImageBlock, width: u16) -> HistoryRow {
+    let max_cols = width.saturating_sub(image_indent());
+    match graphics().and_then(|graphics| image_row(image, graphics, max_cols)) {
+        Some(row) => HistoryRow::Image(row),
+        None => HistoryRow::Text(placeholder_line(image)),
+    }
+}
+
+/// The placeholder for `image`, indented like message text.
+pub fn placeholder_line(image: &ImageBlock) -> Line<'static> {
+    Line::from(vec![
+        Span::raw(Theme::global().gutter()),
+        Span::styled(
+            image.placeholder(),
+            Style::default().fg(Theme::global().muted_fg),
+        ),
+    ])
+}
+
+/// Size and encode `image` for `graphics`, or `None` when it can't be drawn.
+pub fn image_row(image: &ImageBlock, graphics: Graphics, max_cols: u16) -> Option<ImageRow> {
+    let (width, height) = image.width.zip(image.height)?;
+    let (cols, rows) = cell_extent(width, height, graphics.cell_pixels, max_cols)?;
+    let sequence = match graphics.protocol {
+        GraphicsProtocol::Kitty => kitty_sequence(&png_data(image)?, cols, rows),
+        GraphicsProtocol::Iterm2 => iterm2_sequence(&image.data, cols, rows),
+    };
+    Some(ImageRow {
+        rows,
+        placeholder: image.placeholder(),
+        sequence,
+    })
+}
+
+/// Columns and rows an image of `width`x`height` pixels covers at its
+/// natural size, scaled down to fit `max_cols` and [`MAX_IMAGE_ROWS`].
+pub fn cell_extent(
+    width: u32,
+    height: u32,
+    cell_pixels: (u16, u16),
+    max_cols: u16,
+) -> Option<(u16, u16)> {
+    let (cell_width, cell_height) = (
+        f64::from(cell_pixels.0.max(1)),
+        f64::from(cell_pixels.1.max(1)),
+    );
+    if width == 0 || height == 0 || max_cols == 0 {
+        return None;
+    }
+    let (width, height) = (f64::from(width), f64::from(height));
+    let scale = (f64::from(max_cols) * cell_width / width)
+        .min(f64::from(MAX_IMAGE_ROWS) * cell_height / height)
+        .min(1.0);
+    let cols = (width * scale / cell_width)
+        .ceil()
+        .clamp(1.0, f64::from(max_cols));
+    let rows = (height * scale / cell_height)
+        .ceil()
+        .clamp(1.0, f64::from(MAX_IMAGE_ROWS));
+    Some((cols as u16, rows as u16))
+}
+
+/// The image as base64 PNG, which is what Kitty accepts directly.
+fn png_data(image: &ImageBlock) -> Option<String> {
+    if image.media_type == "image/png" {
+        return Some(image.data.clone());
+    }
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(&image.data)
+        .ok()?;
+    let decoded = image::load_from_memory(&bytes).ok()?;
+    let mut png = Cursor::new(Vec::new());
+    decoded.write_to(&mut png, image::ImageFormat::Png).ok()?;
+    Some(base64::engine::general_purpose::STANDARD.encode(png.into_inner()))
+}
+
+/// Kitty transmit-and-display commands for base64 PNG `data`, split into
+/// chunks. The cursor stays put (`C=1`) and replies are suppressed (`q=2`).
+fn kitty_sequence(data: &str, cols: u16, rows: u16) -> String {
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+    let mut sequence = String::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(index + 1 < chunks.len());
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if index == 0 {
+            sequence.push_str(&format!(
+                "\x1b_Ga=T,f=100,t=d,c={cols},r={rows},C=1,q=2,m={more};{chunk}\x1b\\"
+            ));
+        } else {
+            sequence.push_str(&format!("\x1b_Gm={more};{chunk}\x1b\\"));
+        }
+    }
+    sequence
+}
+
+/// iTerm2 inline image command for base64 `data` in any format it reads.
+fn iterm2_sequence(data: &str, cols: u16, rows: u16) -> String {
+    let size = data.len() / 4 * 3;
+    format!(
+        "\x1b]1337;File=inline=1;size={size};width={cols};height={rows};preserveAspectRatio=1:{data}\x07"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png_image(width: u32, height: u32) -> ImageBlock {
+        let mut png = Cursor::new(Vec::new());
+        image::RgbaImage::new(width, height)
+            .write_to(&mut png, image::ImageFormat::Png)
+            .unwrap();
+        ImageBlock::new(
+            "image/png".to_string(),
+            base64::engine::general_purpose::STANDARD.encode(png.into_inner()),
+        )
+    }
+
+    #[test]
+    fn test_protocol_from_env() {
+        let detect = GraphicsProtocol::from_env_values;
+        assert_eq!(
+            detect(Some("xterm-kitty"), None, None, None),
+            Some(GraphicsProtocol::Kitty)
+        );
+        assert_eq!(
+            detect(Some("xterm-256color"), Some("ghostty"), None, None),
+            Some(GraphicsProtocol::Kitty)
+        );
+        assert_eq!(
+            detect(Some("xterm-256color"), Some("iTerm.app"), None, None),
+            Some(GraphicsProtocol::Iterm2)
+        );
+        assert_eq!(
+            detect(Some("xterm-256color"), Some("WezTerm"), None, None),
+            Some(GraphicsProtocol::Iterm2)
+        );
+        assert_eq!(
+            detect(Some("xterm-256color"), Some("Apple_Terminal"), None, None),
+            None
+        );
+        assert_eq!(
+            detect(Some("xterm-kitty"), None, None, Some("/tmp/tmux")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cell_extent_scales_down_to_fit() {
+        // Natural size
+        assert_eq!(cell_extent(80, 64, (8, 16), 100), Some((10, 4)));
+        // Too wide: keeps the aspect ratio
+        assert_eq!(cell_extent(1600, 320, (8, 16), 100), Some((100, 10)));
+        // Too tall
+        assert_eq!(
+            cell_extent(160, 1600, (8, 16), 100),
+            Some((4, MAX_IMAGE_ROWS))
+        );
+        assert_eq!(cell_extent(1, 1, (8, 16), 100), Some((1, 1)));
+        assert_eq!(cell_extent(0, 10, (8, 16), 100), None);
+    }
+
+    #[test]
+    fn test_image_row_sequences() {
+        let image = png_image(16, 32);
+        let kitty = Graphics {
+            protocol: GraphicsProtocol::Kitty,
+            cell_pixels: (8, 16),
+        };
+        let row = image_row(&image, kitty, 80).unwrap();
+        assert_eq!(row.rows, 2);
+        assert_eq!(row.placeholder, "[image 16x32 (image/png)]");
+        assert!(row
+            .sequence
+            .starts_with("\x1b_Ga=T,f=100,t=d,c=2,r=2,C=1,q=2,m=0;"));
+        assert!(row.sequence.ends_with(&format!("{}\x1b\\", image.data)));
+
+        let iterm = Graphics {
+            protocol: GraphicsProtocol::Iterm2,
+            ..kitty
+        };
+        let row = image_row(&image, iterm, 80).unwrap();
+        assert!(row.sequence.starts_with("\x1b]1337;File=inline=1;"));
+        assert!(row.sequence.contains(";width=2;height=2;"));
+
+        // Undecodable data has no size and stays a placeholder
+        let broken = ImageBlock::new("image/png".to_string(), "bm90IGFuIGltYWdl".to_string());
+        assert_eq!(image_row(&broken, kitty, 80), None);
+    }
+
+    #[test]
+    fn test_kitty_sequence_is_chunked() {
+        let data = "A".repeat(KITTY_CHUNK_SIZE * 2 + 10);
+        let sequence = kitty_sequence(&data, 4, 2);
+        assert_eq!(sequence.matches("\x1b_G").count(), 3);
+        assert_eq!(sequence.matches("m=1;").count(), 2);
+        assert!(sequence.contains("\x1b_Gm=0;AAAAAAAAAA\x1b\\"));
+    }
+}
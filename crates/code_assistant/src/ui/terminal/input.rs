@@ -1,16 +1,22 @@
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use base64::Engine;
+use llm::recording::PlaybackAction;
 use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
 use tracing::debug;
 
 use crate::persistence::DraftAttachment;
 
 use super::commands::{CommandProcessor, CommandResult};
+use super::config::TerminalConfig;
+use super::copy::CopySelector;
+use super::export::ExportFormat;
+use super::path_completion::{mention_at_cursor, PathCompletion, PathIndex};
 use super::textarea::TextArea;
-
-/// Threshold in characters above which pasted text is collapsed into a placeholder.
-const LARGE_PASTE_CHAR_THRESHOLD: usize = 200;
+use super::tool_renderers::tools_info;
 
 /// Result of handling a key event
 #[derive(Debug)]
@@ -19,21 +25,120 @@ pub enum KeyEventResult {
     Continue,
     /// Quit the application
     Quit,
+    /// Cancel the running turn (Ctrl+C while the agent works)
+    CancelTurn,
     /// Submit a message with optional attachments
     SendMessage {
         message: String,
         attachments: Vec<DraftAttachment>,
     },
+    /// Redirect the running turn with the composer text (sent normally when idle)
+    SteerMessage {
+        message: String,
+        attachments: Vec<DraftAttachment>,
+    },
     /// Escape key was pressed - main loop decides what to do
     Escape,
     /// Display information message
     ShowInfo(String),
     /// Switch to a different model
     SwitchModel(String),
+    /// Open the interactive model picker
+    OpenModelPicker,
     /// Show current model information
     ShowCurrentModel,
     /// Toggle plan rendering mode
     TogglePlan,
+    /// Toggle no-color (monochrome) mode
+    ToggleNoColor,
+    /// Expand or collapse the most recent thinking block
+    ToggleThinking,
+    /// Switch all tools between full and compact rendering
+    ToggleToolDetail,
+    /// Move the highlight to the previous or next tool of the live message
+    FocusTool { forward: bool },
+    /// Expand or collapse the highlighted tool
+    ToggleFocusedTool,
+    /// Print the full parameters of the highlighted or latest tool
+    InspectTool,
+    /// Ask the agent to run the last executed shell command again
+    RerunLastCommand,
+    /// Write the transcript to a file
+    ExportTranscript {
+        path: Option<PathBuf>,
+        format: ExportFormat,
+    },
+    /// Clear the transcript and scrollback, optionally starting a new session
+    ClearScreen { new_session: bool },
+    /// Print the welcome banner to scrollback
+    ShowWelcome,
+    /// Copy part of the committed transcript to the clipboard
+    Copy { selector: CopySelector },
+    /// Open the most recently mentioned file path in the user's editor
+    OpenLastPath,
+    /// Show or hide line numbers in the composer
+    ToggleLineNumbers,
+    /// Show, retarget or hide the diff pane; `None` follows the latest edited file
+    ToggleDiffPane { path: Option<String> },
+    /// Pause, step or change the speed of a recording being played back
+    Playback(PlaybackAction),
+    /// Open the command palette
+    OpenCommandPalette,
+    /// The user is reading back; hold new output per the auto-scroll setting
+    StopFollowing,
+    /// Write held output and follow new output again
+    JumpToLatest,
+}
+
+/// How long a Ctrl+C that cancelled a turn arms the next one to quit.
+const QUIT_CONFIRM_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How a large paste is labelled in the composer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PastePlaceholderFormat {
+    /// `[Pasted 1200 chars]`
+    #[default]
+    Chars,
+    /// `[Pasted 40 lines]`
+    Lines,
+    /// `[Pasted: fn main() {…]`, the start of the first non-blank line
+    Preview,
+}
+
+/// Shown when an image is pasted onto a recalled message, which is resent
+/// as text only.
+const RECALLED_IMAGE_MESSAGE: &str =
+    "Images can't be attached to a recalled message; press Down to return to the draft";
+
+/// Characters of the first line shown by [`PastePlaceholderFormat::Preview`].
+const PASTE_PREVIEW_CHARS: usize = 24;
+
+impl PastePlaceholderFormat {
+    /// The placeholder label for `pasted` (without a `#N` suffix).
+    pub fn placeholder(self, pasted: &str) -> String {
+        match self {
+            PastePlaceholderFormat::Chars => {
+                format!("[Pasted {} chars]", pasted.chars().count())
+            }
+            PastePlaceholderFormat::Lines => match pasted.lines().count() {
+                1 => "[Pasted 1 line]".to_string(),
+                count => format!("[Pasted {count} lines]"),
+            },
+            PastePlaceholderFormat::Preview => {
+                let mut lines = pasted
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty());
+                let first_line = lines.next().unwrap_or_default();
+                let mut preview: String = first_line.chars().take(PASTE_PREVIEW_CHARS).collect();
+                if preview.len() < first_line.len() || lines.next().is_some() {
+                    preview.push('…');
+                }
+                format!("[Pasted: {preview}]")
+            }
+        }
+    }
 }
 
 /// Manages the input area using the custom TextArea widget
@@ -42,35 +147,339 @@ pub struct InputManager {
     command_processor: Option<CommandProcessor>,
     /// Attachments accumulated from paste operations (images).
     pub attachments: Vec<DraftAttachment>,
+    /// Element text labelling each entry of `attachments`, by index.
+    attachment_elements: Vec<String>,
     /// Counter for image paste placeholders.
     image_counter: usize,
     /// Map from placeholder text to the actual pasted content (for large text pastes).
     pending_pastes: Vec<(String, String)>,
-    /// Counters for generating unique large-paste placeholders (keyed by char_count).
-    large_paste_counters: HashMap<usize, usize>,
+    /// Counters for generating unique large-paste placeholders (keyed by label).
+    large_paste_counters: HashMap<String, usize>,
+    /// Pastes above either limit are collapsed into a placeholder element.
+    paste_char_threshold: usize,
+    paste_line_threshold: usize,
+    paste_placeholder_format: PastePlaceholderFormat,
+    /// Whether plain Enter submits (otherwise Shift/Alt+Enter does).
+    submit_on_enter: bool,
+    /// Trim trailing whitespace from submitted messages.
+    trim_trailing_whitespace: bool,
+    /// Files offered for `@` completion; `None` disables it.
+    path_index: Option<PathIndex>,
+    /// The open `@` completion list, if any.
+    path_completion: Option<PathCompletion>,
+    /// Start of an `@` token whose completion was dismissed with Esc.
+    dismissed_mention: Option<usize>,
+    /// Space, Right and +/- drive playback while the composer is empty.
+    playback_controls: bool,
+    /// Messages sent this session, oldest first, for Up/Down recall.
+    sent_messages: Vec<String>,
+    /// The sent message shown in the composer, if any.
+    recall: Option<Recall>,
+    /// Whether the agent is working on a turn, so Ctrl+C cancels it.
+    turn_active: bool,
+    /// Set by a Ctrl+C that cancelled a turn; another one soon after quits.
+    quit_armed_at: Option<Instant>,
+}
+
+/// A sent message recalled into the composer with Up, and the draft it
+/// replaced. The draft is kept whole (text, cursor and elements) so that
+/// returning to it with Down puts the caret back where it was.
+struct Recall {
+    index: usize,
+    draft: TextArea,
 }
 
 impl InputManager {
     pub fn new() -> Self {
         let command_processor = CommandProcessor::new().ok();
+        let mut textarea = TextArea::new();
+        textarea.set_word_separators(TerminalConfig::global().word_separators);
+        textarea.set_wrap_mode(TerminalConfig::global().composer_wrap);
         Self {
-            textarea: TextArea::new(),
+            textarea,
             command_processor,
             attachments: Vec::new(),
+            attachment_elements: Vec::new(),
             image_counter: 0,
             pending_pastes: Vec::new(),
             large_paste_counters: HashMap::new(),
+            paste_char_threshold: TerminalConfig::global().paste_placeholder_chars,
+            paste_line_threshold: TerminalConfig::global().paste_placeholder_lines,
+            paste_placeholder_format: TerminalConfig::global().paste_placeholder_format,
+            submit_on_enter: TerminalConfig::global().submit_on_enter,
+            trim_trailing_whitespace: TerminalConfig::global().trim_trailing_whitespace,
+            path_index: None,
+            path_completion: None,
+            dismissed_mention: None,
+            playback_controls: false,
+            sent_messages: Vec::new(),
+            recall: None,
+            turn_active: false,
+            quit_armed_at: None,
+        }
+    }
+
+    /// Offer `@` completion of files under `root`. `on_update` is notified
+    /// when a background scan finishes.
+    pub fn enable_path_completion(
+        &mut self,
+        root: PathBuf,
+        on_update: tokio::sync::watch::Sender<()>,
+    ) {
+        self.path_index = Some(PathIndex::new(root, Some(on_update)));
+    }
+
+    /// Map playback keys to playback actions (when replaying a recording).
+    pub fn enable_playback_controls(&mut self) {
+        self.playback_controls = true;
+    }
+
+    /// The draft's text and attachment labels, to tell whether a key
+    /// changed it.
+    pub fn draft_snapshot(&self) -> (String, Vec<String>) {
+        (
+            self.textarea.text().to_string(),
+            self.attachment_elements.clone(),
+        )
+    }
+
+    /// Tell the composer whether a turn is running, which decides what
+    /// Ctrl+C does.
+    pub fn set_turn_active(&mut self, active: bool) {
+        self.turn_active = active;
+    }
+
+    /// Time left at `now` in which a second Ctrl+C quits, if a cancellation
+    /// armed it.
+    pub fn quit_confirm_remaining(&self, now: Instant) -> Option<Duration> {
+        self.quit_armed_at
+            .map(|armed| QUIT_CONFIRM_TIMEOUT.saturating_sub(now.saturating_duration_since(armed)))
+    }
+
+    /// Forget an armed quit once its confirmation window has passed.
+    pub fn expire_quit_confirm(&mut self) {
+        self.quit_armed_at = None;
+    }
+
+    /// Ctrl+C cancels a running turn and quits otherwise. A second Ctrl+C
+    /// shortly after a cancellation quits even if the turn is still
+    /// winding down.
+    fn handle_ctrl_c(&mut self, now: Instant) -> KeyEventResult {
+        let quit_confirmed = self
+            .quit_armed_at
+            .take()
+            .is_some_and(|armed| now.saturating_duration_since(armed) < QUIT_CONFIRM_TIMEOUT);
+        if self.turn_active && !quit_confirmed {
+            self.quit_armed_at = Some(now);
+            KeyEventResult::CancelTurn
+        } else {
+            KeyEventResult::Quit
         }
     }
 
     /// Handle a key event and return the appropriate result
     pub fn handle_key_event(&mut self, key_event: KeyEvent) -> KeyEventResult {
+        if let Some(result) = self.handle_completion_key(key_event) {
+            return result;
+        }
+        if let Some(action) = self.playback_action(key_event) {
+            return KeyEventResult::Playback(action);
+        }
+        let result = self.dispatch_key_event(key_event);
+        self.update_path_completion();
+        self.prune_detached_attachments();
+        result
+    }
+
+    /// Keys that drive an open completion list. Returns `None` for keys the
+    /// composer should handle as usual.
+    fn handle_completion_key(&mut self, key_event: KeyEvent) -> Option<KeyEventResult> {
+        let completion = self.path_completion.as_mut()?;
+        let index = self.path_index.as_ref()?;
+        let matches = completion.matches(&index.files());
+        match key_event.code {
+            KeyCode::Up => completion.select_previous(),
+            KeyCode::Down => completion.select_next(matches.len()),
+            KeyCode::Esc => {
+                self.dismissed_mention = Some(completion.range.start);
+                self.path_completion = None;
+            }
+            KeyCode::Tab | KeyCode::Enter if key_event.modifiers == KeyModifiers::NONE => {
+                let path = matches.get(completion.selected)?.clone();
+                let range = completion.range.clone();
+                self.path_completion = None;
+                self.textarea.replace_range(range.clone(), "");
+                self.textarea.set_cursor(range.start);
+                self.textarea.insert_element(&path);
+                self.textarea.insert_str(" ");
+            }
+            _ => return None,
+        }
+        Some(KeyEventResult::Continue)
+    }
+
+    /// The playback action for `key_event`. Only an empty composer takes
+    /// playback keys, so a message can still be typed normally.
+    fn playback_action(&self, key_event: KeyEvent) -> Option<PlaybackAction> {
+        if !self.playback_controls || !self.textarea.is_empty() {
+            return None;
+        }
+        if key_event
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            return None;
+        }
+        match key_event.code {
+            KeyCode::Char(' ') => Some(PlaybackAction::TogglePause),
+            KeyCode::Right => Some(PlaybackAction::Step),
+            KeyCode::Char('+') | KeyCode::Char('=') => Some(PlaybackAction::Faster),
+            KeyCode::Char('-') => Some(PlaybackAction::Slower),
+            _ => None,
+        }
+    }
+
+    /// Open, update or close the completion list for the `@` token at the cursor.
+    fn update_path_completion(&mut self) {
+        let Some(index) = self.path_index.as_mut() else {
+            return;
+        };
+        let Some(range) = mention_at_cursor(self.textarea.text(), self.textarea.cursor()) else {
+            self.path_completion = None;
+            self.dismissed_mention = None;
+            return;
+        };
+        if self.dismissed_mention == Some(range.start) {
+            return;
+        }
+        self.dismissed_mention = None;
+
+        index.refresh();
+        let query = self.textarea.text()[range.start + 1..range.end].to_string();
+        let selected = match &self.path_completion {
+            Some(previous) if previous.query == query => previous.selected,
+            _ => 0,
+        };
+        self.path_completion = Some(PathCompletion {
+            range,
+            query,
+            selected,
+        });
+    }
+
+    /// Status-area text for the open `@` completion list.
+    pub fn path_completion_text(&self) -> Option<String> {
+        let completion = self.path_completion.as_ref()?;
+        let index = self.path_index.as_ref()?;
+        Some(completion.to_status_text(&index.files(), index.is_scanning()))
+    }
+
+    fn dispatch_key_event(&mut self, key_event: KeyEvent) -> KeyEventResult {
         match key_event {
             KeyEvent {
                 code: KeyCode::Char('c'),
                 modifiers: KeyModifiers::CONTROL,
                 ..
-            } => KeyEventResult::Quit,
+            } => self.handle_ctrl_c(Instant::now()),
+            // With a draft in the composer Ctrl+T transposes characters instead
+            KeyEvent {
+                code: KeyCode::Char('t'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } if self.textarea.is_empty() => KeyEventResult::ToggleThinking,
+            // PageUp/End only steer following when there's no draft to move through
+            KeyEvent {
+                code: KeyCode::PageUp,
+                modifiers: KeyModifiers::NONE,
+                ..
+            } if self.textarea.is_empty() => KeyEventResult::StopFollowing,
+            KeyEvent {
+                code: KeyCode::End,
+                modifiers: KeyModifiers::NONE,
+                ..
+            } if self.textarea.is_empty() => KeyEventResult::JumpToLatest,
+            // Likewise Ctrl+P only opens the palette when there's no draft to move through
+            KeyEvent {
+                code: KeyCode::Char('p'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } if self.textarea.is_empty() => KeyEventResult::OpenCommandPalette,
+            // Up on the first line and Down on the last step through sent messages
+            KeyEvent {
+                code: KeyCode::Up,
+                modifiers: KeyModifiers::NONE,
+                ..
+            } if self.cursor_on_first_line() && self.recall_previous() => KeyEventResult::Continue,
+            KeyEvent {
+                code: KeyCode::Down,
+                modifiers: KeyModifiers::NONE,
+                ..
+            } if self.cursor_on_last_line() && self.recall_next() => KeyEventResult::Continue,
+            // Slash commands aren't messages, so they can't steer
+            KeyEvent {
+                code: KeyCode::Char('s'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } if !self.textarea.is_empty() && !self.textarea.text().starts_with('/') => {
+                let message = self.submit_content();
+                if message.is_empty() {
+                    return KeyEventResult::Continue;
+                }
+                let attachments = self.take_sent(&message, false);
+                KeyEventResult::SteerMessage {
+                    message,
+                    attachments,
+                }
+            }
+            KeyEvent {
+                code: KeyCode::Char('r'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => KeyEventResult::RerunLastCommand,
+            KeyEvent {
+                code: KeyCode::Char('o'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => KeyEventResult::ToggleToolDetail,
+            KeyEvent {
+                code: KeyCode::Up,
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => KeyEventResult::FocusTool { forward: false },
+            KeyEvent {
+                code: KeyCode::Down,
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => KeyEventResult::FocusTool { forward: true },
+            KeyEvent {
+                code: KeyCode::Char('o'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => KeyEventResult::ToggleFocusedTool,
+            // While a region is marked Ctrl+G is left to the textarea, which
+            // cancels the mark
+            KeyEvent {
+                code: KeyCode::Char('g'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } if self.textarea.mark().is_none() => KeyEventResult::OpenLastPath,
+            KeyEvent {
+                code: KeyCode::Char('l'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => KeyEventResult::ToggleLineNumbers,
+            // A lone `@path` mention in the composer picks the file to show
+            KeyEvent {
+                code: KeyCode::Char('x'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => {
+                let path = self.diff_pane_target();
+                if path.is_some() {
+                    self.clear();
+                }
+                KeyEventResult::ToggleDiffPane { path }
+            }
             // Ctrl-V / Alt-V: try to paste an image from clipboard.
             // On macOS, Cmd-V is handled by the terminal and produces Event::Paste for text.
             // Ctrl-V lets users explicitly paste clipboard images (which don't produce Paste events).
@@ -79,6 +488,9 @@ impl InputManager {
                 modifiers,
                 ..
             } if modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+                if self.recall.is_some() {
+                    return KeyEventResult::ShowInfo(RECALLED_IMAGE_MESSAGE.to_string());
+                }
                 if !self.try_paste_clipboard_image() {
                     debug!("No clipboard image found on Ctrl/Alt-V");
                 }
@@ -91,55 +503,16 @@ impl InputManager {
             } => KeyEventResult::Escape,
             KeyEvent {
                 code: KeyCode::Enter,
-                modifiers: KeyModifiers::SHIFT,
-                ..
-            } => {
-                self.textarea.insert_str("\n");
-                KeyEventResult::Continue
-            }
-            KeyEvent {
-                code: KeyCode::Enter,
-                modifiers: KeyModifiers::NONE,
+                modifiers,
                 ..
-            } => {
-                // Submit input
-                let content = self.build_submit_content();
-                if !content.is_empty() {
-                    // Take attachments before clearing, so they're not lost.
-                    let attachments = self.take_attachments();
-                    self.clear();
-
-                    // Check if this is a slash command
-                    if let Some(ref processor) = self.command_processor {
-                        match processor.process_command(&content) {
-                            CommandResult::Continue => KeyEventResult::SendMessage {
-                                message: content,
-                                attachments,
-                            },
-                            CommandResult::Help(help_text) => KeyEventResult::ShowInfo(help_text),
-                            CommandResult::ListModels => {
-                                KeyEventResult::ShowInfo(processor.get_models_list())
-                            }
-                            CommandResult::ListProviders => {
-                                KeyEventResult::ShowInfo(processor.get_providers_list())
-                            }
-                            CommandResult::SwitchModel(model_name) => {
-                                KeyEventResult::SwitchModel(model_name)
-                            }
-                            CommandResult::ShowCurrentModel => KeyEventResult::ShowCurrentModel,
-                            CommandResult::TogglePlan => KeyEventResult::TogglePlan,
-                            CommandResult::InvalidCommand(error) => {
-                                KeyEventResult::ShowInfo(format!("Error: {error}"))
-                            }
-                        }
-                    } else {
-                        // Command processor not available, treat as regular message
-                        KeyEventResult::SendMessage {
-                            message: content,
-                            attachments,
-                        }
-                    }
+            } if modifiers == KeyModifiers::NONE
+                || modifiers == KeyModifiers::SHIFT
+                || modifiers == KeyModifiers::ALT =>
+            {
+                if (modifiers == KeyModifiers::NONE) == self.submit_on_enter {
+                    self.submit()
                 } else {
+                    self.textarea.insert_str("\n");
                     KeyEventResult::Continue
                 }
             }
@@ -151,14 +524,193 @@ impl InputManager {
         }
     }
 
-    /// Handle a terminal paste event (from bracketed paste).
-    pub fn handle_paste(&mut self, pasted: String) {
+    /// Submit the composer content as a message or slash command.
+    fn submit(&mut self) -> KeyEventResult {
+        let content = self.submit_content();
+        if !content.is_empty() {
+            let command = self
+                .command_processor
+                .as_ref()
+                .map(|processor| processor.process_command(&content));
+            // A slash command sends nothing, so its attachments wait for the next message
+            let sends_message = matches!(command, None | Some(CommandResult::Continue));
+            let attachments = self.take_sent(&content, !sends_message);
+
+            // Check if this is a slash command
+            if let (Some(command), Some(processor)) = (command, &self.command_processor) {
+                match command {
+                    CommandResult::Continue => KeyEventResult::SendMessage {
+                        message: content,
+                        attachments,
+                    },
+                    CommandResult::Help(help_text) => KeyEventResult::ShowInfo(help_text),
+                    CommandResult::ListModels => {
+                        KeyEventResult::ShowInfo(processor.get_models_list())
+                    }
+                    CommandResult::ListProviders => {
+                        KeyEventResult::ShowInfo(processor.get_providers_list())
+                    }
+                    CommandResult::SwitchModel(model_name) => {
+                        KeyEventResult::SwitchModel(model_name)
+                    }
+                    CommandResult::OpenModelPicker => KeyEventResult::OpenModelPicker,
+                    CommandResult::ShowCurrentModel => KeyEventResult::ShowCurrentModel,
+                    CommandResult::TogglePlan => KeyEventResult::TogglePlan,
+                    CommandResult::ToggleNoColor => KeyEventResult::ToggleNoColor,
+                    CommandResult::ExportTranscript { path, format } => {
+                        KeyEventResult::ExportTranscript { path, format }
+                    }
+                    CommandResult::ClearScreen { new_session } => {
+                        KeyEventResult::ClearScreen { new_session }
+                    }
+                    CommandResult::ShowWelcome => KeyEventResult::ShowWelcome,
+                    CommandResult::ListTools => KeyEventResult::ShowInfo(tools_info()),
+                    CommandResult::InspectTool => KeyEventResult::InspectTool,
+                    CommandResult::Copy(selector) => KeyEventResult::Copy { selector },
+                    CommandResult::InvalidCommand(error) => {
+                        KeyEventResult::ShowInfo(format!("Error: {error}"))
+                    }
+                }
+            } else {
+                // Command processor not available, treat as regular message
+                KeyEventResult::SendMessage {
+                    message: content,
+                    attachments,
+                }
+            }
+        } else {
+            KeyEventResult::Continue
+        }
+    }
+
+    /// The composer content as it goes out on submit or steer, with trailing
+    /// whitespace trimmed when configured.
+    fn submit_content(&self) -> String {
+        let content = self.build_submit_content();
+        if self.trim_trailing_whitespace {
+            trim_trailing_whitespace(&content)
+        } else {
+            content
+        }
+    }
+
+    /// Record `content` as sent and empty the composer, returning the
+    /// attachments that go with it. With `keep_attachments` they stay in the
+    /// composer instead. A recalled message is sent on its own and the draft
+    /// it replaced comes back.
+    fn take_sent(&mut self, content: &str, keep_attachments: bool) -> Vec<DraftAttachment> {
+        if self.sent_messages.last().map(String::as_str) != Some(content) {
+            self.sent_messages.push(content.to_string());
+        }
+        if let Some(recall) = self.recall.take() {
+            self.textarea = recall.draft;
+            return Vec::new();
+        }
+        // Take attachments before clearing, so they're not lost.
+        self.prune_detached_attachments();
+        let placeholders = std::mem::take(&mut self.attachment_elements);
+        let attachments = std::mem::take(&mut self.attachments);
+        self.clear();
+        if !keep_attachments {
+            return attachments;
+        }
+        for (attachment, placeholder) in attachments.into_iter().zip(placeholders) {
+            if !self.textarea.text().is_empty() {
+                self.textarea.insert_str(" ");
+            }
+            self.insert_attachment(attachment, placeholder);
+        }
+        Vec::new()
+    }
+
+    fn cursor_on_first_line(&self) -> bool {
+        !self.textarea.text()[..self.textarea.cursor()].contains('\n')
+    }
+
+    fn cursor_on_last_line(&self) -> bool {
+        !self.textarea.text()[self.textarea.cursor()..].contains('\n')
+    }
+
+    /// Show the sent message before the one in the composer, setting the
+    /// draft aside on the first step. Returns whether there was one.
+    fn recall_previous(&mut self) -> bool {
+        let index = match &self.recall {
+            Some(recall) => recall.index,
+            None => self.sent_messages.len(),
+        };
+        let Some(index) = index.checked_sub(1) else {
+            return false;
+        };
+        let draft = match self.recall.take() {
+            Some(recall) => recall.draft,
+            None => self.textarea.clone(),
+        };
+        self.show_sent_message(index);
+        self.recall = Some(Recall { index, draft });
+        true
+    }
+
+    /// Show the next newer sent message, or the draft with its cursor after
+    /// the newest. Returns whether a recalled message was showing.
+    fn recall_next(&mut self) -> bool {
+        let Some(recall) = self.recall.take() else {
+            return false;
+        };
+        if recall.index + 1 < self.sent_messages.len() {
+            self.show_sent_message(recall.index + 1);
+            self.recall = Some(Recall {
+                index: recall.index + 1,
+                ..recall
+            });
+        } else {
+            self.textarea = recall.draft;
+        }
+        true
+    }
+
+    /// Put sent message `index` in the composer with the cursor at its end.
+    fn show_sent_message(&mut self, index: usize) {
+        let message = &self.sent_messages[index];
+        let len = self.textarea.text().len();
+        self.textarea.replace_range(0..len, message);
+        self.textarea.set_cursor(message.len());
+    }
+
+    /// The composer's own draft, set aside while a sent message is recalled.
+    fn draft_textarea(&self) -> &TextArea {
+        self.recall
+            .as_ref()
+            .map_or(&self.textarea, |recall| &recall.draft)
+    }
+
+    /// Handle a terminal paste event (from bracketed paste). Pasting the path
+    /// of an image file (e.g. by dropping it onto the terminal) attaches it;
+    /// the error describes why such an image couldn't be attached, and the
+    /// path is then inserted as text instead.
+    ///
+    /// Other text is inserted verbatim, bypassing key handling: only line
+    /// endings are normalized (`\r\n` and lone `\r`, which many terminals
+    /// send for newlines, become `\n`). Tabs, indentation and trailing
+    /// whitespace are kept.
+    pub fn handle_paste(&mut self, pasted: String) -> Result<(), String> {
+        if let Some(path) = pasted_image_path(&pasted) {
+            if let Err(error) = self.attach_image_file(&path) {
+                self.insert_pasted_text(pasted);
+                return Err(error);
+            }
+            return Ok(());
+        }
+        self.insert_pasted_text(pasted);
+        Ok(())
+    }
+
+    fn insert_pasted_text(&mut self, pasted: String) {
         let pasted = pasted.replace("\r\n", "\n").replace('\r', "\n");
         let char_count = pasted.chars().count();
+        let line_count = pasted.lines().count();
 
-        if char_count > LARGE_PASTE_CHAR_THRESHOLD {
-            let line_count = pasted.lines().count();
-            let placeholder = self.next_large_paste_placeholder(line_count);
+        if char_count > self.paste_char_threshold || line_count > self.paste_line_threshold {
+            let placeholder = self.next_large_paste_placeholder(&pasted);
             self.textarea.insert_element(&placeholder);
             self.pending_pastes.push((placeholder, pasted));
         } else {
@@ -166,6 +718,123 @@ impl InputManager {
         }
     }
 
+    /// Put a saved draft back into the composer. `[Image…]` placeholders in
+    /// the text become elements again, linked to the draft's images in
+    /// order; attachments left without a placeholder get one at the end.
+    pub fn restore_draft(&mut self, text: &str, attachments: Vec<DraftAttachment>) {
+        let (images, others): (Vec<_>, Vec<_>) = attachments
+            .into_iter()
+            .partition(|attachment| matches!(attachment, DraftAttachment::Image { .. }));
+        let mut images = images.into_iter();
+        let mut rest = text;
+        while let Some(start) = rest.find("[Image") {
+            let Some(end) = rest[start..].find(']').map(|len| start + len + 1) else {
+                break;
+            };
+            let Some(image) = images.next() else {
+                break;
+            };
+            self.textarea.insert_str(&rest[..start]);
+            self.insert_attachment(image, rest[start..end].to_string());
+            rest = &rest[end..];
+        }
+        self.textarea.insert_str(rest);
+
+        for attachment in images.chain(others) {
+            let placeholder = match &attachment {
+                DraftAttachment::File { filename, .. } => format!("[File: {filename}]"),
+                DraftAttachment::Text { .. } => "[Text]".to_string(),
+                DraftAttachment::Image { .. } => format!("[Image {}]", self.image_counter + 1),
+            };
+            if !self.textarea.text().is_empty() {
+                self.textarea.insert_str(" ");
+            }
+            self.insert_attachment(attachment, placeholder);
+        }
+    }
+
+    /// Add `attachment` with an element labelled `placeholder` at the cursor.
+    fn insert_attachment(&mut self, attachment: DraftAttachment, placeholder: String) {
+        if matches!(attachment, DraftAttachment::Image { .. }) {
+            // Keep later `[Image N]` pastes from reusing a restored label
+            self.image_counter += 1;
+        }
+        self.attachments.push(attachment);
+        self.attachment_elements.push(placeholder.clone());
+        self.textarea.insert_element(&placeholder);
+    }
+
+    /// Attach an image file and insert an `[Image: name]` element for it.
+    pub fn attach_image_file(&mut self, path: &Path) -> Result<(), String> {
+        if self.recall.is_some() {
+            return Err(RECALLED_IMAGE_MESSAGE.to_string());
+        }
+        let shown = path.display();
+        if !path.is_file() {
+            return Err(format!("Image not found: {shown}"));
+        }
+        let Some(mime_type) = image_mime_type(path) else {
+            return Err(format!(
+                "Unsupported image type: {shown} (use PNG, JPEG, GIF or WebP)"
+            ));
+        };
+        let bytes =
+            std::fs::read(path).map_err(|e| format!("Failed to read image {shown}: {e}"))?;
+        // Measured from the bytes just read, so the file is only opened once
+        let (width, height) = image::ImageReader::new(std::io::Cursor::new(&bytes))
+            .with_guessed_format()
+            .map_err(|e| format!("Failed to read image {shown}: {e}"))?
+            .into_dimensions()
+            .map_err(|e| format!("Failed to read image {shown}: {e}"))?;
+
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| shown.to_string());
+        let mut placeholder = format!("[Image: {name}]");
+        let mut n = 1;
+        while self.attachment_elements.contains(&placeholder) {
+            n += 1;
+            placeholder = format!("[Image: {name} #{n}]");
+        }
+
+        self.attachments.push(DraftAttachment::Image {
+            content: base64::engine::general_purpose::STANDARD.encode(&bytes),
+            mime_type: mime_type.to_string(),
+            width: Some(width),
+            height: Some(height),
+        });
+        self.attachment_elements.push(placeholder.clone());
+        self.textarea.insert_element(&placeholder);
+        debug!("Attached image file {shown} as {placeholder}");
+        Ok(())
+    }
+
+    /// Drop attachments whose element has been deleted from the composer.
+    fn prune_detached_attachments(&mut self) {
+        if self.attachment_elements.is_empty() {
+            return;
+        }
+        // Borrowed field by field, so the lists below can be changed
+        let textarea = self
+            .recall
+            .as_ref()
+            .map_or(&self.textarea, |recall| &recall.draft);
+        let text = textarea.text();
+        let present: Vec<&str> = textarea
+            .element_ranges()
+            .map(|range| &text[range])
+            .collect();
+        for idx in (0..self.attachment_elements.len()).rev() {
+            if !present.contains(&self.attachment_elements[idx].as_str()) {
+                self.attachment_elements.remove(idx);
+                if idx < self.attachments.len() {
+                    self.attachments.remove(idx);
+                }
+            }
+        }
+    }
+
     /// Try to read an image from the system clipboard and attach it.
     /// Returns true if an image was found and attached.
     pub fn try_paste_clipboard_image(&mut self) -> bool {
@@ -209,6 +878,7 @@ impl InputManager {
                     width: Some(w),
                     height: Some(h),
                 });
+                self.attachment_elements.push(placeholder.clone());
 
                 self.textarea.insert_element(&placeholder);
                 debug!("Attached clipboard image as {}", placeholder);
@@ -219,44 +889,167 @@ impl InputManager {
     }
 
     /// Build the final message content, expanding large-paste placeholders.
+    /// Only placeholder elements are expanded, so typing the placeholder text
+    /// by hand leaves it as is.
     fn build_submit_content(&self) -> String {
-        let raw = self.textarea.text().to_string();
+        self.expand_pastes(&self.textarea)
+    }
+
+    fn expand_pastes(&self, textarea: &TextArea) -> String {
+        let raw = textarea.text();
         if self.pending_pastes.is_empty() {
-            return raw;
+            return raw.to_string();
         }
 
-        let mut result = raw;
-        for (placeholder, content) in &self.pending_pastes {
-            result = result.replace(placeholder, content);
+        let mut result = String::with_capacity(raw.len());
+        let mut last = 0;
+        for range in textarea.element_ranges() {
+            let element = &raw[range.clone()];
+            if let Some((_, content)) = self
+                .pending_pastes
+                .iter()
+                .find(|(placeholder, _)| placeholder == element)
+            {
+                result.push_str(&raw[last..range.start]);
+                result.push_str(content);
+                last = range.end;
+            }
         }
+        result.push_str(&raw[last..]);
         result
     }
 
     /// Take the accumulated attachments, leaving the internal list empty.
     pub fn take_attachments(&mut self) -> Vec<DraftAttachment> {
+        self.prune_detached_attachments();
+        self.attachment_elements.clear();
         std::mem::take(&mut self.attachments)
     }
 
+    /// The composer content as it would be submitted, with its attachments,
+    /// for saving as the session draft.
+    pub fn draft(&mut self) -> (String, Vec<DraftAttachment>) {
+        self.prune_detached_attachments();
+        (
+            self.expand_pastes(self.draft_textarea()),
+            self.attachments.clone(),
+        )
+    }
+
+    /// The file named by a composer holding just an `@path` mention.
+    fn diff_pane_target(&self) -> Option<String> {
+        let text = self.textarea.text();
+        let path = text.trim().strip_prefix('@')?;
+        (!path.is_empty() && !path.contains(char::is_whitespace)).then(|| path.to_string())
+    }
+
     /// Clear the textarea content and all paste state.
     pub fn clear(&mut self) {
         self.textarea.clear();
+        self.recall = None;
+        self.path_completion = None;
+        self.dismissed_mention = None;
         self.attachments.clear();
+        self.attachment_elements.clear();
         self.image_counter = 0;
         self.pending_pastes.clear();
         self.large_paste_counters.clear();
     }
 
-    fn next_large_paste_placeholder(&mut self, line_count: usize) -> String {
-        let counter = self.large_paste_counters.entry(line_count).or_insert(0);
+    fn next_large_paste_placeholder(&mut self, pasted: &str) -> String {
+        let label = self.paste_placeholder_format.placeholder(pasted);
+        let counter = self.large_paste_counters.entry(label.clone()).or_insert(0);
         *counter += 1;
         if *counter == 1 {
-            format!("[Pasted {} lines]", line_count)
+            label
         } else {
-            format!("[Pasted {} lines] #{}", line_count, counter)
+            format!("{} #{}", label, counter)
         }
     }
 }
 
+/// `text` with trailing whitespace removed from every line and trailing
+/// blank lines dropped. Lines inside ``` or ~~~ code fences are kept as is.
+fn trim_trailing_whitespace(text: &str) -> String {
+    let mut fence: Option<&str> = None;
+    let mut lines = Vec::new();
+    for line in text.split('\n') {
+        let marker = ["```", "~~~"]
+            .into_iter()
+            .find(|marker| line.trim_start().starts_with(marker));
+        match (fence, marker) {
+            (Some(open), Some(close)) if open == close => {
+                fence = None;
+                lines.push(line.trim_end());
+            }
+            (Some(_), _) => lines.push(line),
+            (None, marker) => {
+                fence = marker;
+                lines.push(line.trim_end());
+            }
+        }
+    }
+    if fence.is_none() {
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
+        }
+    }
+    lines.join("\n")
+}
+
+/// Image file extensions recognised in pasted paths, whether or not they
+/// can be sent to the model.
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "webp", "bmp", "tif", "tiff", "heic", "svg",
+];
+
+fn image_mime_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    match extension.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// The path in a paste that consists of a single image file path, as
+/// produced by dragging a file onto the terminal. Handles quoting,
+/// backslash-escaped spaces, `file://` URLs and `~`.
+fn pasted_image_path(pasted: &str) -> Option<PathBuf> {
+    let trimmed = pasted.trim();
+    if trimmed.is_empty() || trimmed.contains('\n') {
+        return None;
+    }
+    let unquoted = trimmed
+        .strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+        .or_else(|| trimmed.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+        .unwrap_or(trimmed);
+    let unescaped = unquoted.replace("\\ ", " ");
+    // Dropped files may arrive as `file://` URLs with `%20` for spaces
+    let raw = match unescaped.strip_prefix("file://") {
+        Some(url_path) => percent_encoding::percent_decode_str(url_path)
+            .decode_utf8()
+            .ok()?
+            .into_owned(),
+        None => unescaped,
+    };
+
+    let path = match raw.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()?.join(rest),
+        None => PathBuf::from(raw),
+    };
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    if !IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        return None;
+    }
+    // Only treat it as a file drop if the file is there, so pasting e.g.
+    // "logo.png" or a path from another machine stays plain text
+    path.is_file().then_some(path)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -271,6 +1064,59 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_playback_keys_only_with_empty_composer() {
+        let mut input_manager = InputManager::new();
+        let space = create_key_event(KeyCode::Char(' '), KeyModifiers::NONE);
+
+        // Off unless a recording is played back
+        assert!(matches!(
+            input_manager.handle_key_event(space),
+            KeyEventResult::Continue
+        ));
+        assert_eq!(input_manager.textarea.text(), " ");
+        input_manager.textarea.clear();
+
+        input_manager.enable_playback_controls();
+        for (code, modifiers, expected) in [
+            (
+                KeyCode::Char(' '),
+                KeyModifiers::NONE,
+                PlaybackAction::TogglePause,
+            ),
+            (KeyCode::Right, KeyModifiers::NONE, PlaybackAction::Step),
+            (
+                KeyCode::Char('+'),
+                KeyModifiers::SHIFT,
+                PlaybackAction::Faster,
+            ),
+            (
+                KeyCode::Char('='),
+                KeyModifiers::NONE,
+                PlaybackAction::Faster,
+            ),
+            (
+                KeyCode::Char('-'),
+                KeyModifiers::NONE,
+                PlaybackAction::Slower,
+            ),
+        ] {
+            match input_manager.handle_key_event(create_key_event(code, modifiers)) {
+                KeyEventResult::Playback(action) => assert_eq!(action, expected),
+                other => panic!("expected {expected:?}, got {other:?}"),
+            }
+        }
+        assert_eq!(input_manager.textarea.text(), "");
+
+        // With a draft the keys edit it as usual
+        input_manager.textarea.insert_str("a");
+        assert!(matches!(
+            input_manager.handle_key_event(space),
+            KeyEventResult::Continue
+        ));
+        assert_eq!(input_manager.textarea.text(), "a ");
+    }
+
     #[test]
     fn test_input_manager_basic_operations() {
         let mut input_manager = InputManager::new();
@@ -318,6 +1164,53 @@ mod tests {
         assert!(matches!(result, KeyEventResult::Quit));
     }
 
+    #[test]
+    fn test_ctrl_c_cancels_running_turn_then_quits() {
+        let mut input_manager = InputManager::new();
+        let ctrl_c = || create_key_event(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        input_manager.set_turn_active(true);
+
+        let result = input_manager.handle_key_event(ctrl_c());
+        assert!(matches!(result, KeyEventResult::CancelTurn));
+        // The turn is still winding down; a second press quits
+        let result = input_manager.handle_key_event(ctrl_c());
+        assert!(matches!(result, KeyEventResult::Quit));
+
+        // Once the confirmation window has passed, Ctrl+C cancels again
+        let armed = Instant::now();
+        let expired = armed + QUIT_CONFIRM_TIMEOUT;
+        let result = input_manager.handle_ctrl_c(armed);
+        assert!(matches!(result, KeyEventResult::CancelTurn));
+        assert_eq!(
+            input_manager.quit_confirm_remaining(armed),
+            Some(QUIT_CONFIRM_TIMEOUT)
+        );
+        assert_eq!(
+            input_manager.quit_confirm_remaining(expired),
+            Some(Duration::ZERO)
+        );
+        let result = input_manager.handle_ctrl_c(expired);
+        assert!(matches!(result, KeyEventResult::CancelTurn));
+
+        // When idle, even an armed press just quits
+        input_manager.set_turn_active(false);
+        let result = input_manager.handle_ctrl_c(expired);
+        assert!(matches!(result, KeyEventResult::Quit));
+    }
+
+    #[test]
+    fn test_draft_snapshot_ignores_cursor_movement() {
+        let mut input_manager = InputManager::new();
+        input_manager.handle_key_event(create_key_event(KeyCode::Char('a'), KeyModifiers::NONE));
+        let draft = input_manager.draft_snapshot();
+
+        input_manager.handle_key_event(create_key_event(KeyCode::Left, KeyModifiers::NONE));
+        assert_eq!(input_manager.draft_snapshot(), draft);
+
+        input_manager.handle_key_event(create_key_event(KeyCode::Char('b'), KeyModifiers::NONE));
+        assert_ne!(input_manager.draft_snapshot(), draft);
+    }
+
     #[test]
     fn test_escape_key() {
         let mut input_manager = InputManager::new();
@@ -360,26 +1253,162 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_enter_inserts_newline_when_submit_on_enter_is_off() {
+        let mut input_manager = InputManager::new();
+        input_manager.submit_on_enter = false;
+        input_manager.handle_key_event(create_key_event(KeyCode::Char('a'), KeyModifiers::NONE));
+
+        let result =
+            input_manager.handle_key_event(create_key_event(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(matches!(result, KeyEventResult::Continue));
+        input_manager.handle_key_event(create_key_event(KeyCode::Char('b'), KeyModifiers::NONE));
+        assert_eq!(input_manager.textarea.text(), "a\nb");
+
+        let result =
+            input_manager.handle_key_event(create_key_event(KeyCode::Enter, KeyModifiers::ALT));
+        match result {
+            KeyEventResult::SendMessage { message, .. } => assert_eq!(message, "a\nb"),
+            other => panic!("Expected SendMessage, got {:?}", other),
+        }
+
+        // Alt+Enter breaks the line in the default mode too
+        input_manager.submit_on_enter = true;
+        input_manager.handle_key_event(create_key_event(KeyCode::Char('c'), KeyModifiers::NONE));
+        let result =
+            input_manager.handle_key_event(create_key_event(KeyCode::Enter, KeyModifiers::ALT));
+        assert!(matches!(result, KeyEventResult::Continue));
+        assert_eq!(input_manager.textarea.text(), "c\n");
+    }
+
+    #[test]
+    fn test_submit_trims_trailing_whitespace_when_enabled() {
+        let mut input_manager = InputManager::new();
+        input_manager.trim_trailing_whitespace = true;
+        input_manager.paste_line_threshold = 2;
+        input_manager.textarea.insert_str("Fix this:  \n");
+        let code = "```\nlet x = 1;   \n\n```\n";
+        input_manager.handle_paste(code.to_string()).unwrap();
+        input_manager.textarea.insert_str("thanks \t\n\n\n");
+
+        // The draft keeps the whitespace and the paste placeholder
+        let draft = input_manager.textarea.text().to_string();
+        assert!(draft.starts_with("Fix this:  \n[Pasted"), "{draft:?}");
+        assert!(draft.ends_with("thanks \t\n\n\n"));
+
+        match input_manager.submit() {
+            KeyEventResult::SendMessage { message, .. } => {
+                assert_eq!(message, "Fix this:\n```\nlet x = 1;   \n\n```\nthanks")
+            }
+            other => panic!("Expected SendMessage, got {other:?}"),
+        }
+
+        assert_eq!(
+            trim_trailing_whitespace("a \n~~~\n b  \n"),
+            "a\n~~~\n b  \n"
+        );
+    }
+
+    #[test]
+    fn test_recall_then_return_restores_draft_and_cursor() {
+        let mut input_manager = InputManager::new();
+        let up = || create_key_event(KeyCode::Up, KeyModifiers::NONE);
+        let down = || create_key_event(KeyCode::Down, KeyModifiers::NONE);
+        for message in ["first", "second"] {
+            input_manager.textarea.insert_str(message);
+            input_manager.submit();
+        }
+
+        input_manager.paste_line_threshold = 1;
+        input_manager.textarea.insert_str("see ");
+        input_manager.handle_paste("a\nb".to_string()).unwrap();
+        input_manager.textarea.insert_str(" now");
+        input_manager.textarea.set_cursor(2);
+
+        input_manager.handle_key_event(up());
+        assert_eq!(input_manager.textarea.text(), "second");
+        assert_eq!(input_manager.textarea.cursor(), "second".len());
+        input_manager.handle_key_event(up());
+        assert_eq!(input_manager.textarea.text(), "first");
+        // The draft is what gets saved meanwhile
+        assert_eq!(input_manager.draft().0, "see a\nb now");
+
+        input_manager.handle_key_event(down());
+        assert_eq!(input_manager.textarea.text(), "second");
+        input_manager.handle_key_event(down());
+        assert!(input_manager.textarea.text().starts_with("see [Pasted"));
+        assert_eq!(input_manager.textarea.cursor(), 2);
+        assert!(input_manager.textarea.has_elements());
+
+        // Sending a recalled message brings the draft back afterwards
+        input_manager.handle_key_event(up());
+        match input_manager.submit() {
+            KeyEventResult::SendMessage { message, .. } => assert_eq!(message, "second"),
+            other => panic!("Expected SendMessage, got {other:?}"),
+        }
+        assert_eq!(input_manager.textarea.cursor(), 2);
+        match input_manager.submit() {
+            KeyEventResult::SendMessage { message, .. } => assert_eq!(message, "see a\nb now"),
+            other => panic!("Expected SendMessage, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_up_moves_within_multiline_draft_before_recalling() {
+        let mut input_manager = InputManager::new();
+        input_manager.textarea.insert_str("sent");
+        input_manager.submit();
+
+        input_manager.textarea.insert_str("one\ntwo");
+        input_manager.handle_key_event(create_key_event(KeyCode::Up, KeyModifiers::NONE));
+        assert_eq!(input_manager.textarea.text(), "one\ntwo");
+        assert!(input_manager.textarea.cursor() <= 3);
+        input_manager.handle_key_event(create_key_event(KeyCode::Up, KeyModifiers::NONE));
+        assert_eq!(input_manager.textarea.text(), "sent");
+        // Down without a recalled message moves the cursor as usual
+        input_manager.handle_key_event(create_key_event(KeyCode::Down, KeyModifiers::NONE));
+        input_manager.handle_key_event(create_key_event(KeyCode::Down, KeyModifiers::NONE));
+        assert_eq!(input_manager.textarea.text(), "one\ntwo");
+    }
+
     #[test]
     fn test_small_paste_inserts_directly() {
         let mut input_manager = InputManager::new();
-        input_manager.handle_paste("hello world".to_string());
+        input_manager
+            .handle_paste("hello world".to_string())
+            .unwrap();
         assert_eq!(input_manager.textarea.text(), "hello world");
         assert!(input_manager.pending_pastes.is_empty());
     }
 
+    #[test]
+    fn test_paste_keeps_indentation_verbatim() {
+        let code = "fn main() {\r\n\tif ok {\r\n        run();  \r\n\t}\r\n}\r\n";
+        let mut input_manager = InputManager::new();
+        input_manager.paste_line_threshold = 100;
+        input_manager.textarea.insert_str("see:\n");
+        input_manager.handle_paste(code.to_string()).unwrap();
+
+        let expected = "see:\nfn main() {\n\tif ok {\n        run();  \n\t}\n}\n";
+        assert_eq!(input_manager.textarea.text(), expected);
+        match input_manager.submit() {
+            KeyEventResult::SendMessage { message, .. } => assert_eq!(message, expected),
+            other => panic!("Expected SendMessage, got {other:?}"),
+        }
+    }
+
     #[test]
     fn test_large_paste_uses_placeholder() {
         let mut input_manager = InputManager::new();
         // Create a paste larger than threshold
         let large_text: String = (0..50).map(|i| format!("line {}\n", i)).collect();
-        let line_count = large_text.lines().count();
-        input_manager.handle_paste(large_text.clone());
+        let char_count = large_text.chars().count();
+        input_manager.handle_paste(large_text.clone()).unwrap();
 
         // Should show placeholder
         let content = input_manager.textarea.text();
         assert!(
-            content.contains(&format!("[Pasted {} lines]", line_count)),
+            content.contains(&format!("[Pasted {} chars]", char_count)),
             "Expected placeholder in: {}",
             content
         );
@@ -397,7 +1426,7 @@ mod tests {
         let mut input_manager = InputManager::new();
         input_manager.textarea.insert_str("before ");
         let large_text: String = (0..50).map(|i| format!("line {}\n", i)).collect();
-        input_manager.handle_paste(large_text.clone());
+        input_manager.handle_paste(large_text.clone()).unwrap();
         input_manager.textarea.insert_str(" after");
 
         let content = input_manager.build_submit_content();
@@ -407,11 +1436,347 @@ mod tests {
         assert!(content.contains("line 49"));
     }
 
+    #[test]
+    fn test_many_short_lines_use_placeholder() {
+        let mut input_manager = InputManager::new();
+        input_manager.paste_char_threshold = 1000;
+        input_manager.paste_line_threshold = 3;
+        input_manager
+            .handle_paste("a\nb\nc\nd".to_string())
+            .unwrap();
+        assert_eq!(input_manager.textarea.text(), "[Pasted 7 chars]");
+        assert_eq!(input_manager.build_submit_content(), "a\nb\nc\nd");
+    }
+
+    #[test]
+    fn test_paste_placeholder_formats() {
+        let pasted: String = (0..40).map(|i| format!("fn line_{i}() {{}}\n")).collect();
+
+        let chars = PastePlaceholderFormat::Chars.placeholder(&pasted);
+        assert_eq!(chars, format!("[Pasted {} chars]", pasted.chars().count()));
+        assert_eq!(
+            PastePlaceholderFormat::Lines.placeholder(&pasted),
+            "[Pasted 40 lines]"
+        );
+        assert_eq!(
+            PastePlaceholderFormat::Preview.placeholder(&format!("\n  {pasted}")),
+            "[Pasted: fn line_0() {}…]"
+        );
+        assert_eq!(
+            PastePlaceholderFormat::Preview.placeholder("  short\n\n"),
+            "[Pasted: short]"
+        );
+    }
+
+    #[test]
+    fn test_paste_placeholder_format_keeps_content_intact() {
+        for format in [
+            PastePlaceholderFormat::Chars,
+            PastePlaceholderFormat::Lines,
+            PastePlaceholderFormat::Preview,
+        ] {
+            let mut input_manager = InputManager::new();
+            input_manager.paste_placeholder_format = format;
+            let first: String = (0..30).map(|i| format!("first {i}\n")).collect();
+            let second: String = (0..30).map(|i| format!("second {i}\n")).collect();
+            let repeat = first.clone();
+
+            input_manager.textarea.insert_str("a ");
+            input_manager.handle_paste(first.clone()).unwrap();
+            input_manager.textarea.insert_str(" b ");
+            input_manager.handle_paste(second.clone()).unwrap();
+            input_manager.textarea.insert_str(" c ");
+            input_manager.handle_paste(repeat.clone()).unwrap();
+
+            let text = input_manager.textarea.text().to_string();
+            let elements: Vec<&str> = input_manager
+                .textarea
+                .element_ranges()
+                .map(|range| &text[range])
+                .collect();
+            assert_eq!(elements.len(), 3, "{format:?}: {text}");
+            assert!(
+                elements[0] != elements[1]
+                    && elements[1] != elements[2]
+                    && elements[0] != elements[2],
+                "{format:?}: {text}"
+            );
+            assert_eq!(
+                input_manager.build_submit_content(),
+                format!("a {first} b {second} c {repeat}"),
+                "{format:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_typed_placeholder_text_is_not_expanded() {
+        let mut input_manager = InputManager::new();
+        let large_text = "x".repeat(300);
+        input_manager.handle_paste(large_text.clone()).unwrap();
+        input_manager.textarea.insert_str(" [Pasted 300 chars]");
+
+        assert_eq!(
+            input_manager.build_submit_content(),
+            format!("{large_text} [Pasted 300 chars]")
+        );
+    }
+
+    fn type_str(input_manager: &mut InputManager, text: &str) {
+        for c in text.chars() {
+            input_manager.handle_key_event(create_key_event(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+    }
+
+    #[test]
+    fn test_at_mention_completes_path() {
+        let mut input_manager = InputManager::new();
+        input_manager.path_index = Some(PathIndex::with_files(vec![
+            "README.md".to_string(),
+            "src/main.rs".to_string(),
+            "src/lib.rs".to_string(),
+        ]));
+
+        type_str(&mut input_manager, "look at @src/l");
+        let text = input_manager.path_completion_text().unwrap();
+        assert!(text.contains("› **src/lib.rs**"), "{text}");
+
+        let result =
+            input_manager.handle_key_event(create_key_event(KeyCode::Tab, KeyModifiers::NONE));
+        assert!(matches!(result, KeyEventResult::Continue));
+        assert_eq!(input_manager.textarea.text(), "look at src/lib.rs ");
+        assert!(input_manager.textarea.has_elements());
+        assert!(input_manager.path_completion_text().is_none());
+    }
+
+    #[test]
+    fn test_escape_dismisses_completion_for_token() {
+        let mut input_manager = InputManager::new();
+        input_manager.path_index = Some(PathIndex::with_files(vec!["main.rs".to_string()]));
+
+        type_str(&mut input_manager, "@ma");
+        assert!(input_manager.path_completion_text().is_some());
+        let result =
+            input_manager.handle_key_event(create_key_event(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(matches!(result, KeyEventResult::Continue));
+        type_str(&mut input_manager, "i");
+        assert!(input_manager.path_completion_text().is_none());
+
+        // Enter submits normally once the list is dismissed
+        let result =
+            input_manager.handle_key_event(create_key_event(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(matches!(result, KeyEventResult::SendMessage { .. }));
+    }
+
+    #[test]
+    fn test_ctrl_s_steers_with_composer_text() {
+        let mut input_manager = InputManager::new();
+        let ctrl_s = || create_key_event(KeyCode::Char('s'), KeyModifiers::CONTROL);
+
+        // Nothing to steer with
+        let result = input_manager.handle_key_event(ctrl_s());
+        assert!(matches!(result, KeyEventResult::Continue));
+
+        type_str(&mut input_manager, "use the other API");
+        match input_manager.handle_key_event(ctrl_s()) {
+            KeyEventResult::SteerMessage { message, .. } => {
+                assert_eq!(message, "use the other API")
+            }
+            other => panic!("Expected SteerMessage, got {:?}", other),
+        }
+        assert!(input_manager.textarea.is_empty());
+
+        // Slash commands stay in the composer
+        type_str(&mut input_manager, "/help");
+        input_manager.handle_key_event(ctrl_s());
+        assert_eq!(input_manager.textarea.text(), "/help");
+    }
+
+    #[test]
+    fn test_ctrl_s_trims_like_submit() {
+        let mut input_manager = InputManager::new();
+        input_manager.trim_trailing_whitespace = true;
+        let ctrl_s = || create_key_event(KeyCode::Char('s'), KeyModifiers::CONTROL);
+
+        type_str(&mut input_manager, "use v2  ");
+        match input_manager.handle_key_event(ctrl_s()) {
+            KeyEventResult::SteerMessage { message, .. } => assert_eq!(message, "use v2"),
+            other => panic!("Expected SteerMessage, got {:?}", other),
+        }
+
+        // Whitespace alone trims to nothing, so there's nothing to steer with
+        type_str(&mut input_manager, "   ");
+        let result = input_manager.handle_key_event(ctrl_s());
+        assert!(matches!(result, KeyEventResult::Continue));
+        assert_eq!(input_manager.textarea.text(), "   ");
+    }
+
+    #[test]
+    fn test_ctrl_g_opens_last_path_unless_a_region_is_marked() {
+        let mut input_manager = InputManager::new();
+        let ctrl_g = || create_key_event(KeyCode::Char('g'), KeyModifiers::CONTROL);
+
+        let result = input_manager.handle_key_event(ctrl_g());
+        assert!(matches!(result, KeyEventResult::OpenLastPath));
+
+        type_str(&mut input_manager, "draft");
+        let result = input_manager.handle_key_event(ctrl_g());
+        assert!(matches!(result, KeyEventResult::OpenLastPath));
+
+        input_manager.handle_key_event(create_key_event(KeyCode::Char(' '), KeyModifiers::CONTROL));
+        let result = input_manager.handle_key_event(ctrl_g());
+        assert!(!matches!(result, KeyEventResult::OpenLastPath));
+        assert_eq!(input_manager.textarea.mark(), None);
+        assert_eq!(input_manager.textarea.text(), "draft");
+    }
+
+    fn write_png(dir: &Path, name: &str) -> PathBuf {
+        let path = dir.join(name);
+        image::RgbaImage::new(3, 2).save(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_pasting_image_path_attaches_image() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_png(dir.path(), "shot one.png");
+        let mut input_manager = InputManager::new();
+
+        let escaped = path.display().to_string().replace(' ', "\\ ");
+        input_manager.handle_paste(escaped).unwrap();
+        assert_eq!(input_manager.textarea.text(), "[Image: shot one.png]");
+        assert!(input_manager.textarea.has_elements());
+        match &input_manager.attachments[..] {
+            [DraftAttachment::Image {
+                mime_type,
+                width,
+                height,
+                ..
+            }] => {
+                assert_eq!(mime_type, "image/png");
+                assert_eq!((*width, *height), (Some(3), Some(2)));
+            }
+            other => panic!("unexpected attachments: {other:?}"),
+        }
+
+        // Deleting the element drops the attachment
+        input_manager.handle_key_event(create_key_event(KeyCode::Backspace, KeyModifiers::NONE));
+        assert_eq!(input_manager.textarea.text(), "");
+        assert!(input_manager.take_attachments().is_empty());
+
+        // Dropped as a percent-encoded file URL
+        let url = format!("file://{}", path.display()).replace(' ', "%20");
+        input_manager.handle_paste(url).unwrap();
+        assert_eq!(input_manager.textarea.text(), "[Image: shot one.png]");
+        assert_eq!(input_manager.take_attachments().len(), 1);
+    }
+
+    #[test]
+    fn test_slash_command_keeps_attachments_for_next_message() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_png(dir.path(), "shot.png");
+        let mut input_manager = InputManager::new();
+        let enter = || create_key_event(KeyCode::Enter, KeyModifiers::NONE);
+
+        type_str(&mut input_manager, "/help ");
+        input_manager
+            .handle_paste(path.display().to_string())
+            .unwrap();
+        let result = input_manager.handle_key_event(enter());
+        assert!(matches!(result, KeyEventResult::ShowInfo(_)));
+        assert_eq!(input_manager.textarea.text(), "[Image: shot.png]");
+        assert_eq!(input_manager.attachments.len(), 1);
+
+        type_str(&mut input_manager, " what is this?");
+        match input_manager.handle_key_event(enter()) {
+            KeyEventResult::SendMessage {
+                message,
+                attachments,
+            } => {
+                assert_eq!(message, "[Image: shot.png] what is this?");
+                assert_eq!(attachments.len(), 1);
+            }
+            other => panic!("Expected SendMessage, got {:?}", other),
+        }
+        assert!(input_manager.textarea.is_empty());
+    }
+
+    #[test]
+    fn test_restored_draft_relinks_image_placeholders() {
+        let image = || DraftAttachment::Image {
+            content: "aGk=".to_string(),
+            mime_type: "image/png".to_string(),
+            width: None,
+            height: None,
+        };
+        let mut input_manager = InputManager::new();
+        input_manager.restore_draft("see [Image 1] and", vec![image(), image()]);
+
+        assert_eq!(input_manager.textarea.text(), "see [Image 1] and [Image 2]");
+        let (draft, attachments) = input_manager.draft();
+        assert_eq!(draft, "see [Image 1] and [Image 2]");
+        assert_eq!(attachments.len(), 2);
+
+        // Deleting a restored element drops its image
+        input_manager.handle_key_event(create_key_event(KeyCode::Backspace, KeyModifiers::NONE));
+        assert_eq!(input_manager.take_attachments().len(), 1);
+    }
+
+    #[test]
+    fn test_image_paste_refused_while_recalling() {
+        let dir = tempfile::tempdir().unwrap();
+        let png = write_png(dir.path(), "shot.png");
+        let mut input_manager = InputManager::new();
+        input_manager.textarea.insert_str("sent");
+        input_manager.submit();
+        input_manager.handle_key_event(create_key_event(KeyCode::Up, KeyModifiers::NONE));
+
+        let err = input_manager
+            .handle_paste(png.display().to_string())
+            .unwrap_err();
+        assert_eq!(err, RECALLED_IMAGE_MESSAGE);
+        let result = input_manager
+            .handle_key_event(create_key_event(KeyCode::Char('v'), KeyModifiers::CONTROL));
+        assert!(matches!(result, KeyEventResult::ShowInfo(_)));
+        assert!(input_manager.attachments.is_empty());
+        // The refused path is kept as text
+        assert_eq!(
+            input_manager.textarea.text(),
+            format!("sent{}", png.display())
+        );
+    }
+
+    #[test]
+    fn test_pasting_unsupported_or_missing_image_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let bmp = dir.path().join("scan.bmp");
+        std::fs::write(&bmp, b"BM").unwrap();
+        let mut input_manager = InputManager::new();
+
+        let err = input_manager
+            .handle_paste(bmp.display().to_string())
+            .unwrap_err();
+        assert!(err.starts_with("Unsupported image type"), "{err}");
+        assert!(input_manager.attachments.is_empty());
+        assert_eq!(input_manager.textarea.text(), bmp.display().to_string());
+        input_manager.clear();
+
+        // Paths to missing files and bare file names are ordinary text
+        let missing = dir.path().join("missing.png").display().to_string();
+        input_manager.handle_paste(missing.clone()).unwrap();
+        assert!(input_manager.attachments.is_empty());
+        assert_eq!(input_manager.textarea.text(), missing);
+
+        input_manager.clear();
+        input_manager.handle_paste("logo.png".to_string()).unwrap();
+        assert_eq!(input_manager.textarea.text(), "logo.png");
+    }
+
     #[test]
     fn test_clear_resets_paste_state() {
         let mut input_manager = InputManager::new();
         let large_text: String = (0..50).map(|i| format!("line {}\n", i)).collect();
-        input_manager.handle_paste(large_text);
+        input_manager.handle_paste(large_text).unwrap();
         input_manager.image_counter = 2;
         input_manager.attachments.push(DraftAttachment::Image {
             content: "abc".to_string(),
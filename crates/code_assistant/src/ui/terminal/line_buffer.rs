@@ -0,0 +1,92 @@
+//! Carriage-return handling for streamed text and command output.
+//!
+//! Progress bars redraw their line with a bare `\r`, and Windows tools end
+//! lines with `\r\n`. Text is appended the way a terminal would show it, so
+//! `Downloading 50%\rDownloading 100%` keeps only the latest line.
+
+/// Append `chunk` to `text`: `\r\n` becomes a newline and a bare `\r`
+/// returns to the start of the current line, so whatever follows replaces
+/// that line. A run of `\r` counts as one, so `\r\r\n` is still a single
+/// newline. A `\r` ending a chunk is carried in `pending_cr` until the
+/// next chunk shows whether it was the first half of `\r\n`.
+pub fn push_with_carriage_returns(text: &mut String, pending_cr: &mut bool, chunk: &str) {
+    text.reserve(chunk.len());
+    for c in chunk.chars() {
+        if c == '\r' {
+            *pending_cr = true;
+            continue;
+        }
+        if std::mem::take(pending_cr) && c != '\n' {
+            clear_current_line(text);
+        }
+        text.push(c);
+    }
+}
+
+/// `text` with carriage returns applied; a trailing `\r` is dropped.
+pub fn apply_carriage_returns(text: &str) -> String {
+    if !text.contains('\r') {
+        return text.to_string();
+    }
+    let mut out = String::with_capacity(text.len());
+    let mut pending_cr = false;
+    push_with_carriage_returns(&mut out, &mut pending_cr, text);
+    out
+}
+
+fn clear_current_line(text: &mut String) {
+    let start = text.rfind('\n').map_or(0, |newline| newline + 1);
+    text.truncate(start);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_chunks(chunks: &[&str]) -> (String, bool) {
+        let mut text = String::new();
+        let mut pending_cr = false;
+        for chunk in chunks {
+            push_with_carriage_returns(&mut text, &mut pending_cr, chunk);
+        }
+        (text, pending_cr)
+    }
+
+    #[test]
+    fn test_progress_bar_overwrites_current_line() {
+        assert_eq!(
+            apply_carriage_returns("Fetching\nDownloading 50%\rDownloading 100%\ndone\n"),
+            "Fetching\nDownloading 100%\ndone\n"
+        );
+        // Shorter redraws leave nothing of the longer line behind
+        assert_eq!(apply_carriage_returns("[#####     ] 50%\r[#] 5%"), "[#] 5%");
+        assert_eq!(apply_carriage_returns("50%\r"), "50%");
+    }
+
+    #[test]
+    fn test_crlf_is_a_single_newline() {
+        assert_eq!(apply_carriage_returns("one\r\ntwo\r\n"), "one\ntwo\n");
+        assert_eq!(apply_carriage_returns("no returns\n"), "no returns\n");
+        assert_eq!(apply_carriage_returns("one\r\r\ntwo"), "one\ntwo");
+        assert_eq!(push_chunks(&["one\r", "\r", "\ntwo"]).0, "one\ntwo");
+    }
+
+    #[test]
+    fn test_carriage_return_split_across_chunks() {
+        // `\r` | `\n` is still one newline
+        assert_eq!(
+            push_chunks(&["one\r", "\ntwo"]),
+            ("one\ntwo".to_string(), false)
+        );
+
+        // The old line stays visible until the redraw arrives
+        let (text, pending_cr) = push_chunks(&["step 1\n", "10%\r"]);
+        assert_eq!(text, "step 1\n10%");
+        assert!(pending_cr);
+
+        assert_eq!(
+            push_chunks(&["step 1\n", "10%\r", "20%\r", "30%"]),
+            ("step 1\n30%".to_string(), false)
+        );
+    }
+}
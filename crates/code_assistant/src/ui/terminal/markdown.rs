@@ -0,0 +1,373 @@
+//! Minimal markdown renderer for the subset assistant replies use: ATX
+//! headings, bold/italic/code spans, bullet and numbered lists, and fenced
+//! code blocks.
+//!
+//! `tui_markdown` renders messages by default; the `markdown_renderer`
+//! terminal option switches to this module, whose output does not move
+//! when that crate is upgraded. Each source line renders on its own (soft
+//! line breaks are kept), and runs of blank lines collapse to one.
+
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use super::theme::Theme;
+
+const BULLET: &str = "• ";
+
+/// Which renderer lays out message markdown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MarkdownRenderer {
+    /// The `tui_markdown` crate
+    #[default]
+    TuiMarkdown,
+    /// [`render_lines`], covering the subset replies use
+    Builtin,
+}
+
+fn code_style() -> Style {
+    Style::default().fg(Theme::global().accent_fg)
+}
+
+fn heading_style(level: usize) -> Style {
+    let style = Style::default().add_modifier(Modifier::BOLD);
+    if level == 1 {
+        style.add_modifier(Modifier::UNDERLINED)
+    } else {
+        style
+    }
+}
+
+/// Render `text` into lines, wrapping prose at `width` columns when given.
+/// Code block lines are never wrapped.
+pub fn render_lines(text: &str, width: Option<usize>) -> Vec<Line<'static>> {
+    let width = width.filter(|width| *width > 0);
+    let mut lines: Vec<Line<'static>> = Vec::new();
+    let mut in_fence = false;
+
+    for source in text.lines() {
+        let trimmed = source.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            lines.push(Line::from(Span::styled(source.to_string(), code_style())));
+            continue;
+        }
+        if trimmed.is_empty() {
+            if lines.last().is_some_and(|line| line.width() > 0) {
+                lines.push(Line::default());
+            }
+            continue;
+        }
+
+        if let Some((level, heading)) = parse_heading(trimmed) {
+            let spans = parse_inline(heading)
+                .into_iter()
+                .map(|span| {
+                    let style = heading_style(level).patch(span.style);
+                    Span::styled(span.content, style)
+                })
+                .collect();
+            lines.extend(wrap_spans(spans, "", "", width));
+        } else if let Some((marker, item)) = parse_list_item(source) {
+            let indent = " ".repeat(marker.width());
+            lines.extend(wrap_spans(parse_inline(item), &marker, &indent, width));
+        } else {
+            lines.extend(wrap_spans(parse_inline(source), "", "", width));
+        }
+    }
+
+    while lines.last().is_some_and(|line| line.width() == 0) {
+        lines.pop();
+    }
+    lines
+}
+
+/// `# Title` → `(1, "Title")`; up to six `#` followed by a space.
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.len() - line.trim_start_matches('#').len();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if rest.is_empty() {
+        return Some((level, ""));
+    }
+    rest.strip_prefix(' ')
+        .map(|title| (level, title.trim_end_matches([' ', '#'])))
+}
+
+/// Split a list item into its rendered marker (leading indent kept, `-`,
+/// `*` and `+` drawn as a bullet) and the item text.
+fn parse_list_item(line: &str) -> Option<(String, &str)> {
+    let item = line.trim_start_matches(' ');
+    let indent = &line[..line.len() - item.len()];
+    if let Some(rest) = item
+        .strip_prefix(['-', '*', '+'])
+        .and_then(|rest| rest.strip_prefix(' '))
+    {
+        return Some((format!("{indent}{BULLET}"), rest));
+    }
+
+    let digits = item.len() - item.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits == 0 {
+        return None;
+    }
+    let rest = item[digits..].strip_prefix(['.', ')'])?.strip_prefix(' ')?;
+    Some((format!("{indent}{} ", &item[..digits + 1]), rest))
+}
+
+/// Parse `**bold**`, `*italic*`, `_italic_` and `` `code` `` spans.
+/// Markers without a closing partner are kept as literal text, and `_`
+/// inside a word (`snake_case`) is never emphasis.
+fn parse_inline(text: &str) -> Vec<Span<'static>> {
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut current = String::new();
+    let mut bold = false;
+    let mut italic = false;
+    let style = |bold: bool, italic: bool| {
+        let mut style = Style::default();
+        if bold {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        if italic {
+            style = style.add_modifier(Modifier::ITALIC);
+        }
+        style
+    };
+    let flush = |spans: &mut Vec<Span<'static>>, current: &mut String, style: Style| {
+        if !current.is_empty() {
+            spans.push(Span::styled(std::mem::take(current), style));
+        }
+    };
+
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c == '`' {
+            if let Some(end) = rest[1..].find('`') {
+                flush(&mut spans, &mut current, style(bold, italic));
+                spans.push(Span::styled(rest[1..=end].to_string(), code_style()));
+                rest = &rest[end + 2..];
+                continue;
+            }
+        }
+
+        let before = text[..text.len() - rest.len()].chars().next_back();
+        let opens = |after: &str| after.chars().next().is_some_and(|c| !c.is_whitespace());
+        let closes = before.is_some_and(|c| !c.is_whitespace());
+
+        if let Some(marker) = ["**", "__"].into_iter().find(|m| rest.starts_with(m)) {
+            let toggles = if bold {
+                closes
+            } else {
+                opens(&rest[2..]) && rest[2..].contains(marker)
+            };
+            if toggles {
+                flush(&mut spans, &mut current, style(bold, italic));
+                bold = !bold;
+            } else {
+                current.push_str(marker);
+            }
+            rest = &rest[2..];
+            continue;
+        }
+
+        if c == '*' || c == '_' {
+            // `_` between two word characters is part of an identifier
+            let in_word = c == '_'
+                && before.is_some_and(char::is_alphanumeric)
+                && rest[1..].chars().next().is_some_and(char::is_alphanumeric);
+            let toggles = !in_word
+                && if italic {
+                    closes
+                } else {
+                    opens(&rest[1..]) && rest[1..].contains(c)
+                };
+            if toggles {
+                flush(&mut spans, &mut current, style(bold, italic));
+                italic = !italic;
+                rest = &rest[1..];
+                continue;
+            }
+        }
+
+        current.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    flush(&mut spans, &mut current, style(bold, italic));
+    spans
+}
+
+/// Greedy word wrap of styled spans. The first row starts with `first`,
+/// continuation rows with `rest`; words wider than a row are split
+/// between graphemes.
+fn wrap_spans(
+    spans: Vec<Span<'static>>,
+    first: &str,
+    rest: &str,
+    width: Option<usize>,
+) -> Vec<Line<'static>> {
+    let start = |prefix: &str| {
+        let spans = if prefix.is_empty() {
+            Vec::new()
+        } else {
+            vec![Span::raw(prefix.to_string())]
+        };
+        (spans, prefix.width())
+    };
+
+    let Some(width) = width else {
+        let (mut line, _) = start(first);
+        line.extend(spans);
+        return vec![Line::from(line)];
+    };
+
+    let mut lines = Vec::new();
+    let (mut line, mut used) = start(first);
+    let mut line_start = used;
+    for span in spans {
+        for word in span.content.split_word_bounds() {
+            let word_width = word.width();
+            let is_space = word.chars().all(char::is_whitespace);
+            if used + word_width > width && used > line_start {
+                lines.push(finish_row(std::mem::take(&mut line)));
+                (line, used) = start(rest);
+                line_start = used;
+                if is_space {
+                    continue;
+                }
+            }
+            if used + word_width <= width {
+                push_text(&mut line, word, span.style);
+                used += word_width;
+                continue;
+            }
+            // Wider than a whole row: split between graphemes
+            for grapheme in word.graphemes(true) {
+                let grapheme_width = grapheme.width();
+                if used + grapheme_width > width && used > line_start {
+                    lines.push(finish_row(std::mem::take(&mut line)));
+                    (line, used) = start(rest);
+                    line_start = used;
+                }
+                push_text(&mut line, grapheme, span.style);
+                used += grapheme_width;
+            }
+        }
+    }
+    lines.push(Line::from(line));
+    lines
+}
+
+/// A wrapped row without the spaces left before the break.
+fn finish_row(mut spans: Vec<Span<'static>>) -> Line<'static> {
+    while let Some(last) = spans.last_mut() {
+        let trimmed = last.content.trim_end().len();
+        if trimmed > 0 {
+            last.content.to_mut().truncate(trimmed);
+            break;
+        }
+        spans.pop();
+    }
+    Line::from(spans)
+}
+
+/// Append `text`, extending the last span when the style matches.
+fn push_text(line: &mut Vec<Span<'static>>, text: &str, style: Style) {
+    match line.last_mut() {
+        Some(last) if last.style == style => last.content.to_mut().push_str(text),
+        _ => line.push(Span::styled(text.to_string(), style)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(lines: &[Line]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_headings_and_inline_styles() {
+        let lines = render_lines("# Title\nSome **bold**, *italic* and `code`.", None);
+        assert_eq!(plain(&lines), vec!["Title", "Some bold, italic and code."]);
+        assert_eq!(lines[0].spans[0].style, heading_style(1));
+
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let italic = Style::default().add_modifier(Modifier::ITALIC);
+        assert_eq!(
+            lines[1].spans,
+            vec![
+                Span::raw("Some "),
+                Span::styled("bold", bold),
+                Span::raw(", "),
+                Span::styled("italic", italic),
+                Span::raw(" and "),
+                Span::styled("code", code_style()),
+                Span::raw("."),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unmatched_markers_and_snake_case_stay_literal() {
+        let lines = render_lines("a * b, snake_case_name and 2**3", None);
+        assert_eq!(plain(&lines), vec!["a * b, snake_case_name and 2**3"]);
+        assert!(lines[0]
+            .spans
+            .iter()
+            .all(|span| span.style == Style::default()));
+
+        let lines = render_lines("_whole_ and `unclosed", None);
+        assert_eq!(plain(&lines), vec!["whole and `unclosed"]);
+    }
+
+    #[test]
+    fn test_lists_wrap_with_hanging_indent() {
+        let source = "- first item wraps onto the next row\n  - nested\n10. tenth";
+        let lines = render_lines(source, Some(16));
+        assert_eq!(
+            plain(&lines),
+            vec![
+                "• first item",
+                "  wraps onto the",
+                "  next row",
+                "  • nested",
+                "10. tenth",
+            ]
+        );
+        assert!(lines.iter().all(|line| line.width() <= 16));
+    }
+
+    #[test]
+    fn test_code_fences_are_verbatim_and_unwrapped() {
+        let source = "Run:\n\n\n```sh\ncargo test --workspace **all**\n```\nDone.";
+        let lines = render_lines(source, Some(10));
+        assert_eq!(
+            plain(&lines),
+            vec!["Run:", "", "cargo test --workspace **all**", "Done."]
+        );
+        assert_eq!(lines[2].spans[0].style, code_style());
+    }
+
+    #[test]
+    fn test_long_words_split_between_graphemes() {
+        let lines = render_lines("ファイルが見つかりません", Some(8));
+        assert_eq!(plain(&lines), vec!["ファイル", "が見つか", "りません"]);
+        assert!(lines.iter().all(|line| line.width() <= 8));
+    }
+}
@@ -1,21 +1,80 @@
 use indexmap::IndexMap;
 use ratatui::prelude::*;
 use ratatui::widgets::{Paragraph, Wrap};
+use serde::{Deserialize, Serialize};
 use tui_markdown as md;
 
-use super::tool_renderers::ToolRendererRegistry;
-use super::tool_widget::{is_full_width_parameter, should_hide_parameter, ToolWidget};
+use super::inline_image::placeholder_line;
+use super::line_buffer::{apply_carriage_returns, push_with_carriage_returns};
+use super::theme::Theme;
+use super::tool_renderers::{renderer_for, truncate_to_width, ToolDetail};
+use super::tool_widget::{
+    fallback_json_output, is_full_width_parameter, should_hide_parameter, ToolWidget,
+};
 use crate::ui::ToolStatus;
 
 /// A complete message containing multiple blocks
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LiveMessage {
     pub blocks: Vec<MessageBlock>,
     pub finalized: bool,
     /// When true, the committed stream lines for this message were progressively
     /// sent to scrollback during streaming. Only the final tail needs to be sent
     /// on finalization — the bulk of the content is already in scrollback.
+    #[serde(skip)]
     pub streamed_to_scrollback: bool,
+    /// Tokens and cost of the response, once it has finished
+    #[serde(default)]
+    pub usage: Option<MessageUsage>,
+}
+
+/// Token usage and estimated cost of one LLM response, or a sum of them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MessageUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    /// Dollars; `None` when the model has no pricing configured
+    pub cost: Option<f64>,
+}
+
+impl MessageUsage {
+    /// Add `other` to this usage. The cost counts the parts that have one.
+    pub fn add(&mut self, other: MessageUsage) {
+        self.prompt_tokens = self.prompt_tokens.saturating_add(other.prompt_tokens);
+        self.completion_tokens = self
+            .completion_tokens
+            .saturating_add(other.completion_tokens);
+        self.cost = match (self.cost, other.cost) {
+            (None, None) => None,
+            (a, b) => Some(a.unwrap_or(0.0) + b.unwrap_or(0.0)),
+        };
+    }
+
+    /// `12.3k in · 456 out · $0.0123`
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} in · {} out",
+            format_token_count(self.prompt_tokens),
+            format_token_count(self.completion_tokens)
+        );
+        if let Some(cost) = self.cost {
+            if cost < 1.0 {
+                summary.push_str(&format!(" · ${cost:.4}"));
+            } else {
+                summary.push_str(&format!(" · ${cost:.2}"));
+            }
+        }
+        summary
+    }
+}
+
+/// `950`, `12.3k`, `1.2M`
+fn format_token_count(tokens: u32) -> String {
+    match tokens {
+        0..=999 => tokens.to_string(),
+        1_000..=999_999 => format!("{:.1}k", f64::from(tokens) / 1_000.0),
+        _ => format!("{:.1}M", f64::from(tokens) / 1_000_000.0),
+    }
 }
 
 impl LiveMessage {
@@ -24,6 +83,18 @@ impl LiveMessage {
             blocks: Vec::new(),
             finalized: false,
             streamed_to_scrollback: false,
+            usage: None,
+        }
+    }
+
+    /// Mark the message done; tool parameters still streaming when it ends
+    /// (e.g. a cancelled request) are shown as they are.
+    pub fn finalize(&mut self) {
+        self.finalized = true;
+        for block in &mut self.blocks {
+            if let MessageBlock::ToolUse(tool_block) = block {
+                tool_block.complete_parameters();
+            }
         }
     }
 
@@ -49,6 +120,25 @@ impl LiveMessage {
         None
     }
 
+    /// Get the tool block for `tool_id`, adding a placeholder block if the
+    /// update arrived before the tool was started.
+    pub fn get_or_insert_tool_block_mut(&mut self, tool_id: &str) -> &mut ToolUseBlock {
+        let index = self.blocks.iter().position(
+            |block| matches!(block, MessageBlock::ToolUse(tool_block) if tool_block.id == tool_id),
+        );
+        let index = index.unwrap_or_else(|| {
+            tracing::debug!("Adding placeholder block for unknown tool id {tool_id}");
+            self.add_block(MessageBlock::ToolUse(ToolUseBlock::placeholder(
+                tool_id.to_string(),
+            )));
+            self.blocks.len() - 1
+        });
+        match &mut self.blocks[index] {
+            MessageBlock::ToolUse(tool_block) => tool_block,
+            _ => unreachable!("index points at a tool block"),
+        }
+    }
+
     /// Check if this message has any content
     pub fn has_content(&self) -> bool {
         !self.blocks.is_empty() && self.blocks.iter().any(|block| block.has_content())
@@ -56,12 +146,17 @@ impl LiveMessage {
 }
 
 /// Different types of blocks within a message
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum MessageBlock {
     PlainText(PlainTextBlock),
     Thinking(ThinkingBlock),
     ToolUse(ToolUseBlock),
     UserText(PlainTextBlock),
+    /// Instructions and other system notices (e.g. compaction summaries)
+    SystemText(PlainTextBlock),
+    /// Image attached by the user or shown by a tool
+    Image(ImageBlock),
 }
 
 impl MessageBlock {
@@ -72,6 +167,8 @@ impl MessageBlock {
             MessageBlock::Thinking(block) => !block.content.trim().is_empty(),
             MessageBlock::ToolUse(block) => !block.name.is_empty(),
             MessageBlock::UserText(block) => !block.content.trim().is_empty(),
+            MessageBlock::SystemText(block) => !block.content.trim().is_empty(),
+            MessageBlock::Image(block) => !block.data.is_empty(),
         }
     }
 
@@ -80,27 +177,43 @@ impl MessageBlock {
         match self {
             MessageBlock::PlainText(block) => block.content.push_str(content),
             MessageBlock::Thinking(block) => block.content.push_str(content),
-            MessageBlock::ToolUse(_) => {
-                // Tool use blocks don't support general content appending
+            MessageBlock::ToolUse(_) | MessageBlock::Image(_) => {
+                // Tool use and image blocks don't support general content appending
                 // Parameter updates are handled separately
             }
             MessageBlock::UserText(block) => block.content.push_str(content),
+            MessageBlock::SystemText(block) => block.content.push_str(content),
+        }
+    }
+
+    /// Left indent of text and thinking blocks in a `width` wide area: the
+    /// width of the user marker, so content lines up after it. Areas too
+    /// narrow to spare it get no indent.
+    fn indent(width: u16) -> u16 {
+        let gutter = Theme::global().gutter_width() as u16;
+        if width > gutter {
+            gutter
+        } else {
+            0
         }
     }
 
-    /// Width reserved for the left indent on text/thinking/tool blocks,
-    /// aligning content with the user's "› " prefix.
-    const INDENT: u16 = 2;
+    /// Width left for text and thinking content in a `width` wide area.
+    pub fn content_width(width: u16) -> u16 {
+        width - Self::indent(width)
+    }
 
     /// Calculate the height needed to render this block
+    #[cfg_attr(not(test), allow(dead_code))]
     pub fn calculate_height(&self, width: u16) -> u16 {
-        let inner_width = if width > Self::INDENT {
-            width - Self::INDENT
-        } else {
-            width
-        };
+        self.calculate_height_with(width, ToolDetail::Full)
+    }
+
+    /// Like [`Self::calculate_height`], with tools shown at `detail`.
+    pub fn calculate_height_with(&self, width: u16, detail: ToolDetail) -> u16 {
+        let inner_width = Self::content_width(width);
         match self {
-            MessageBlock::PlainText(block) => {
+            MessageBlock::PlainText(block) | MessageBlock::SystemText(block) => {
                 if block.content.trim().is_empty() {
                     return 0;
                 }
@@ -120,44 +233,54 @@ impl MessageBlock {
                 let content_lines = block.content.lines().count().max(1) as u16;
                 2 + content_lines // 1 blank before + content + 1 blank after
             }
+            // The live viewport shows the placeholder; the image itself is
+            // drawn once the message reaches the scrollback
+            MessageBlock::Image(_) => 1,
             MessageBlock::ToolUse(block) => {
-                // Try a registered renderer first.
-                if let Some(registry) = ToolRendererRegistry::global() {
-                    if let Some(renderer) = registry.get(&block.name) {
-                        return renderer.calculate_height(block, width);
-                    }
-                }
+                tool_body_height(block, width, detail)
+                    + u16::from(block.awaiting_approval)
+                    + u16::from(block.visible_progress().is_some())
+            }
+        }
+    }
+}
 
-                // Fallback: generic height calculation
-                let mut height: u16 = 1; // Tool name line
+/// Height of a tool block without the approval prompt.
+fn tool_body_height(block: &ToolUseBlock, width: u16, detail: ToolDetail) -> u16 {
+    // Try a registered renderer first.
+    if let Some(renderer) = renderer_for(&block.name, detail) {
+        return renderer.calculate_height(block, width);
+    }
 
-                for (name, param) in &block.parameters {
-                    if should_hide_parameter(&block.name, name, &param.value) {
-                        continue;
-                    }
-                    if is_full_width_parameter(&block.name, name) {
-                        height += 1; // Parameter name
-                        height += param.value.lines().count() as u16;
-                    } else {
-                        height += 1;
-                    }
-                }
+    // Fallback: generic height calculation
+    let mut height: u16 = 1; // Tool name line
 
-                if block.status_message.is_some() && block.status == ToolStatus::Error {
-                    height += 1;
-                }
+    for (name, param) in &block.parameters {
+        if should_hide_parameter(&block.name, name, &param.value) {
+            continue;
+        }
+        if is_full_width_parameter(&block.name, name) {
+            height += 1; // Parameter name
+            height += param.value.lines().count() as u16;
+        } else {
+            height += 1;
+        }
+    }
 
-                // Output (used by spawn_agent for streaming sub-agent activity)
-                if let Some(ref output) = block.output {
-                    if !output.is_empty() {
-                        height += output.lines().count() as u16;
-                    }
-                }
+    if block.status_message.is_some() && block.status == ToolStatus::Error {
+        height += 1;
+    }
 
-                height
-            }
+    // Output (used by spawn_agent for streaming sub-agent activity)
+    if let Some(ref output) = block.output {
+        if !output.is_empty() {
+            height += fallback_json_output(output)
+                .map_or_else(|| output.lines().count(), |lines| lines.len())
+                as u16;
         }
     }
+
+    height
 }
 
 fn measure_markdown_height(content: &str, width: u16) -> u16 {
@@ -195,11 +318,14 @@ fn measure_markdown_height(content: &str, width: u16) -> u16 {
 
 impl Widget for MessageBlock {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let indent = if area.width > Self::INDENT {
-            Self::INDENT
-        } else {
-            0
-        };
+        self.render_with_detail(area, buf, ToolDetail::Full);
+    }
+}
+
+impl MessageBlock {
+    /// Render this block, with tools shown at `detail`.
+    pub fn render_with_detail(self, area: Rect, buf: &mut Buffer, detail: ToolDetail) {
+        let indent = Self::indent(area.width);
         let inner = Rect {
             x: area.x + indent,
             y: area.y,
@@ -221,7 +347,7 @@ impl Widget for MessageBlock {
                     let paragraph = ratatui::widgets::Paragraph::new(text)
                         .style(
                             Style::default()
-                                .fg(Color::DarkGray)
+                                .fg(Theme::global().thinking_fg)
                                 .add_modifier(Modifier::DIM)
                                 .add_modifier(Modifier::ITALIC),
                         )
@@ -236,13 +362,13 @@ impl Widget for MessageBlock {
                     for (i, line) in block.content.lines().enumerate() {
                         let prefix = if i == 0 {
                             Span::styled(
-                                "› ",
+                                Theme::global().user_marker,
                                 Style::default()
-                                    .add_modifier(Modifier::BOLD)
-                                    .add_modifier(Modifier::DIM),
+                                    .fg(Theme::global().user_marker_fg)
+                                    .add_modifier(Modifier::BOLD),
                             )
                         } else {
-                            Span::raw("  ")
+                            Span::raw(Theme::global().gutter())
                         };
                         lines.push(Line::from(vec![prefix, Span::raw(line.to_string())]));
                     }
@@ -251,18 +377,53 @@ impl Widget for MessageBlock {
                     paragraph.render(area, buf);
                 }
             }
+            MessageBlock::SystemText(block) => {
+                if !block.content.trim().is_empty() {
+                    let text = md::from_str(&block.content);
+                    let paragraph = Paragraph::new(text)
+                        .style(
+                            Style::default()
+                                .fg(Theme::global().system_fg)
+                                .add_modifier(Modifier::DIM),
+                        )
+                        .wrap(Wrap { trim: false });
+                    paragraph.render(inner, buf);
+                }
+            }
             MessageBlock::ToolUse(block) => {
                 // ToolWidget renders its own "● name" layout starting at area.x,
                 // so it uses the full area (dot at col 0, text at col 2).
-                let tool_widget = ToolWidget::new(&block);
-                tool_widget.render(area, buf);
+                let mut tool_area = area;
+                if block.awaiting_approval && area.height > 0 {
+                    tool_area.height -= 1;
+                    render_approval_prompt(area.x, area.bottom() - 1, area.width, buf);
+                }
+                let tool_widget = ToolWidget::new(&block, detail);
+                tool_widget.render(tool_area, buf);
+            }
+            MessageBlock::Image(block) => {
+                buf.set_line(area.x, area.y, &placeholder_line(&block), area.width);
             }
         }
     }
 }
 
+/// `⏎ to approve / Esc to skip` line under a tool waiting for approval.
+fn render_approval_prompt(x: u16, y: u16, width: u16, buf: &mut Buffer) {
+    let style = Style::default().fg(Theme::global().status_pending);
+    let key = style.add_modifier(Modifier::BOLD);
+    let line = Line::from(vec![
+        Span::raw(Theme::global().gutter()),
+        Span::styled("⏎", key),
+        Span::styled(" to approve / ", style),
+        Span::styled("Esc", key),
+        Span::styled(" to skip", style),
+    ]);
+    buf.set_line(x, y, &line, width);
+}
+
 /// Plain text block for regular assistant responses
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlainTextBlock {
     pub content: String,
 }
@@ -275,10 +436,56 @@ impl PlainTextBlock {
     }
 }
 
+/// Image in a message, drawn inline in the scrollback where the terminal
+/// supports it (see `inline_image`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageBlock {
+    pub media_type: String,
+    /// Base64-encoded image data
+    pub data: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+impl ImageBlock {
+    /// An image block, with the dimensions read from the image header.
+    pub fn new(media_type: String, data: String) -> Self {
+        let (width, height) = image_dimensions(&data).unzip();
+        Self {
+            media_type,
+            data,
+            width,
+            height,
+        }
+    }
+
+    /// Text shown where the image can't be drawn.
+    pub fn placeholder(&self) -> String {
+        match self.width.zip(self.height) {
+            Some((width, height)) => format!("[image {width}x{height} ({})]", self.media_type),
+            None => format!("[image ({})]", self.media_type),
+        }
+    }
+}
+
+fn image_dimensions(data: &str) -> Option<(u32, u32)> {
+    use base64::Engine;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .ok()?;
+    image::ImageReader::new(std::io::Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
 /// Thinking block for assistant reasoning
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThinkingBlock {
     pub content: String,
+    /// Not meaningful outside this process, so restored blocks start anew
+    #[serde(skip, default = "std::time::Instant::now")]
     pub start_time: std::time::Instant,
 }
 
@@ -304,7 +511,7 @@ impl ThinkingBlock {
 }
 
 /// Tool use block with parameters
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolUseBlock {
     pub name: String,
     pub id: String,
@@ -312,6 +519,31 @@ pub struct ToolUseBlock {
     pub status: ToolStatus,
     pub status_message: Option<String>,
     pub output: Option<String>,
+    /// Lines dropped from the front of `output` to stay within the tail window
+    pub truncated_lines: usize,
+    /// The first line of `output` lost its start to the byte limit
+    pub truncated_first_line: bool,
+    /// The call is gated and waits for the user to approve or skip it
+    pub awaiting_approval: bool,
+    /// Fraction done (0.0 to 1.0) reported by the running tool
+    pub progress: Option<f32>,
+    /// How long the tool ran, reported once it finished
+    pub duration: Option<std::time::Duration>,
+    /// The tool succeeded without changing anything
+    pub unchanged: bool,
+    /// Streamed output ended in `\r`, which may be half of a `\r\n`
+    #[serde(skip)]
+    pub pending_cr: bool,
+}
+
+/// Name shown for a tool whose updates arrived before it was started.
+const PLACEHOLDER_TOOL_NAME: &str = "tool";
+
+/// Bounds on how much tool output a block keeps for display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputLimit {
+    pub max_bytes: usize,
+    pub max_lines: usize,
 }
 
 impl ToolUseBlock {
@@ -323,29 +555,158 @@ impl ToolUseBlock {
             status: ToolStatus::Pending,
             status_message: None,
             output: None,
+            truncated_lines: 0,
+            truncated_first_line: false,
+            awaiting_approval: false,
+            progress: None,
+            duration: None,
+            unchanged: false,
+            pending_cr: false,
+        }
+    }
+
+    /// Progress to show under the header; only while the tool is running.
+    pub fn visible_progress(&self) -> Option<f32> {
+        self.progress.filter(|_| self.status == ToolStatus::Running)
+    }
+
+    /// A block for a tool that has only been seen through later updates; the
+    /// name is filled in once the tool is started.
+    pub fn placeholder(id: String) -> Self {
+        Self::new(PLACEHOLDER_TOOL_NAME.to_string(), id)
+    }
+
+    pub fn is_placeholder(&self) -> bool {
+        self.name == PLACEHOLDER_TOOL_NAME
+    }
+
+    /// Replace the output, keeping only its tail within `limit`.
+    pub fn set_output(&mut self, output: Option<String>, limit: OutputLimit) {
+        self.output = output.map(|output| apply_carriage_returns(&output));
+        self.truncated_lines = 0;
+        self.truncated_first_line = false;
+        self.pending_cr = false;
+        self.enforce_output_limit(limit);
+    }
+
+    /// Append streamed output, dropping the oldest lines beyond `limit`.
+    /// Carriage returns redraw the current line, as in a terminal.
+    pub fn append_output(&mut self, chunk: &str, limit: OutputLimit) {
+        let output = self.output.get_or_insert_with(String::new);
+        push_with_carriage_returns(output, &mut self.pending_cr, chunk);
+        self.enforce_output_limit(limit);
+    }
+
+    fn enforce_output_limit(&mut self, limit: OutputLimit) {
+        let Some(output) = self.output.as_mut() else {
+            return;
+        };
+
+        let mut line_count = output.matches('\n').count() + usize::from(!output.ends_with('\n'));
+        let mut cut = 0;
+        let mut dropped = 0;
+        let mut cut_inside_line = false;
+        while output.len() - cut > limit.max_bytes || line_count > limit.max_lines {
+            match output[cut..].find('\n') {
+                Some(newline) => {
+                    cut += newline + 1;
+                    dropped += 1;
+                    line_count -= 1;
+                }
+                None => {
+                    // A single oversized line: keep its tail
+                    let mut start = output.len().saturating_sub(limit.max_bytes);
+                    while !output.is_char_boundary(start) {
+                        start += 1;
+                    }
+                    cut_inside_line = start > cut;
+                    cut = cut.max(start);
+                    break;
+                }
+            }
         }
+
+        if cut > 0 {
+            output.drain(..cut);
+            self.truncated_lines += dropped;
+            self.truncated_first_line =
+                cut_inside_line || (self.truncated_first_line && dropped == 0);
+        }
+    }
+
+    /// Whether `output` is missing anything from its start.
+    pub fn is_output_truncated(&self) -> bool {
+        self.truncated_lines > 0 || self.truncated_first_line
     }
 
-    /// Add or update a parameter value
+    /// Whether this call repeats `previous`: the same tool and parameters
+    /// with the same status and output, so collapsing it hides nothing.
+    /// Failed calls never count as repeats so errors stay visible.
+    pub fn is_repeat_of(&self, previous: &ToolUseBlock) -> bool {
+        self.status != ToolStatus::Error
+            && self.status == previous.status
+            && self.output == previous.output
+            && self.name == previous.name
+            && self.parameters.len() == previous.parameters.len()
+            && self.parameters.iter().all(|(name, value)| {
+                previous
+                    .parameters
+                    .get(name)
+                    .is_some_and(|other| other.value == value.value)
+            })
+    }
+
+    /// Add or update a parameter value. Parameters stream in one after
+    /// another, so starting a new one completes the others.
     pub fn add_or_update_parameter(&mut self, name: String, value: String) {
         match self.parameters.get_mut(&name) {
             Some(param) => param.append_value(&value),
             None => {
-                self.parameters.insert(name, ParameterValue::new(value));
+                self.complete_parameters();
+                self.parameters
+                    .insert(name, ParameterValue::streaming(value));
             }
         }
     }
+
+    /// Mark all parameters as fully received.
+    pub fn complete_parameters(&mut self) {
+        for param in self.parameters.values_mut() {
+            param.complete = true;
+        }
+    }
 }
 
 /// Parameter value that can be streamed
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct ParameterValue {
     pub value: String,
+    /// Whether the whole value has arrived; saved values always have
+    #[serde(skip, default = "complete_by_default")]
+    pub complete: bool,
+}
+
+fn complete_by_default() -> bool {
+    true
 }
 
 impl ParameterValue {
+    /// A value received in full.
+    #[cfg(test)]
     pub fn new(value: String) -> Self {
-        Self { value }
+        Self {
+            value,
+            complete: true,
+        }
+    }
+
+    /// The first chunk of a value that is still streaming in.
+    pub fn streaming(value: String) -> Self {
+        Self {
+            value,
+            complete: false,
+        }
     }
 
     pub fn append_value(&mut self, content: &str) {
@@ -354,10 +715,6 @@ impl ParameterValue {
 
     pub fn get_display_value(&self) -> String {
         // Truncate long values for regular parameters
-        if self.value.len() > 100 {
-            format!("{}...", &self.value[..97])
-        } else {
-            self.value.clone()
-        }
+        truncate_to_width(&self.value, 100)
     }
 }
@@ -1,20 +1,39 @@
 pub mod app;
+pub mod color_support;
+pub mod command_palette;
 pub mod commands;
 pub mod composer;
+pub mod config;
+pub mod copy;
 pub mod custom_terminal;
+pub mod diff_pane;
+pub mod export;
+pub mod fuzzy;
 pub mod history_insert;
+pub mod inline_image;
 pub mod input;
+pub mod line_buffer;
+pub mod markdown;
 pub mod message;
+pub mod model_picker;
+pub mod path_completion;
+pub mod path_links;
+pub mod redact;
 pub mod renderer;
+pub mod session_replay;
+pub mod spinner;
 pub mod state;
 pub mod streaming;
 pub mod terminal_color;
 pub mod textarea;
+pub mod theme;
 pub mod tool_renderers;
 pub mod tool_widget;
 pub mod transcript;
+pub mod transcript_writer;
 pub mod tui;
 pub mod ui;
+pub mod viewer;
 pub mod welcome_banner;
 
 pub use app::TerminalTuiApp as TerminalApp;
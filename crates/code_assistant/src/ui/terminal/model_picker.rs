@@ -0,0 +1,272 @@
+//! Model picker opened by `/model` without arguments, plus the fuzzy
+//! matching used to resolve `/model <partial>`.
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::fuzzy::{fuzzy_filter, match_score};
+
+/// Maximum number of models listed in the picker at once.
+const MAX_VISIBLE_MODELS: usize = 8;
+
+/// How well a candidate matches a query; lower is better.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum MatchTier {
+    Exact,
+    Prefix,
+    Substring,
+    Subsequence,
+}
+
+fn match_tier(candidate: &str, query: &str) -> Option<MatchTier> {
+    let candidate = candidate.to_lowercase();
+    let query = query.to_lowercase();
+
+    if candidate == query {
+        return Some(MatchTier::Exact);
+    }
+    if candidate.starts_with(&query) {
+        return Some(MatchTier::Prefix);
+    }
+    if candidate.contains(&query) {
+        return Some(MatchTier::Substring);
+    }
+
+    match_score(&query, &candidate).map(|_| MatchTier::Subsequence)
+}
+
+/// Outcome of resolving a partial model name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelMatch {
+    /// A single best match
+    Found(String),
+    /// Several equally good matches
+    Ambiguous(Vec<String>),
+    NoMatch,
+}
+
+/// Resolve a partial model name against the available models. The match
+/// is unique only if no other candidate matches equally well.
+pub fn resolve_model(candidates: &[String], query: &str) -> ModelMatch {
+    if let Some(exact) = candidates.iter().find(|candidate| *candidate == query) {
+        return ModelMatch::Found(exact.clone());
+    }
+
+    let query = query.trim();
+    let mut best: Option<MatchTier> = None;
+    let mut best_matches: Vec<String> = Vec::new();
+    for candidate in candidates {
+        let Some(tier) = match_tier(candidate, query) else {
+            continue;
+        };
+        match best {
+            Some(current) if tier > current => {}
+            Some(current) if tier == current => best_matches.push(candidate.clone()),
+            _ => {
+                best = Some(tier);
+                best_matches = vec![candidate.clone()];
+            }
+        }
+    }
+
+    match best_matches.len() {
+        0 => ModelMatch::NoMatch,
+        1 => ModelMatch::Found(best_matches.remove(0)),
+        _ => ModelMatch::Ambiguous(best_matches),
+    }
+}
+
+/// Result of a key press while the picker is open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PickerAction {
+    /// Keep the picker open
+    None,
+    /// A model was chosen
+    Select(String),
+    /// The picker was dismissed
+    Cancel,
+}
+
+/// Interactive list of models, narrowed by typing a fuzzy query.
+#[derive(Debug, Clone)]
+pub struct ModelPicker {
+    models: Vec<String>,
+    query: String,
+    selected: usize,
+}
+
+impl ModelPicker {
+    pub fn new(models: Vec<String>, current_model: Option<&str>) -> Self {
+        let selected = current_model
+            .and_then(|current| models.iter().position(|model| model == current))
+            .unwrap_or(0);
+        Self {
+            models,
+            query: String::new(),
+            selected,
+        }
+    }
+
+    /// Models matching the current query, best matches first.
+    pub fn filtered(&self) -> Vec<&str> {
+        fuzzy_filter(&self.models, &self.query)
+    }
+
+    pub fn selected_model(&self) -> Option<String> {
+        self.filtered()
+            .get(self.selected)
+            .map(|model| model.to_string())
+    }
+
+    pub fn handle_key(&mut self, key_event: KeyEvent) -> PickerAction {
+        let match_count = self.filtered().len();
+        match key_event.code {
+            KeyCode::Esc => PickerAction::Cancel,
+            KeyCode::Char('c') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                PickerAction::Cancel
+            }
+            KeyCode::Enter => match self.selected_model() {
+                Some(model) => PickerAction::Select(model),
+                None => PickerAction::None,
+            },
+            KeyCode::Up => {
+                self.selected = self.selected.saturating_sub(1);
+                PickerAction::None
+            }
+            KeyCode::Down => {
+                if self.selected + 1 < match_count {
+                    self.selected += 1;
+                }
+                PickerAction::None
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.selected = 0;
+                PickerAction::None
+            }
+            KeyCode::Char(c) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.query.push(c);
+                self.selected = 0;
+                PickerAction::None
+            }
+            _ => PickerAction::None,
+        }
+    }
+
+    /// Status-area text for the picker: a header with the query and a
+    /// window of matching models with the selection marked.
+    pub fn to_status_text(&self) -> String {
+        let matches = self.filtered();
+        let mut text = if self.query.is_empty() {
+            String::from("Select model (type to filter, Enter to switch, Esc to cancel)")
+        } else {
+            format!("Select model: {}", escape_markdown(&self.query))
+        };
+
+        if matches.is_empty() {
+            text.push_str("\nNo matching models");
+            return text;
+        }
+
+        let start = self
+            .selected
+            .saturating_sub(MAX_VISIBLE_MODELS - 1)
+            .min(matches.len().saturating_sub(MAX_VISIBLE_MODELS));
+        let end = (start + MAX_VISIBLE_MODELS).min(matches.len());
+        for (idx, model) in matches[start..end].iter().enumerate() {
+            text.push('\n');
+            let model = escape_markdown(model);
+            if start + idx == self.selected {
+                text.push_str(&format!("› **{model}**"));
+            } else {
+                text.push_str(&format!("  {model}"));
+            }
+        }
+        if end < matches.len() {
+            text.push_str(&format!("\n  (+{} more)", matches.len() - end));
+        }
+        text
+    }
+}
+
+/// `text` with the characters that would start markdown emphasis or code
+/// escaped, so model names show literally.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn models() -> Vec<String> {
+        vec![
+            "Claude Sonnet 4".to_string(),
+            "Claude Sonnet 4.5".to_string(),
+            "GPT-5".to_string(),
+            "GPT-5 Mini".to_string(),
+        ]
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_resolve_model_unique_and_ambiguous() {
+        let models = models();
+        assert_eq!(
+            resolve_model(&models, "gpt-5"),
+            ModelMatch::Found("GPT-5".to_string())
+        );
+        assert_eq!(
+            resolve_model(&models, "4.5"),
+            ModelMatch::Found("Claude Sonnet 4.5".to_string())
+        );
+        assert_eq!(
+            resolve_model(&models, "sonnet"),
+            ModelMatch::Ambiguous(vec![
+                "Claude Sonnet 4".to_string(),
+                "Claude Sonnet 4.5".to_string()
+            ])
+        );
+        assert_eq!(resolve_model(&models, "llama"), ModelMatch::NoMatch);
+    }
+
+    #[test]
+    fn test_fuzzy_filter_matches_subsequence() {
+        let models = models();
+        assert_eq!(fuzzy_filter(&models, "gmini"), vec!["GPT-5 Mini"]);
+        assert_eq!(fuzzy_filter(&models, "").len(), models.len());
+    }
+
+    #[test]
+    fn test_picker_filters_and_selects() {
+        let mut picker = ModelPicker::new(models(), Some("GPT-5"));
+        assert_eq!(picker.selected_model().as_deref(), Some("GPT-5"));
+
+        for c in "son".chars() {
+            picker.handle_key(key(KeyCode::Char(c)));
+        }
+        assert_eq!(picker.filtered().len(), 2);
+        picker.handle_key(key(KeyCode::Down));
+        assert_eq!(
+            picker.handle_key(key(KeyCode::Enter)),
+            PickerAction::Select("Claude Sonnet 4.5".to_string())
+        );
+        assert!(picker.to_status_text().contains("› **Claude Sonnet 4.5**"));
+        assert_eq!(picker.handle_key(key(KeyCode::Esc)), PickerAction::Cancel);
+    }
+
+    #[test]
+    fn test_status_text_escapes_model_names() {
+        let picker = ModelPicker::new(vec!["my_model*`v2`".to_string()], None);
+        assert!(picker.to_status_text().contains(r"› **my\_model\*\`v2\`**"));
+    }
+}
@@ -0,0 +1,238 @@
+//! `@`-mention path completion for the composer: typing `@` followed by a
+//! partial path lists matching files under the session root.
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ignore::WalkBuilder;
+use tokio::sync::watch;
+use tracing::debug;
+
+use super::fuzzy::fuzzy_filter;
+
+/// Maximum number of completions listed at once.
+const MAX_COMPLETIONS: usize = 8;
+/// Stop indexing after this many files to bound scan time in huge trees.
+const MAX_INDEXED_FILES: usize = 20_000;
+/// Minimum time between two filesystem scans.
+const RESCAN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Relative paths of the files under a root directory. Scans run on a
+/// background thread and are debounced, so completion never blocks input.
+pub struct PathIndex {
+    root: PathBuf,
+    files: Arc<Mutex<Arc<Vec<String>>>>,
+    scanning: Arc<AtomicBool>,
+    last_scan: Option<Instant>,
+    /// Notified when a scan finishes so the completion list gets redrawn
+    on_update: Option<watch::Sender<()>>,
+}
+
+impl PathIndex {
+    pub fn new(root: PathBuf, on_update: Option<watch::Sender<()>>) -> Self {
+        Self {
+            root,
+            files: Arc::new(Mutex::new(Arc::new(Vec::new()))),
+            scanning: Arc::new(AtomicBool::new(false)),
+            last_scan: None,
+            on_update,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_files(files: Vec<String>) -> Self {
+        let mut index = Self::new(PathBuf::new(), None);
+        index.files = Arc::new(Mutex::new(Arc::new(files)));
+        index.last_scan = Some(Instant::now());
+        index
+    }
+
+    /// Start a background rescan unless one is running or the last one
+    /// started less than `RESCAN_INTERVAL` ago.
+    pub fn refresh(&mut self) {
+        if self
+            .last_scan
+            .is_some_and(|last| last.elapsed() < RESCAN_INTERVAL)
+        {
+            return;
+        }
+        if self.scanning.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        self.last_scan = Some(Instant::now());
+
+        let root = self.root.clone();
+        let files = self.files.clone();
+        let scanning = self.scanning.clone();
+        let on_update = self.on_update.clone();
+        std::thread::spawn(move || {
+            let scanned = scan_files(&root);
+            debug!("Indexed {} files for path completion", scanned.len());
+            *files.lock().unwrap() = Arc::new(scanned);
+            scanning.store(false, Ordering::SeqCst);
+            if let Some(on_update) = on_update {
+                let _ = on_update.send(());
+            }
+        });
+    }
+
+    pub fn files(&self) -> Arc<Vec<String>> {
+        self.files.lock().unwrap().clone()
+    }
+
+    pub fn is_scanning(&self) -> bool {
+        self.scanning.load(Ordering::SeqCst)
+    }
+}
+
+/// Files under `root` as sorted `/`-separated relative paths, honouring
+/// `.gitignore` and friends.
+fn scan_files(root: &Path) -> Vec<String> {
+    let mut files: Vec<String> = WalkBuilder::new(root)
+        .require_git(false)
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+        .filter_map(|entry| {
+            let relative = entry.path().strip_prefix(root).ok()?;
+            Some(
+                relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/"),
+            )
+        })
+        .take(MAX_INDEXED_FILES)
+        .collect();
+    files.sort();
+    files
+}
+
+/// Byte range of the `@token` ending at `cursor`, if the cursor is inside
+/// one. The `@` must start the text or follow whitespace, so e-mail
+/// addresses don't trigger completion.
+pub fn mention_at_cursor(text: &str, cursor: usize) -> Option<Range<usize>> {
+    let before = text.get(..cursor)?;
+    let start = before
+        .char_indices()
+        .rev()
+        .find(|(_, c)| c.is_whitespace())
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    let token = &before[start..];
+    if !token.starts_with('@') {
+        return None;
+    }
+    // Extend over the rest of the token after the cursor
+    let end = text[cursor..]
+        .find(char::is_whitespace)
+        .map_or(text.len(), |offset| cursor + offset);
+    Some(start..end)
+}
+
+/// An open completion list for the `@token` at `range`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathCompletion {
+    pub range: Range<usize>,
+    pub query: String,
+    pub selected: usize,
+}
+
+impl PathCompletion {
+    /// Matching paths for the query, best first, limited to `MAX_COMPLETIONS`.
+    pub fn matches(&self, files: &[String]) -> Vec<String> {
+        fuzzy_filter(files, &self.query)
+            .into_iter()
+            .take(MAX_COMPLETIONS)
+            .map(str::to_string)
+            .collect()
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn select_next(&mut self, match_count: usize) {
+        if self.selected + 1 < match_count {
+            self.selected += 1;
+        }
+    }
+
+    /// Status-area text: a header and the matches with the selection marked.
+    pub fn to_status_text(&self, files: &[String], scanning: bool) -> String {
+        let mut text = format!(
+            "Files matching `@{}` (Tab to insert, Esc to dismiss)",
+            self.query
+        );
+        let matches = self.matches(files);
+        if matches.is_empty() {
+            text.push_str(if scanning {
+                "\nScanning files…"
+            } else {
+                "\nNo matching files"
+            });
+            return text;
+        }
+        for (idx, path) in matches.iter().enumerate() {
+            text.push('\n');
+            if idx == self.selected {
+                text.push_str(&format!("› **{path}**"));
+            } else {
+                text.push_str(&format!("  {path}"));
+            }
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mention_at_cursor() {
+        assert_eq!(mention_at_cursor("@src", 4), Some(0..4));
+        assert_eq!(mention_at_cursor("see @src/ma now", 9), Some(4..11));
+        assert_eq!(mention_at_cursor("see @src/main", 6), Some(4..13));
+        assert_eq!(mention_at_cursor("mail me@host", 12), None);
+        assert_eq!(mention_at_cursor("@src done", 9), None);
+        assert_eq!(mention_at_cursor("plain", 5), None);
+    }
+
+    #[test]
+    fn test_scan_respects_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("src")).unwrap();
+        std::fs::create_dir_all(dir.path().join("target")).unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+        std::fs::write(dir.path().join("src/main.rs"), "").unwrap();
+        std::fs::write(dir.path().join("target/out.bin"), "").unwrap();
+        std::fs::write(dir.path().join("README.md"), "").unwrap();
+
+        assert_eq!(scan_files(dir.path()), vec!["README.md", "src/main.rs"]);
+    }
+
+    #[test]
+    fn test_matches_are_limited_and_ranked() {
+        let files: Vec<String> = (0..20)
+            .map(|i| format!("src/module_{i}.rs"))
+            .chain(["main.rs".to_string()])
+            .collect();
+        let completion = PathCompletion {
+            range: 0..3,
+            query: "ma".to_string(),
+            selected: 0,
+        };
+        assert_eq!(completion.matches(&files), vec!["main.rs"]);
+
+        let completion = PathCompletion {
+            range: 0..4,
+            query: "src".to_string(),
+            selected: 0,
+        };
+        assert_eq!(completion.matches(&files).len(), MAX_COMPLETIONS);
+    }
+}
@@ -0,0 +1,309 @@
+//! File paths and `path:line` references in assistant prose.
+//!
+//! Detection is deliberately conservative: a token counts only if it has a
+//! file extension and either a directory part (`src/main.rs`, `./foo.rs`)
+//! or a line number (`main.rs:42`). URLs, version numbers and words joined
+//! by a slash are left alone.
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use regex::Regex;
+
+use super::theme::Theme;
+
+/// A path mentioned in text, with the line it points at if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathRef {
+    pub path: String,
+    pub line: Option<u32>,
+}
+
+/// A [`PathRef`] and the byte range it covers in the scanned text
+/// (including any `:line` suffix).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathMatch {
+    pub range: Range<usize>,
+    pub path_ref: PathRef,
+}
+
+fn path_regex() -> &'static Regex {
+    static PATH_REGEX: OnceLock<Regex> = OnceLock::new();
+    PATH_REGEX.get_or_init(|| {
+        Regex::new(
+            r#"(?:^|[\s(\[{"'`])(?P<path>(?:\.{1,2}/|~/|/)?(?:[\w.-]+/)*[\w-][\w.-]*\.[A-Za-z][A-Za-z0-9]{0,7})(?::(?P<line>\d+)(?::\d+)?)?"#,
+        )
+        .unwrap()
+    })
+}
+
+/// Characters that may follow a reference, e.g. the period ending a sentence.
+fn is_trailing_boundary(c: char) -> bool {
+    c.is_whitespace() || ".,;:!?)]}\"'`".contains(c)
+}
+
+/// All path references in `text`, in order.
+pub fn find_path_refs(text: &str) -> Vec<PathMatch> {
+    path_regex()
+        .captures_iter(text)
+        .filter_map(|captures| {
+            let path = captures.name("path")?;
+            let line = captures.name("line");
+            let end = captures.get(0)?.end();
+            if !text[end..].chars().next().is_none_or(is_trailing_boundary) {
+                return None;
+            }
+            if !path.as_str().contains('/') && line.is_none() {
+                return None;
+            }
+            Some(PathMatch {
+                range: path.start()..end,
+                path_ref: PathRef {
+                    path: path.as_str().to_string(),
+                    line: line.and_then(|line| line.as_str().parse().ok()),
+                },
+            })
+        })
+        .collect()
+}
+
+/// The last path reference in `text`.
+pub fn last_path_ref(text: &str) -> Option<PathRef> {
+    find_path_refs(text)
+        .pop()
+        .map(|path_match| path_match.path_ref)
+}
+
+fn path_style() -> Style {
+    Style::default()
+        .fg(Theme::global().accent_fg)
+        .add_modifier(Modifier::UNDERLINED)
+}
+
+/// Restyle the path references in `line`. References split across two
+/// wrapped rows are not recognised.
+pub fn highlight_path_refs(line: Line<'static>) -> Line<'static> {
+    let text: String = line
+        .spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect();
+    let matches = find_path_refs(&text);
+    if matches.is_empty() {
+        return line;
+    }
+
+    let mut spans = Vec::with_capacity(line.spans.len() + matches.len() * 2);
+    let mut offset = 0;
+    for span in &line.spans {
+        let start = offset;
+        let end = offset + span.content.len();
+        offset = end;
+
+        let mut cursor = start;
+        for path_match in matches
+            .iter()
+            .filter(|path_match| path_match.range.start < end && path_match.range.end > start)
+        {
+            let from = path_match.range.start.max(start);
+            let to = path_match.range.end.min(end);
+            if from > cursor {
+                spans.push(Span::styled(text[cursor..from].to_string(), span.style));
+            }
+            spans.push(Span::styled(
+                text[from..to].to_string(),
+                span.style.patch(path_style()),
+            ));
+            cursor = to;
+        }
+        if cursor < end {
+            spans.push(Span::styled(text[cursor..end].to_string(), span.style));
+        }
+    }
+    Line { spans, ..line }
+}
+
+/// A hyperlink target (OSC 8) for a highlighted path reference: the byte
+/// range it covers in the line's text and the `file://` URI it opens.
+pub type PathLink = (Range<usize>, String);
+
+/// Links for the references in `line` that [`highlight_path_refs`]
+/// restyled; paths in tool output or user text stay plain. Relative paths
+/// are resolved against the project `root`.
+pub fn path_links(line: &Line<'_>, root: &Path) -> Vec<PathLink> {
+    let text: String = line
+        .spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect();
+    let highlight = path_style();
+    let mut offset = 0;
+    let highlighted: Vec<Range<usize>> = line
+        .spans
+        .iter()
+        .filter_map(|span| {
+            let range = offset..offset + span.content.len();
+            offset = range.end;
+            let style = span.style;
+            (style.fg == highlight.fg && style.add_modifier.contains(Modifier::UNDERLINED))
+                .then_some(range)
+        })
+        .collect();
+
+    find_path_refs(&text)
+        .into_iter()
+        .filter(|path_match| {
+            highlighted
+                .iter()
+                .any(|range| range.start == path_match.range.start)
+        })
+        .map(|path_match| {
+            let path = resolve_path(&path_match.path_ref.path, root);
+            (path_match.range, format!("file://{}", path.display()))
+        })
+        .collect()
+}
+
+/// Resolve `path` against the project `root`, expanding a leading `~/`.
+pub fn resolve_path(path: &str, root: &Path) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/") {
+        if let Some(home) = dirs::home_dir() {
+            return home.join(rest);
+        }
+    }
+    root.join(path)
+}
+
+/// Program and arguments opening `path` at `line` in `editor` (the value
+/// of `$VISUAL` or `$EDITOR`, which may carry its own arguments). The line
+/// is passed as `+N`, which vi, vim, nano, emacs and micro understand.
+pub fn editor_command(
+    editor: &str,
+    path: &Path,
+    line: Option<u32>,
+) -> Option<(String, Vec<String>)> {
+    let mut words = editor.split_whitespace().map(str::to_string);
+    let program = words.next()?;
+    let mut args: Vec<String> = words.collect();
+    if let Some(line) = line {
+        args.push(format!("+{line}"));
+    }
+    args.push(path.display().to_string());
+    Some((program, args))
+}
+
+/// The user's editor: `$VISUAL`, then `$EDITOR`, then `vi`.
+pub fn preferred_editor() -> String {
+    ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(text: &str) -> Vec<(String, Option<u32>)> {
+        find_path_refs(text)
+            .into_iter()
+            .map(|path_match| (path_match.path_ref.path, path_match.path_ref.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_finds_paths_and_line_refs() {
+        assert_eq!(
+            found("See src/main.rs:42, then ./foo/bar.rs and `lib.rs:7`."),
+            vec![
+                ("src/main.rs".to_string(), Some(42)),
+                ("./foo/bar.rs".to_string(), None),
+                ("lib.rs".to_string(), Some(7)),
+            ]
+        );
+        assert_eq!(
+            found("(crates/llm/src/types.rs:10:5)"),
+            vec![("crates/llm/src/types.rs".to_string(), Some(10))]
+        );
+    }
+
+    #[test]
+    fn test_prose_is_not_a_path() {
+        for text in [
+            "Use and/or as needed.",
+            "Bumped to 1.2.3 and v1/v2.0 stays.",
+            "Docs at https://example.com/guide.html today.",
+            "Edit main.rs first.",
+            "Speed in km/h.",
+            "e.g. this, i.e. that",
+        ] {
+            assert_eq!(found(text), Vec::new(), "{text}");
+        }
+    }
+
+    #[test]
+    fn test_highlight_splits_spans_at_matches() {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let line = Line::from(vec![
+            Span::raw("Open "),
+            Span::styled("src/app.rs:3 now", bold),
+        ]);
+        let highlighted = highlight_path_refs(line);
+        assert_eq!(
+            highlighted.spans,
+            vec![
+                Span::raw("Open "),
+                Span::styled("src/app.rs:3", bold.patch(path_style())),
+                Span::styled(" now", bold),
+            ]
+        );
+
+        let plain = Line::from("nothing to see");
+        assert_eq!(highlight_path_refs(plain.clone()), plain);
+    }
+
+    #[test]
+    fn test_links_only_highlighted_paths() {
+        let root = Path::new("/work");
+        let line = highlight_path_refs(Line::from("Open src/app.rs:3 or /tmp/out.log"));
+        assert_eq!(
+            path_links(&line, root),
+            vec![
+                (5..17, "file:///work/src/app.rs".to_string()),
+                (21..33, "file:///tmp/out.log".to_string()),
+            ]
+        );
+
+        // The same text without highlighting (e.g. tool output) gets no links
+        assert!(path_links(&Line::from("Open src/app.rs:3"), root).is_empty());
+    }
+
+    #[test]
+    fn test_editor_command() {
+        let path = Path::new("/work/src/main.rs");
+        assert_eq!(
+            editor_command("emacsclient -t", path, Some(12)),
+            Some((
+                "emacsclient".to_string(),
+                vec![
+                    "-t".to_string(),
+                    "+12".to_string(),
+                    "/work/src/main.rs".to_string()
+                ]
+            ))
+        );
+        assert_eq!(
+            editor_command("vim", path, None),
+            Some(("vim".to_string(), vec!["/work/src/main.rs".to_string()]))
+        );
+        assert_eq!(editor_command("  ", path, None), None);
+        assert_eq!(
+            resolve_path("src/main.rs", Path::new("/work")),
+            PathBuf::from("/work/src/main.rs")
+        );
+    }
+}
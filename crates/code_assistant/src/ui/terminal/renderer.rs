@@ -7,15 +7,94 @@ use tui_markdown as md;
 
 use super::textarea::TextArea;
 
-use super::composer::Composer;
+use super::composer::{Composer, StatusBarInfo, STATUS_BAR_HEIGHT};
+use super::config::TerminalConfig;
+use super::copy::{extract_copy_text, CopySelector};
 use super::custom_terminal;
-use super::message::{LiveMessage, MessageBlock, PlainTextBlock, ToolUseBlock};
+use super::diff_pane::{self, DiffPane, PaneContent};
+use super::export::{render_export, ExportFormat};
+use super::history_insert::HistoryRow;
+use super::message::{
+    ImageBlock, LiveMessage, MessageBlock, MessageUsage, OutputLimit, PlainTextBlock, ToolUseBlock,
+};
+use super::path_links::{highlight_path_refs, last_path_ref, PathRef};
+use super::redact::redact;
+use super::session_replay::live_messages_from_session;
+use super::spinner::SpinnerStyle;
 use super::streaming::controller::{DrainedLines, StreamKind, StreamingController};
-use super::transcript::TranscriptState;
+use super::theme::Theme;
+use super::tool_renderers::{tool_project, truncate_to_width, ToolDetail};
+use super::transcript::{
+    thinking_summary_line, thought_for_line, HistoryKind, ThinkingUnit, ThinkingVisibility,
+    TranscriptState, TurnSeparator,
+};
+use super::transcript_writer::TranscriptWriter;
 use crate::types::{PlanItemStatus, PlanState};
+use crate::ui::ui_events::{ErrorInfo, MessageData, ToolResultData};
 use crate::ui::ToolStatus;
-use std::time::Instant;
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
 use tracing::{debug, info, trace, warn};
+use unicode_width::UnicodeWidthStr;
+
+/// Characters of each queued message shown in the queue indicator.
+const QUEUED_PREVIEW_CHARS: usize = 60;
+
+const BACKEND_DISCONNECTED_MESSAGE: &str = "Backend disconnected — press Ctrl+C to exit";
+
+/// Narrowest terminal width the normal UI is laid out for.
+const MIN_TERMINAL_WIDTH: u16 = 20;
+
+const TOO_SMALL_MESSAGE: &str = "Terminal too small";
+
+/// Plan entries shown at once in the expanded plan view.
+const PLAN_VISIBLE_ENTRIES: usize = 4;
+
+/// How long a newly completed plan item stays highlighted.
+const PLAN_COMPLETION_HIGHLIGHT: Duration = Duration::from_secs(1);
+
+/// Most history lines held back while not following. Beyond this the
+/// oldest are written to scrollback anyway, so reading back through a long
+/// run doesn't grow memory without bound.
+const MAX_HELD_HISTORY_LINES: usize = 10_000;
+
+/// Upper bound on cached measurements; the status area only ever shows a
+/// handful of entries, so older keys are simply dropped when it fills up.
+const MARKDOWN_HEIGHT_CACHE_CAPACITY: usize = 64;
+
+/// Memoized markdown heights keyed by content hash, width and the height
+/// they were measured up to.
+#[derive(Debug, Default)]
+struct MarkdownHeightCache {
+    entries: HashMap<(u64, u16, u16), u16>,
+}
+
+impl MarkdownHeightCache {
+    fn get_or_measure(
+        &mut self,
+        content: &str,
+        width: u16,
+        max_height: u16,
+        measure: impl FnOnce(&str, u16, u16) -> u16,
+    ) -> u16 {
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+        let key = (hasher.finish(), width, max_height);
+
+        if let Some(&height) = self.entries.get(&key) {
+            return height;
+        }
+        if self.entries.len() >= MARKDOWN_HEIGHT_CACHE_CAPACITY {
+            self.entries.clear();
+        }
+        let height = measure(content, width, max_height);
+        self.entries.insert(key, height);
+        height
+    }
+}
 
 /// Spinner state for loading indication
 #[derive(Debug, Clone)]
@@ -23,44 +102,72 @@ pub enum SpinnerState {
     Hidden,
     Loading {
         start_time: Instant,
+        /// What the agent is doing, e.g. `Running execute_command`; `None`
+        /// while it waits for the model
+        label: Option<String>,
     },
     RateLimit {
         start_time: Instant,
-        seconds_remaining: u64,
+        initial_seconds: u64,
+    },
+    /// A failed request is being retried after a backoff delay
+    Retrying {
+        attempt: u32,
+        max: u32,
+        start_time: Instant,
     },
 }
 
 impl SpinnerState {
-    fn get_spinner_char(&self) -> Option<(char, Color)> {
+    fn get_spinner_frame(&self, style: SpinnerStyle) -> Option<(&'static str, Color)> {
         match self {
             SpinnerState::Hidden => None,
-            SpinnerState::Loading { start_time } => {
-                let braille_chars = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
-                let elapsed_ms = start_time.elapsed().as_millis();
-                let index = (elapsed_ms / 100) % braille_chars.len() as u128;
-                Some((braille_chars[index as usize], Color::Blue))
-            }
-            SpinnerState::RateLimit { start_time, .. } => {
-                let braille_chars = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
-                let elapsed_ms = start_time.elapsed().as_millis();
-                let index = (elapsed_ms / 100) % braille_chars.len() as u128;
-                Some((braille_chars[index as usize], Color::LightRed))
-            }
+            SpinnerState::Loading { start_time, .. } => Some((
+                style.frame_at(start_time.elapsed()),
+                Theme::global().spinner_fg,
+            )),
+            SpinnerState::RateLimit { start_time, .. } => Some((
+                style.frame_at(start_time.elapsed()),
+                Theme::global().rate_limit_fg,
+            )),
+            SpinnerState::Retrying { start_time, .. } => Some((
+                style.frame_at(start_time.elapsed()),
+                Theme::global().retry_fg,
+            )),
         }
     }
 
     fn get_status_text(&self) -> Option<String> {
         match self {
             SpinnerState::Hidden => None,
-            SpinnerState::Loading { .. } => None,
+            SpinnerState::Loading { label, .. } => {
+                Some(format!("{}…", label.as_deref().unwrap_or("Thinking")))
+            }
+            SpinnerState::RateLimit { .. } => {
+                let remaining = self.rate_limit_seconds_remaining().unwrap_or(0);
+                Some(format!("Rate limited ({remaining}s)"))
+            }
+            SpinnerState::Retrying { attempt, max, .. } => {
+                Some(format!("Retrying ({attempt}/{max})…"))
+            }
+        }
+    }
+
+    /// Seconds left on a rate-limit countdown, derived from the elapsed time.
+    fn rate_limit_seconds_remaining(&self) -> Option<u64> {
+        match self {
             SpinnerState::RateLimit {
-                seconds_remaining, ..
-            } => Some(format!("Rate limited ({seconds_remaining}s)")),
+                start_time,
+                initial_seconds,
+            } => Some(initial_seconds.saturating_sub(start_time.elapsed().as_secs())),
+            _ => None,
         }
     }
 }
 
 enum StatusKind {
+    Error,
+    Warning,
     Info,
     Plan,
     Pending,
@@ -70,6 +177,17 @@ struct StatusEntry {
     kind: StatusKind,
     content: String,
     height: u16,
+    /// Content was taller than `height`; the last row shows `…` instead
+    truncated: bool,
+}
+
+/// Status entries with their final heights, shared by the measure and
+/// paint passes so both agree on the size of the status area.
+struct StatusLayout {
+    entries: Vec<StatusEntry>,
+    /// Total rows including gaps between entries and the gap above the
+    /// composer
+    height: u16,
 }
 
 /// Handles the terminal display and rendering using ratatui.
@@ -79,24 +197,60 @@ pub struct TerminalRenderer {
     pub transcript: TranscriptState,
     /// Optional pending user message (displayed between input and live content while streaming)
     pending_user_message: Option<String>,
+    queued_messages: Vec<String>,
+    steering_message: Option<String>,
     /// Current error message to display
-    current_error: Option<String>,
+    current_error: Option<ErrorInfo>,
+    /// Current warning; shown below an error's priority but above info
+    current_warning: Option<String>,
     /// Current info message to display
     info_message: Option<String>,
     /// Latest plan state received from the agent
     plan_state: Option<PlanState>,
     /// Whether to render the expanded plan view
     plan_expanded: bool,
+    /// First plan entry shown in the expanded view after manual scrolling;
+    /// `None` keeps the window on the active entry
+    plan_scroll: Option<usize>,
+    /// Side pane with the current turn's changes to one file
+    diff_pane: Option<DiffPane>,
+    /// Indices of plan items that just flipped to completed, with the time
+    /// they did
+    plan_highlights: Vec<(usize, Instant)>,
     /// When overlay is active, history commits are deferred and flushed on close.
     overlay_active: bool,
-    /// Buffered history lines emitted while overlay is active.
-    deferred_history_lines: Vec<Line<'static>>,
-    /// History lines ready to be inserted into terminal scrollback.
+    /// Buffered history rows emitted while overlay is active.
+    deferred_history_rows: Vec<HistoryRow>,
+    /// Whether new history goes to scrollback; while off it is deferred
+    /// like under an overlay.
+    following: bool,
+    /// History rows ready to be inserted into terminal scrollback.
     /// Drained by the Tui orchestration layer before each draw cycle.
-    pending_history_lines: Vec<Line<'static>>,
+    pending_history_rows: Vec<HistoryRow>,
+    /// Whether the last line sent to scrollback (or deferred) is blank.
+    history_ends_blank: bool,
+    /// What goes between an assistant turn and the next user message
+    turn_separator: TurnSeparator,
 
     /// Bottom composer rendering and sizing.
     composer: Composer,
+    /// Session details shown in the status bar below the composer.
+    status_bar: StatusBarInfo,
+    /// Set once sending to the backend fails; see `mark_backend_disconnected`.
+    backend_disconnected: bool,
+    /// Model picker or path completion list; shown in place of
+    /// info/pending messages while open.
+    popup_text: Option<String>,
+    /// Receives each committed message when transcript saving is enabled.
+    transcript_writer: Option<TranscriptWriter>,
+    /// Off-screen buffer the live content is composed into during `paint`.
+    scratch: Buffer,
+    /// Status-area heights, re-measured only when content or width change.
+    markdown_height_cache: RefCell<MarkdownHeightCache>,
+    /// Rows a single status entry may take; taller ones end in `…`.
+    max_status_height: u16,
+    /// Committed messages kept after they reach scrollback; 0 keeps all
+    max_retained_messages: usize,
     /// Queue of incoming stream deltas, drained on render commit ticks.
     streaming_controller: StreamingController,
     /// True while actively receiving stream deltas for the current assistant turn.
@@ -105,12 +259,36 @@ pub struct TerminalRenderer {
     last_stream_kind: Option<StreamKind>,
     /// Spinner state for loading indication
     spinner_state: SpinnerState,
+    spinner_style: SpinnerStyle,
     /// Tracks the last block type for hidden tool paragraph breaks
     last_block_type_for_hidden_tool: Option<LastBlockType>,
     /// Flag indicating a hidden tool completed and we may need a paragraph break
     needs_paragraph_break_after_hidden_tool: bool,
     /// Last known terminal width (updated in prepare(), used for history rendering).
     last_known_width: u16,
+    /// Whether new thinking blocks go to scrollback in full, as a one-line
+    /// summary, or not at all.
+    thinking_visibility: ThinkingVisibility,
+    /// Whether tools are drawn by their own renderers or as header lines only
+    tool_detail: ToolDetail,
+    /// Tool block of the live message highlighted with Alt+Up/Down
+    focused_block: Option<usize>,
+    /// Live tool blocks shown at the other detail level, toggled with Alt+O
+    flipped_blocks: HashSet<usize>,
+    /// When the hidden thinking block currently streaming started
+    hidden_thinking_since: Option<Instant>,
+    /// Tail window applied to streamed tool output
+    tool_output_limit: OutputLimit,
+    /// Styled thinking lines of the block currently streaming, kept until the
+    /// block ends so it can be summarized and retained as a collapsible unit.
+    pending_thinking_lines: Vec<Line<'static>>,
+    /// Tool parameters inspected while text streams, printed once the
+    /// stream's pending lines are in scrollback so they don't land above them
+    held_tool_parameters: Vec<Line<'static>>,
+    /// Whether to note project switches between tool calls in scrollback
+    project_breadcrumbs: bool,
+    /// Project of the most recent committed tool call
+    last_project: Option<String>,
 }
 
 /// Tracks the last block type for paragraph breaks after hidden tools
@@ -128,22 +306,53 @@ impl TerminalRenderer {
         Ok(Self {
             transcript: TranscriptState::new(),
             pending_user_message: None,
+            queued_messages: Vec::new(),
+            steering_message: None,
             current_error: None,
+            current_warning: None,
             info_message: None,
 
             plan_state: None,
             plan_expanded: false,
+            plan_scroll: None,
+            diff_pane: None,
+            plan_highlights: Vec::new(),
             overlay_active: false,
-            deferred_history_lines: Vec::new(),
-            pending_history_lines: Vec::new(),
+            deferred_history_rows: Vec::new(),
+            following: true,
+            pending_history_rows: Vec::new(),
+            history_ends_blank: true,
+            turn_separator: TerminalConfig::global().turn_separator,
             composer: Composer::new(5),
+            status_bar: StatusBarInfo::default(),
+            backend_disconnected: false,
+            popup_text: None,
+            transcript_writer: None,
+            scratch: Buffer::default(),
+            markdown_height_cache: RefCell::new(MarkdownHeightCache::default()),
+            max_status_height: TerminalConfig::global().max_status_height,
+            max_retained_messages: TerminalConfig::global().max_retained_messages,
             streaming_controller: StreamingController::new(),
             streaming_open: false,
             last_stream_kind: None,
             spinner_state: SpinnerState::Hidden,
+            spinner_style: TerminalConfig::global().spinner_style.resolve(),
             last_block_type_for_hidden_tool: None,
             needs_paragraph_break_after_hidden_tool: false,
             last_known_width: 80,
+            thinking_visibility: TerminalConfig::global().thinking_visibility(),
+            tool_detail: ToolDetail::Full,
+            focused_block: None,
+            flipped_blocks: HashSet::new(),
+            hidden_thinking_since: None,
+            tool_output_limit: OutputLimit {
+                max_bytes: TerminalConfig::global().tool_output_max_bytes,
+                max_lines: TerminalConfig::global().tool_output_max_lines,
+            },
+            pending_thinking_lines: Vec::new(),
+            held_tool_parameters: Vec::new(),
+            project_breadcrumbs: TerminalConfig::global().project_breadcrumbs,
+            last_project: None,
         })
     }
 
@@ -154,26 +363,55 @@ impl TerminalRenderer {
         let pending = self.streaming_controller.flush_pending();
         self.apply_drained_lines(pending);
         self.sync_live_stream_tails();
+        self.finish_thinking_unit();
 
         // Show loading spinner
         self.spinner_state = SpinnerState::Loading {
             start_time: Instant::now(),
+            label: None,
         };
         self.streaming_controller.clear();
         self.last_stream_kind = None;
         self.transcript.start_active_message();
+        self.clear_tool_focus();
         self.streaming_open = true;
     }
 
     /// Start a new tool use block within the current message
     pub fn start_tool_use_block(&mut self, name: String, id: String) {
+        self.end_stream_for_block();
+
+        self.ensure_active_message();
+        let Some(live_message) = self.transcript.active_message_mut() else {
+            return;
+        };
+
+        // Updates that raced ahead of the start already created the block
+        match live_message.get_tool_block_mut(&id) {
+            Some(tool_block) if tool_block.is_placeholder() => tool_block.name = name,
+            _ => live_message.add_block(MessageBlock::ToolUse(ToolUseBlock::new(name, id))),
+        }
+    }
+
+    /// Add an image shown by the assistant or a tool to the current message.
+    pub fn add_image_block(&mut self, image: ImageBlock) {
+        self.end_stream_for_block();
+
+        self.ensure_active_message();
+        if let Some(live_message) = self.transcript.active_message_mut() {
+            live_message.add_block(MessageBlock::Image(image));
+        }
+    }
+
+    /// Prepare for a non-streamed block (tool, image) in the live message.
+    fn end_stream_for_block(&mut self) {
         // Hide spinner when first content arrives
         self.hide_loading_spinner_if_active();
 
         // Flush any in-progress streaming text/thinking to scrollback so
-        // the tool block in the live viewport doesn't overlap with it.
+        // the block in the live viewport doesn't overlap with it.
         // Also insert a blank separator so the scrollback content is visually
-        // separated from the tool block that will appear in the viewport.
+        // separated from the block that will appear in the viewport.
         if self.last_stream_kind.is_some() {
             self.flush_streaming_pending();
             self.insert_or_defer_history_lines(vec![Line::from("")]);
@@ -182,13 +420,6 @@ impl TerminalRenderer {
             }
             self.last_stream_kind = None;
         }
-
-        self.ensure_active_message();
-        let Some(live_message) = self.transcript.active_message_mut() else {
-            return;
-        };
-
-        live_message.add_block(MessageBlock::ToolUse(ToolUseBlock::new(name, id)));
     }
 
     /// Ensure the last block in the live message is of the specified type.
@@ -264,6 +495,51 @@ impl TerminalRenderer {
         self.pending_user_message = message;
     }
 
+    /// Messages sent during the active turn that the agent hasn't picked up yet
+    pub fn set_queued_messages(&mut self, messages: Vec<String>) {
+        self.queued_messages = messages;
+    }
+
+    /// Steering text waiting for the next tool boundary of the active turn
+    pub fn set_steering_message(&mut self, message: Option<String>) {
+        self.steering_message = message;
+    }
+
+    /// Text for the pending-message slot: the steering and queue indicators
+    /// while messages wait, otherwise the pending user message.
+    fn pending_status_text(&self) -> Option<String> {
+        let preview = |message: &str| {
+            let first_line = message.lines().next().unwrap_or("").trim();
+            let mut preview: String = first_line.chars().take(QUEUED_PREVIEW_CHARS).collect();
+            if preview.len() < first_line.len() || message.trim().lines().count() > 1 {
+                preview.push('…');
+            }
+            format!("\"{preview}\"")
+        };
+
+        let steering = self
+            .steering_message
+            .as_deref()
+            .map(|message| format!("steering: {}", preview(message)));
+        let queued = match self.queued_messages.as_slice() {
+            [] => None,
+            [message] => Some(format!("queued: {}", preview(message))),
+            messages => {
+                let mut text = format!("queued ({}):", messages.len());
+                for message in messages {
+                    text.push_str(&format!("\n- {}", preview(message)));
+                }
+                Some(text)
+            }
+        };
+
+        match (steering, queued) {
+            (None, None) => self.pending_user_message.clone(),
+            (Some(steering), Some(queued)) => Some(format!("{steering}\n{queued}")),
+            (steering, queued) => steering.or(queued),
+        }
+    }
+
     /// Update the stored plan state for rendering
     pub fn set_plan_state(&mut self, plan: Option<PlanState>) {
         if let Some(ref plan_state) = plan {
@@ -275,9 +551,125 @@ impl TerminalRenderer {
         } else {
             debug!("renderer::set_plan_state clearing plan state");
         }
+        if self.plan_state != plan {
+            self.plan_scroll = None;
+        }
+        // An item keeps its index while its content stays the same
+        let content = |plan: Option<&PlanState>, index: usize| {
+            plan.and_then(|plan| plan.entries.get(index))
+                .map(|entry| entry.content.clone())
+        };
+        let (previous, next) = (self.plan_state.as_ref(), plan.as_ref());
+        self.plan_highlights.retain(|&(index, since)| {
+            since.elapsed() < PLAN_COMPLETION_HIGHLIGHT
+                && content(previous, index).is_some_and(|c| content(next, index) == Some(c))
+        });
+        if let (Some(previous), Some(next)) = (self.plan_state.as_ref(), plan.as_ref()) {
+            let now = Instant::now();
+            for (index, entry) in next.entries.iter().enumerate() {
+                let Some(prev) = previous.entries.get(index) else {
+                    continue;
+                };
+                if entry.status == PlanItemStatus::Completed
+                    && prev.content == entry.content
+                    && prev.status != PlanItemStatus::Completed
+                {
+                    self.plan_highlights.push((index, now));
+                }
+            }
+        }
         self.plan_state = plan;
     }
 
+    /// Indices of plan items still within their completion highlight,
+    /// newest last.
+    fn active_plan_highlights(&self) -> Vec<usize> {
+        self.plan_highlights
+            .iter()
+            .filter(|(_, since)| since.elapsed() < PLAN_COMPLETION_HIGHLIGHT)
+            .map(|(index, _)| *index)
+            .collect()
+    }
+
+    /// Lines of the plan status text that show a just-completed item.
+    fn plan_highlight_rows(&self) -> Vec<usize> {
+        let highlights = self.active_plan_highlights();
+        let Some(plan_state) = &self.plan_state else {
+            return Vec::new();
+        };
+        if !self.plan_expanded {
+            // The collapsed summary calls out the newest one on its only line
+            return if highlights.is_empty() {
+                Vec::new()
+            } else {
+                vec![0]
+            };
+        }
+        let start = self.plan_window_start(plan_state);
+        highlights
+            .into_iter()
+            .filter(|index| (start..start + PLAN_VISIBLE_ENTRIES).contains(index))
+            // Below the "Plan" heading
+            .map(|index| index - start + 1)
+            .collect()
+    }
+
+    /// Show the diff pane for `path` (or the latest edited file), retarget
+    /// it to another path, or hide it. Returns whether the pane is shown.
+    pub fn toggle_diff_pane(&mut self, path: Option<String>) -> bool {
+        let pane = DiffPane { path };
+        self.diff_pane = match self.diff_pane.take() {
+            Some(current) if pane.path.is_none() || current == pane => None,
+            _ => Some(pane),
+        };
+        self.diff_pane.is_some()
+    }
+
+    /// Content of the diff pane and the width left for the transcript, when
+    /// the pane is shown and `width` has room for it.
+    fn diff_pane_layout(&self, width: u16) -> Option<(PaneContent, Rect, Rect)> {
+        let pane = self.diff_pane.as_ref()?;
+        let (main, side) = diff_pane::split(Rect::new(0, 0, width, 1))?;
+        let messages = self
+            .transcript
+            .committed_messages()
+            .iter()
+            .chain(self.transcript.active_message());
+        Some((pane.content(messages), main, side))
+    }
+
+    /// Scroll the expanded plan window by `delta` entries, clamped so the
+    /// window stays full. Returns false when there is nothing to scroll.
+    pub fn scroll_plan(&mut self, delta: isize) -> bool {
+        let Some(plan_state) = self.plan_state.as_ref() else {
+            return false;
+        };
+        let total = plan_state.entries.len();
+        if !self.plan_expanded || total <= PLAN_VISIBLE_ENTRIES {
+            return false;
+        }
+        let max_start = total - PLAN_VISIBLE_ENTRIES;
+        let start = self.plan_window_start(plan_state);
+        self.plan_scroll = Some(start.saturating_add_signed(delta).min(max_start));
+        true
+    }
+
+    /// First entry of the expanded plan window: the scrolled position, or by
+    /// default the first entry after the completed ones.
+    fn plan_window_start(&self, plan_state: &PlanState) -> usize {
+        let total = plan_state.entries.len();
+        let max_start = total.saturating_sub(PLAN_VISIBLE_ENTRIES);
+        if let Some(scroll) = self.plan_scroll {
+            return scroll.min(max_start);
+        }
+        plan_state
+            .entries
+            .iter()
+            .take(max_start)
+            .take_while(|entry| matches!(entry.status, PlanItemStatus::Completed))
+            .count()
+    }
+
     /// Toggle whether the expanded plan view should be rendered
     pub fn set_plan_expanded(&mut self, expanded: bool) {
         self.plan_expanded = expanded;
@@ -288,6 +680,16 @@ impl TerminalRenderer {
         self.overlay_active = active;
     }
 
+    /// Toggle whether new history goes to scrollback or is held until
+    /// following resumes.
+    pub fn set_following(&mut self, following: bool) {
+        self.following = following;
+    }
+
+    fn defers_history(&self) -> bool {
+        self.overlay_active || !self.following
+    }
+
     /// Append text to the last block in the current message
     #[cfg_attr(not(test), allow(dead_code))]
     pub fn append_to_live_block(&mut self, text: &str) {
@@ -321,15 +723,19 @@ impl TerminalRenderer {
         // in the viewport.
         if self.last_stream_kind == Some(StreamKind::Thinking) {
             let flushed_thinking = self.streaming_controller.flush_kind(StreamKind::Thinking);
-            if !flushed_thinking.is_empty() {
+            let has_flushed = !flushed_thinking.is_empty();
+            if has_flushed {
                 let lines = style_thinking_lines(flushed_thinking);
-                self.insert_or_defer_history_lines(indent_lines(lines));
-                // Blank line between thinking and text blocks
-                self.insert_or_defer_history_lines(vec![Line::from("")]);
+                self.emit_thinking_lines(indent_lines(lines));
                 if let Some(msg) = self.transcript.active_message_mut() {
                     msg.streamed_to_scrollback = true;
                 }
             }
+            let summarized = self.finish_thinking_unit();
+            if has_flushed || summarized {
+                // Blank line between thinking and text blocks
+                self.insert_or_defer_history_lines(vec![Line::from("")]);
+            }
         }
         self.last_stream_kind = Some(StreamKind::Text);
         self.streaming_controller.push(StreamKind::Text, content);
@@ -353,7 +759,7 @@ impl TerminalRenderer {
         if self.last_stream_kind == Some(StreamKind::Text) {
             let flushed_text = self.streaming_controller.flush_kind(StreamKind::Text);
             if !flushed_text.is_empty() {
-                self.insert_or_defer_history_lines(indent_lines(flushed_text));
+                self.insert_or_defer_history_lines(indent_lines(text_lines(flushed_text)));
                 // Blank line between text and thinking blocks
                 self.insert_or_defer_history_lines(vec![Line::from("")]);
                 if let Some(msg) = self.transcript.active_message_mut() {
@@ -362,6 +768,11 @@ impl TerminalRenderer {
             }
         }
         self.last_stream_kind = Some(StreamKind::Thinking);
+        if self.thinking_visibility == ThinkingVisibility::Hidden {
+            // Only the duration is kept, for the note once thinking ends
+            self.hidden_thinking_since.get_or_insert_with(Instant::now);
+            return;
+        }
         self.streaming_controller
             .push(StreamKind::Thinking, content);
     }
@@ -371,21 +782,180 @@ impl TerminalRenderer {
         let flushed = self.streaming_controller.flush_pending();
         self.apply_drained_lines(flushed);
         self.sync_live_stream_tails();
+        self.finish_thinking_unit();
         self.streaming_open = false;
+        let held = std::mem::take(&mut self.held_tool_parameters);
+        self.insert_or_defer_history_lines(held);
     }
 
-    /// Add or update a tool parameter in the current message
-    pub fn add_or_update_tool_parameter(&mut self, tool_id: &str, name: String, value: String) {
-        let Some(live_message) = self.transcript.active_message_mut() else {
-            tracing::warn!("Ignoring tool parameter update without active message");
-            return;
+    /// Send styled thinking lines to scrollback (when expanded by default)
+    /// and collect them for the current thinking unit.
+    fn emit_thinking_lines(&mut self, lines: Vec<Line<'static>>) {
+        if self.thinking_visibility == ThinkingVisibility::Full {
+            self.insert_or_defer_history_lines(lines.clone());
+        }
+        self.pending_thinking_lines.extend(lines);
+    }
+
+    /// Close the current thinking unit. When collapsed, its summary line is
+    /// sent to scrollback; when hidden, a note of how long it took. Returns
+    /// true if such a line was emitted.
+    fn finish_thinking_unit(&mut self) -> bool {
+        if let Some(since) = self.hidden_thinking_since.take() {
+            self.insert_or_defer_history_lines(vec![thought_for_line(since.elapsed())]);
+            return true;
+        }
+        if self.pending_thinking_lines.is_empty() {
+            return false;
+        }
+        let lines = std::mem::take(&mut self.pending_thinking_lines);
+        let expanded = self.thinking_visibility == ThinkingVisibility::Full;
+        if !expanded {
+            self.insert_or_defer_history_lines(vec![thinking_summary_line(lines.len(), false)]);
+        }
+        self.transcript
+            .push_thinking_unit(ThinkingUnit { lines, expanded });
+        !expanded
+    }
+
+    /// Toggle the most recent thinking block. Scrollback cannot be rewritten,
+    /// so expanding re-emits the full content and collapsing emits the summary.
+    /// Returns false if there is no thinking block to toggle.
+    pub fn toggle_last_thinking_block(&mut self) -> bool {
+        let Some(unit) = self.transcript.last_thinking_unit_mut() else {
+            return false;
         };
+        unit.expanded = !unit.expanded;
+        let mut lines = vec![thinking_summary_line(unit.lines.len(), unit.expanded)];
+        if unit.expanded {
+            lines.extend(unit.lines.iter().cloned());
+        }
+        self.insert_or_defer_history_lines(lines);
+        true
+    }
 
-        if let Some(tool_block) = live_message.get_tool_block_mut(tool_id) {
+    /// Switch all tools between full and header-only rendering. Applies to
+    /// the live viewport and to messages committed from now on; scrollback
+    /// already printed by the terminal can't be rewritten.
+    pub fn toggle_tool_detail(&mut self) -> ToolDetail {
+        self.tool_detail = self.tool_detail.toggled();
+        self.tool_detail
+    }
+
+    /// Move the highlight to the previous or next tool block of the live
+    /// message; the first move starts at the newest tool. Returns false when
+    /// the live message has no tools.
+    pub fn focus_tool(&mut self, forward: bool) -> bool {
+        let Some(message) = self.transcript.active_message() else {
+            return false;
+        };
+        let tools: Vec<usize> = message
+            .blocks
+            .iter()
+            .enumerate()
+            .filter(|(_, block)| matches!(block, MessageBlock::ToolUse(_)))
+            .map(|(index, _)| index)
+            .collect();
+        let next = match self.focused_block {
+            None => tools.last(),
+            Some(current) if forward => tools.iter().find(|&&i| i > current).or(tools.last()),
+            Some(current) => tools.iter().rev().find(|&&i| i < current).or(tools.first()),
+        };
+        self.focused_block = next.copied();
+        self.focused_block.is_some()
+    }
+
+    /// Expand or collapse the highlighted tool block in the viewport.
+    /// Returns false when no tool is highlighted.
+    pub fn toggle_focused_tool(&mut self) -> bool {
+        let Some(index) = self.focused_block else {
+            return false;
+        };
+        if !self.flipped_blocks.remove(&index) {
+            self.flipped_blocks.insert(index);
+        }
+        true
+    }
+
+    /// Print the full parameters of the highlighted tool, or else the most
+    /// recent one, to scrollback, where long values aren't cut off. While a
+    /// response streams they follow once it ends. Returns false when there
+    /// is no tool to inspect.
+    pub fn inspect_tool(&mut self) -> bool {
+        let active = self.transcript.active_message();
+        let focused = self
+            .focused_block
+            .and_then(|index| active?.blocks.get(index));
+        let tool = focused
+            .into_iter()
+            .chain(
+                active
+                    .into_iter()
+                    .chain(self.transcript.committed_messages().iter().rev())
+                    .flat_map(|message| message.blocks.iter().rev()),
+            )
+            .find_map(|block| match block {
+                MessageBlock::ToolUse(tool) => Some(tool),
+                _ => None,
+            });
+        let Some(tool) = tool else {
+            return false;
+        };
+        let lines = tool_parameter_lines(tool);
+        if self.streaming_open && self.last_stream_kind.is_some() {
+            self.held_tool_parameters.extend(lines);
+        } else {
+            self.insert_or_defer_history_lines(lines);
+        }
+        true
+    }
+
+    /// Drop the tool highlight, once the live message it points into is
+    /// finalized or replaced.
+    fn clear_tool_focus(&mut self) {
+        self.focused_block = None;
+        self.flipped_blocks.clear();
+    }
+
+    /// Detail level of the live message's block at `index`.
+    fn live_block_detail(&self, index: usize) -> ToolDetail {
+        if self.flipped_blocks.contains(&index) {
+            self.tool_detail.toggled()
+        } else {
+            self.tool_detail
+        }
+    }
+
+    /// Add or update a tool parameter in the current message
+    pub fn add_or_update_tool_parameter(&mut self, tool_id: &str, name: String, value: String) {
+        if let Some(tool_block) = self.updatable_tool_block(tool_id, "parameter update") {
             tool_block.add_or_update_parameter(name, value);
         }
     }
 
+    /// All parameters of `tool_id` have arrived; edit diffs render now.
+    pub fn complete_tool_parameters(&mut self, tool_id: &str) {
+        if let Some(tool_block) = self.updatable_tool_block(tool_id, "parameter end") {
+            tool_block.complete_parameters();
+        }
+    }
+
+    /// The active message's block for `tool_id`. Updates may race ahead of
+    /// the tool's start, so an unknown id gets a placeholder block; updates
+    /// without an active message, or for a tool of an already finalized
+    /// message, are stale and dropped.
+    fn updatable_tool_block(&mut self, tool_id: &str, update: &str) -> Option<&mut ToolUseBlock> {
+        if self.transcript.has_committed_tool(tool_id) {
+            tracing::warn!("Ignoring tool {update} for finalized tool {tool_id}");
+            return None;
+        }
+        let Some(live_message) = self.transcript.active_message_mut() else {
+            tracing::warn!("Ignoring tool {update} without active message");
+            return None;
+        };
+        Some(live_message.get_or_insert_tool_block_mut(tool_id))
+    }
+
     /// Update tool status in the current message
     pub fn update_tool_status(
         &mut self,
@@ -394,53 +964,98 @@ impl TerminalRenderer {
         message: Option<String>,
         output: Option<String>,
     ) {
-        let Some(live_message) = self.transcript.active_message_mut() else {
-            tracing::warn!("Ignoring tool status update without active message");
+        let limit = self.tool_output_limit;
+        let Some(tool_block) = self.updatable_tool_block(tool_id, "status update") else {
             return;
         };
+        tool_block.status = status;
+        tool_block.awaiting_approval = false;
+        tool_block.complete_parameters();
+        if matches!(status, ToolStatus::Success | ToolStatus::Error) {
+            tool_block.progress = None;
+        }
+        tool_block.status_message = message;
+        tool_block.set_output(output, limit);
+    }
 
-        if let Some(tool_block) = live_message.get_tool_block_mut(tool_id) {
-            tool_block.status = status;
-            tool_block.status_message = message;
-            tool_block.output = output;
+    /// Record how long a finished tool ran, shown in its scrollback header.
+    pub fn set_tool_duration(&mut self, tool_id: &str, duration: Duration) {
+        if let Some(tool_block) = self.updatable_tool_block(tool_id, "duration update") {
+            tool_block.duration = Some(duration);
         }
     }
 
-    /// Append streaming output to a tool block (used by execute_command).
-    pub fn append_tool_output(&mut self, tool_id: &str, chunk: &str) {
+    /// Mark a finished tool as having succeeded without changing anything.
+    pub fn mark_tool_unchanged(&mut self, tool_id: &str) {
+        if let Some(tool_block) = self.updatable_tool_block(tool_id, "unchanged update") {
+            tool_block.unchanged = true;
+        }
+    }
+
+    /// Attach token usage to the response being shown; it is rendered under
+    /// the message once it goes to scrollback.
+    pub fn set_message_usage(&mut self, usage: MessageUsage) {
+        match self.transcript.active_message_mut() {
+            Some(live_message) => live_message.usage = Some(usage),
+            None => tracing::warn!("Ignoring message usage without active message"),
+        }
+    }
+
+    /// Record the fraction done of a running tool, shown as a progress bar.
+    /// A NaN or infinite fraction makes the progress indeterminate again.
+    pub fn set_tool_progress(&mut self, tool_id: &str, fraction: f32) {
+        if let Some(tool_block) = self.updatable_tool_block(tool_id, "progress update") {
+            tool_block.progress = fraction.is_finite().then(|| fraction.clamp(0.0, 1.0));
+        }
+    }
+
+    /// Show or hide the approve/skip prompt under a pending tool block.
+    pub fn set_tool_awaiting_approval(&mut self, tool_id: &str, awaiting: bool) {
         let Some(live_message) = self.transcript.active_message_mut() else {
-            tracing::warn!("Ignoring tool output append without active message");
+            tracing::warn!("Ignoring tool approval request without active message");
             return;
         };
 
         if let Some(tool_block) = live_message.get_tool_block_mut(tool_id) {
-            match &mut tool_block.output {
-                Some(existing) => existing.push_str(chunk),
-                None => tool_block.output = Some(chunk.to_string()),
-            }
+            tool_block.awaiting_approval = awaiting;
+        }
+    }
+
+    /// Append streaming output to a tool block (used by execute_command).
+    pub fn append_tool_output(&mut self, tool_id: &str, chunk: &str) {
+        let limit = self.tool_output_limit;
+        if let Some(tool_block) = self.updatable_tool_block(tool_id, "output append") {
+            tool_block.append_output(chunk, limit);
         }
     }
 
     /// Add a user message as finalized message and clear any pending user message.
     /// Before adding, finalizes any active streaming message so it appears in
     /// scrollback history BEFORE this user message (correct chronological order).
+    #[cfg_attr(not(test), allow(dead_code))]
     pub fn add_user_message(&mut self, content: &str) -> Result<()> {
+        self.add_user_message_with_images(content, Vec::new())
+    }
+
+    /// Like [`Self::add_user_message`], with the user's attached images
+    /// shown below the text.
+    pub fn add_user_message_with_images(
+        &mut self,
+        content: &str,
+        images: Vec<ImageBlock>,
+    ) -> Result<()> {
         // Finalize any active streaming message first so it gets committed
         // to history BEFORE this user message
-        self.flush_streaming_pending();
-        self.transcript.finalize_active_if_content();
-        // Clear stale stream state so prepare()/sync_live_stream_tails() won't
-        // re-create a phantom active message from leftover tail text.
-        self.streaming_controller.clear();
-        self.last_stream_kind = None;
-        // Flush the now-finalized agent response into scrollback
-        self.flush_new_finalized_messages(self.last_known_width);
+        self.finalize_active_message();
 
         // Create a finalized message with a UserText block for proper styling
         let mut user_message = LiveMessage::new();
         let mut text_block = PlainTextBlock::new();
         text_block.content = content.to_string();
         user_message.add_block(MessageBlock::UserText(text_block));
+        for image in images {
+            user_message.add_block(MessageBlock::Image(image));
+        }
         user_message.finalized = true;
 
         self.transcript.push_committed_message(user_message);
@@ -448,13 +1063,27 @@ impl TerminalRenderer {
         Ok(())
     }
 
+    /// Commit the active message (if it has content) and flush it into
+    /// scrollback.
+    fn finalize_active_message(&mut self) {
+        self.flush_streaming_pending();
+        self.transcript.finalize_active_if_content();
+        self.clear_tool_focus();
+        // Clear stale stream state so prepare()/sync_live_stream_tails() won't
+        // re-create a phantom active message from leftover tail text.
+        self.streaming_controller.clear();
+        self.last_stream_kind = None;
+        // Flush the now-finalized agent response into scrollback
+        self.flush_new_finalized_messages(self.last_known_width);
+    }
+
     /// Add an instruction/informational message as a finalized message
     /// This is for system messages, welcome text, etc.
     pub fn add_instruction_message(&mut self, content: &str) -> Result<()> {
         let mut instruction_message = LiveMessage::new();
         let mut text_block = PlainTextBlock::new();
         text_block.content = content.to_string();
-        instruction_message.add_block(MessageBlock::PlainText(text_block));
+        instruction_message.add_block(MessageBlock::SystemText(text_block));
         instruction_message.finalized = true;
 
         self.transcript.push_committed_message(instruction_message);
@@ -465,7 +1094,22 @@ impl TerminalRenderer {
     /// Used for the welcome banner which needs custom styling beyond what
     /// markdown rendering provides.
     pub fn add_styled_history_lines(&mut self, lines: Vec<Line<'static>>) {
-        self.pending_history_lines.extend(lines);
+        self.history_ends_blank = lines.last().is_none_or(is_blank_line);
+        self.pending_history_rows
+            .extend(lines.into_iter().map(HistoryRow::Text));
+    }
+
+    /// Show a saved session: its messages become the committed transcript
+    /// and go to scrollback on the next `prepare`.
+    pub fn load_session(&mut self, messages: &[MessageData], tool_results: &[ToolResultData]) {
+        self.clear_all_messages();
+        let messages = live_messages_from_session(messages, tool_results, self.tool_output_limit);
+        self.transcript.load_from(messages);
+    }
+
+    /// Disable the composer, for viewing a session without a backend
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.composer.set_read_only(read_only);
     }
 
     /// Clear all messages and reset state
@@ -474,73 +1118,298 @@ impl TerminalRenderer {
         self.streaming_controller.clear();
         self.streaming_open = false;
         self.last_stream_kind = None;
-        self.deferred_history_lines.clear();
-        self.pending_history_lines.clear();
+        self.deferred_history_rows.clear();
+        self.pending_history_rows.clear();
+        self.history_ends_blank = true;
+        self.pending_thinking_lines.clear();
+        self.held_tool_parameters.clear();
+        self.hidden_thinking_since = None;
         self.spinner_state = SpinnerState::Hidden;
+        self.last_project = None;
+        self.clear_tool_focus();
+    }
+
+    /// Whether the committed message at `index` is a user message right
+    /// after an assistant turn.
+    fn starts_turn(&self, index: usize) -> bool {
+        let messages = self.transcript.committed_messages();
+        let is_user = |message: &LiveMessage| {
+            matches!(message.blocks.first(), Some(MessageBlock::UserText(_)))
+        };
+        index > 0 && is_user(&messages[index]) && !is_user(&messages[index - 1])
+    }
+
+    /// A `— now in project: foo —` line if the tool calls in the committed
+    /// message at `index` move to a different project than the last one seen.
+    /// The first project of a session is not announced.
+    fn project_breadcrumb(&mut self, index: usize) -> Option<Line<'static>> {
+        let message = &self.transcript.committed_messages()[index];
+        let mut switched_to = None;
+        for block in &message.blocks {
+            let MessageBlock::ToolUse(tool) = block else {
+                continue;
+            };
+            let Some(project) = tool_project(tool) else {
+                continue;
+            };
+            if self.last_project.as_deref() == Some(project) {
+                continue;
+            }
+            if self.last_project.is_some() && switched_to.is_none() {
+                switched_to = Some(project.to_string());
+            }
+            self.last_project = Some(project.to_string());
+        }
+        let project = switched_to.filter(|_| self.project_breadcrumbs)?;
+        Some(Line::styled(
+            format!("— now in project: {project} —"),
+            Style::default()
+                .fg(Theme::global().muted_fg)
+                .add_modifier(Modifier::DIM),
+        ))
+    }
+
+    /// The `command_line` of the most recent `execute_command` tool, looking
+    /// at the live message first and then back through committed messages.
+    pub fn last_executed_command(&self) -> Option<String> {
+        self.transcript
+            .active_message()
+            .into_iter()
+            .chain(self.transcript.committed_messages().iter().rev())
+            .flat_map(|message| message.blocks.iter().rev())
+            .find_map(|block| match block {
+                MessageBlock::ToolUse(tool) if tool.name == "execute_command" => tool
+                    .parameters
+                    .get("command_line")
+                    .map(|param| param.value.trim())
+                    .filter(|command| !command.is_empty())
+                    .map(str::to_string),
+                _ => None,
+            })
+    }
+
+    /// Render the committed transcript for `/export`
+    pub fn export_transcript(&self, format: ExportFormat) -> Result<String> {
+        render_export(self.transcript.committed_messages(), format)
+    }
+
+    /// The most recent path reference in assistant text, live message first
+    pub fn last_path_ref(&self) -> Option<PathRef> {
+        self.transcript
+            .active_message()
+            .into_iter()
+            .chain(self.transcript.committed_messages().iter().rev())
+            .flat_map(|message| message.blocks.iter().rev())
+            .find_map(|block| match block {
+                MessageBlock::PlainText(text) => last_path_ref(&text.content),
+                _ => None,
+            })
+    }
+
+    /// Text of the committed messages `selector` picks, for `/copy`
+    pub fn copy_text(&self, selector: CopySelector) -> Result<String, String> {
+        extract_copy_text(self.transcript.committed_messages(), selector)
+    }
+
+    /// Mirror committed messages into a transcript file
+    pub fn set_transcript_writer(&mut self, writer: Option<TranscriptWriter>) {
+        self.transcript_writer = writer;
+    }
+
+    /// Move the transcript to `session_id`'s file after a session switch
+    pub fn switch_transcript(&mut self, session_id: &str) {
+        let switched = self
+            .transcript_writer
+            .as_ref()
+            .is_some_and(|writer| writer.session_id() != session_id);
+        if switched {
+            self.transcript_writer = TranscriptWriter::for_session(session_id);
+        }
+    }
+
+    /// Set or clear the picker list shown in the status area
+    pub fn set_popup_text(&mut self, text: Option<String>) {
+        self.popup_text = text;
+    }
+
+    /// Show or hide line numbers in the composer. Returns whether they are now shown.
+    pub fn toggle_line_numbers(&mut self) -> bool {
+        self.composer.toggle_line_numbers()
+    }
+
+    /// Dim the composer while the terminal is unfocused
+    pub fn set_focused(&mut self, focused: bool) {
+        self.composer.set_focused(focused);
+    }
+
+    /// Update the session details shown in the status bar
+    pub fn set_status_bar(&mut self, info: StatusBarInfo) {
+        self.status_bar = info;
     }
 
     /// Show rate limit spinner with countdown
     pub fn show_rate_limit_spinner(&mut self, seconds_remaining: u64) {
         self.spinner_state = SpinnerState::RateLimit {
             start_time: Instant::now(),
-            seconds_remaining,
+            initial_seconds: seconds_remaining,
         };
     }
 
-    /// Hide spinner
-    pub fn hide_spinner(&mut self) {
-        self.spinner_state = SpinnerState::Hidden;
+    /// Show the retry spinner for a request that failed and is retried
+    pub fn show_retry_spinner(&mut self, attempt: u32, max: u32) {
+        self.spinner_state = SpinnerState::Retrying {
+            attempt,
+            max,
+            start_time: Instant::now(),
+        };
     }
 
-    /// Hide spinner if it's currently showing loading state
-    pub fn hide_loading_spinner_if_active(&mut self) {
-        if matches!(self.spinner_state, SpinnerState::Loading { .. }) {
+    /// Hide the rate limit spinner once its countdown has reached zero.
+    /// Returns true if the spinner was hidden.
+    pub fn expire_rate_limit_spinner(&mut self) -> bool {
+        if self.spinner_state.rate_limit_seconds_remaining() == Some(0) {
             self.spinner_state = SpinnerState::Hidden;
+            true
+        } else {
+            false
         }
     }
 
-    fn flush_deferred_history_lines(&mut self) {
-        if self.deferred_history_lines.is_empty() {
-            return;
-        }
-
-        let lines = std::mem::take(&mut self.deferred_history_lines);
-        self.insert_or_defer_history_lines(lines);
+    /// Show what the agent is busy with next to the spinner. `None` ends
+    /// that phase and hides the spinner until the next request starts.
+    pub fn set_spinner_phase(&mut self, label: Option<String>) {
+        match label {
+            Some(label) => {
+                self.spinner_state = SpinnerState::Loading {
+                    start_time: Instant::now(),
+                    label: Some(label),
+                }
+            }
+            None => {
+                if matches!(
+                    self.spinner_state,
+                    SpinnerState::Loading { label: Some(_), .. }
+                ) {
+                    self.spinner_state = SpinnerState::Hidden;
+                }
+            }
+        }
+    }
+
+    /// Hide spinner
+    pub fn hide_spinner(&mut self) {
+        self.spinner_state = SpinnerState::Hidden;
+    }
+
+    /// Hide spinner if it's currently showing loading state
+    pub fn hide_loading_spinner_if_active(&mut self) {
+        if matches!(
+            self.spinner_state,
+            SpinnerState::Loading { .. } | SpinnerState::Retrying { .. }
+        ) {
+            self.spinner_state = SpinnerState::Hidden;
+        }
+    }
+
+    fn flush_deferred_history_rows(&mut self) {
+        if self.deferred_history_rows.is_empty() {
+            return;
+        }
+
+        let rows = std::mem::take(&mut self.deferred_history_rows);
+        self.insert_or_defer_history_rows(rows);
     }
 
     fn flush_new_finalized_messages(&mut self, width: u16) {
-        let unrendered = self.transcript.unrendered_committed_messages();
+        let unrendered = self.transcript.unrendered_committed_range();
         if unrendered.is_empty() {
             return;
         }
 
         let mut lines = Vec::new();
-        for message in unrendered {
+        let mut thinking_units = Vec::new();
+        for index in unrendered {
+            let message = &self.transcript.committed_messages()[index];
+            if let Some(writer) = &self.transcript_writer {
+                writer.append_message(message);
+            }
+            let breadcrumb = self.project_breadcrumb(index);
+            let message = &self.transcript.committed_messages()[index];
             if message.streamed_to_scrollback {
                 // PlainText and Thinking blocks were already progressively sent
                 // to scrollback during streaming. Only send non-streamed blocks
                 // (ToolUse, UserText) that were added directly to the message.
-                let tool_lines =
-                    TranscriptState::as_history_lines_non_streamed_only(message, width);
+                let tool_lines = self.transcript.committed_history_lines(
+                    index,
+                    width,
+                    HistoryKind::NonStreamedOnly {
+                        tools: self.tool_detail,
+                    },
+                );
                 if !tool_lines.is_empty() {
                     // The blank separator before these tool blocks was already
                     // inserted by start_tool_use_block when it flushed the
                     // preceding streamed content.
+                    lines.extend(breadcrumb.map(HistoryRow::Text));
                     lines.extend(tool_lines);
                     // Trailing blank so the next streamed content doesn't
                     // visually merge with the tool block.
-                    lines.push(Line::from(""));
+                    lines.push(HistoryRow::Text(Line::from("")));
                 }
                 continue;
             }
-            if !lines.is_empty() {
-                lines.push(Line::from(""));
+            let mut message_lines = self.transcript.committed_history_lines(
+                index,
+                width,
+                HistoryKind::Full {
+                    thinking: self.thinking_visibility,
+                    tools: self.tool_detail,
+                },
+            );
+            if self.starts_turn(index) {
+                // The separator takes the place of the user message's leading
+                // blank, and a blank line already above it (e.g. the one
+                // after a tool block) counts as its own
+                let leading_blanks = message_lines
+                    .iter()
+                    .take_while(|row| is_blank_row(row))
+                    .count();
+                message_lines.drain(..leading_blanks);
+                let mut separator = self.turn_separator.lines(width);
+                let ends_blank = lines.last().map_or(self.history_ends_blank, is_blank_row);
+                if ends_blank && separator.first().is_some_and(is_blank_line) {
+                    separator.remove(0);
+                }
+                lines.extend(separator.into_iter().map(HistoryRow::Text));
+            } else if !lines.is_empty() {
+                lines.push(HistoryRow::Text(Line::from("")));
+            }
+            lines.extend(breadcrumb.map(HistoryRow::Text));
+            lines.extend(message_lines);
+            if self.thinking_visibility == ThinkingVisibility::Hidden {
+                continue;
+            }
+            for block in &self.transcript.committed_messages()[index].blocks {
+                if let MessageBlock::Thinking(thinking) = block {
+                    if !thinking.content.trim().is_empty() {
+                        thinking_units.push(ThinkingUnit {
+                            lines: TranscriptState::thinking_history_lines(
+                                &thinking.content,
+                                width,
+                            ),
+                            expanded: self.thinking_visibility == ThinkingVisibility::Full,
+                        });
+                    }
+                }
             }
-            lines.extend(TranscriptState::as_history_lines(message, width));
         }
 
-        self.insert_or_defer_history_lines(lines);
+        self.insert_or_defer_history_rows(lines);
+        for unit in thinking_units {
+            self.transcript.push_thinking_unit(unit);
+        }
         self.transcript.mark_committed_as_rendered();
+        self.transcript.trim_committed(self.max_retained_messages);
     }
 
     fn apply_streaming_commit_tick(&mut self) {
@@ -567,12 +1436,12 @@ impl TerminalRenderer {
         let has_lines = !drained.text.is_empty() || !drained.thinking.is_empty();
 
         if !drained.text.is_empty() {
-            self.insert_or_defer_history_lines(indent_lines(drained.text));
+            self.insert_or_defer_history_lines(indent_lines(text_lines(drained.text)));
         }
 
         if !drained.thinking.is_empty() {
             let lines = style_thinking_lines(drained.thinking);
-            self.insert_or_defer_history_lines(indent_lines(lines));
+            self.emit_thinking_lines(indent_lines(lines));
         }
 
         // Mark the active message so flush_new_finalized_messages() won't
@@ -633,21 +1502,46 @@ impl TerminalRenderer {
     }
 
     fn insert_or_defer_history_lines(&mut self, lines: Vec<Line<'static>>) {
-        if lines.is_empty() {
+        self.insert_or_defer_history_rows(lines.into_iter().map(HistoryRow::Text).collect());
+    }
+
+    fn insert_or_defer_history_rows(&mut self, rows: Vec<HistoryRow>) {
+        if rows.is_empty() {
             return;
         }
-
-        if self.overlay_active {
-            self.deferred_history_lines.extend(lines);
+        self.history_ends_blank = rows.last().is_some_and(is_blank_row);
+
+        if self.defers_history() {
+            self.deferred_history_rows.extend(rows);
+            let excess = self
+                .deferred_history_rows
+                .len()
+                .saturating_sub(MAX_HELD_HISTORY_LINES);
+            if !self.following && excess > 0 {
+                self.pending_history_rows
+                    .extend(self.deferred_history_rows.drain(..excess));
+            }
             return;
         }
 
-        self.pending_history_lines.extend(lines);
+        self.pending_history_rows.extend(rows);
     }
 
-    /// Drain pending history lines for the Tui layer to insert into scrollback.
+    /// Drain pending history rows for the Tui layer to insert into scrollback.
+    pub fn drain_pending_history_rows(&mut self) -> Vec<HistoryRow> {
+        std::mem::take(&mut self.pending_history_rows)
+    }
+
+    /// The pending history as text, with images as their placeholders.
+    #[cfg(test)]
     pub fn drain_pending_history_lines(&mut self) -> Vec<Line<'static>> {
-        std::mem::take(&mut self.pending_history_lines)
+        self.drain_pending_history_rows()
+            .into_iter()
+            .map(|row| match row {
+                HistoryRow::Text(line) => line,
+                HistoryRow::Image(image) => Line::from(image.placeholder),
+            })
+            .collect()
     }
 
     /// Prepare for the next frame: flush streaming data, commit finalized messages.
@@ -655,37 +1549,59 @@ impl TerminalRenderer {
     pub fn prepare(&mut self, width: u16, screen_height: u16) {
         let _ = screen_height; // Reserved for future partial-scrollback support
         self.last_known_width = width;
-        // Account for 2-char indent when computing streaming wrap width
-        let stream_width = width.saturating_sub(2).max(1) as usize;
+        // Wrap streamed text as wide as the live message draws it, beside
+        // the diff pane when one is shown
+        let content_width = self
+            .diff_pane_layout(width)
+            .map_or(width, |(_, main, _)| main.width);
+        let stream_width = MessageBlock::content_width(content_width).max(1) as usize;
         self.streaming_controller.set_width(Some(stream_width));
         self.apply_streaming_commit_tick();
-        if !self.overlay_active {
-            self.flush_deferred_history_lines();
+        if !self.defers_history() {
+            self.flush_deferred_history_rows();
         }
         self.flush_new_finalized_messages(width);
+        self.status_bar.held_lines = (!self.following).then_some(self.deferred_history_rows.len());
     }
 
     /// Compute the desired viewport height for the current content.
     pub fn desired_viewport_height(&self, textarea: &TextArea, screen_width: u16) -> u16 {
+        if screen_width < MIN_TERMINAL_WIDTH {
+            // Only the "too small" notice is shown
+            return 1;
+        }
         let input_height = self.composer.calculate_input_height(textarea, screen_width);
         let mut content_height: u16 = 0;
+        let pane = self.diff_pane_layout(screen_width);
+        let content_width = pane
+            .as_ref()
+            .map_or(screen_width, |(_, main, _)| main.width);
 
         // Live message height
         if let Some(live_message) = self.transcript.active_message() {
             if live_message.has_content() {
-                for block in &live_message.blocks {
-                    content_height = content_height
-                        .saturating_add(block.calculate_height(screen_width))
-                        .saturating_add(1); // gap between blocks
+                for (index, block) in live_message.blocks.iter().enumerate() {
+                    content_height =
+                        content_height
+                            .saturating_add(block.calculate_height_with(
+                                content_width,
+                                self.live_block_detail(index),
+                            ))
+                            .saturating_add(1); // gap between blocks
                 }
             }
         }
 
         // Spinner height
-        if self.spinner_state.get_spinner_char().is_some() {
+        if !matches!(self.spinner_state, SpinnerState::Hidden) {
             content_height = content_height.saturating_add(2); // spinner + gap
         }
 
+        // The diff pane sits beside the live content
+        if let Some((content, _, _)) = &pane {
+            content_height = content_height.max(content.height());
+        }
+
         // Status/error height
         content_height = content_height.saturating_add(self.measure_status_height(screen_width));
 
@@ -693,44 +1609,76 @@ impl TerminalRenderer {
         // scrollback and the composer when no live content is displayed.
         content_height = content_height.max(1);
 
-        content_height.saturating_add(input_height)
+        content_height
+            .saturating_add(input_height)
+            .saturating_add(STATUS_BAR_HEIGHT)
     }
 
     fn measure_status_height(&self, width: u16) -> u16 {
-        let mut height: u16 = 0;
-        if self.current_error.is_some() {
-            let formatted = Self::format_error_message(self.current_error.as_deref().unwrap());
-            height = Self::measure_markdown_height(&formatted, width, 20);
-            if height > 0 {
-                height = height.saturating_add(1); // gap
-            }
+        self.layout_status(width, u16::MAX).height
+    }
+
+    /// Collect the status entries to show (an error replaces everything
+    /// else; a warning sits above info and pending text) and size each one:
+    /// its wrapped height, capped at `max_status_height` and whatever is
+    /// left of `budget`.
+    fn layout_status(&self, width: u16, budget: u16) -> StatusLayout {
+        let mut entries: Vec<StatusEntry> = Vec::new();
+        let mut push = |kind: StatusKind, content: String| {
+            entries.push(StatusEntry {
+                kind,
+                content,
+                height: 0,
+                truncated: false,
+            });
+        };
+        if let Some(ref error) = self.current_error {
+            push(StatusKind::Error, Self::format_error_message(error));
         } else {
-            let mut has_any = false;
-            if let Some(plan_text) = self.build_plan_text() {
-                let h = Self::measure_markdown_height(&plan_text, width, 20);
-                height = height.saturating_add(h);
-                has_any = true;
+            if let Some(plan_text) = self.build_plan_text(width) {
+                push(StatusKind::Plan, plan_text);
             }
-            if let Some(ref info_msg) = self.info_message {
-                if has_any {
-                    height = height.saturating_add(1);
-                }
-                let h = Self::measure_markdown_height(info_msg, width, 20);
-                height = height.saturating_add(h);
-                has_any = true;
-            } else if let Some(ref pending_msg) = self.pending_user_message {
-                if has_any {
-                    height = height.saturating_add(1);
-                }
-                let h = Self::measure_markdown_height(pending_msg, width, 20);
-                height = height.saturating_add(h);
-                has_any = true;
+            if let Some(ref warning) = self.current_warning {
+                push(
+                    StatusKind::Warning,
+                    format!("⚠ {warning} (Press Esc to dismiss)"),
+                );
             }
-            if has_any {
-                height = height.saturating_add(1); // gap above status
+            if let Some(info_msg) = self.popup_text.as_ref().or(self.info_message.as_ref()) {
+                push(StatusKind::Info, info_msg.clone());
+            } else if let Some(pending_msg) = self.pending_status_text() {
+                push(StatusKind::Pending, pending_msg);
             }
         }
-        height
+
+        let mut budget = budget;
+        let mut height: u16 = 0;
+        let mut any_rendered = false;
+        let count = entries.len();
+        for (idx, entry) in entries.iter_mut().enumerate() {
+            if budget == 0 {
+                break;
+            }
+            let full = self.cached_markdown_height(&entry.content, width);
+            entry.height = full.min(self.max_status_height.max(1)).min(budget);
+            entry.truncated = entry.height < full;
+            if entry.height == 0 {
+                continue;
+            }
+
+            any_rendered = true;
+            budget -= entry.height;
+            height = height.saturating_add(entry.height);
+            if idx + 1 < count && budget > 0 {
+                budget -= 1;
+                height = height.saturating_add(1);
+            }
+        }
+        if any_rendered && budget > 0 {
+            height = height.saturating_add(1); // gap above the composer
+        }
+
+        StatusLayout { entries, height }
     }
 
     /// Paint the current state into the provided frame.
@@ -739,108 +1687,57 @@ impl TerminalRenderer {
         let full = f.area();
         let width = full.width;
         let input_height = self.composer.calculate_input_height(textarea, width);
-        let available = full.height.saturating_sub(input_height);
+        if Self::is_too_small(width, full.height, input_height) {
+            Self::render_too_small(f);
+            return;
+        }
+        let available = full
+            .height
+            .saturating_sub(input_height)
+            .saturating_sub(STATUS_BAR_HEIGHT);
 
         let headroom: u16 = 200;
         let scratch_height = available.saturating_add(headroom).max(available);
-        let mut scratch = Buffer::empty(Rect::new(0, 0, width, scratch_height));
-
-        let mut cursor_y = scratch_height;
-
-        cursor_y = cursor_y.saturating_sub(1);
-
-        let mut status_entries: Vec<StatusEntry> = Vec::new();
-        if let Some(plan_text) = self.build_plan_text() {
-            status_entries.push(StatusEntry {
-                kind: StatusKind::Plan,
-                content: plan_text,
-                height: 0,
-            });
-        }
-
-        if let Some(ref info_msg) = self.info_message {
-            status_entries.push(StatusEntry {
-                kind: StatusKind::Info,
-                content: info_msg.clone(),
-                height: 0,
-            });
-        } else if let Some(ref pending_msg) = self.pending_user_message {
-            status_entries.push(StatusEntry {
-                kind: StatusKind::Pending,
-                content: pending_msg.clone(),
-                height: 0,
-            });
+        // Reuse the scratch buffer across frames; it is only reallocated
+        // when the dimensions change, and cleared otherwise.
+        let mut scratch = std::mem::take(&mut self.scratch);
+        let scratch_area = Rect::new(0, 0, width, scratch_height);
+        if scratch.area != scratch_area {
+            scratch.resize(scratch_area);
         }
+        scratch.reset();
 
-        let mut status_height: u16 = 0;
-        let mut error_display: Option<String> = None;
-
-        if let Some(ref error_msg) = self.current_error {
-            let formatted = Self::format_error_message(error_msg);
-            let max_height = cursor_y.min(scratch_height).max(1);
-            let rendered_height = Self::measure_markdown_height(&formatted, width, max_height);
-            let actual_height = rendered_height.min(cursor_y);
-            if actual_height > 0 {
-                cursor_y = cursor_y.saturating_sub(actual_height);
-                status_height = status_height.saturating_add(actual_height);
-                if cursor_y > 0 {
-                    cursor_y = cursor_y.saturating_sub(1);
-                    status_height = status_height.saturating_add(1);
-                }
-            }
-            error_display = Some(formatted);
-        } else if !status_entries.is_empty() {
-            let mut any_rendered = false;
-            for idx in 0..status_entries.len() {
-                if cursor_y == 0 {
-                    break;
-                }
-
-                let entry = &mut status_entries[idx];
-                let max_height = cursor_y.min(scratch_height).max(1);
-                let rendered_height =
-                    Self::measure_markdown_height(&entry.content, width, max_height);
-                let actual_height = rendered_height.min(cursor_y);
-                entry.height = actual_height;
+        let mut cursor_y = scratch_height.saturating_sub(1);
+        let pane = self.diff_pane_layout(width);
+        let content_width = pane.as_ref().map_or(width, |(_, main, _)| main.width);
 
-                if actual_height > 0 {
-                    any_rendered = true;
-                    cursor_y = cursor_y.saturating_sub(actual_height);
-                    status_height = status_height.saturating_add(actual_height);
-
-                    if idx + 1 < status_entries.len() && cursor_y > 0 {
-                        cursor_y = cursor_y.saturating_sub(1);
-                        status_height = status_height.saturating_add(1);
-                    }
-                }
-            }
-
-            if any_rendered && cursor_y > 0 {
-                cursor_y = cursor_y.saturating_sub(1);
-                status_height = status_height.saturating_add(1);
-            }
-        }
-
-        let status_height = status_height;
+        // Status rows are drawn into their own layout area below the content,
+        // so they are budgeted separately from the scratch buffer rows.
+        let StatusLayout {
+            entries: status_entries,
+            height: status_height,
+        } = self.layout_status(width, cursor_y);
 
         // 1) Render spinner if active (closest to input)
-        if let Some((spinner_char, spinner_color)) = self.spinner_state.get_spinner_char() {
+        if let Some((spinner_frame, spinner_color)) =
+            self.spinner_state.get_spinner_frame(self.spinner_style)
+        {
             if cursor_y > 0 {
                 cursor_y = cursor_y.saturating_sub(1);
 
                 scratch.set_string(
                     2,
                     cursor_y,
-                    spinner_char.to_string(),
+                    spinner_frame,
                     Style::default().fg(spinner_color),
                 );
 
                 if let Some(status_text) = self.spinner_state.get_status_text() {
                     scratch.set_string(
-                        4,
+                        3 + self.spinner_style.width(),
                         cursor_y,
                         &status_text,
-                        Style::default().fg(Color::LightRed),
+                        Style::default().fg(spinner_color),
                     );
                 }
 
@@ -851,7 +1748,12 @@ impl TerminalRenderer {
         // 2) Render current live message (so it is closest to the input)
         if let Some(live_message) = self.transcript.active_message() {
             if live_message.has_content() && cursor_y > 0 {
-                self.render_message_to_buffer(live_message, &mut scratch, &mut cursor_y, width);
+                self.render_message_to_buffer(
+                    live_message,
+                    &mut scratch,
+                    &mut cursor_y,
+                    content_width,
+                );
                 cursor_y = cursor_y.saturating_sub(1);
             }
         }
@@ -859,10 +1761,11 @@ impl TerminalRenderer {
         // Composed content occupies rows [cursor_y .. scratch_height)
         let total_height = scratch_height.saturating_sub(cursor_y);
 
-        let [content_area, status_area, input_area] = Layout::vertical([
+        let [content_area, status_area, input_area, status_bar_area] = Layout::vertical([
             Constraint::Min(0),
             Constraint::Length(status_height),
             Constraint::Length(input_height),
+            Constraint::Length(STATUS_BAR_HEIGHT),
         ])
         .areas(full);
 
@@ -901,16 +1804,96 @@ impl TerminalRenderer {
                 }
             }
         }
+        self.scratch = scratch;
+
+        // Draw the diff pane over the right side, aligned at the bottom
+        if let Some((content, _, side)) = pane {
+            let height = content.height().min(content_area.height);
+            content.render(
+                Rect::new(
+                    content_area.x + side.x,
+                    content_area.bottom() - height,
+                    side.width,
+                    height,
+                ),
+                dst,
+            );
+        }
 
-        // Render status area (error takes priority over other messages)
-        if let Some(ref error_msg) = error_display {
-            Self::render_error_message(f, status_area, error_msg);
-        } else if status_entries.iter().any(|entry| entry.height > 0) {
-            Self::render_status_entries(f, status_area, &status_entries);
+        // Render status area (an error replaces the other entries)
+        if status_entries.iter().any(|entry| entry.height > 0) {
+            let plan_highlights = self.plan_highlight_rows();
+            Self::render_status_entries(f, status_area, &status_entries, &plan_highlights);
         }
 
         // Render input area (block + textarea)
         self.composer.render(f, input_area, textarea);
+
+        // Render the persistent status bar below the composer footer
+        self.composer
+            .render_status_bar(f, status_bar_area, &self.status_bar);
+    }
+
+    /// Plain-text snapshot of the viewport as `paint` would draw it at
+    /// `width`: live message, spinner, status and composer, top to bottom.
+    /// Trailing spaces are trimmed from each row. Reflects the state as of
+    /// the last `prepare` call.
+    #[cfg(test)]
+    pub fn screen_text(&mut self, textarea: &TextArea, width: u16) -> String {
+        let area = Rect::new(0, 0, width, self.desired_viewport_height(textarea, width));
+        let mut buffer = Buffer::empty(area);
+        let mut frame = custom_terminal::Frame {
+            cursor_position: None,
+            viewport_area: area,
+            buffer: &mut buffer,
+        };
+        self.paint(&mut frame, textarea);
+
+        let mut rows = Vec::with_capacity(area.height as usize);
+        for y in area.top()..area.bottom() {
+            let mut row = String::new();
+            let mut x = area.left();
+            while x < area.right() {
+                let symbol = buffer[(x, y)].symbol();
+                row.push_str(symbol);
+                // Wide glyphs cover the following cell(s); skip them
+                x += (UnicodeWidthStr::width(symbol) as u16).max(1);
+            }
+            rows.push(row.trim_end().to_string());
+        }
+        rows.join("\n")
+    }
+
+    /// Whether the frame cannot fit the composer plus at least one row
+    /// of content, or is narrower than the layout supports.
+    fn is_too_small(width: u16, height: u16, input_height: u16) -> bool {
+        width < MIN_TERMINAL_WIDTH
+            || height
+                < input_height
+                    .saturating_add(STATUS_BAR_HEIGHT)
+                    .saturating_add(1)
+    }
+
+    /// Replace the whole frame with a centered "too small" notice.
+    fn render_too_small(f: &mut custom_terminal::Frame) {
+        let area = f.area();
+        let dst = f.buffer_mut();
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                if let Some(cell) = dst.cell_mut((x, y)) {
+                    cell.reset();
+                }
+            }
+        }
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        let message_area = Rect::new(area.x, area.y + area.height / 2, area.width, 1);
+        Paragraph::new(TOO_SMALL_MESSAGE)
+            .style(Style::default().add_modifier(Modifier::BOLD))
+            .alignment(Alignment::Center)
+            .render(message_area, dst);
     }
 
     /// Render a message to the scratch buffer, updating cursor_y
@@ -922,12 +1905,13 @@ impl TerminalRenderer {
         width: u16,
     ) {
         // Render blocks from last to first (bottom to top)
-        for block in message.blocks.iter().rev() {
+        for (index, block) in message.blocks.iter().enumerate().rev() {
             if *cursor_y == 0 {
                 break;
             }
 
-            let block_height = block.calculate_height(width).min(*cursor_y);
+            let detail = self.live_block_detail(index);
+            let block_height = block.calculate_height_with(width, detail).min(*cursor_y);
 
             if block_height > 0 {
                 let area = Rect::new(
@@ -936,7 +1920,16 @@ impl TerminalRenderer {
                     width,
                     block_height,
                 );
-                block.clone().render(area, scratch);
+                block.clone().render_with_detail(area, scratch, detail);
+                if self.focused_block == Some(index) {
+                    // Brighten the focused tool's header line
+                    scratch.set_style(
+                        Rect::new(area.x, area.y, area.width, 1),
+                        Style::default()
+                            .add_modifier(Modifier::BOLD | Modifier::REVERSED)
+                            .remove_modifier(Modifier::DIM),
+                    );
+                }
                 *cursor_y = cursor_y.saturating_sub(block_height);
 
                 // Add one line gap between blocks within a message
@@ -945,6 +1938,20 @@ impl TerminalRenderer {
         }
     }
 
+    /// Like [`Self::measure_markdown_height`], but reuses the result while
+    /// the content and dimensions are unchanged between frames. Measures one
+    /// row past `max_status_height`, which is enough to tell whether the
+    /// content gets truncated.
+    fn cached_markdown_height(&self, content: &str, width: u16) -> u16 {
+        let max_height = self.max_status_height.max(1).saturating_add(1);
+        self.markdown_height_cache.borrow_mut().get_or_measure(
+            content,
+            width,
+            max_height,
+            Self::measure_markdown_height,
+        )
+    }
+
     fn measure_markdown_height(content: &str, width: u16, max_height: u16) -> u16 {
         if content.trim().is_empty() || width == 0 || max_height == 0 {
             return 0;
@@ -967,7 +1974,9 @@ impl TerminalRenderer {
         for y in (0..max_height).rev() {
             let mut row_empty = true;
             for x in 0..width {
-                let c = tmp.cell((x, y)).expect("cell in tmp buffer");
+                let Some(c) = tmp.cell((x, y)) else {
+                    continue;
+                };
                 if !c.symbol().is_empty() && c.symbol() != " " {
                     row_empty = false;
                     break;
@@ -981,7 +1990,7 @@ impl TerminalRenderer {
         used
     }
 
-    fn build_plan_text(&self) -> Option<String> {
+    fn build_plan_text(&self, width: u16) -> Option<String> {
         let plan_state = match &self.plan_state {
             Some(plan) if !plan.entries.is_empty() => plan,
             _ => return None,
@@ -989,18 +1998,8 @@ impl TerminalRenderer {
 
         if self.plan_expanded {
             let total = plan_state.entries.len();
-            let mut start = 0usize;
-
-            if total > 4 {
-                while start < total
-                    && matches!(plan_state.entries[start].status, PlanItemStatus::Completed)
-                    && total - start > 4
-                {
-                    start += 1;
-                }
-            }
-
-            let end = (start + 4).min(total);
+            let start = self.plan_window_start(plan_state);
+            let end = (start + PLAN_VISIBLE_ENTRIES).min(total);
             let visible = &plan_state.entries[start..end];
             let hidden = total.saturating_sub(visible.len());
 
@@ -1023,6 +2022,20 @@ impl TerminalRenderer {
 
             Some(text)
         } else {
+            // Briefly call out a just-completed item before the summary
+            let highlighted = self.active_plan_highlights();
+            if let Some(entry) = highlighted
+                .last()
+                .and_then(|&index| plan_state.entries.get(index))
+            {
+                let prefix = "Plan: ✓ ";
+                let available = (width as usize).saturating_sub(prefix.width()).max(1);
+                return Some(format!(
+                    "{prefix}{}",
+                    truncate_to_width(&entry.content, available)
+                ));
+            }
+
             let total = plan_state.entries.len();
             if let Some((index, item)) = plan_state
                 .entries
@@ -1030,19 +2043,39 @@ impl TerminalRenderer {
                 .enumerate()
                 .find(|(_, entry)| !matches!(entry.status, PlanItemStatus::Completed))
             {
-                Some(format!(
-                    "Plan: {} ({} of {})",
-                    item.content,
+                let count = |status: PlanItemStatus| {
+                    plan_state
+                        .entries
+                        .iter()
+                        .filter(|entry| entry.status == status)
+                        .count()
+                };
+                let prefix = "Plan: ";
+                let suffix = format!(
+                    " ({}/{total}) · ✓{} ~{} ·{}",
                     index + 1,
-                    total
-                ))
+                    count(PlanItemStatus::Completed),
+                    count(PlanItemStatus::InProgress),
+                    count(PlanItemStatus::Pending),
+                );
+                // Shorten the current item so the summary stays on one line
+                let available = (width as usize)
+                    .saturating_sub(prefix.width() + suffix.width())
+                    .max(1);
+                let content = truncate_to_width(&item.content, available);
+                Some(format!("{prefix}{content}{suffix}"))
             } else {
                 Some(format!("Plan: All tasks completed ({total} items)"))
             }
         }
     }
 
-    fn render_status_entries(f: &mut custom_terminal::Frame, area: Rect, entries: &[StatusEntry]) {
+    fn render_status_entries(
+        f: &mut custom_terminal::Frame,
+        area: Rect,
+        entries: &[StatusEntry],
+        plan_highlights: &[usize],
+    ) {
         if area.height == 0 {
             return;
         }
@@ -1065,10 +2098,17 @@ impl TerminalRenderer {
 
             let entry_area = Rect::new(area.x, y, area.width, height);
             match entry.kind {
+                StatusKind::Error => Self::render_error_message(f, entry_area, &entry.content),
+                StatusKind::Warning => Self::render_warning_message(f, entry_area, &entry.content),
                 StatusKind::Info => Self::render_info_message(f, entry_area, &entry.content),
-                StatusKind::Plan => Self::render_plan_message(f, entry_area, &entry.content),
+                StatusKind::Plan => {
+                    Self::render_plan_message(f, entry_area, &entry.content, plan_highlights)
+                }
                 StatusKind::Pending => Self::render_pending_message(f, entry_area, &entry.content),
             }
+            if entry.truncated {
+                Self::render_truncation_marker(f, Rect::new(area.x, y + height - 1, area.width, 1));
+            }
 
             y = y.saturating_add(height);
             if idx + 1 < entries.len() && y < area.y + area.height {
@@ -1078,6 +2118,19 @@ impl TerminalRenderer {
         }
     }
 
+    fn render_warning_message(f: &mut custom_terminal::Frame, area: Rect, message: &str) {
+        if area.height == 0 {
+            return;
+        }
+
+        let text = md::from_str(message);
+        let paragraph = Paragraph::new(text)
+            .style(Style::default().fg(Theme::global().warning_fg))
+            .wrap(Wrap { trim: false });
+
+        f.render_widget(paragraph, area);
+    }
+
     fn render_info_message(f: &mut custom_terminal::Frame, area: Rect, message: &str) {
         if area.height == 0 {
             return;
@@ -1085,25 +2138,57 @@ impl TerminalRenderer {
 
         let text = md::from_str(message);
         let paragraph = Paragraph::new(text)
-            .style(Style::default().fg(Color::Gray))
+            .style(Style::default().fg(Theme::global().secondary_fg))
             .wrap(Wrap { trim: false });
 
         f.render_widget(paragraph, area);
     }
 
-    fn render_plan_message(f: &mut custom_terminal::Frame, area: Rect, plan_text: &str) {
+    fn render_plan_message(
+        f: &mut custom_terminal::Frame,
+        area: Rect,
+        plan_text: &str,
+        highlight_rows: &[usize],
+    ) {
         if area.height == 0 {
             return;
         }
 
-        let text = md::from_str(plan_text);
+        let mut text = md::from_str(plan_text);
+        // Just-completed items show green and struck through
+        let highlight_style = Style::default()
+            .fg(Theme::global().status_success)
+            .remove_modifier(Modifier::DIM)
+            .add_modifier(Modifier::CROSSED_OUT);
+        for &row in highlight_rows {
+            if let Some(line) = text.lines.get_mut(row) {
+                for span in &mut line.spans {
+                    span.style = span.style.patch(highlight_style);
+                }
+            }
+        }
         let paragraph = Paragraph::new(text)
-            .style(Style::default().fg(Color::Gray).add_modifier(Modifier::DIM))
+            .style(
+                Style::default()
+                    .fg(Theme::global().secondary_fg)
+                    .add_modifier(Modifier::DIM),
+            )
             .wrap(Wrap { trim: false });
 
         f.render_widget(paragraph, area);
     }
 
+    /// Replace the last visible row of a clipped status entry with `…`.
+    fn render_truncation_marker(f: &mut custom_terminal::Frame, area: Rect) {
+        Self::clear_status_gap(f, area);
+        f.buffer_mut().set_string(
+            area.x,
+            area.y,
+            "…",
+            Style::default().fg(Theme::global().muted_fg),
+        );
+    }
+
     fn clear_status_gap(f: &mut custom_terminal::Frame, area: Rect) {
         if area.height == 0 {
             return;
@@ -1141,7 +2226,7 @@ impl TerminalRenderer {
         let paragraph = Paragraph::new(text)
             .style(
                 Style::default()
-                    .fg(Color::DarkGray)
+                    .fg(Theme::global().muted_fg)
                     .add_modifier(Modifier::ITALIC),
             )
             .wrap(Wrap { trim: false });
@@ -1149,33 +2234,78 @@ impl TerminalRenderer {
         f.render_widget(paragraph, area);
     }
 
-    /// Render error message with red styling and dismiss instructions
+    /// Render an error message, already formatted with its dismiss
+    /// instructions, with red styling
     fn render_error_message(f: &mut custom_terminal::Frame, area: Rect, message: &str) {
         if area.height == 0 {
             return;
         }
 
-        let error_text = Self::format_error_message(message);
-        let text = md::from_str(&error_text);
+        let text = md::from_str(message);
         let paragraph = Paragraph::new(text)
-            .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+            .style(
+                Style::default()
+                    .fg(Theme::global().error_fg)
+                    .add_modifier(Modifier::BOLD),
+            )
             .wrap(Wrap { trim: false });
 
         f.render_widget(paragraph, area);
     }
 
-    fn format_error_message(message: &str) -> String {
-        format!("Error: {message} (Press Esc to dismiss)")
+    /// A plain error on one line; a structured one as its title with the
+    /// provider and kind, the message, and what the user can do.
+    fn format_error_message(error: &ErrorInfo) -> String {
+        let Some(api) = &error.api else {
+            return format!("Error: {} (Press Esc to dismiss)", error.message);
+        };
+
+        let mut text = error.title.clone();
+        for field in api.provider.iter().chain([&api.code]) {
+            text.push_str(&format!(" · {field}"));
+        }
+        text.push('\n');
+        text.push_str(&error.message);
+        text.push('\n');
+        text.push_str(if api.retryable {
+            "Retryable — press ⏎ to retry, Esc to dismiss"
+        } else {
+            "Press Esc to dismiss"
+        });
+        text
     }
 
     /// Set an error message to display
     pub fn set_error(&mut self, error_message: String) {
-        self.current_error = Some(error_message);
+        self.set_error_detailed(ErrorInfo::plain(error_message));
+    }
+
+    /// Set an error with its cause and whether retrying may help
+    pub fn set_error_detailed(&mut self, error: ErrorInfo) {
+        self.current_error = Some(error);
     }
 
-    /// Clear the current error message
+    /// Whether the displayed error offers a retry
+    pub fn has_retryable_error(&self) -> bool {
+        self.current_error
+            .as_ref()
+            .is_some_and(ErrorInfo::is_retryable)
+    }
+
+    /// Clear the current error message. The backend-disconnected error
+    /// stays, since nothing but exiting helps at that point.
     pub fn clear_error(&mut self) {
-        self.current_error = None;
+        self.current_error = self
+            .backend_disconnected
+            .then(|| ErrorInfo::plain(BACKEND_DISCONNECTED_MESSAGE));
+    }
+
+    /// The backend task is gone: show a persistent error and stop animating,
+    /// since nothing will stream anymore.
+    pub fn mark_backend_disconnected(&mut self) {
+        self.backend_disconnected = true;
+        self.spinner_state = SpinnerState::Hidden;
+        self.current_error = Some(ErrorInfo::plain(BACKEND_DISCONNECTED_MESSAGE));
     }
 
     /// Check if there's currently an error being displayed
@@ -1183,11 +2313,29 @@ impl TerminalRenderer {
         self.current_error.is_some()
     }
 
+    /// Set a warning to display until dismissed
+    pub fn set_warning(&mut self, warning_message: String) {
+        self.current_warning = Some(warning_message);
+    }
+
+    /// Clear the current warning
+    pub fn clear_warning(&mut self) {
+        self.current_warning = None;
+    }
+
+    /// Check if there's currently a warning being displayed
+    pub fn has_warning(&self) -> bool {
+        self.current_warning.is_some()
+    }
+
     /// Returns true when the UI has time-varying content that requires
     /// periodic redraws even without external events (spinner animation,
     /// streaming commit ticks).
     pub fn needs_animation_timer(&self) -> bool {
-        !matches!(self.spinner_state, SpinnerState::Hidden) || self.streaming_open
+        !self.backend_disconnected
+            && (!matches!(self.spinner_state, SpinnerState::Hidden)
+                || self.streaming_open
+                || !self.active_plan_highlights().is_empty())
     }
 
     /// Set an info message to display
@@ -1209,12 +2357,13 @@ impl TerminalRenderer {
 
     #[cfg(test)]
     fn deferred_history_line_count(&self) -> usize {
-        self.deferred_history_lines.len()
+        self.deferred_history_rows.len()
     }
 }
 
-/// Apply Yellow+Italic style to thinking lines while preserving per-span markdown styling.
+/// Apply the themed dim+italic style to thinking lines while preserving per-span markdown styling.
 fn style_thinking_lines(thinking: Vec<Line<'static>>) -> Vec<Line<'static>> {
+    let thinking_fg = Theme::global().thinking_fg;
     thinking
         .into_iter()
         .map(|line| {
@@ -1224,7 +2373,7 @@ fn style_thinking_lines(thinking: Vec<Line<'static>>) -> Vec<Line<'static>> {
                 .map(|span| {
                     let style = span
                         .style
-                        .fg(Color::DarkGray)
+                        .fg(thinking_fg)
                         .add_modifier(Modifier::DIM)
                         .add_modifier(Modifier::ITALIC);
                     Span::styled(span.content.to_string(), style)
@@ -1235,23 +2384,71 @@ fn style_thinking_lines(thinking: Vec<Line<'static>>) -> Vec<Line<'static>> {
         .collect()
 }
 
-/// Prepend a 2-space indent to each line so scrollback content aligns with
-/// the user's "› " prefix.
+/// Streamed assistant text on its way to scrollback, with path references
+/// highlighted when enabled.
+fn text_lines(lines: Vec<Line<'static>>) -> Vec<Line<'static>> {
+    if !TerminalConfig::global().highlight_paths {
+        return lines;
+    }
+    lines.into_iter().map(highlight_path_refs).collect()
+}
+
+/// Prepend the gutter indent to each line so scrollback content aligns
+/// with the user marker, whatever its width.
 fn indent_lines(lines: Vec<Line<'static>>) -> Vec<Line<'static>> {
+    let gutter = Theme::global().gutter();
     lines
         .into_iter()
         .map(|mut line| {
-            line.spans.insert(0, Span::raw("  ".to_string()));
+            line.spans.insert(0, Span::raw(gutter.clone()));
             line
         })
         .collect()
 }
 
+/// `tool`'s parameters in full, one indented block per parameter.
+fn tool_parameter_lines(tool: &ToolUseBlock) -> Vec<Line<'static>> {
+    let muted = Style::default().fg(Theme::global().muted_fg);
+    let mut lines = vec![
+        Line::from(""),
+        Line::styled(
+            format!("Parameters of {} ({})", tool.name, tool.id),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+    ];
+    if tool.parameters.is_empty() {
+        lines.push(Line::styled("  (none)", muted));
+    }
+    for (name, parameter) in &tool.parameters {
+        lines.push(Line::styled(format!("  {name}:"), muted));
+        let value = redact(&parameter.value);
+        if value.is_empty() {
+            lines.push(Line::styled("    (empty)", muted));
+        }
+        lines.extend(value.lines().map(|line| Line::from(format!("    {line}"))));
+    }
+    lines.push(Line::from(""));
+    lines
+}
+
+/// Whether `line` shows as an empty row: only whitespace (such as the
+/// indent) and no background fill.
+fn is_blank_line(line: &Line<'_>) -> bool {
+    line.style.bg.is_none() && line.spans.iter().all(|span| span.content.trim().is_empty())
+}
+
+fn is_blank_row(row: &HistoryRow) -> bool {
+    row.as_line().is_some_and(is_blank_line)
+}
+
 fn stream_kind_for_block(block: &MessageBlock) -> Option<StreamKind> {
     match block {
         MessageBlock::PlainText(_) => Some(StreamKind::Text),
         MessageBlock::Thinking(_) => Some(StreamKind::Thinking),
-        MessageBlock::ToolUse(_) | MessageBlock::UserText(_) => None,
+        MessageBlock::ToolUse(_)
+        | MessageBlock::UserText(_)
+        | MessageBlock::SystemText(_)
+        | MessageBlock::Image(_) => None,
     }
 }
 
@@ -1360,11 +2557,66 @@ fn merge_blocks_preserving_stream_slots(
     rebuilt
 }
 
+/// One step of a scripted agent turn for [`TerminalRenderer::simulate_turn`].
+#[cfg(test)]
+#[derive(Debug, Clone)]
+pub enum TurnEvent<'a> {
+    Thinking(&'a str),
+    Text(&'a str),
+    ToolStart {
+        name: &'a str,
+        id: &'a str,
+    },
+    ToolParameter {
+        id: &'a str,
+        name: &'a str,
+        value: &'a str,
+    },
+    ToolStatus {
+        id: &'a str,
+        status: ToolStatus,
+        output: Option<&'a str>,
+    },
+}
+
+#[cfg(test)]
+impl TerminalRenderer {
+    /// Play a whole agent turn through the same methods the UI event
+    /// handler calls: start a message, apply `script`, stop streaming and
+    /// finalize. Returns the scrollback lines the turn produced, at `width`.
+    pub fn simulate_turn(&mut self, width: u16, script: &[TurnEvent]) -> Vec<Line<'static>> {
+        self.prepare(width, u16::MAX);
+        self.start_new_message(0);
+        for event in script {
+            match *event {
+                TurnEvent::Thinking(delta) => self.queue_thinking_delta(delta.to_string()),
+                TurnEvent::Text(delta) => self.queue_text_delta(delta.to_string()),
+                TurnEvent::ToolStart { name, id } => {
+                    self.start_tool_use_block(name.to_string(), id.to_string())
+                }
+                TurnEvent::ToolParameter { id, name, value } => {
+                    self.add_or_update_tool_parameter(id, name.to_string(), value.to_string())
+                }
+                TurnEvent::ToolStatus { id, status, output } => {
+                    self.update_tool_status(id, status, None, output.map(str::to_string))
+                }
+            }
+            self.prepare(width, u16::MAX);
+        }
+        self.flush_streaming_pending();
+        self.hide_loading_spinner_if_active();
+        self.finalize_active_message();
+        self.prepare(width, u16::MAX);
+        self.drain_pending_history_lines()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::types::{PlanItem, PlanItemStatus, PlanState};
     use crate::ui::terminal::message::{LiveMessage, MessageBlock, PlainTextBlock};
+    use crate::ui::ui_events::ApiErrorInfo;
 
     /// Test harness that provides a TerminalRenderer and a buffer to render into.
     /// This replaces the old approach where TerminalRenderer owned a Terminal<TestBackend>.
@@ -1398,49 +2650,665 @@ mod tests {
                 viewport_area: area,
                 buffer: &mut self.buffer,
             };
-            self.renderer.paint(&mut frame, textarea);
-            &self.buffer
-        }
+            self.renderer.paint(&mut frame, textarea);
+            &self.buffer
+        }
+
+        /// Access the buffer after rendering
+        fn buffer(&self) -> &Buffer {
+            &self.buffer
+        }
+    }
+
+    impl std::ops::Deref for TestHarness {
+        type Target = TerminalRenderer;
+        fn deref(&self) -> &Self::Target {
+            &self.renderer
+        }
+    }
+
+    impl std::ops::DerefMut for TestHarness {
+        fn deref_mut(&mut self) -> &mut Self::Target {
+            &mut self.renderer
+        }
+    }
+
+    fn create_test_harness(width: u16, height: u16) -> TestHarness {
+        TestHarness::new(width, height)
+    }
+
+    fn create_default_test_harness() -> TestHarness {
+        create_test_harness(80, 20)
+    }
+
+    /// Text of one buffer row
+    fn row_text(buffer: &Buffer, y: u16) -> String {
+        (0..buffer.area.width)
+            .map(|x| buffer.cell((x, y)).unwrap().symbol().to_string())
+            .collect()
+    }
+
+    /// Helper to create a simple text message
+    fn create_text_message(content: &str) -> LiveMessage {
+        let mut message = LiveMessage::new();
+        let mut text_block = PlainTextBlock::new();
+        text_block.content = content.to_string();
+        message.add_block(MessageBlock::PlainText(text_block));
+        message.finalized = true;
+        message
+    }
+
+    mod scrollback_tests {
+        use super::*;
+
+        #[test]
+        fn test_screen_text_matches_painted_rows() {
+            let mut renderer = create_default_test_harness();
+            renderer.start_new_message(1);
+            renderer.start_tool_use_block("write_file".to_string(), "tool-1".to_string());
+            renderer.add_or_update_tool_parameter(
+                "tool-1",
+                "path".to_string(),
+                "notes.txt".to_string(),
+            );
+            renderer.set_info("Switched to model: test".to_string());
+            let mut textarea = TextArea::new();
+            textarea.insert_str("draft reply");
+
+            renderer.prepare(40, 20);
+            let text = renderer.screen_text(&textarea, 40);
+            let rows: Vec<&str> = text.lines().collect();
+            let agent_row = rows
+                .iter()
+                .position(|row| row.contains("notes.txt"))
+                .unwrap();
+            let info_row = rows
+                .iter()
+                .position(|row| row.contains("Switched to model: test"))
+                .unwrap();
+            let draft_row = rows
+                .iter()
+                .position(|row| row.contains("draft reply"))
+                .unwrap();
+            assert!(agent_row < info_row && info_row < draft_row);
+            assert!(rows.iter().all(|row| !row.ends_with(' ')));
+        }
+
+        #[test]
+        fn test_composer_line_numbers_skip_wrapped_rows() {
+            let mut renderer = create_default_test_harness();
+            let mut textarea = TextArea::new();
+            textarea.insert_str("first\nsecond line wraps onto another row");
+            assert!(renderer.toggle_line_numbers());
+
+            renderer.prepare(24, 20);
+            let text = renderer.screen_text(&textarea, 24);
+            let rows: Vec<&str> = text.lines().collect();
+            let first = rows.iter().position(|row| *row == "› 1 first").unwrap();
+            assert!(rows[first + 1].starts_with("  2 second"));
+            // Continuation rows are indented past the gutter without a number
+            assert!(rows[first + 2].starts_with("    ") && rows[first + 2].trim() != "");
+
+            assert!(!renderer.toggle_line_numbers());
+            let text = renderer.screen_text(&textarea, 24);
+            assert!(text.lines().any(|row| row == "› first"));
+        }
+
+        #[test]
+        fn test_diff_pane_beside_live_message() {
+            let mut renderer = create_default_test_harness();
+            renderer.start_new_message(1);
+            renderer.start_tool_use_block("edit".to_string(), "tool-1".to_string());
+            for (name, value) in [
+                ("path", "src/lib.rs"),
+                ("old_text", "fn old() {}"),
+                ("new_text", "fn new() {}"),
+            ] {
+                renderer.add_or_update_tool_parameter(
+                    "tool-1",
+                    name.to_string(),
+                    value.to_string(),
+                );
+            }
+            renderer.complete_tool_parameters("tool-1");
+            assert!(renderer.toggle_diff_pane(None));
+            let textarea = TextArea::new();
+
+            renderer.prepare(120, 30);
+            let text = renderer.screen_text(&textarea, 120);
+            let pane_row = text
+                .lines()
+                .find(|row| row.contains("± src/lib.rs (1 edit this turn)"))
+                .unwrap();
+            // The pane starts at the right of the transcript column
+            assert_eq!(pane_row.find('±'), Some(72));
+            assert!(text.contains("+fn new() {}"));
+
+            // Collapsed on narrow terminals
+            renderer.prepare(80, 30);
+            assert!(!renderer.screen_text(&textarea, 80).contains('±'));
+
+            assert!(!renderer.toggle_diff_pane(None));
+        }
+
+        /// Paint only the status entries into a tall buffer and return the
+        /// non-blank rows they produced, top to bottom.
+        fn rendered_status_rows(renderer: &TerminalRenderer, width: u16) -> Vec<String> {
+            let layout = renderer.layout_status(width, u16::MAX);
+            let area = Rect::new(0, 0, width, 100);
+            let mut buffer = Buffer::empty(area);
+            let mut frame = custom_terminal::Frame {
+                cursor_position: None,
+                viewport_area: area,
+                buffer: &mut buffer,
+            };
+            TerminalRenderer::render_status_entries(&mut frame, area, &layout.entries, &[]);
+
+            let rows: Vec<String> = (0..area.height)
+                .map(|y| {
+                    (0..width)
+                        .map(|x| buffer[(x, y)].symbol())
+                        .collect::<String>()
+                        .trim_end()
+                        .to_string()
+                })
+                .collect();
+            let used = rows
+                .iter()
+                .rposition(|row| !row.is_empty())
+                .map_or(0, |i| i + 1);
+            rows[..used].to_vec()
+        }
+
+        #[test]
+        fn test_status_height_matches_rendered_rows() {
+            let mut renderer = create_default_test_harness();
+            renderer.max_status_height = 20;
+            let long_line = "word ".repeat(30);
+            renderer.set_info(format!("First line\n{long_line}\n\nLast line"));
+
+            for width in [20, 33, 80] {
+                let rows = rendered_status_rows(&renderer, width);
+                assert_eq!(rows.last().map(String::as_str), Some("Last line"));
+                // The measured height also counts the gap above the composer
+                assert_eq!(
+                    renderer.measure_status_height(width),
+                    rows.len() as u16 + 1,
+                    "width {width}"
+                );
+            }
+
+            renderer.set_error("Request failed\nconnection reset".to_string());
+            let rows = rendered_status_rows(&renderer, 40);
+            assert_eq!(renderer.measure_status_height(40), rows.len() as u16 + 1);
+            assert!(rows[0].starts_with("Error: Request failed"));
+        }
+
+        #[test]
+        fn test_structured_error_shows_fields_and_retry_hint() {
+            let mut renderer = create_default_test_harness();
+            renderer.set_error_detailed(ErrorInfo {
+                title: "Service overloaded".to_string(),
+                message: "Please try again later".to_string(),
+                api: Some(ApiErrorInfo {
+                    code: "overloaded".to_string(),
+                    provider: Some("Anthropic".to_string()),
+                    retryable: true,
+                }),
+            });
+            assert!(renderer.has_retryable_error());
+
+            let rows = rendered_status_rows(&renderer, 60);
+            assert_eq!(
+                rows,
+                vec![
+                    "Service overloaded · Anthropic · overloaded",
+                    "Please try again later",
+                    "Retryable — press ⏎ to retry, Esc to dismiss",
+                ]
+            );
+
+            renderer.clear_error();
+            assert!(!renderer.has_retryable_error());
+        }
+
+        #[test]
+        fn test_status_height_is_capped_with_ellipsis() {
+            let mut renderer = create_default_test_harness();
+            renderer.max_status_height = 4;
+            let lines: Vec<String> = (1..=30).map(|i| format!("line {i}")).collect();
+            renderer.set_info(lines.join("\n"));
+
+            let rows = rendered_status_rows(&renderer, 40);
+            assert_eq!(rows, vec!["line 1", "line 2", "line 3", "…"]);
+            assert_eq!(renderer.measure_status_height(40), 5);
+
+            // Painting reserves the same rows and keeps the composer visible
+            let textarea = TextArea::new();
+            renderer.prepare(40, 20);
+            let text = renderer.screen_text(&textarea, 40);
+            assert!(text.contains("line 3\n…"));
+            assert!(!text.contains("line 4"));
+        }
+
+        #[test]
+        fn test_long_info_message_truncated_at_configured_height() {
+            let mut renderer = create_default_test_harness();
+            renderer.max_status_height = 12;
+            let lines: Vec<String> = (1..=50).map(|i| format!("line {i}")).collect();
+            renderer.set_info(lines.join("\n"));
+
+            let rows = rendered_status_rows(&renderer, 40);
+            assert_eq!(rows.len(), 12);
+            assert_eq!(rows[10], "line 11");
+            assert_eq!(rows[11], "…");
+            assert_eq!(renderer.measure_status_height(40), 13);
+
+            let textarea = TextArea::new();
+            renderer.prepare(40, 30);
+            let text = renderer.screen_text(&textarea, 40);
+            assert!(text.contains("line 11\n…"));
+            assert!(!text.contains("line 12"));
+        }
+
+        #[test]
+        fn test_status_precedence_error_warning_info() {
+            let mut renderer = create_default_test_harness();
+            renderer.set_info("Saved transcript".to_string());
+            renderer.set_warning("Context window nearly full".to_string());
+
+            let rows = rendered_status_rows(&renderer, 80).join("\n");
+            let warning_at = rows.find("⚠ Context window nearly full").unwrap();
+            let info_at = rows.find("Saved transcript").unwrap();
+            assert!(warning_at < info_at);
+
+            renderer.set_error("Request failed".to_string());
+            let rows = rendered_status_rows(&renderer, 80).join("\n");
+            assert!(rows.contains("Request failed"));
+            assert!(!rows.contains("Context window"));
+            assert!(!rows.contains("Saved transcript"));
+
+            renderer.clear_error();
+            renderer.clear_warning();
+            let rows = rendered_status_rows(&renderer, 80).join("\n");
+            assert!(!rows.contains("⚠"));
+            assert!(rows.contains("Saved transcript"));
+        }
+
+        #[test]
+        fn test_user_and_system_messages_get_role_markers() {
+            let theme = Theme::global();
+            let mut renderer = create_default_test_harness();
+            renderer.add_user_message("Fix the bug").unwrap();
+            renderer
+                .add_instruction_message("Session compacted")
+                .unwrap();
+
+            let transcript = &mut renderer.transcript;
+            let kind = HistoryKind::Full {
+                thinking: ThinkingVisibility::Collapsed,
+                tools: ToolDetail::Full,
+            };
+            let user_lines = transcript.committed_history_lines(0, 80, kind);
+            let user_line = user_lines
+                .iter()
+                .filter_map(HistoryRow::as_line)
+                .find(|line| line.to_string().contains("Fix the bug"))
+                .unwrap();
+            assert_eq!(user_line.spans[0].content, theme.user_marker);
+            assert_eq!(user_line.spans[0].style.fg, Some(theme.user_marker_fg));
+
+            let system_lines = transcript.committed_history_lines(1, 80, kind);
+            let system_line = system_lines
+                .iter()
+                .filter_map(HistoryRow::as_line)
+                .find(|line| line.to_string().contains("Session compacted"))
+                .unwrap();
+            assert_eq!(system_line.spans[0].content, theme.system_marker);
+            assert!(system_line.spans[1..]
+                .iter()
+                .all(|span| span.style.add_modifier.contains(Modifier::DIM)));
+        }
+
+        #[test]
+        fn test_multi_char_user_prompt_aligns_assistant_content() {
+            let config: TerminalConfig = serde_json::from_str(r#"{"user_prompt": ">>>"}"#).unwrap();
+            crate::ui::terminal::theme::set_test_theme(
+                Theme::default_theme().with_user_prompt(&config),
+            );
+            let mut transcript = TranscriptState::new();
+            let mut user = LiveMessage::new();
+            let mut user_block = PlainTextBlock::new();
+            user_block.content = "first line\nsecond line".to_string();
+            user.add_block(MessageBlock::UserText(user_block));
+            user.finalized = true;
+            transcript.push_committed_message(user);
+            transcript.push_committed_message(create_text_message("The answer"));
+            let kind = HistoryKind::Full {
+                thinking: ThinkingVisibility::Collapsed,
+                tools: ToolDetail::Full,
+            };
+
+            let rows: Vec<String> = (0..2)
+                .flat_map(|index| transcript.committed_history_lines(index, 40, kind))
+                .filter_map(|row| Some(row.as_line()?.to_string().trim_end().to_string()))
+                .collect();
+            assert!(rows.contains(&">>> first line".to_string()));
+            assert!(rows.contains(&"    second line".to_string()));
+            assert!(rows.contains(&"    The answer".to_string()));
+        }
+
+        #[test]
+        fn test_multi_char_user_prompt_aligns_live_tool_content() {
+            let config: TerminalConfig = serde_json::from_str(r#"{"user_prompt": ">>>"}"#).unwrap();
+            crate::ui::terminal::theme::set_test_theme(
+                Theme::default_theme().with_user_prompt(&config),
+            );
+            let mut renderer = create_default_test_harness();
+            renderer.start_new_message(1);
+            renderer.start_tool_use_block("custom_tool".to_string(), "tool-1".to_string());
+            renderer.add_or_update_tool_parameter(
+                "tool-1",
+                "query".to_string(),
+                "needle".to_string(),
+            );
+
+            renderer.prepare(40, 20);
+            let text = renderer.screen_text(&TextArea::new(), 40);
+            let rows: Vec<&str> = text.lines().collect();
+            assert!(rows.contains(&"●   custom_tool"), "{text}");
+            assert!(rows.contains(&"    query"), "{text}");
+            assert!(rows.contains(&"      needle"), "{text}");
+        }
+
+        #[test]
+        fn test_repeated_tool_calls_collapse_to_badged_header() {
+            let tool_message_with_output = |command: &str, status: ToolStatus, output: &str| {
+                let mut tool = ToolUseBlock::new("execute_command".to_string(), "id".to_string());
+                tool.add_or_update_parameter("command_line".to_string(), command.to_string());
+                tool.status = status;
+                tool.output = Some(output.to_string());
+                let mut message = LiveMessage::new();
+                message.add_block(MessageBlock::ToolUse(tool));
+                message.finalized = true;
+                message
+            };
+            let tool_message = |command: &str, status: ToolStatus| {
+                tool_message_with_output(command, status, "boom")
+            };
+
+            let mut transcript = TranscriptState::new();
+            transcript.push_committed_message(tool_message("make", ToolStatus::Success));
+            transcript.push_committed_message(tool_message("make", ToolStatus::Success));
+            transcript.push_committed_message(tool_message("make", ToolStatus::Success));
+            transcript.push_committed_message(tool_message("make", ToolStatus::Error));
+            transcript.push_committed_message(tool_message("make", ToolStatus::Success));
+            transcript.push_committed_message(create_text_message("Trying again"));
+            transcript.push_committed_message(tool_message("make", ToolStatus::Success));
+            // Same call, different result
+            transcript.push_committed_message(tool_message_with_output(
+                "make",
+                ToolStatus::Success,
+                "ok",
+            ));
+            transcript.push_committed_message(tool_message("make", ToolStatus::Pending));
+
+            let kind = HistoryKind::NonStreamedOnly {
+                tools: ToolDetail::Full,
+            };
+            let rendered = (0..9)
+                .map(|index| {
+                    transcript
+                        .committed_history_lines(index, 80, kind)
+                        .iter()
+                        .filter_map(HistoryRow::as_line)
+                        .map(|line| line.to_string())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            assert!(rendered[0].len() > 1);
+            assert_eq!(rendered[1], vec!["● execute_command ×2"]);
+            assert_eq!(rendered[2], vec!["● execute_command ×3"]);
+            // Failures and the call after them are shown in full
+            assert!(rendered[3].len() > 1);
+            assert!(rendered[4].len() > 1);
+            // Intervening text starts a new run
+            assert!(rendered[6].len() > 1);
+            // Other output or status isn't a repeat
+            assert!(rendered[7].len() > 1);
+            assert!(rendered[8].len() > 1);
+            assert!(rendered.iter().flatten().all(|line| !line.contains("×1")));
+        }
+
+        #[test]
+        fn test_tool_awaiting_approval_shows_prompt_until_resolved() {
+            let mut renderer = create_default_test_harness();
+            renderer.start_new_message(1);
+            renderer.start_tool_use_block("execute_command".to_string(), "tool-1".to_string());
+            let textarea = TextArea::new();
+
+            renderer.set_tool_awaiting_approval("tool-1", true);
+            renderer.prepare(60, 20);
+            let text = renderer.screen_text(&textarea, 60);
+            assert!(text.contains("⏎ to approve / Esc to skip"));
+
+            renderer.update_tool_status("tool-1", ToolStatus::Running, None, None);
+            renderer.prepare(60, 20);
+            let text = renderer.screen_text(&textarea, 60);
+            assert!(!text.contains("to approve"));
+        }
+
+        #[test]
+        fn test_tool_progress_bar_under_header_while_running() {
+            let mut renderer = create_default_test_harness();
+            renderer.start_new_message(1);
+            renderer.start_tool_use_block("execute_command".to_string(), "tool-1".to_string());
+            renderer.add_or_update_tool_parameter(
+                "tool-1",
+                "command_line".to_string(),
+                "fetch data".to_string(),
+            );
+            renderer.update_tool_status("tool-1", ToolStatus::Running, None, None);
+            let textarea = TextArea::new();
+
+            let block = |renderer: &TerminalRenderer| {
+                renderer.transcript.active_message().unwrap().blocks[0].clone()
+            };
+            let base_height = block(&renderer).calculate_height(60);
+            renderer.set_tool_progress("tool-1", 0.6);
+            assert_eq!(block(&renderer).calculate_height(60), base_height + 1);
+
+            renderer.prepare(60, 20);
+            let text = renderer.screen_text(&textarea, 60);
+            let rows: Vec<&str> = text.lines().collect();
+            let header = rows
+                .iter()
+                .position(|row| row.contains("execute_command"))
+                .unwrap();
+            assert_eq!(rows[header + 1].trim(), "████████████░░░░░░░░ 60%");
+            assert!(rows[header + 2].contains("fetch data"));
+
+            // A fraction that isn't a number drops back to no bar
+            renderer.set_tool_progress("tool-1", f32::NAN);
+            assert_eq!(block(&renderer).calculate_height(60), base_height);
+
+            renderer.update_tool_status("tool-1", ToolStatus::Success, None, None);
+            assert_eq!(block(&renderer).calculate_height(60), base_height);
+            renderer.prepare(60, 20);
+            assert!(!renderer.screen_text(&textarea, 60).contains('█'));
+        }
+
+        #[test]
+        fn test_toggle_tool_detail_compacts_live_and_new_history() {
+            let mut renderer = create_default_test_harness();
+            let textarea = TextArea::new();
+            let start_tool = |renderer: &mut TerminalRenderer, id: &str| {
+                renderer.start_tool_use_block("execute_command".to_string(), id.to_string());
+                renderer.add_or_update_tool_parameter(
+                    id,
+                    "command_line".to_string(),
+                    "cargo build".to_string(),
+                );
+                renderer.update_tool_status(
+                    id,
+                    ToolStatus::Success,
+                    None,
+                    Some("Compiling crate".to_string()),
+                );
+            };
 
-        /// Access the buffer after rendering
-        fn buffer(&self) -> &Buffer {
-            &self.buffer
+            renderer.start_new_message(1);
+            start_tool(&mut renderer, "tool-1");
+            renderer.prepare(60, 20);
+            assert!(renderer.screen_text(&textarea, 60).contains("cargo build"));
+
+            assert_eq!(renderer.toggle_tool_detail(), ToolDetail::Compact);
+            renderer.prepare(60, 20);
+            let text = renderer.screen_text(&textarea, 60);
+            assert!(text.contains("● execute_command"), "{text}");
+            assert!(!text.contains("cargo build"), "{text}");
+            assert!(!text.contains("Compiling"), "{text}");
+
+            renderer.start_new_message(2);
+            renderer.prepare(60, 20);
+            let history: Vec<String> = renderer
+                .drain_pending_history_lines()
+                .iter()
+                .map(|line| line.to_string())
+                .collect();
+            assert!(history.iter().any(|line| line == "● execute_command"));
+            assert!(!history.iter().any(|line| line.contains("cargo build")));
+
+            assert_eq!(renderer.toggle_tool_detail(), ToolDetail::Full);
+            start_tool(&mut renderer, "tool-2");
+            renderer.prepare(60, 20);
+            assert!(renderer.screen_text(&textarea, 60).contains("cargo build"));
         }
-    }
 
-    impl std::ops::Deref for TestHarness {
-        type Target = TerminalRenderer;
-        fn deref(&self) -> &Self::Target {
-            &self.renderer
+        #[test]
+        fn test_focused_tool_can_be_collapsed_until_message_ends() {
+            let mut renderer = create_default_test_harness();
+            let textarea = TextArea::new();
+            renderer.start_new_message(1);
+            assert!(!renderer.focus_tool(false));
+            for (id, command) in [("tool-1", "cargo build"), ("tool-2", "cargo test")] {
+                renderer.start_tool_use_block("execute_command".to_string(), id.to_string());
+                renderer.add_or_update_tool_parameter(
+                    id,
+                    "command_line".to_string(),
+                    command.to_string(),
+                );
+            }
+
+            // The first move lands on the newest tool; moves stop at the ends
+            assert!(renderer.focus_tool(false));
+            assert!(renderer.focus_tool(false));
+            assert!(renderer.focus_tool(false));
+            assert!(renderer.toggle_focused_tool());
+            renderer.prepare(60, 20);
+            let text = renderer.screen_text(&textarea, 60);
+            assert!(!text.contains("cargo build"), "{text}");
+            assert!(text.contains("cargo test"), "{text}");
+
+            assert!(renderer.focus_tool(true));
+            assert!(renderer.toggle_focused_tool());
+            renderer.prepare(60, 20);
+            let text = renderer.screen_text(&textarea, 60);
+            assert!(!text.contains("cargo test"), "{text}");
+
+            renderer.start_new_message(2);
+            assert!(!renderer.toggle_focused_tool());
         }
-    }
 
-    impl std::ops::DerefMut for TestHarness {
-        fn deref_mut(&mut self) -> &mut Self::Target {
-            &mut self.renderer
+        #[test]
+        fn test_inspect_prints_full_parameters_of_latest_tool() {
+            let mut renderer = create_default_test_harness();
+            assert!(!renderer.inspect_tool());
+
+            let long_command = format!("echo {}", "x".repeat(300));
+            renderer.start_new_message(1);
+            renderer.start_tool_use_block("read_files".to_string(), "tool-1".to_string());
+            renderer.start_tool_use_block("execute_command".to_string(), "tool-2".to_string());
+            renderer.add_or_update_tool_parameter(
+                "tool-2",
+                "command_line".to_string(),
+                long_command.clone(),
+            );
+            renderer.drain_pending_history_lines();
+
+            assert!(renderer.inspect_tool());
+            let lines: Vec<String> = renderer
+                .drain_pending_history_lines()
+                .iter()
+                .map(|line| line.to_string())
+                .collect();
+            assert_eq!(lines[1], "Parameters of execute_command (tool-2)");
+            assert_eq!(lines[2], "  command_line:");
+            assert_eq!(lines[3], format!("    {long_command}"));
+
+            // The highlighted tool wins over the latest one
+            renderer.focus_tool(false);
+            renderer.focus_tool(false);
+            assert!(renderer.inspect_tool());
+            let lines = renderer.drain_pending_history_lines();
+            assert_eq!(lines[1].to_string(), "Parameters of read_files (tool-1)");
+            assert_eq!(lines[2].to_string(), "  (none)");
         }
-    }
 
-    fn create_test_harness(width: u16, height: u16) -> TestHarness {
-        TestHarness::new(width, height)
-    }
+        #[test]
+        fn test_inspect_while_streaming_prints_after_streamed_text() {
+            let mut renderer = create_default_test_harness();
+            renderer.start_new_message(1);
+            renderer.start_tool_use_block("read_files".to_string(), "tool-1".to_string());
+            renderer.start_new_message(2);
+            renderer.queue_text_delta("Reading the files\n".to_string());
+            renderer.drain_pending_history_lines();
 
-    fn create_default_test_harness() -> TestHarness {
-        create_test_harness(80, 20)
-    }
+            assert!(renderer.inspect_tool());
+            assert!(renderer.drain_pending_history_lines().is_empty());
 
-    /// Helper to create a simple text message
-    fn create_text_message(content: &str) -> LiveMessage {
-        let mut message = LiveMessage::new();
-        let mut text_block = PlainTextBlock::new();
-        text_block.content = content.to_string();
-        message.add_block(MessageBlock::PlainText(text_block));
-        message.finalized = true;
-        message
-    }
+            renderer.flush_streaming_pending();
+            let lines: Vec<String> = renderer
+                .drain_pending_history_lines()
+                .iter()
+                .map(|line| line.to_string())
+                .collect();
+            let text = lines
+                .iter()
+                .position(|line| line.contains("Reading the files"));
+            let parameters = lines
+                .iter()
+                .position(|line| line == "Parameters of read_files (tool-1)");
+            assert!(text.unwrap() < parameters.unwrap(), "{lines:?}");
+        }
 
-    mod scrollback_tests {
-        use super::*;
+        #[test]
+        fn test_render_progress_bar() {
+            use crate::ui::terminal::tool_widget::render_progress_bar;
+            assert_eq!(render_progress_bar(0.0, 4), "░░░░ 0%");
+            assert_eq!(render_progress_bar(0.5, 4), "██░░ 50%");
+            assert_eq!(render_progress_bar(1.7, 4), "████ 100%");
+            assert_eq!(render_progress_bar(0.6, 0), " 60%");
+        }
+
+        #[test]
+        fn test_backend_disconnect_error_persists_and_stops_animation() {
+            let mut renderer = create_default_test_harness();
+            renderer.start_new_message(1);
+            assert!(renderer.needs_animation_timer());
+
+            renderer.mark_backend_disconnected();
+            assert!(!renderer.needs_animation_timer());
+            renderer.clear_error();
+            assert_eq!(
+                renderer
+                    .current_error
+                    .as_ref()
+                    .map(|error| error.message.as_str()),
+                Some(BACKEND_DISCONNECTED_MESSAGE)
+            );
+        }
 
         #[test]
         fn test_basic_renderer_creation_and_state() {
@@ -1539,6 +3407,54 @@ mod tests {
             }
         }
 
+        #[test]
+        fn test_steering_message_shown_before_queue() {
+            let mut renderer = create_default_test_harness();
+            renderer.set_pending_user_message(Some("use the v2 API".to_string()));
+
+            renderer.set_steering_message(Some("use the v2 API".to_string()));
+            assert_eq!(
+                renderer.pending_status_text().as_deref(),
+                Some("steering: \"use the v2 API\"")
+            );
+
+            renderer.set_queued_messages(vec!["then run tests".to_string()]);
+            assert_eq!(
+                renderer.pending_status_text().as_deref(),
+                Some("steering: \"use the v2 API\"\nqueued: \"then run tests\"")
+            );
+        }
+
+        #[test]
+        fn test_queued_messages_replace_pending_slot() {
+            let mut renderer = create_default_test_harness();
+            let textarea = TextArea::new();
+            renderer.set_pending_user_message(Some("fix tests\nrun clippy".to_string()));
+
+            renderer.set_queued_messages(vec!["fix tests".to_string()]);
+            assert_eq!(
+                renderer.pending_status_text().as_deref(),
+                Some("queued: \"fix tests\"")
+            );
+
+            renderer.set_queued_messages(vec![
+                "fix tests".to_string(),
+                "run clippy\nand fmt".to_string(),
+            ]);
+            renderer.render(&textarea);
+            let screen: Vec<String> = (0..20).map(|y| row_text(renderer.buffer(), y)).collect();
+            assert!(screen.iter().any(|row| row.contains("queued (2):")));
+            assert!(screen.iter().any(|row| row.contains("\"run clippy…\"")));
+            assert!(!screen.iter().any(|row| row.contains("and fmt")));
+
+            // Once consumed, the plain pending message shows again
+            renderer.set_queued_messages(Vec::new());
+            assert_eq!(
+                renderer.pending_status_text().as_deref(),
+                Some("fix tests\nrun clippy")
+            );
+        }
+
         #[test]
         fn test_pending_message_rendering() {
             let mut renderer = create_default_test_harness();
@@ -1653,7 +3569,7 @@ mod tests {
                     let cell = buffer.cell((x, y)).unwrap();
                     line_text.push_str(cell.symbol());
                 }
-                if line_text.contains("Plan: Update documentation (2 of 4)") {
+                if line_text.contains("Plan: Update documentation (2/4) · ✓1 ~0 ·3") {
                     found_summary = true;
                     break;
                 }
@@ -1662,6 +3578,131 @@ mod tests {
             assert!(found_summary, "Collapsed plan summary should be rendered");
         }
 
+        #[test]
+        fn test_newly_completed_plan_item_is_highlighted_briefly() {
+            let mut renderer = create_default_test_harness();
+            let plan_with = |first: PlanItemStatus| PlanState {
+                entries: vec![
+                    PlanItem {
+                        content: "Write parser".to_string(),
+                        status: first,
+                        ..Default::default()
+                    },
+                    PlanItem {
+                        content: "Add tests".to_string(),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            };
+
+            renderer.set_plan_state(Some(plan_with(PlanItemStatus::InProgress)));
+            assert!(!renderer.needs_animation_timer());
+
+            renderer.set_plan_state(Some(plan_with(PlanItemStatus::Completed)));
+            assert_eq!(
+                renderer.build_plan_text(80).as_deref(),
+                Some("Plan: ✓ Write parser")
+            );
+            assert!(renderer.needs_animation_timer());
+
+            let textarea = TextArea::new();
+            renderer.render(&textarea);
+            let buffer = renderer.buffer();
+            let highlighted = (0..buffer.area.height).any(|y| {
+                let row: String = (0..buffer.area.width)
+                    .map(|x| buffer.cell((x, y)).unwrap().symbol())
+                    .collect();
+                let marker = row
+                    .find("Write parser")
+                    .map(|byte| row[..byte].width() as u16);
+                marker.is_some_and(|x| {
+                    let cell = buffer.cell((x, y)).unwrap();
+                    cell.modifier.contains(Modifier::CROSSED_OUT)
+                        && cell.fg == Theme::global().status_success
+                })
+            });
+            assert!(highlighted, "completed item should render struck through");
+
+            // Once the highlight expires the normal summary returns
+            renderer.plan_highlights[0].1 -= PLAN_COMPLETION_HIGHLIGHT;
+            assert!(!renderer.needs_animation_timer());
+            assert_eq!(
+                renderer.build_plan_text(80).as_deref(),
+                Some("Plan: Add tests (2/2) · ✓1 ~0 ·1")
+            );
+
+            // Re-sending an already completed item doesn't highlight it again
+            renderer.set_plan_state(Some(plan_with(PlanItemStatus::Completed)));
+            assert!(renderer.active_plan_highlights().is_empty());
+        }
+
+        #[test]
+        fn test_plan_highlight_matches_the_item_not_its_text() {
+            let mut renderer = create_default_test_harness();
+            renderer.set_plan_expanded(true);
+            let plan_with = |first: PlanItemStatus| PlanState {
+                entries: vec![
+                    PlanItem {
+                        content: "Write parser".to_string(),
+                        status: first,
+                        ..Default::default()
+                    },
+                    PlanItem {
+                        content: "Write parser tests".to_string(),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            };
+            renderer.set_plan_state(Some(plan_with(PlanItemStatus::InProgress)));
+            renderer.set_plan_state(Some(plan_with(PlanItemStatus::Completed)));
+
+            let textarea = TextArea::new();
+            renderer.render(&textarea);
+            let buffer = renderer.buffer();
+            let struck_through = |item: &str| {
+                (0..buffer.area.height).any(|y| {
+                    let row: String = (0..buffer.area.width)
+                        .map(|x| buffer.cell((x, y)).unwrap().symbol())
+                        .collect();
+                    row.trim_end().ends_with(item)
+                        && (0..buffer.area.width).any(|x| {
+                            buffer
+                                .cell((x, y))
+                                .unwrap()
+                                .modifier
+                                .contains(Modifier::CROSSED_OUT)
+                        })
+                })
+            };
+            assert!(struck_through("[x] Write parser"));
+            assert!(!struck_through("[ ] Write parser tests"));
+        }
+
+        #[test]
+        fn test_plan_collapsed_summary_truncates_to_width() {
+            let mut renderer = create_default_test_harness();
+            renderer.set_plan_state(Some(PlanState {
+                entries: vec![
+                    PlanItem {
+                        content: "Rewrite the configuration loader".to_string(),
+                        status: PlanItemStatus::InProgress,
+                        ..Default::default()
+                    },
+                    PlanItem {
+                        content: "Ship it".to_string(),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            }));
+
+            let text = renderer.build_plan_text(40).unwrap();
+            assert_eq!(text, "Plan: Rewrite the conf… (1/2) · ✓0 ~1 ·1");
+            assert!(text.width() <= 40);
+        }
+
         #[test]
         fn test_plan_expanded_rendering_limits_entries() {
             let mut renderer = create_default_test_harness();
@@ -1777,6 +3818,81 @@ mod tests {
             );
         }
 
+        #[test]
+        fn test_plan_scroll_moves_window_and_resets_on_change() {
+            let mut renderer = create_default_test_harness();
+            renderer.set_plan_expanded(true);
+
+            let plan_with = |completed: usize| PlanState {
+                entries: (0..7)
+                    .map(|i| PlanItem {
+                        content: format!("Step {i}"),
+                        status: if i < completed {
+                            PlanItemStatus::Completed
+                        } else {
+                            PlanItemStatus::Pending
+                        },
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            };
+            let first_visible = |renderer: &TerminalRenderer| {
+                let text = renderer.build_plan_text(80).unwrap();
+                text.lines().nth(1).unwrap().to_string()
+            };
+
+            renderer.set_plan_state(Some(plan_with(2)));
+            // Defaults to the first entry after the completed ones
+            assert_eq!(first_visible(&renderer), "[ ] Step 2");
+
+            assert!(renderer.scroll_plan(-1));
+            assert_eq!(first_visible(&renderer), "[x] Step 1");
+            renderer.scroll_plan(-5);
+            assert_eq!(first_visible(&renderer), "[x] Step 0");
+            // Clamped so the window stays full
+            renderer.scroll_plan(10);
+            assert_eq!(first_visible(&renderer), "[ ] Step 3");
+            assert_eq!(renderer.build_plan_text(80).unwrap().lines().count(), 5);
+
+            // Re-sending the same plan keeps the position, a new plan resets it
+            renderer.set_plan_state(Some(plan_with(2)));
+            assert_eq!(first_visible(&renderer), "[ ] Step 3");
+            renderer.set_plan_state(Some(plan_with(1)));
+            assert_eq!(first_visible(&renderer), "[ ] Step 1");
+
+            renderer.set_plan_expanded(false);
+            assert!(!renderer.scroll_plan(1));
+        }
+
+        #[test]
+        fn test_composer_dims_when_unfocused() {
+            let mut renderer = create_default_test_harness();
+            let mut textarea = TextArea::new();
+            textarea.insert_str("draft");
+
+            let prompt_modifier = |renderer: &mut TestHarness| {
+                renderer.render(&textarea);
+                let buffer = renderer.buffer();
+                let area = buffer.area;
+                (0..area.height)
+                    .flat_map(|y| (0..area.width).map(move |x| (x, y)))
+                    .map(|pos| buffer.cell(pos).unwrap())
+                    .find(|cell| cell.symbol() == "›")
+                    .map(|cell| cell.modifier)
+                    .expect("composer prompt should be rendered")
+            };
+
+            let focused = prompt_modifier(&mut renderer);
+            assert!(focused.contains(Modifier::BOLD));
+            assert!(!focused.contains(Modifier::DIM));
+
+            renderer.set_focused(false);
+            let unfocused = prompt_modifier(&mut renderer);
+            assert!(unfocused.contains(Modifier::DIM));
+            assert!(!unfocused.contains(Modifier::BOLD));
+        }
+
         #[test]
         fn test_error_message_rendering() {
             let mut renderer = create_default_test_harness();
@@ -1884,6 +4000,76 @@ mod tests {
             assert_eq!(renderer.deferred_history_line_count(), 0);
         }
 
+        #[test]
+        fn test_not_following_holds_history_until_resumed() {
+            let mut renderer = create_default_test_harness();
+            let textarea = TextArea::new();
+
+            renderer.set_following(false);
+            renderer.start_new_message(1);
+            renderer.queue_text_delta("held line\n".to_string());
+            renderer.render(&textarea);
+            renderer.start_new_message(2);
+            renderer.render(&textarea);
+
+            let held = renderer.deferred_history_line_count();
+            assert!(held > 0, "History should be held while not following");
+            let last_row: String = (0..80)
+                .map(|x| {
+                    renderer
+                        .buffer()
+                        .cell((x, 19))
+                        .unwrap()
+                        .symbol()
+                        .to_string()
+                })
+                .collect();
+            assert!(
+                last_row.contains(&format!("⇣ {held} new lines (End)")),
+                "Status bar should count held lines, got: {last_row:?}"
+            );
+
+            renderer.set_following(true);
+            renderer.render(&textarea);
+            assert_eq!(renderer.deferred_history_line_count(), 0);
+        }
+
+        #[test]
+        fn test_held_history_spills_oldest_lines_past_cap() {
+            let mut renderer = create_default_test_harness();
+            renderer.set_following(false);
+
+            let lines = (0..MAX_HELD_HISTORY_LINES + 3)
+                .map(|i| Line::from(format!("line {i}")))
+                .collect();
+            renderer.insert_or_defer_history_lines(lines);
+
+            assert_eq!(
+                renderer.deferred_history_line_count(),
+                MAX_HELD_HISTORY_LINES
+            );
+            let spilled = renderer.drain_pending_history_lines();
+            let spilled: Vec<String> = spilled.iter().map(|line| line.to_string()).collect();
+            assert_eq!(spilled, ["line 0", "line 1", "line 2"]);
+        }
+
+        #[test]
+        fn test_held_image_rows_reach_scrollback_whole() {
+            let mut renderer = create_default_test_harness();
+            let image = HistoryRow::Image(crate::ui::terminal::inline_image::ImageRow {
+                rows: 4,
+                placeholder: "[image 1x1 (image/png)]".to_string(),
+                sequence: "\x1b_Ga=T;abc\x1b\\".to_string(),
+            });
+            renderer.set_following(false);
+            renderer.insert_or_defer_history_rows(vec![image.clone()]);
+            assert!(renderer.drain_pending_history_rows().is_empty());
+
+            renderer.set_following(true);
+            renderer.prepare(80, 24);
+            assert_eq!(renderer.drain_pending_history_rows(), vec![image]);
+        }
+
         #[test]
         fn test_overlay_deferral_survives_resize_until_close() {
             let mut renderer = create_default_test_harness();
@@ -1963,6 +4149,69 @@ mod tests {
             );
         }
 
+        #[test]
+        fn test_updates_for_unknown_tool_create_placeholder() {
+            let mut renderer = create_default_test_harness();
+
+            renderer.start_new_message(1);
+            renderer.append_tool_output("tool-1", "early output\n");
+            renderer.update_tool_status("tool-1", ToolStatus::Running, None, None);
+            renderer.append_tool_output("tool-1", "more output\n");
+            renderer.add_or_update_tool_parameter(
+                "tool-1",
+                "command_line".to_string(),
+                "ls".to_string(),
+            );
+
+            let message = renderer.transcript.active_message().unwrap();
+            assert_eq!(message.blocks.len(), 1);
+            let MessageBlock::ToolUse(tool) = &message.blocks[0] else {
+                panic!("expected a tool block");
+            };
+            assert!(tool.is_placeholder());
+            assert_eq!(tool.output.as_deref(), Some("more output\n"));
+
+            renderer.start_tool_use_block("execute_command".to_string(), "tool-1".to_string());
+            let message = renderer.transcript.active_message().unwrap();
+            assert_eq!(message.blocks.len(), 1);
+            let MessageBlock::ToolUse(tool) = &message.blocks[0] else {
+                panic!("expected a tool block");
+            };
+            assert_eq!(tool.name, "execute_command");
+            assert_eq!(tool.status, ToolStatus::Running);
+            assert_eq!(tool.parameters["command_line"].value, "ls");
+        }
+
+        #[test]
+        fn test_tool_updates_without_active_message_are_ignored() {
+            let mut renderer = create_default_test_harness();
+
+            renderer.update_tool_status("stale", ToolStatus::Success, None, None);
+            renderer.append_tool_output("stale", "late output");
+            assert!(renderer.transcript.active_message().is_none());
+            assert!(renderer.transcript.committed_messages().is_empty());
+
+            // Late updates for a finalized message's tool don't leak into
+            // the next message
+            renderer.start_new_message(1);
+            renderer.start_tool_use_block("read_files".to_string(), "tool-1".to_string());
+            renderer.start_new_message(2);
+            renderer.update_tool_status("tool-1", ToolStatus::Success, None, None);
+            renderer.append_tool_output("tool-1", "late output");
+            assert!(renderer
+                .transcript
+                .active_message()
+                .unwrap()
+                .blocks
+                .is_empty());
+            let MessageBlock::ToolUse(tool) =
+                &renderer.transcript.committed_messages()[0].blocks[0]
+            else {
+                panic!("expected a tool block");
+            };
+            assert_eq!(tool.status, ToolStatus::Pending);
+        }
+
         #[test]
         fn test_pre_start_delta_recovers_streaming_state() {
             let mut renderer = create_default_test_harness();
@@ -1992,6 +4241,26 @@ mod tests {
                 SpinnerState::Loading { .. }
             ));
 
+            assert_eq!(
+                renderer.spinner_state.get_status_text().as_deref(),
+                Some("Thinking…")
+            );
+
+            // A tool phase relabels the spinner and clears once it ends
+            renderer.set_spinner_phase(Some("Running execute_command".to_string()));
+            assert_eq!(
+                renderer.spinner_state.get_status_text().as_deref(),
+                Some("Running execute_command…")
+            );
+            renderer.set_spinner_phase(None);
+            assert!(matches!(renderer.spinner_state, SpinnerState::Hidden));
+            renderer.start_new_message(2);
+            renderer.set_spinner_phase(None);
+            assert!(matches!(
+                renderer.spinner_state,
+                SpinnerState::Loading { label: None, .. }
+            ));
+
             // Hide spinner
             renderer.hide_spinner();
             assert!(matches!(renderer.spinner_state, SpinnerState::Hidden));
@@ -2001,12 +4270,60 @@ mod tests {
             assert!(matches!(
                 renderer.spinner_state,
                 SpinnerState::RateLimit {
-                    seconds_remaining: 30,
+                    initial_seconds: 30,
                     ..
                 }
             ));
         }
 
+        #[test]
+        fn test_rate_limit_countdown_ticks_down_and_expires() {
+            let mut renderer = create_default_test_harness();
+
+            renderer.spinner_state = SpinnerState::RateLimit {
+                start_time: Instant::now() - std::time::Duration::from_secs(12),
+                initial_seconds: 30,
+            };
+            assert_eq!(
+                renderer.spinner_state.get_status_text().as_deref(),
+                Some("Rate limited (18s)")
+            );
+            assert!(!renderer.expire_rate_limit_spinner());
+
+            renderer.spinner_state = SpinnerState::RateLimit {
+                start_time: Instant::now() - std::time::Duration::from_secs(45),
+                initial_seconds: 30,
+            };
+            assert_eq!(
+                renderer.spinner_state.get_status_text().as_deref(),
+                Some("Rate limited (0s)")
+            );
+            assert!(renderer.expire_rate_limit_spinner());
+            assert!(matches!(renderer.spinner_state, SpinnerState::Hidden));
+        }
+
+        #[test]
+        fn test_retry_spinner_is_distinct_from_rate_limit() {
+            let mut renderer = create_default_test_harness();
+
+            renderer.show_retry_spinner(2, 5);
+            assert_eq!(
+                renderer.spinner_state.get_status_text().as_deref(),
+                Some("Retrying (2/5)…")
+            );
+            let (_, color) = renderer
+                .spinner_state
+                .get_spinner_frame(SpinnerStyle::Ascii)
+                .unwrap();
+            assert_eq!(color, Theme::global().retry_fg);
+            // Not a countdown, so the rate-limit timer leaves it alone
+            assert!(!renderer.expire_rate_limit_spinner());
+
+            // The retried request's first content replaces the spinner
+            renderer.hide_loading_spinner_if_active();
+            assert!(matches!(renderer.spinner_state, SpinnerState::Hidden));
+        }
+
         #[test]
         fn test_clear_all_messages() {
             let mut renderer = create_default_test_harness();
@@ -2014,7 +4331,7 @@ mod tests {
             // Add some finalized messages
             for i in 0..3 {
                 let message = create_text_message(&format!("Message {i}"));
-                renderer.transcript.committed_messages_mut().push(message);
+                renderer.transcript.push_committed_message(message);
             }
 
             // Add live message
@@ -2067,6 +4384,35 @@ mod tests {
         use super::*;
         use crate::ui::terminal::message::{ThinkingBlock, ToolUseBlock};
 
+        #[test]
+        fn test_markdown_height_cache_hits_and_busts() {
+            let mut cache = MarkdownHeightCache::default();
+            let measured = std::cell::Cell::new(0);
+            let measure = |content: &str, width: u16, max_height: u16| {
+                measured.set(measured.get() + 1);
+                TerminalRenderer::measure_markdown_height(content, width, max_height)
+            };
+
+            let first = cache.get_or_measure("Plan:\n- one\n- two", 40, 20, measure);
+            let second = cache.get_or_measure("Plan:\n- one\n- two", 40, 20, measure);
+            assert_eq!(first, second);
+            assert_eq!(measured.get(), 1, "identical content should hit the cache");
+
+            cache.get_or_measure("Plan:\n- one\n- two\n- three", 40, 20, measure);
+            assert_eq!(measured.get(), 2, "changed content should be re-measured");
+
+            cache.get_or_measure("Plan:\n- one\n- two", 10, 20, measure);
+            assert_eq!(measured.get(), 3, "changed width should be re-measured");
+
+            let capped = cache.get_or_measure("Plan:\n- one\n- two", 40, 2, measure);
+            assert_eq!(
+                measured.get(),
+                4,
+                "changed max height should be re-measured"
+            );
+            assert_eq!(capped, 2);
+        }
+
         #[test]
         fn test_plain_text_height_calculation() {
             let width = 80;
@@ -2276,6 +4622,115 @@ mod tests {
     mod integration_tests {
         use super::*;
 
+        #[test]
+        fn test_repeated_paints_reuse_scratch_buffer() {
+            let mut renderer = create_default_test_harness();
+            let textarea = TextArea::new();
+            renderer.start_new_message(1);
+            renderer.queue_text_delta("Streaming line\n".to_string());
+            renderer.render(&textarea);
+
+            let area = renderer.scratch.area;
+            let capacity = renderer.scratch.content.capacity();
+            // Available content rows plus the fixed headroom
+            let input_height = renderer.calculate_input_height(&textarea, 80);
+            assert_eq!(area.height, 20 - input_height - STATUS_BAR_HEIGHT + 200);
+
+            for i in 0..10 {
+                renderer.queue_text_delta(format!("more text {i}\n"));
+                renderer.render(&textarea);
+                assert_eq!(renderer.scratch.area, area);
+                assert_eq!(renderer.scratch.content.capacity(), capacity);
+            }
+
+            // Content rendered into the scratch buffer on an earlier frame
+            // must not leak into later ones
+            renderer.clear_all_messages();
+            renderer.render(&textarea);
+            let leftover = renderer
+                .scratch
+                .content
+                .iter()
+                .any(|cell| cell.symbol() != " ");
+            assert!(!leftover, "scratch buffer should be cleared between frames");
+        }
+
+        #[test]
+        fn test_status_bar_renders_on_last_row() {
+            let mut renderer = create_default_test_harness();
+            let textarea = TextArea::new();
+
+            renderer.set_status_bar(StatusBarInfo {
+                model: Some("test-model".to_string()),
+                session_name: Some("Refactor session".to_string()),
+                sandbox_policy: Some(sandbox::SandboxPolicy::ReadOnly),
+                tool_syntax: Some(crate::types::ToolSyntax::Native),
+                playback: Some(llm::recording::PlaybackStatus {
+                    paused: true,
+                    speed: 0.5,
+                }),
+                held_lines: None,
+                usage: None,
+            });
+            renderer.render(&textarea);
+            let buffer = renderer.buffer();
+
+            let last_row: String = (0..80)
+                .map(|x| buffer.cell((x, 19)).unwrap().symbol().to_string())
+                .collect();
+            assert!(
+                last_row
+                    .contains("test-model · Refactor session · read-only · native · ⏸ paused 0.5x"),
+                "Status bar should occupy the last row, got: {last_row:?}"
+            );
+
+            let footer_row: String = (0..80)
+                .map(|x| buffer.cell((x, 18)).unwrap().symbol().to_string())
+                .collect();
+            assert!(
+                footer_row.contains("/help"),
+                "Composer footer hints should sit directly above the status bar"
+            );
+
+            // The viewport reserves a row for the status bar
+            let input_height = renderer.calculate_input_height(&textarea, 80);
+            assert_eq!(
+                renderer.desired_viewport_height(&textarea, 80),
+                1 + input_height + STATUS_BAR_HEIGHT
+            );
+        }
+
+        #[test]
+        fn test_too_small_terminal_shows_notice() {
+            let mut renderer = create_test_harness(10, 3);
+            let textarea = TextArea::new();
+            renderer.start_new_message(1);
+            renderer.queue_text_delta("Some streamed text".to_string());
+            renderer.set_error("Something failed".to_string());
+
+            let buffer = renderer.render(&textarea).clone();
+            let middle = row_text(&buffer, 1);
+            assert!(
+                middle.contains("Terminal"),
+                "Expected the too-small notice, got: {middle:?}"
+            );
+            assert_eq!(row_text(&buffer, 0).trim(), "");
+            assert_eq!(row_text(&buffer, 2).trim(), "");
+            assert_eq!(renderer.desired_viewport_height(&textarea, 10), 1);
+        }
+
+        #[test]
+        fn test_one_by_one_terminal_does_not_panic() {
+            let mut renderer = create_test_harness(1, 1);
+            let textarea = TextArea::new();
+            renderer.set_info("Info".to_string());
+
+            let buffer = renderer.render(&textarea).clone();
+            assert_eq!(buffer.area, Rect::new(0, 0, 1, 1));
+            assert_eq!(renderer.desired_viewport_height(&textarea, 1), 1);
+            assert_eq!(TerminalRenderer::measure_markdown_height("Info", 1, 1), 1);
+        }
+
         #[test]
         fn test_complete_message_workflow_rendering() {
             let mut renderer = create_default_test_harness();
@@ -2399,6 +4854,46 @@ mod tests {
             assert!(renderer.transcript.committed_messages()[0].finalized);
         }
 
+        #[test]
+        fn test_simulated_turn_returns_its_scrollback() {
+            let mut renderer = create_test_harness(80, 20);
+            let lines = renderer.simulate_turn(
+                80,
+                &[
+                    TurnEvent::Text("Running the tests"),
+                    TurnEvent::Text(" now.\n"),
+                    TurnEvent::ToolStart {
+                        name: "execute_command",
+                        id: "tool-1",
+                    },
+                    TurnEvent::ToolParameter {
+                        id: "tool-1",
+                        name: "command_line",
+                        value: "cargo test",
+                    },
+                    TurnEvent::ToolStatus {
+                        id: "tool-1",
+                        status: ToolStatus::Success,
+                        output: Some("test result: ok"),
+                    },
+                ],
+            );
+
+            let text: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+            let answer = text
+                .iter()
+                .position(|row| row.contains("Running the tests now."))
+                .unwrap();
+            let command = text
+                .iter()
+                .position(|row| row.contains("cargo test"))
+                .unwrap();
+            assert!(answer < command);
+            assert_eq!(renderer.transcript.committed_messages().len(), 1);
+            assert!(renderer.transcript.active_message().is_none());
+            assert!(renderer.drain_pending_history_lines().is_empty());
+        }
+
         #[test]
         fn test_finalized_messages_produce_pending_history_lines() {
             let mut renderer = create_test_harness(80, 10);
@@ -2407,7 +4902,7 @@ mod tests {
             // Add finalized messages
             for i in 0..3 {
                 let message = create_text_message(&format!("Message {i}"));
-                renderer.transcript.committed_messages_mut().push(message);
+                renderer.transcript.push_committed_message(message);
             }
 
             // Render to flush finalized messages
@@ -2443,6 +4938,71 @@ mod tests {
             );
         }
 
+        #[test]
+        fn test_turn_separator_between_assistant_turn_and_user_message() {
+            let textarea = TextArea::new();
+            let history = |separator: TurnSeparator| {
+                let mut renderer = create_test_harness(20, 10);
+                renderer.turn_separator = separator;
+                let mut tool = ToolUseBlock::new("read_files".to_string(), "id".to_string());
+                tool.add_or_update_parameter("project".to_string(), "api".to_string());
+                let mut message = create_text_message("Answer");
+                message.add_block(MessageBlock::ToolUse(tool));
+                renderer.transcript.push_committed_message(message);
+                renderer.add_user_message("Next").unwrap();
+                renderer.render(&textarea);
+                let lines: Vec<String> = renderer
+                    .drain_pending_history_lines()
+                    .iter()
+                    .map(|line| line.to_string())
+                    .collect();
+                // Rows between the tool block and the user message's padding
+                let user = lines.iter().position(|l| l.contains("› Next")).unwrap();
+                let padding = user - 1;
+                let gap_start = lines[..padding]
+                    .iter()
+                    .rposition(|l| !l.trim().is_empty() && !l.starts_with('─'))
+                    .unwrap()
+                    + 1;
+                lines[gap_start..padding].to_vec()
+            };
+
+            assert_eq!(history(TurnSeparator::BlankLine), vec![""]);
+            assert_eq!(history(TurnSeparator::Rule), vec!["", &"─".repeat(20)]);
+            assert!(history(TurnSeparator::None).is_empty());
+        }
+
+        #[test]
+        fn test_project_switch_adds_breadcrumb() {
+            let mut renderer = create_test_harness(80, 10);
+            let textarea = TextArea::new();
+            let tool_message = |project: &str| {
+                let mut tool = ToolUseBlock::new("read_files".to_string(), "id".to_string());
+                tool.add_or_update_parameter("project".to_string(), project.to_string());
+                let mut message = LiveMessage::new();
+                message.add_block(MessageBlock::ToolUse(tool));
+                message.finalized = true;
+                message
+            };
+            for project in ["api", "api", "web", "api"] {
+                renderer
+                    .transcript
+                    .push_committed_message(tool_message(project));
+            }
+
+            renderer.render(&textarea);
+            let breadcrumbs: Vec<String> = renderer
+                .drain_pending_history_lines()
+                .iter()
+                .map(|line| line.to_string())
+                .filter(|line| line.starts_with('—'))
+                .collect();
+            assert_eq!(
+                breadcrumbs,
+                vec!["— now in project: web —", "— now in project: api —"]
+            );
+        }
+
         #[test]
         fn test_live_message_not_in_pending_history() {
             let mut renderer = create_test_harness(80, 10);
@@ -2482,7 +5042,7 @@ mod tests {
             // Add some finalized messages
             for i in 0..2 {
                 let message = create_text_message(&format!("Finalized message {i}"));
-                renderer.transcript.committed_messages_mut().push(message);
+                renderer.transcript.push_committed_message(message);
             }
 
             // Start a live message
@@ -2677,8 +5237,8 @@ mod tests {
             let buffer = renderer.buffer();
 
             let mut has_status_content = false;
-            for y in 0..17 {
-                // Check status area (excluding input border)
+            for y in 0..16 {
+                // Check status area (excluding input border and status bar)
                 for x in 0..80 {
                     let cell = buffer.cell((x, y)).unwrap();
                     if !cell.symbol().trim().is_empty() {
@@ -2696,6 +5256,169 @@ mod tests {
             );
         }
 
+        #[test]
+        fn test_collapsed_thinking_emits_summary_and_toggles() {
+            let mut renderer = create_test_harness(80, 20);
+            let textarea = TextArea::new();
+            renderer.thinking_visibility = ThinkingVisibility::Collapsed;
+
+            let line_text = |lines: &[Line<'static>]| -> Vec<String> {
+                lines
+                    .iter()
+                    .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+                    .collect()
+            };
+
+            renderer.start_new_message(1);
+            renderer.queue_thinking_delta("First thought.\n\nSecond thought.\n".to_string());
+            renderer.render(&textarea);
+            renderer.queue_text_delta("Answer.\n".to_string());
+            renderer.flush_streaming_pending();
+            let lines = line_text(&renderer.drain_pending_history_lines());
+
+            assert!(
+                lines.iter().any(|l| l.contains("▸ Thinking (")),
+                "Collapsed thinking should emit a summary line: {lines:?}"
+            );
+            assert!(
+                !lines.iter().any(|l| l.contains("First thought")),
+                "Collapsed thinking content should stay out of scrollback: {lines:?}"
+            );
+            assert_eq!(renderer.transcript.thinking_units().len(), 1);
+
+            // Expanding re-emits the full content under a header
+            assert!(renderer.toggle_last_thinking_block());
+            let lines = line_text(&renderer.drain_pending_history_lines());
+            assert!(lines[0].contains("▾ Thinking ("));
+            assert!(lines.iter().any(|l| l.contains("First thought")));
+            assert!(lines.iter().any(|l| l.contains("Second thought")));
+
+            // Collapsing again emits just the summary
+            assert!(renderer.toggle_last_thinking_block());
+            let lines = line_text(&renderer.drain_pending_history_lines());
+            assert_eq!(lines.len(), 1);
+            assert!(lines[0].contains("▸ Thinking ("));
+        }
+
+        #[test]
+        fn test_expanded_thinking_default_streams_full_content() {
+            let mut renderer = create_test_harness(80, 20);
+            renderer.thinking_visibility = ThinkingVisibility::Full;
+
+            renderer.start_new_message(1);
+            renderer.queue_thinking_delta("Visible thought.\n".to_string());
+            renderer.queue_text_delta("Answer.\n".to_string());
+            renderer.flush_streaming_pending();
+            let lines: Vec<String> = renderer
+                .drain_pending_history_lines()
+                .iter()
+                .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+                .collect();
+
+            assert!(lines.iter().any(|l| l.contains("Visible thought")));
+            assert!(!lines.iter().any(|l| l.contains("Thinking (")));
+            assert_eq!(renderer.transcript.thinking_units().len(), 1);
+        }
+
+        #[test]
+        fn test_hidden_thinking_leaves_only_duration_note() {
+            let mut renderer = create_test_harness(80, 20);
+            let textarea = TextArea::new();
+            renderer.thinking_visibility = ThinkingVisibility::Hidden;
+
+            renderer.start_new_message(1);
+            renderer.queue_thinking_delta("Secret thought.\n".to_string());
+            renderer.render(&textarea);
+            let live = renderer.transcript.active_message().unwrap();
+            assert!(
+                !live
+                    .blocks
+                    .iter()
+                    .any(|block| matches!(block, MessageBlock::Thinking(_))),
+                "Hidden thinking should not appear in the viewport"
+            );
+            renderer.queue_text_delta("Answer.\n".to_string());
+            renderer.flush_streaming_pending();
+            let lines: Vec<String> = renderer
+                .drain_pending_history_lines()
+                .iter()
+                .map(|l| l.to_string())
+                .collect();
+
+            assert_eq!(lines[0], "  (thought for 0s)");
+            assert!(!lines.iter().any(|l| l.contains("Secret thought")));
+            assert!(!lines.iter().any(|l| l.contains("Thinking (")));
+            assert!(lines.iter().any(|l| l.contains("Answer.")));
+            assert!(!renderer.toggle_last_thinking_block());
+        }
+
+        #[test]
+        fn test_hidden_thinking_in_committed_history() {
+            let mut message = LiveMessage::new();
+            let mut thinking = crate::ui::terminal::message::ThinkingBlock::new();
+            thinking.content = "Secret thought.".to_string();
+            message.add_block(MessageBlock::Thinking(thinking));
+            message.add_block(MessageBlock::PlainText(PlainTextBlock {
+                content: "Answer.".to_string(),
+            }));
+
+            let history = |thinking| -> Vec<String> {
+                TranscriptState::as_history_lines(&message, 80, thinking, ToolDetail::Full, &[])
+                    .iter()
+                    .filter_map(HistoryRow::as_line)
+                    .map(|l| l.to_string())
+                    .collect()
+            };
+            let hidden = history(ThinkingVisibility::Hidden);
+            assert!(!hidden
+                .iter()
+                .any(|l| l.contains("Secret") || l.contains("Thinking")));
+            assert!(history(ThinkingVisibility::Collapsed)
+                .iter()
+                .any(|l| l.contains("▸ Thinking (1 line)")));
+            assert!(history(ThinkingVisibility::Full)
+                .iter()
+                .any(|l| l.contains("Secret thought.")));
+        }
+
+        #[test]
+        fn test_toggle_thinking_without_blocks_is_noop() {
+            let mut renderer = create_default_test_harness();
+            assert!(!renderer.toggle_last_thinking_block());
+            assert!(renderer.drain_pending_history_lines().is_empty());
+        }
+
+        #[test]
+        fn test_last_executed_command_prefers_most_recent() {
+            let mut renderer = create_default_test_harness();
+            assert_eq!(renderer.last_executed_command(), None);
+
+            renderer.start_new_message(1);
+            renderer.start_tool_use_block("execute_command".to_string(), "t1".to_string());
+            renderer.add_or_update_tool_parameter(
+                "t1",
+                "command_line".to_string(),
+                "cargo build".to_string(),
+            );
+            renderer.start_new_message(2);
+            assert_eq!(
+                renderer.last_executed_command().as_deref(),
+                Some("cargo build")
+            );
+
+            renderer.start_tool_use_block("execute_command".to_string(), "t2".to_string());
+            renderer.add_or_update_tool_parameter(
+                "t2",
+                "command_line".to_string(),
+                "cargo test".to_string(),
+            );
+            renderer.start_tool_use_block("read_files".to_string(), "t3".to_string());
+            assert_eq!(
+                renderer.last_executed_command().as_deref(),
+                Some("cargo test")
+            );
+        }
+
         #[test]
         fn test_streamed_thinking_text_then_tool_has_single_blank_before_tool() {
             let mut renderer = create_test_harness(80, 20);
@@ -2862,6 +5585,79 @@ mod tests {
             );
         }
 
+        /// The same turn driven through `simulate_turn`, which also hides the
+        /// spinner and finalizes the message like the live event loop.
+        #[test]
+        fn test_simulated_turn_has_single_blank_before_tool() {
+            let mut renderer = create_test_harness(80, 20);
+            let lines = renderer.simulate_turn(
+                80,
+                &[
+                    TurnEvent::Thinking("Let me think about this.\n"),
+                    TurnEvent::Text("Here is my answer.\n"),
+                    TurnEvent::ToolStart {
+                        name: "write_file",
+                        id: "tool_1",
+                    },
+                    TurnEvent::ToolParameter {
+                        id: "tool_1",
+                        name: "path",
+                        value: "/tmp/test.txt",
+                    },
+                    TurnEvent::ToolStatus {
+                        id: "tool_1",
+                        status: ToolStatus::Success,
+                        output: None,
+                    },
+                ],
+            );
+
+            let line_strs: Vec<String> = lines
+                .iter()
+                .map(|l| {
+                    if l.spans.is_empty() {
+                        "<<blank>>".to_string()
+                    } else {
+                        l.spans
+                            .iter()
+                            .map(|s| s.content.as_ref())
+                            .collect::<String>()
+                    }
+                })
+                .collect();
+
+            // Find the tool line
+            let tool_line_idx = line_strs
+                .iter()
+                .position(|s| s.contains("●"))
+                .expect("Should have a tool line");
+
+            // Count consecutive blank lines immediately before the tool
+            let mut blank_count = 0;
+            let mut idx = tool_line_idx;
+            while idx > 0 {
+                idx -= 1;
+                if line_strs[idx] == "<<blank>>" {
+                    blank_count += 1;
+                } else {
+                    break;
+                }
+            }
+
+            assert_eq!(
+                blank_count,
+                1,
+                "Expected exactly 1 blank line before tool block, got {}.\nAll lines:\n{}",
+                blank_count,
+                line_strs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, s)| format!("  [{i:2}] {s}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            );
+        }
+
         /// Test: text → tool → text → tool (interleaved) — each tool should have
         /// exactly 1 blank line before it.
         #[test]
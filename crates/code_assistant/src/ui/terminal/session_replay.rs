@@ -0,0 +1,224 @@
+//! Transcript messages rebuilt from a saved session, for the read-only
+//! viewer and for sessions loaded into the live terminal UI.
+
+use std::collections::HashMap;
+
+use super::message::{
+    LiveMessage, MessageBlock, OutputLimit, PlainTextBlock, ThinkingBlock, ToolUseBlock,
+};
+use crate::ui::gpui::elements::MessageRole;
+use crate::ui::ui_events::{MessageData, ToolResultData};
+use crate::ui::DisplayFragment;
+
+/// Build transcript messages from a session's UI data, the same blocks the
+/// live stream would have produced. Tool calls take their final status and
+/// output from `tool_results`.
+pub fn live_messages_from_session(
+    messages: &[MessageData],
+    tool_results: &[ToolResultData],
+    limit: OutputLimit,
+) -> Vec<LiveMessage> {
+    let results: HashMap<&str, &ToolResultData> = tool_results
+        .iter()
+        .map(|result| (result.tool_id.as_str(), result))
+        .collect();
+
+    let mut live_messages = Vec::new();
+    for message in messages {
+        let mut live = LiveMessage::new();
+        for fragment in &message.fragments {
+            match fragment {
+                DisplayFragment::PlainText(text) => {
+                    let block = match message.role {
+                        MessageRole::User => MessageBlock::UserText(PlainTextBlock::new()),
+                        MessageRole::Assistant => MessageBlock::PlainText(PlainTextBlock::new()),
+                    };
+                    append_to_block(&mut live, block, text);
+                }
+                DisplayFragment::ThinkingText(text)
+                | DisplayFragment::ReasoningSummaryDelta(text) => {
+                    append_to_block(
+                        &mut live,
+                        MessageBlock::Thinking(ThinkingBlock::new()),
+                        text,
+                    );
+                }
+                DisplayFragment::ToolName { name, id } => {
+                    let mut tool = ToolUseBlock::new(name.clone(), id.clone());
+                    if let Some(result) = results.get(id.as_str()) {
+                        tool.status = result.status;
+                        tool.status_message = result.message.clone();
+                        tool.set_output(result.output.clone(), limit);
+                        tool.unchanged = result.unchanged;
+                    }
+                    live.add_block(MessageBlock::ToolUse(tool));
+                }
+                DisplayFragment::ToolParameter {
+                    name,
+                    value,
+                    tool_id,
+                } => {
+                    if let Some(tool) = live.get_tool_block_mut(tool_id) {
+                        tool.add_or_update_parameter(name.clone(), value.clone());
+                    }
+                }
+                DisplayFragment::ToolOutput { tool_id, chunk } => {
+                    // Streamed output only stands in for a missing final result
+                    if !results.contains_key(tool_id.as_str()) {
+                        if let Some(tool) = live.get_tool_block_mut(tool_id) {
+                            tool.append_output(chunk, limit);
+                        }
+                    }
+                }
+                DisplayFragment::CompactionDivider { summary } => {
+                    push_if_content(
+                        &mut live_messages,
+                        std::mem::replace(&mut live, LiveMessage::new()),
+                    );
+                    let mut notice = PlainTextBlock::new();
+                    notice.content = format!("[conversation compacted]\n{summary}");
+                    let mut divider = LiveMessage::new();
+                    divider.add_block(MessageBlock::SystemText(notice));
+                    live_messages.push(divider);
+                }
+                DisplayFragment::Image { .. }
+                | DisplayFragment::ToolEnd { .. }
+                | DisplayFragment::ToolTerminal { .. }
+                | DisplayFragment::ReasoningSummaryStart
+                | DisplayFragment::ReasoningComplete
+                | DisplayFragment::HiddenToolCompleted => {}
+            }
+        }
+        push_if_content(&mut live_messages, live);
+    }
+
+    for message in &mut live_messages {
+        message.finalized = true;
+    }
+    live_messages
+}
+
+/// Append `text` to the last block when it has the same kind as `block`,
+/// otherwise start `block` with it.
+fn append_to_block(message: &mut LiveMessage, block: MessageBlock, text: &str) {
+    let same_kind = message
+        .blocks
+        .last()
+        .is_some_and(|last| std::mem::discriminant(last) == std::mem::discriminant(&block));
+    if !same_kind {
+        message.add_block(block);
+    }
+    if let Some(last) = message.get_last_block_mut() {
+        last.append_content(text);
+    }
+}
+
+fn push_if_content(messages: &mut Vec<LiveMessage>, message: LiveMessage) {
+    if message.has_content() {
+        messages.push(message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::ToolStatus;
+
+    const LIMIT: OutputLimit = OutputLimit {
+        max_bytes: 1024,
+        max_lines: 100,
+    };
+
+    fn message(role: MessageRole, fragments: Vec<DisplayFragment>) -> MessageData {
+        MessageData {
+            role,
+            fragments,
+            node_id: None,
+            branch_info: None,
+        }
+    }
+
+    #[test]
+    fn test_session_becomes_committed_blocks() {
+        let messages = vec![
+            message(
+                MessageRole::User,
+                vec![DisplayFragment::PlainText("Run the tests".to_string())],
+            ),
+            message(
+                MessageRole::Assistant,
+                vec![
+                    DisplayFragment::ThinkingText("Use cargo".to_string()),
+                    DisplayFragment::PlainText("Running ".to_string()),
+                    DisplayFragment::PlainText("them now.".to_string()),
+                    DisplayFragment::ToolName {
+                        name: "execute_command".to_string(),
+                        id: "tool-1".to_string(),
+                    },
+                    DisplayFragment::ToolParameter {
+                        name: "command_line".to_string(),
+                        value: "cargo test".to_string(),
+                        tool_id: "tool-1".to_string(),
+                    },
+                    DisplayFragment::ToolOutput {
+                        tool_id: "tool-1".to_string(),
+                        chunk: "partial".to_string(),
+                    },
+                    DisplayFragment::ToolEnd {
+                        id: "tool-1".to_string(),
+                    },
+                ],
+            ),
+        ];
+        let results = vec![ToolResultData {
+            tool_id: "tool-1".to_string(),
+            status: ToolStatus::Success,
+            message: Some("done".to_string()),
+            output: Some("test result: ok".to_string()),
+            unchanged: false,
+        }];
+
+        let live = live_messages_from_session(&messages, &results, LIMIT);
+        assert_eq!(live.len(), 2);
+        assert!(live.iter().all(|message| message.finalized));
+        assert!(
+            matches!(&live[0].blocks[..], [MessageBlock::UserText(text)] if text.content == "Run the tests")
+        );
+
+        let [MessageBlock::Thinking(thinking), MessageBlock::PlainText(text), MessageBlock::ToolUse(tool)] =
+            &live[1].blocks[..]
+        else {
+            panic!("unexpected blocks: {:?}", live[1].blocks);
+        };
+        assert_eq!(thinking.content, "Use cargo");
+        assert_eq!(text.content, "Running them now.");
+        assert_eq!(tool.status, ToolStatus::Success);
+        assert_eq!(tool.output.as_deref(), Some("test result: ok"));
+        assert_eq!(
+            tool.parameters
+                .get("command_line")
+                .map(|p| p.get_display_value()),
+            Some("cargo test".to_string())
+        );
+    }
+
+    #[test]
+    fn test_compaction_divider_is_its_own_message() {
+        let messages = vec![message(
+            MessageRole::Assistant,
+            vec![
+                DisplayFragment::PlainText("Before".to_string()),
+                DisplayFragment::CompactionDivider {
+                    summary: "Earlier work".to_string(),
+                },
+                DisplayFragment::PlainText("After".to_string()),
+            ],
+        )];
+
+        let live = live_messages_from_session(&messages, &[], LIMIT);
+        assert_eq!(live.len(), 3);
+        assert!(
+            matches!(&live[1].blocks[..], [MessageBlock::SystemText(text)] if text.content.ends_with("Earlier work"))
+        );
+    }
+}
@@ -0,0 +1,111 @@
+//! Spinner animation frames, selectable via the `spinner_style` key in
+//! `terminal.json`.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Spinner animation style.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SpinnerStyle {
+    /// Braille when the terminal is likely to render it, ASCII otherwise
+    #[default]
+    Auto,
+    Braille,
+    Dots,
+    Ascii,
+}
+
+const BRAILLE_FRAMES: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+// Padded to a constant width so text after the spinner doesn't shift
+const DOTS_FRAMES: &[&str] = &[".  ", ".. ", "..."];
+const ASCII_FRAMES: &[&str] = &["|", "/", "-", "\\"];
+
+impl SpinnerStyle {
+    /// Replace `Auto` with a concrete style for the current terminal.
+    pub fn resolve(self) -> Self {
+        match self {
+            SpinnerStyle::Auto if supports_braille() => SpinnerStyle::Braille,
+            SpinnerStyle::Auto => SpinnerStyle::Ascii,
+            style => style,
+        }
+    }
+
+    fn frames(self) -> &'static [&'static str] {
+        match self {
+            SpinnerStyle::Auto | SpinnerStyle::Braille => BRAILLE_FRAMES,
+            SpinnerStyle::Dots => DOTS_FRAMES,
+            SpinnerStyle::Ascii => ASCII_FRAMES,
+        }
+    }
+
+    /// Time each frame stays on screen.
+    fn frame_duration(self) -> Duration {
+        match self {
+            SpinnerStyle::Dots => Duration::from_millis(300),
+            _ => Duration::from_millis(100),
+        }
+    }
+
+    /// Frame to show after `elapsed` time.
+    pub fn frame_at(self, elapsed: Duration) -> &'static str {
+        let frames = self.frames();
+        let index = elapsed.as_millis() / self.frame_duration().as_millis();
+        frames[(index % frames.len() as u128) as usize]
+    }
+
+    /// Columns occupied by a frame.
+    pub fn width(self) -> u16 {
+        self.frames()[0].chars().count() as u16
+    }
+}
+
+/// Best-effort guess whether braille glyphs will render. The Linux console
+/// font lacks them, and without a UTF-8 locale they are unlikely to display.
+fn supports_braille() -> bool {
+    if std::env::var("TERM").is_ok_and(|term| term == "linux") {
+        return false;
+    }
+
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty());
+    match locale {
+        Some(locale) => {
+            let locale = locale.to_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        }
+        // No locale configured: assume a modern terminal
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_each_style_cycles_frames() {
+        for style in [
+            SpinnerStyle::Braille,
+            SpinnerStyle::Dots,
+            SpinnerStyle::Ascii,
+        ] {
+            let frames = style.frames();
+            let step = style.frame_duration();
+            for (i, frame) in frames.iter().enumerate() {
+                assert_eq!(style.frame_at(step * i as u32), *frame);
+                assert_eq!(frame.chars().count() as u16, style.width());
+            }
+            // Wraps around after the last frame
+            assert_eq!(style.frame_at(step * frames.len() as u32), frames[0]);
+        }
+    }
+
+    #[test]
+    fn test_resolve_keeps_explicit_styles() {
+        assert_eq!(SpinnerStyle::Dots.resolve(), SpinnerStyle::Dots);
+        assert_ne!(SpinnerStyle::Auto.resolve(), SpinnerStyle::Auto);
+    }
+}
@@ -1,13 +1,47 @@
+use super::command_palette::CommandPalette;
+use super::message::MessageUsage;
+use super::model_picker::ModelPicker;
 use crate::persistence::ChatMetadata;
 use crate::session::instance::SessionActivityState;
-use crate::types::PlanState;
+use crate::types::{PlanState, ToolSyntax};
+use llm::recording::PlaybackStatus;
 use sandbox::SandboxPolicy;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Minimum time between two turn-completion notifications.
+const TURN_NOTIFICATION_DEBOUNCE: Duration = Duration::from_secs(10);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OverlayState {
     None,
     Plan,
+    CommandPalette,
+}
+
+/// When new output is written to the terminal scrollback. Writing to the
+/// terminal moves many emulators back to the bottom, so while the user
+/// reads back, output can be held and written once they return.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AutoScroll {
+    /// Always write output as it arrives; PageUp doesn't hold it
+    #[default]
+    Always,
+    /// PageUp holds output; End or sending a message writes it and follows
+    /// new output again
+    AtBottom,
+    /// PageUp holds output; only End writes it. Sending a message keeps
+    /// holding, so the view never jumps back to the bottom on its own.
+    Never,
+}
+
+/// A tool call waiting for the user, with the session whose agent asked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingToolApproval {
+    pub session_id: String,
+    pub tool_id: String,
 }
 
 pub struct AppState {
@@ -24,6 +58,33 @@ pub struct AppState {
     pub current_model: Option<String>,
     pub info_message: Option<String>,
     pub current_sandbox_policy: Option<SandboxPolicy>,
+    pub tool_syntax: Option<ToolSyntax>,
+    pub model_picker: Option<ModelPicker>,
+    pub command_palette: Option<CommandPalette>,
+    /// Command awaiting a second re-run keypress when confirmation is enabled
+    pub pending_rerun: Option<String>,
+    /// Messages queued during the active turn, cleared once the agent consumes them
+    pub queued_messages: Vec<String>,
+    /// Steering text for the active turn, cleared once the agent consumes it
+    pub steering_message: Option<String>,
+    /// `/clear new` asked the backend for a session it has not created yet
+    pub awaiting_new_session: bool,
+    /// Gated tool call waiting for Enter (approve) or Esc (skip)
+    pub pending_tool_approval: Option<PendingToolApproval>,
+    /// Whether the terminal window has focus (per focus-change reports)
+    pub focused: bool,
+    /// Pause state and speed when playing back a recording
+    pub playback: Option<PlaybackStatus>,
+    /// New output goes to scrollback; cleared while the user reads back
+    pub following: bool,
+    /// Tokens and cost of the responses in the current session so far
+    pub session_usage: Option<MessageUsage>,
+    /// The transcript or draft changed since the last auto-save
+    autosave_dirty: bool,
+    last_change: Instant,
+    /// A turn finished while unfocused and no notification went out yet
+    pending_turn_notification: bool,
+    last_turn_notification: Option<Instant>,
 }
 
 impl AppState {
@@ -42,7 +103,70 @@ impl AppState {
             current_model: None,
             info_message: None,
             current_sandbox_policy: None,
+            tool_syntax: None,
+            model_picker: None,
+            command_palette: None,
+            pending_rerun: None,
+            queued_messages: Vec::new(),
+            steering_message: None,
+            awaiting_new_session: false,
+            pending_tool_approval: None,
+            focused: true,
+            playback: None,
+            following: true,
+            session_usage: None,
+            autosave_dirty: false,
+            last_change: Instant::now(),
+            pending_turn_notification: false,
+            last_turn_notification: None,
+        }
+    }
+
+    /// Record that the agent finished a turn; the user is notified if the
+    /// terminal isn't focused.
+    pub fn note_turn_completed(&mut self) {
+        if !self.focused {
+            self.pending_turn_notification = true;
+        }
+    }
+
+    /// Whether a turn-completion notification should be sent now. Consumes
+    /// the pending notification; repeats within the debounce window are dropped.
+    pub fn take_turn_notification(&mut self, now: Instant) -> bool {
+        if !std::mem::take(&mut self.pending_turn_notification) {
+            return false;
+        }
+        if self
+            .last_turn_notification
+            .is_some_and(|last| now.duration_since(last) < TURN_NOTIFICATION_DEBOUNCE)
+        {
+            return false;
+        }
+        self.last_turn_notification = Some(now);
+        true
+    }
+
+    /// Record a transcript or draft change; it restarts the idle clock.
+    pub fn note_change(&mut self, now: Instant) {
+        self.autosave_dirty = true;
+        self.last_change = now;
+    }
+
+    /// Time left until unsaved changes have been idle for `interval`, or
+    /// `None` when there is nothing to save.
+    pub fn autosave_delay(&self, interval: Duration, now: Instant) -> Option<Duration> {
+        self.autosave_dirty
+            .then(|| interval.saturating_sub(now.duration_since(self.last_change)))
+    }
+
+    /// Whether unsaved changes have been idle for `interval`. Marks them
+    /// saved, so each change is auto-saved once.
+    pub fn take_autosave(&mut self, interval: Duration, now: Instant) -> bool {
+        if self.autosave_delay(interval, now) != Some(Duration::ZERO) {
+            return false;
         }
+        self.autosave_dirty = false;
+        true
     }
 
     pub fn update_sessions(&mut self, sessions: Vec<ChatMetadata>) {
@@ -54,9 +178,38 @@ impl AppState {
     }
 
     pub fn update_pending_message(&mut self, message: Option<String>) {
+        if message.is_none() {
+            self.queued_messages.clear();
+            self.steering_message = None;
+        }
         self.pending_message = message;
     }
 
+    /// Record a message queued while the agent is busy.
+    pub fn push_queued_message(&mut self, message: String) {
+        self.queued_messages.push(message);
+    }
+
+    /// Record steering text sent while the agent is busy. Repeated steering
+    /// before the agent picks it up is delivered as one message.
+    pub fn push_steering_message(&mut self, message: &str) {
+        match self.steering_message.as_mut() {
+            Some(existing) => {
+                existing.push('\n');
+                existing.push_str(message);
+            }
+            None => self.steering_message = Some(message.to_string()),
+        }
+    }
+
+    /// Steering that the ended turn never picked up is queued for the next
+    /// turn by the backend; show it as queued rather than steering.
+    pub fn requeue_steering_message(&mut self) {
+        if let Some(message) = self.steering_message.take() {
+            self.queued_messages.push(message);
+        }
+    }
+
     pub fn update_session_activity_state(
         &mut self,
         session_id: String,
@@ -74,6 +227,71 @@ impl AppState {
         self.current_sandbox_policy = policy;
     }
 
+    pub fn update_tool_syntax(&mut self, tool_syntax: Option<ToolSyntax>) {
+        self.tool_syntax = tool_syntax;
+    }
+
+    /// Name of the current session, looked up in the session list.
+    pub fn current_session_name(&self) -> Option<&str> {
+        let session_id = self.current_session_id.as_deref()?;
+        self.sessions
+            .iter()
+            .find(|session| session.id == session_id)
+            .map(|session| session.name.as_str())
+            .filter(|name| !name.is_empty())
+    }
+
+    pub fn open_model_picker(&mut self, models: Vec<String>) {
+        self.model_picker = Some(ModelPicker::new(models, self.current_model.as_deref()));
+    }
+
+    pub fn close_model_picker(&mut self) {
+        self.model_picker = None;
+    }
+
+    pub fn open_command_palette(&mut self) {
+        self.command_palette = Some(CommandPalette::new());
+        self.overlay_state = OverlayState::CommandPalette;
+    }
+
+    pub fn close_command_palette(&mut self) {
+        self.command_palette = None;
+        self.overlay_state = if self.plan_expanded {
+            OverlayState::Plan
+        } else {
+            OverlayState::None
+        };
+    }
+
+    /// Hold new output while the user reads back. Returns whether
+    /// following stopped; in [`AutoScroll::Always`] it never does.
+    pub fn stop_following(&mut self, mode: AutoScroll) -> bool {
+        if mode == AutoScroll::Always || !self.following {
+            return false;
+        }
+        self.following = false;
+        true
+    }
+
+    /// Write held output and follow new output again. Returns whether
+    /// following was off.
+    pub fn resume_following(&mut self) -> bool {
+        !std::mem::replace(&mut self.following, true)
+    }
+
+    /// Sending a message counts as returning to the bottom in
+    /// [`AutoScroll::AtBottom`].
+    pub fn note_message_sent(&mut self, mode: AutoScroll) {
+        if mode == AutoScroll::AtBottom {
+            self.following = true;
+        }
+    }
+
+    /// Add a finished response's usage to the session total.
+    pub fn add_session_usage(&mut self, usage: MessageUsage) {
+        self.session_usage.get_or_insert_default().add(usage);
+    }
+
     pub fn set_info_message(&mut self, message: Option<String>) {
         self.info_message = message;
     }
@@ -106,3 +324,77 @@ impl AppState {
         !matches!(self.overlay_state, OverlayState::None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_following_per_auto_scroll_mode() {
+        let mut state = AppState::new();
+        assert!(!state.stop_following(AutoScroll::Always));
+        assert!(state.following);
+
+        assert!(state.stop_following(AutoScroll::AtBottom));
+        assert!(!state.stop_following(AutoScroll::AtBottom));
+        state.note_message_sent(AutoScroll::AtBottom);
+        assert!(state.following);
+    }
+
+    #[test]
+    fn test_never_auto_scroll_resumes_only_on_end() {
+        let mut state = AppState::new();
+        assert!(state.stop_following(AutoScroll::Never));
+
+        // Sending messages keeps the output held
+        state.note_message_sent(AutoScroll::Never);
+        state.note_message_sent(AutoScroll::Never);
+        assert!(!state.following);
+
+        // End is the only way back
+        assert!(state.resume_following());
+        assert!(state.following);
+        assert!(!state.resume_following());
+    }
+
+    #[test]
+    fn test_autosave_after_idle_interval() {
+        let mut state = AppState::new();
+        let interval = Duration::from_secs(30);
+        let start = Instant::now();
+        assert_eq!(state.autosave_delay(interval, start), None);
+        assert!(!state.take_autosave(interval, start + interval));
+
+        state.note_change(start);
+        assert_eq!(
+            state.autosave_delay(interval, start + Duration::from_secs(10)),
+            Some(Duration::from_secs(20))
+        );
+        // Another change restarts the idle clock
+        state.note_change(start + Duration::from_secs(10));
+        assert!(!state.take_autosave(interval, start + interval));
+        assert!(state.take_autosave(interval, start + Duration::from_secs(40)));
+        // Saved once
+        assert!(!state.take_autosave(interval, start + Duration::from_secs(90)));
+    }
+
+    #[test]
+    fn test_turn_notification_only_when_unfocused_and_debounced() {
+        let mut state = AppState::new();
+        let start = Instant::now();
+
+        state.note_turn_completed();
+        assert!(!state.take_turn_notification(start));
+
+        state.focused = false;
+        state.note_turn_completed();
+        assert!(state.take_turn_notification(start));
+        // Consumed
+        assert!(!state.take_turn_notification(start));
+
+        state.note_turn_completed();
+        assert!(!state.take_turn_notification(start + Duration::from_secs(1)));
+        state.note_turn_completed();
+        assert!(state.take_turn_notification(start + TURN_NOTIFICATION_DEBOUNCE));
+    }
+}
@@ -0,0 +1,409 @@
+//! Markdown footnotes (`[^label]` references and `[^label]: text`
+//! definitions). References become dim superscript markers numbered in
+//! order of first use; definitions are taken out of the text and listed
+//! in a footnotes section after the message.
+//!
+//! Anything that does not clearly parse as a footnote stays literal:
+//! references in code, labels with spaces, `[^label](…)` links, regex
+//! classes like `[^/]+` and repeated definitions of a label. A complete
+//! message only replaces references whose label is defined; while
+//! streaming the definition usually hasn't arrived yet, so any reference
+//! counts.
+
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use unicode_width::UnicodeWidthStr;
+
+use super::markdown_stream::{is_fence_line, render_markdown_lines};
+use crate::ui::terminal::theme::Theme;
+
+const SUPERSCRIPT_DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+
+/// Footnotes found in a markdown source.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Footnotes {
+    /// Labels in order of their first reference; a label's number is its
+    /// position here plus one
+    referenced: Vec<String>,
+    /// Definitions in source order
+    definitions: Vec<(String, String)>,
+    /// Which runs of superscript digits in the text are markers, counted
+    /// from zero in text order; text can have its own, like "m²"
+    marker_runs: Vec<usize>,
+}
+
+impl Footnotes {
+    fn number(&mut self, label: &str) -> usize {
+        match self.referenced.iter().position(|known| known == label) {
+            Some(index) => index + 1,
+            None => {
+                self.referenced.push(label.to_string());
+                self.referenced.len()
+            }
+        }
+    }
+
+    /// Definitions with their markers: referenced ones by number, then the
+    /// ones nothing referred to in the order they were defined.
+    fn numbered_definitions(&self) -> Vec<(String, &str)> {
+        let mut numbered: Vec<(usize, &str)> = Vec::new();
+        let mut next_unreferenced = self.referenced.len();
+        for (label, text) in &self.definitions {
+            let number = match self.referenced.iter().position(|known| known == label) {
+                Some(index) => index + 1,
+                None => {
+                    next_unreferenced += 1;
+                    next_unreferenced
+                }
+            };
+            numbered.push((number, text));
+        }
+        numbered.sort_by_key(|(number, _)| *number);
+        numbered
+            .into_iter()
+            .map(|(number, text)| (superscript(number), text))
+            .collect()
+    }
+}
+
+/// Counts runs of superscript digits in text as it is built.
+#[derive(Default)]
+struct SuperscriptRuns {
+    scanned: usize,
+    count: usize,
+    in_run: bool,
+}
+
+impl SuperscriptRuns {
+    /// Index of the run that a superscript appended to `text` belongs to.
+    fn next_index(&mut self, text: &str) -> usize {
+        for c in text[self.scanned..].chars() {
+            let is_digit = is_superscript_digit(c);
+            if is_digit && !self.in_run {
+                self.count += 1;
+            }
+            self.in_run = is_digit;
+        }
+        self.scanned = text.len();
+        if self.in_run {
+            self.count - 1
+        } else {
+            self.count
+        }
+    }
+}
+
+/// Replace footnote references in `source` with superscript markers and
+/// drop definition lines. With `defined_only`, references to labels that
+/// `source` never defines stay literal. Returns `None` when `source` has
+/// no footnotes outside code.
+pub fn extract_footnotes(source: &str, defined_only: bool) -> Option<(String, Footnotes)> {
+    let mut footnotes = Footnotes::default();
+    let mut out = String::with_capacity(source.len());
+    let mut runs = SuperscriptRuns::default();
+    let mut changed = false;
+
+    let defined_labels: Option<Vec<&str>> = defined_only.then(|| {
+        prose_lines(source)
+            .filter_map(|(line, prose)| prose.then(|| parse_definition(line)).flatten())
+            .map(|(label, _)| label)
+            .collect()
+    });
+    let is_referable = |label: &str| {
+        defined_labels
+            .as_ref()
+            .is_none_or(|labels| labels.contains(&label))
+    };
+
+    for (line, prose) in prose_lines(source) {
+        if !prose {
+            out.push_str(line);
+            continue;
+        }
+        if let Some((label, text)) = parse_definition(line) {
+            let defined = footnotes
+                .definitions
+                .iter()
+                .any(|(known, _)| known == label);
+            if !defined {
+                footnotes
+                    .definitions
+                    .push((label.to_string(), text.to_string()));
+                changed = true;
+                continue;
+            }
+        }
+        changed |= replace_references(line, &is_referable, &mut footnotes, &mut runs, &mut out);
+    }
+
+    changed.then_some((out, footnotes))
+}
+
+/// Lines of `source` with whether each is prose, i.e. outside code fences.
+/// Fence lines themselves count as code.
+fn prose_lines(source: &str) -> impl Iterator<Item = (&str, bool)> {
+    let mut in_fence = false;
+    source.split_inclusive('\n').map(move |line| {
+        if is_fence_line(line) {
+            in_fence = !in_fence;
+            return (line, false);
+        }
+        (line, !in_fence)
+    })
+}
+
+/// Dim the superscript markers that [`extract_footnotes`] put in place of
+/// references, leaving superscripts that were already in the text alone.
+pub fn style_footnote_markers(
+    mut lines: Vec<Line<'static>>,
+    footnotes: &Footnotes,
+) -> Vec<Line<'static>> {
+    let marker_style = marker_style();
+    let mut run_index = 0;
+    for line in &mut lines {
+        if !line
+            .spans
+            .iter()
+            .any(|span| span.content.contains(is_superscript_digit))
+        {
+            continue;
+        }
+        let mut in_run = false;
+        let mut spans = Vec::with_capacity(line.spans.len() + 2);
+        for span in line.spans.drain(..) {
+            let mut run = String::new();
+            let mut run_is_marker = false;
+            for c in span.content.chars() {
+                let is_digit = is_superscript_digit(c);
+                if is_digit && !in_run {
+                    run_index += 1;
+                }
+                in_run = is_digit;
+                let is_marker = is_digit && footnotes.marker_runs.contains(&(run_index - 1));
+                if is_marker != run_is_marker && !run.is_empty() {
+                    let style = if run_is_marker {
+                        span.style.patch(marker_style)
+                    } else {
+                        span.style
+                    };
+                    spans.push(Span::styled(std::mem::take(&mut run), style));
+                }
+                run_is_marker = is_marker;
+                run.push(c);
+            }
+            if !run.is_empty() {
+                let style = if run_is_marker {
+                    span.style.patch(marker_style)
+                } else {
+                    span.style
+                };
+                spans.push(Span::styled(run, style));
+            }
+        }
+        line.spans = spans;
+    }
+    lines
+}
+
+/// The footnotes section for `source`: a blank line, then each definition
+/// after its marker, wrapped to `width` with continuation rows indented.
+/// Empty when `source` defines no footnotes.
+pub fn footnote_section_lines(footnotes: &Footnotes, width: Option<usize>) -> Vec<Line<'static>> {
+    let definitions = footnotes.numbered_definitions();
+    if definitions.is_empty() {
+        return Vec::new();
+    }
+
+    let text_style = Style::default()
+        .fg(Theme::global().muted_fg)
+        .add_modifier(Modifier::DIM);
+    let mut lines = vec![Line::from("")];
+    for (marker, text) in definitions {
+        let marker = format!("{marker} ");
+        let indent = marker.width();
+        let text_width = width.map(|width| width.saturating_sub(indent).max(1));
+        for (index, line) in render_markdown_lines(text, text_width)
+            .into_iter()
+            .enumerate()
+        {
+            let gutter = if index == 0 {
+                Span::styled(marker.clone(), marker_style())
+            } else {
+                Span::raw(" ".repeat(indent))
+            };
+            let mut spans = vec![gutter];
+            spans.extend(
+                line.spans
+                    .into_iter()
+                    .map(|span| Span::styled(span.content, span.style.patch(text_style))),
+            );
+            lines.push(Line::from(spans));
+        }
+    }
+    lines
+}
+
+fn marker_style() -> Style {
+    Style::default()
+        .fg(Theme::global().muted_fg)
+        .add_modifier(Modifier::DIM)
+}
+
+fn superscript(number: usize) -> String {
+    number
+        .to_string()
+        .chars()
+        .map(|digit| SUPERSCRIPT_DIGITS[digit.to_digit(10).unwrap_or(0) as usize])
+        .collect()
+}
+
+fn is_superscript_digit(c: char) -> bool {
+    SUPERSCRIPT_DIGITS.contains(&c)
+}
+
+/// A label is non-empty and has no whitespace or brackets.
+fn is_valid_label(label: &str) -> bool {
+    !label.is_empty() && !label.contains(|c: char| c.is_whitespace() || c == '[' || c == ']')
+}
+
+/// `[^label]: text` at the start of a line (up to three spaces in).
+fn parse_definition(line: &str) -> Option<(&str, &str)> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let rest = trimmed.strip_prefix("[^")?;
+    let (label, text) = rest.split_once("]:")?;
+    is_valid_label(label).then(|| (label, text.trim()))
+}
+
+/// Copy `line` into `out` with references to referable labels outside code
+/// spans replaced by markers. Returns whether any reference was replaced.
+fn replace_references(
+    line: &str,
+    is_referable: &impl Fn(&str) -> bool,
+    footnotes: &mut Footnotes,
+    runs: &mut SuperscriptRuns,
+    out: &mut String,
+) -> bool {
+    let mut replaced = false;
+    let mut code_ticks = 0;
+    let mut rest = line;
+    while let Some(c) = rest.chars().next() {
+        if c == '`' {
+            let ticks = rest.len() - rest.trim_start_matches('`').len();
+            if code_ticks == 0 {
+                code_ticks = ticks;
+            } else if code_ticks == ticks {
+                code_ticks = 0;
+            }
+            out.push_str(&rest[..ticks]);
+            rest = &rest[ticks..];
+            continue;
+        }
+        if code_ticks == 0 {
+            if let Some((label, after)) = rest
+                .strip_prefix("[^")
+                .and_then(|inner| inner.split_once(']'))
+            {
+                // `(` makes a link, `:` a definition and a quantifier a
+                // regex class
+                if is_valid_label(label)
+                    && !after.starts_with(['(', ':', '+', '*', '?', '{'])
+                    && is_referable(label)
+                {
+                    let run = runs.next_index(out);
+                    footnotes.marker_runs.push(run);
+                    out.push_str(&superscript(footnotes.number(label)));
+                    rest = after;
+                    replaced = true;
+                    continue;
+                }
+            }
+        }
+        out.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    replaced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(line: &Line<'_>) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn test_references_numbered_by_first_use_and_definitions_removed() {
+        let source = "[^src]: The source\nSee this[^src] and that[^other], again[^src].\n\n[^other]: Another\n";
+        let (text, footnotes) = extract_footnotes(source, true).unwrap();
+        assert_eq!(text, "See this¹ and that², again¹.\n\n");
+
+        let section: Vec<String> = footnote_section_lines(&footnotes, None)
+            .iter()
+            .map(plain)
+            .collect();
+        assert_eq!(section, ["", "¹ The source", "² Another"]);
+        assert_eq!(footnotes.referenced, ["src", "other"]);
+    }
+
+    #[test]
+    fn test_ambiguous_footnote_syntax_stays_literal() {
+        assert_eq!(extract_footnotes("Use `arr[^1]` here\n", false), None);
+        assert_eq!(extract_footnotes("```\nx[^1]\n```\n", false), None);
+        assert_eq!(
+            extract_footnotes("A [^not a label] or [^x](link)\n", false),
+            None
+        );
+        assert_eq!(extract_footnotes("Match [^/]+ then [^,]*\n", false), None);
+
+        let source = "[^1]: First\n[^1]: Second\nText[^1]\n";
+        let (text, _) = extract_footnotes(source, true).unwrap();
+        assert_eq!(text, "[^1]: Second\nText¹\n");
+    }
+
+    #[test]
+    fn test_unreferenced_definitions_listed_after_referenced_ones() {
+        let source = "[^late]: Never cited\nOnly this[^a].\n[^a]: Cited\n";
+        let (_, footnotes) = extract_footnotes(source, true).unwrap();
+        let section: Vec<String> = footnote_section_lines(&footnotes, None)
+            .iter()
+            .map(plain)
+            .collect();
+        assert_eq!(section, ["", "¹ Cited", "² Never cited"]);
+    }
+
+    #[test]
+    fn test_complete_text_only_replaces_defined_labels() {
+        let source = "Filter x[^a] with [^b] in it\n[^a]: Defined\n";
+        let (text, _) = extract_footnotes(source, true).unwrap();
+        assert_eq!(text, "Filter x¹ with [^b] in it\n");
+
+        // While streaming, the definition may still be on its way
+        let (text, _) = extract_footnotes("Filter x[^a] with [^b] in it\n", false).unwrap();
+        assert_eq!(text, "Filter x¹ with ² in it\n");
+    }
+
+    #[test]
+    fn test_only_markers_are_dimmed() {
+        let (text, footnotes) =
+            extract_footnotes("Area in m² here[^a]\n[^a]: Note\n", true).unwrap();
+        assert_eq!(text, "Area in m² here¹\n");
+
+        let lines =
+            style_footnote_markers(vec![Line::from(text.trim_end().to_string())], &footnotes);
+        let dimmed: Vec<&str> = lines[0]
+            .spans
+            .iter()
+            .filter(|span| span.style.add_modifier.contains(Modifier::DIM))
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(dimmed, ["¹"]);
+        assert_eq!(plain(&lines[0]), "Area in m² here¹");
+    }
+}
@@ -1,16 +1,36 @@
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::Style,
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Paragraph, Widget, Wrap},
 };
 use tui_markdown as md;
 
+use super::footnotes::{
+    extract_footnotes, footnote_section_lines, style_footnote_markers, Footnotes,
+};
+use crate::ui::terminal::config::TerminalConfig;
+use crate::ui::terminal::line_buffer::push_with_carriage_returns;
+use crate::ui::terminal::markdown::{self, MarkdownRenderer};
+use crate::ui::terminal::theme::Theme;
+
+/// Gutter drawn in front of each blockquote level
+const BLOCKQUOTE_BAR: &str = "▎ ";
+
+/// Checkbox glyphs substituted for task-list markers
+const TASK_OPEN: char = '☐';
+const TASK_DONE: char = '☑';
+
+/// Rule length when rendering without a known width
+const HORIZONTAL_RULE_FALLBACK_WIDTH: usize = 40;
+
 /// Newline-gated accumulator that renders markdown and commits only fully
 /// completed logical lines.
 pub struct MarkdownStreamCollector {
     buffer: String,
+    /// The last delta ended in `\r`; see `push_with_carriage_returns`
+    pending_cr: bool,
     committed_line_count: usize,
     width: Option<usize>,
 }
@@ -19,6 +39,7 @@ impl MarkdownStreamCollector {
     pub fn new(width: Option<usize>) -> Self {
         Self {
             buffer: String::new(),
+            pending_cr: false,
             committed_line_count: 0,
             width,
         }
@@ -26,6 +47,7 @@ impl MarkdownStreamCollector {
 
     pub fn clear(&mut self) {
         self.buffer.clear();
+        self.pending_cr = false;
         self.committed_line_count = 0;
     }
 
@@ -33,8 +55,10 @@ impl MarkdownStreamCollector {
         self.width = width;
     }
 
+    /// Append a delta; `\r\n` counts as one newline and a bare `\r`
+    /// redraws the not yet committed tail line.
     pub fn push_delta(&mut self, delta: &str) {
-        self.buffer.push_str(delta);
+        push_with_carriage_returns(&mut self.buffer, &mut self.pending_cr, delta);
     }
 
     pub fn current_tail(&self) -> &str {
@@ -69,32 +93,406 @@ impl MarkdownStreamCollector {
         out
     }
 
-    /// Finalize the stream and emit remaining lines.
+    /// Finalize the stream and emit remaining lines, followed by the
+    /// footnotes section if the stream defined any.
     pub fn finalize_and_drain(&mut self) -> Vec<Line<'static>> {
         let mut source = self.buffer.clone();
         if !source.ends_with('\n') {
             source.push('\n');
         }
 
-        let rendered = render_markdown_lines(&source, self.width);
+        let (rendered, footnotes) = render_with_footnotes(&source, self.width, false);
         let mut end = rendered.len();
         // Strip trailing blank lines (consistent with commit_complete_lines)
         while end > self.committed_line_count && is_blank_line_spaces_only(&rendered[end - 1]) {
             end -= 1;
         }
 
-        let out = if self.committed_line_count >= end {
+        let mut out = if self.committed_line_count >= end {
             Vec::new()
         } else {
             rendered[self.committed_line_count..end].to_vec()
         };
+        if let Some(footnotes) = footnotes {
+            out.extend(footnote_section_lines(&footnotes, self.width));
+        }
 
         self.clear();
         out
     }
 }
 
+/// Render `source` with footnote references as markers and definitions
+/// left out; see [`render_markdown_with_footnotes`] for the section.
 pub fn render_markdown_lines(source: &str, width: Option<usize>) -> Vec<Line<'static>> {
+    render_with_footnotes(source, width, false).0
+}
+
+/// Render a complete message, with its footnotes section at the end.
+/// References to labels the message never defines stay literal.
+pub fn render_markdown_with_footnotes(source: &str, width: Option<usize>) -> Vec<Line<'static>> {
+    let (mut lines, footnotes) = render_with_footnotes(source, width, true);
+    if let Some(footnotes) = footnotes {
+        lines.extend(footnote_section_lines(&footnotes, width));
+    }
+    lines
+}
+
+fn render_with_footnotes(
+    source: &str,
+    width: Option<usize>,
+    defined_only: bool,
+) -> (Vec<Line<'static>>, Option<Footnotes>) {
+    match extract_footnotes(source, defined_only) {
+        Some((text, footnotes)) => {
+            let lines = style_footnote_markers(render_segments(&text, width), &footnotes);
+            (lines, Some(footnotes))
+        }
+        None => (render_segments(source, width), None),
+    }
+}
+
+fn render_segments(source: &str, width: Option<usize>) -> Vec<Line<'static>> {
+    let segments = split_segments(source);
+    if let [Segment::Text(text)] = segments.as_slice() {
+        return render_markdown_block(text, width);
+    }
+
+    // Segments are rendered separately, so blank lines between them are
+    // re-inserted here instead of being left to the markdown renderer.
+    let mut lines: Vec<Line<'static>> = Vec::new();
+    let mut pending_gap = false;
+    for segment in &segments {
+        let (rendered, gap_before, gap_after) = match segment {
+            Segment::Text(text) => {
+                let gap_before = text.lines().next().is_some_and(is_blank_source_line);
+                let gap_after = text.lines().last().is_some_and(is_blank_source_line);
+                (
+                    trim_blank_lines(render_markdown_block(text, width)),
+                    gap_before,
+                    gap_after,
+                )
+            }
+            Segment::Quote(inner) => (render_blockquote(inner, width), false, false),
+            Segment::Rule => (vec![render_horizontal_rule(width)], false, false),
+        };
+
+        pending_gap |= gap_before;
+        if rendered.is_empty() {
+            continue;
+        }
+        if pending_gap && !lines.is_empty() {
+            lines.push(Line::from(""));
+        }
+        lines.extend(rendered);
+        pending_gap = gap_after;
+    }
+
+    if lines.is_empty() {
+        lines.push(Line::from(""));
+    }
+    lines
+}
+
+/// A run of source lines that is regular markdown, one blockquote with its
+/// outermost `>` level stripped, or a single horizontal rule.
+enum Segment {
+    Text(String),
+    Quote(String),
+    Rule,
+}
+
+/// Split `source` into blockquote, horizontal-rule and other runs. Lines
+/// inside fenced code blocks or YAML front matter are never treated as
+/// quotes or rules. Front matter needs its closing `---` (or `...`) line;
+/// without one a leading `---` is a rule.
+fn split_segments(source: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut current: Option<Segment> = None;
+    let mut in_fence = false;
+    let mut in_front_matter = false;
+    // A `---` directly under a paragraph line is a setext heading underline
+    let mut prev_is_text = false;
+
+    for (index, line) in source.split_inclusive('\n').enumerate() {
+        if index == 0 && line.trim_end() == "---" {
+            in_front_matter = source
+                .lines()
+                .skip(1)
+                .any(|line| matches!(line.trim_end(), "---" | "..."));
+        } else if in_front_matter && matches!(line.trim_end(), "---" | "...") {
+            in_front_matter = false;
+        }
+
+        let literal = in_fence || in_front_matter;
+        let quoted = if literal {
+            None
+        } else {
+            strip_blockquote_marker(line)
+        };
+        if quoted.is_none() && is_fence_line(line) {
+            in_fence = !in_fence;
+        }
+        let is_rule = !literal
+            && quoted.is_none()
+            && is_horizontal_rule(line)
+            && !(prev_is_text && line.trim_start().starts_with('-'));
+        prev_is_text = quoted.is_none() && !is_rule && !is_blank_source_line(line);
+
+        if is_rule {
+            segments.extend(current.take());
+            segments.push(Segment::Rule);
+            continue;
+        }
+
+        match (quoted, current.as_mut()) {
+            (Some(inner), Some(Segment::Quote(buf))) => buf.push_str(inner),
+            (None, Some(Segment::Text(buf))) => buf.push_str(line),
+            (quoted, _) => {
+                segments.extend(current.take());
+                current = Some(match quoted {
+                    Some(inner) => Segment::Quote(inner.to_string()),
+                    None => Segment::Text(line.to_string()),
+                });
+            }
+        }
+    }
+
+    segments.extend(current);
+    if segments.is_empty() {
+        segments.push(Segment::Text(String::new()));
+    }
+    segments
+}
+
+/// Whether `line` is a markdown thematic break: three or more `-`, `*` or
+/// `_` (optionally space-separated) and nothing else.
+fn is_horizontal_rule(line: &str) -> bool {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return false;
+    }
+    let mut marks = trimmed.chars().filter(|c| !c.is_whitespace());
+    let Some(marker) = marks.next().filter(|c| matches!(c, '-' | '*' | '_')) else {
+        return false;
+    };
+    let mut count = 1;
+    for c in marks {
+        if c != marker {
+            return false;
+        }
+        count += 1;
+    }
+    count >= 3
+}
+
+/// A full-width dim line standing in for a markdown horizontal rule.
+fn render_horizontal_rule(width: Option<usize>) -> Line<'static> {
+    let width = width
+        .filter(|w| *w > 0)
+        .unwrap_or(HORIZONTAL_RULE_FALLBACK_WIDTH);
+    Line::from(Span::styled(
+        "─".repeat(width),
+        Style::default()
+            .fg(Theme::global().muted_fg)
+            .add_modifier(Modifier::DIM),
+    ))
+}
+
+/// Return the rest of `line` after its `>` marker (and one optional space),
+/// or `None` if the line is not a blockquote line.
+fn strip_blockquote_marker(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let rest = trimmed.strip_prefix('>')?;
+    Some(rest.strip_prefix(' ').unwrap_or(rest))
+}
+
+pub(super) fn is_fence_line(line: &str) -> bool {
+    let trimmed = line.trim_start_matches(' ');
+    line.len() - trimmed.len() <= 3 && (trimmed.starts_with("```") || trimmed.starts_with("~~~"))
+}
+
+fn is_blank_source_line(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+/// Render the body of a blockquote with a dim bar gutter and dimmed text.
+/// Nested quotes recurse, so each level adds another bar.
+fn render_blockquote(inner: &str, width: Option<usize>) -> Vec<Line<'static>> {
+    let bar_width = BLOCKQUOTE_BAR.chars().count();
+    let inner_width = width.map(|w| w.saturating_sub(bar_width).max(1));
+    let bar_style = Style::default().fg(Theme::global().muted_fg);
+
+    render_segments(inner, inner_width)
+        .into_iter()
+        .map(|line| {
+            let mut spans = Vec::with_capacity(line.spans.len() + 1);
+            spans.push(Span::styled(BLOCKQUOTE_BAR, bar_style));
+            spans.extend(line.spans.into_iter().map(|span| Span {
+                style: span.style.add_modifier(Modifier::DIM),
+                content: span.content,
+            }));
+            Line {
+                style: line.style,
+                alignment: line.alignment,
+                spans,
+            }
+        })
+        .collect()
+}
+
+fn trim_blank_lines(mut lines: Vec<Line<'static>>) -> Vec<Line<'static>> {
+    while lines.last().is_some_and(is_blank_line_spaces_only) {
+        lines.pop();
+    }
+    let leading = lines
+        .iter()
+        .take_while(|line| is_blank_line_spaces_only(line))
+        .count();
+    lines.drain(..leading);
+    lines
+}
+
+fn render_markdown_block(source: &str, width: Option<usize>) -> Vec<Line<'static>> {
+    render_markdown_block_with(TerminalConfig::global().markdown_renderer, source, width)
+}
+
+fn render_markdown_block_with(
+    renderer: MarkdownRenderer,
+    source: &str,
+    width: Option<usize>,
+) -> Vec<Line<'static>> {
+    let layout = |source: &str| match renderer {
+        MarkdownRenderer::TuiMarkdown => layout_markdown(source, width),
+        MarkdownRenderer::Builtin => {
+            let mut lines = markdown::render_lines(source, width);
+            if lines.is_empty() {
+                lines.push(Line::from(""));
+            }
+            lines
+        }
+    };
+    match replace_task_markers(source) {
+        Some(source) => style_completed_tasks(layout(&source)),
+        None => layout(source),
+    }
+}
+
+/// Replace GitHub task-list markers (`- [ ]` / `- [x]`) with checkbox glyphs.
+/// Returns `None` when `source` contains no task items outside code fences.
+fn replace_task_markers(source: &str) -> Option<String> {
+    let mut out = String::with_capacity(source.len());
+    let mut in_fence = false;
+    let mut replaced = false;
+
+    for line in source.split_inclusive('\n') {
+        if is_fence_line(line) {
+            in_fence = !in_fence;
+        }
+        match (!in_fence).then(|| split_task_marker(line)).flatten() {
+            Some((prefix, done, rest)) => {
+                out.push_str(prefix);
+                out.push(if done { TASK_DONE } else { TASK_OPEN });
+                out.push_str(rest);
+                replaced = true;
+            }
+            None => out.push_str(line),
+        }
+    }
+
+    replaced.then_some(out)
+}
+
+/// Split a task-list item into the text before its `[ ]` marker, whether it
+/// is checked, and the text after the marker.
+fn split_task_marker(line: &str) -> Option<(&str, bool, &str)> {
+    let item = line.trim_start_matches(' ');
+    let body = if let Some(body) = item.strip_prefix(['-', '*', '+']) {
+        body
+    } else {
+        let digits = item.len() - item.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits == 0 {
+            return None;
+        }
+        item[digits..].strip_prefix(['.', ')'])?
+    };
+    let marker = body.trim_start_matches(' ');
+    if marker.len() == body.len() {
+        return None;
+    }
+
+    let done = match marker.get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    let rest = &marker[3..];
+    if !(rest.is_empty() || rest.starts_with([' ', '\n', '\r'])) {
+        return None;
+    }
+    let prefix = &line[..line.len() - marker.len()];
+    Some((prefix, done, rest))
+}
+
+/// Dim and strike through completed task items, including the wrapped rows
+/// that follow them up to the next blank row or list item.
+fn style_completed_tasks(mut lines: Vec<Line<'static>>) -> Vec<Line<'static>> {
+    let done_style = Style::default()
+        .fg(Theme::global().muted_fg)
+        .add_modifier(Modifier::DIM | Modifier::CROSSED_OUT);
+    let mut in_done_item = false;
+
+    for line in &mut lines {
+        let text = line
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect::<String>();
+        let starts_item =
+            text.trim_start().starts_with(['-', '*', '+', '•']) || text.contains(TASK_OPEN);
+        if text.contains(TASK_DONE) {
+            in_done_item = true;
+            strike_after_glyph(line, done_style);
+        } else if is_blank_line_spaces_only(line) || starts_item {
+            in_done_item = false;
+        } else if in_done_item {
+            for span in &mut line.spans {
+                span.style = span.style.patch(done_style);
+            }
+        }
+    }
+
+    lines
+}
+
+/// Apply `style` to everything from the checked glyph to the end of `line`.
+fn strike_after_glyph(line: &mut Line<'static>, style: Style) {
+    let mut spans = Vec::with_capacity(line.spans.len() + 1);
+    let mut found = false;
+    for span in line.spans.drain(..) {
+        if found {
+            spans.push(Span::styled(span.content, span.style.patch(style)));
+            continue;
+        }
+        match span.content.find(TASK_DONE) {
+            Some(index) => {
+                found = true;
+                let (before, after) = span.content.split_at(index);
+                if !before.is_empty() {
+                    spans.push(Span::styled(before.to_string(), span.style));
+                }
+                spans.push(Span::styled(after.to_string(), span.style.patch(style)));
+            }
+            None => spans.push(span),
+        }
+    }
+    line.spans = spans;
+}
+
+fn layout_markdown(source: &str, width: Option<usize>) -> Vec<Line<'static>> {
     let Some(width) = width.filter(|w| *w > 0) else {
         let text = md::from_str(source);
         let mut lines = text.lines.iter().map(line_to_static).collect::<Vec<_>>();
@@ -242,6 +640,22 @@ mod tests {
         assert_eq!(lines.len(), 1);
     }
 
+    #[test]
+    fn footnotes_collected_across_deltas() {
+        let mut collector = MarkdownStreamCollector::new(None);
+        collector.push_delta("[^early]: Defined first\nA claim[^ear");
+        collector.push_delta("ly] and another[^late].\n");
+        let committed = collector.commit_complete_lines();
+        assert_eq!(
+            committed.iter().map(plain).collect::<Vec<_>>(),
+            ["A claim¹ and another²."]
+        );
+
+        collector.push_delta("\n[^late]: Defined last");
+        let lines: Vec<String> = collector.finalize_and_drain().iter().map(plain).collect();
+        assert_eq!(lines, ["", "¹ Defined first", "² Defined last"]);
+    }
+
     #[test]
     fn finalize_commits_partial_line() {
         let mut collector = MarkdownStreamCollector::new(None);
@@ -261,4 +675,161 @@ mod tests {
             lines.iter().map(plain).collect::<Vec<_>>()
         );
     }
+
+    #[test]
+    fn blockquotes_render_with_bar_gutter() {
+        let source = "intro\n\n> quoted\n>> nested\n\n```\n> not a quote\n```\n";
+        let lines = render_markdown_lines(source, None);
+        let plain_lines = lines.iter().map(plain).collect::<Vec<_>>();
+
+        assert_eq!(plain_lines[0], "intro");
+        assert_eq!(plain_lines[1], "");
+        assert_eq!(plain_lines[2], "▎ quoted");
+        assert_eq!(plain_lines[3], "▎ ▎ nested");
+        assert_eq!(plain_lines[4], "");
+        assert!(plain_lines.contains(&"> not a quote".to_string()));
+
+        let quoted = &lines[2];
+        assert!(quoted.spans[1].style.add_modifier.contains(Modifier::DIM));
+    }
+
+    #[test]
+    fn carriage_returns_redraw_the_tail_line() {
+        let mut collector = MarkdownStreamCollector::new(None);
+        collector.push_delta("first\r");
+        collector.push_delta("\nDownloading 50%\rDownloading 100%");
+        assert_eq!(collector.current_tail(), "Downloading 100%");
+
+        let committed = collector.commit_complete_lines();
+        assert_eq!(committed.iter().map(plain).collect::<Vec<_>>(), ["first"]);
+
+        collector.push_delta("\r\n");
+        let lines = collector.finalize_and_drain();
+        assert_eq!(
+            lines.iter().map(plain).collect::<Vec<_>>(),
+            ["Downloading 100%"]
+        );
+    }
+
+    #[test]
+    fn blockquote_marker_before_content_commits_bar() {
+        let mut collector = MarkdownStreamCollector::new(Some(20));
+        collector.push_delta(">");
+        assert!(collector.commit_complete_lines().is_empty());
+        assert_eq!(
+            plain(&render_markdown_lines(collector.current_tail(), None)[0]),
+            "▎ "
+        );
+
+        collector.push_delta(" first\n> second\n");
+        let lines = collector.commit_complete_lines();
+        let plain_lines = lines.iter().map(plain).collect::<Vec<_>>();
+        assert!(plain_lines.iter().all(|line| line.starts_with("▎ ")));
+        assert!(plain_lines[0].contains("first"));
+    }
+
+    #[test]
+    fn horizontal_rules_span_the_width() {
+        let lines = render_markdown_lines("above\n\n---\n\nbelow\n* * *\n", Some(12));
+        let plain_lines = lines
+            .iter()
+            .map(|line| plain(line).trim_end().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            plain_lines,
+            vec![
+                "above",
+                "",
+                "─".repeat(12).as_str(),
+                "",
+                "below",
+                "─".repeat(12).as_str()
+            ]
+        );
+    }
+
+    #[test]
+    fn setext_tables_and_front_matter_are_not_rules() {
+        let rule = "─".repeat(12);
+        for source in [
+            "Heading\n---\n",
+            "| a | b |\n|---|---|\n",
+            "---\ntitle: x\n---\nbody\n",
+            "```\n---\n```\n",
+        ] {
+            let lines = render_markdown_lines(source, Some(12));
+            assert!(
+                lines.iter().all(|line| plain(line) != rule),
+                "unexpected rule in {source:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn leading_rule_without_closing_line_is_not_front_matter() {
+        let rule = "─".repeat(12);
+        let lines = render_markdown_lines("---\nSummary\n", Some(12));
+        assert_eq!(plain(&lines[0]), rule);
+        assert_eq!(plain(&lines[1]).trim_end(), "Summary");
+    }
+
+    #[test]
+    fn builtin_renderer_lays_out_blocks() {
+        let source = "# Plan\n- [x] write **tests**\n- [ ] ship\n";
+        let lines = render_markdown_block_with(MarkdownRenderer::Builtin, source, Some(20));
+        assert_eq!(
+            lines.iter().map(plain).collect::<Vec<_>>(),
+            ["Plan", "• ☑ write tests", "• ☐ ship"]
+        );
+        assert!(lines[0].spans[0]
+            .style
+            .add_modifier
+            .contains(Modifier::BOLD));
+        // Completed items are struck through from the checkbox on
+        let done = lines[1].spans.last().unwrap();
+        assert_eq!(done.content, "tests");
+        assert!(done
+            .style
+            .add_modifier
+            .contains(Modifier::BOLD | Modifier::CROSSED_OUT));
+    }
+
+    #[test]
+    fn task_list_items_render_with_checkboxes() {
+        let source = "- [ ] open\n  - [x] done\n1. [X] numbered\n\n```\n- [ ] code\n```\n";
+        let lines = render_markdown_lines(source, None);
+        let plain_lines = lines.iter().map(plain).collect::<Vec<_>>();
+
+        assert_eq!(plain_lines[0], "- ☐ open");
+        assert_eq!(plain_lines[1], "  - ☑ done");
+        assert_eq!(plain_lines[2], "1. ☑ numbered");
+        assert!(plain_lines.contains(&"- [ ] code".to_string()));
+
+        let done = lines[1].spans.last().unwrap();
+        assert!(done.content.starts_with('☑'));
+        assert!(done.style.add_modifier.contains(Modifier::CROSSED_OUT));
+        assert!(!lines[0]
+            .spans
+            .iter()
+            .any(|span| span.style.add_modifier.contains(Modifier::CROSSED_OUT)));
+    }
+
+    #[test]
+    fn task_marker_split_across_deltas() {
+        let mut collector = MarkdownStreamCollector::new(None);
+        collector.push_delta("- [");
+        assert!(collector.commit_complete_lines().is_empty());
+        collector.push_delta("x] shipped\n");
+        let lines = collector.commit_complete_lines();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(plain(&lines[0]), "- ☑ shipped");
+    }
+
+    #[test]
+    fn brackets_that_are_not_task_markers_are_kept() {
+        for source in ["- [link](url)\n", "[ ] not a list\n", "-[x] no space\n"] {
+            let lines = render_markdown_lines(source, None);
+            assert_eq!(plain(&lines[0]), source.trim_end());
+        }
+    }
 }
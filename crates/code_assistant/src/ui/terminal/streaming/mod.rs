@@ -9,6 +9,7 @@ use self::markdown_stream::MarkdownStreamCollector;
 pub mod chunking;
 pub mod commit_tick;
 pub mod controller;
+pub mod footnotes;
 pub mod markdown_stream;
 
 pub struct QueuedLine {
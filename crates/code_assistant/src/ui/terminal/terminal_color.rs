@@ -7,8 +7,27 @@
 // on light terminals it blends black at 4% opacity.
 
 use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
 use std::sync::OnceLock;
 
+use super::color_support::{self, ColorDepth};
+use super::config::TerminalConfig;
+use super::theme;
+
+/// How to decide whether the terminal background is light or dark.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackgroundMode {
+    /// Use the background reported by the terminal (OSC 11)
+    #[default]
+    Auto,
+    Light,
+    Dark,
+}
+
+/// Background assumed when light mode is forced but no light bg was detected.
+const ASSUMED_LIGHT_BG: (u8, u8, u8) = (255, 255, 255);
+
 /// Cached terminal background color, queried once at startup.
 static TERMINAL_BG: OnceLock<Option<(u8, u8, u8)>> = OnceLock::new();
 
@@ -23,11 +42,38 @@ pub fn terminal_bg() -> Option<(u8, u8, u8)> {
     *TERMINAL_BG.get_or_init(query_terminal_bg)
 }
 
+/// Background used for palette decisions, honoring a forced light/dark mode.
+/// A detected color is only used when it agrees with the forced mode.
+fn resolve_bg(detected: Option<(u8, u8, u8)>, mode: BackgroundMode) -> Option<(u8, u8, u8)> {
+    match mode {
+        BackgroundMode::Auto => detected,
+        BackgroundMode::Light => Some(
+            detected
+                .filter(|bg| is_light(*bg))
+                .unwrap_or(ASSUMED_LIGHT_BG),
+        ),
+        BackgroundMode::Dark => detected.filter(|bg| !is_light(*bg)),
+    }
+}
+
+/// The effective background color (detected, or implied by the configured mode).
+pub fn effective_bg() -> Option<(u8, u8, u8)> {
+    resolve_bg(terminal_bg(), TerminalConfig::global().background)
+}
+
+/// Whether the UI should use a palette suited for light backgrounds.
+pub fn is_light_background() -> bool {
+    effective_bg().is_some_and(is_light)
+}
+
 /// Compute the composer background color based on the terminal's actual background.
 /// Returns a subtle overlay: white at 12% on dark, black at 4% on light terminals.
 /// Falls back to a reasonable default if the terminal bg couldn't be detected.
 pub fn composer_bg() -> Color {
-    match terminal_bg() {
+    if theme::is_monochrome() {
+        return Color::Reset;
+    }
+    let tint = match effective_bg() {
         Some(bg) => {
             let (top, alpha) = if is_light(bg) {
                 ((0, 0, 0), 0.04)
@@ -38,13 +84,21 @@ pub fn composer_bg() -> Color {
             Color::Rgb(r, g, b)
         }
         None => Color::Rgb(40, 40, 40), // fallback for terminals that don't support OSC 11
-    }
+    };
+    background_tint(tint)
 }
 
 /// Compute a subtle background tint for tool content areas (diffs, terminal output).
 /// Slightly less prominent than the composer background so it blends more gently.
+/// A background configured on the theme replaces the derived tint.
 pub fn tool_content_bg() -> Color {
-    match terminal_bg() {
+    if theme::is_monochrome() {
+        return Color::Reset;
+    }
+    if let Some(color) = theme::Theme::global().tool_content_bg {
+        return color;
+    }
+    let tint = match effective_bg() {
         Some(bg) => {
             let (top, alpha) = if is_light(bg) {
                 ((0, 0, 0), 0.03)
@@ -55,6 +109,26 @@ pub fn tool_content_bg() -> Color {
             Color::Rgb(r, g, b)
         }
         None => Color::Rgb(35, 35, 35), // fallback for terminals that don't support OSC 11
+    };
+    background_tint(tint)
+}
+
+/// Tool content background for a specific tool, honoring per-tool overrides.
+pub fn tool_content_bg_for(tool_name: &str) -> Color {
+    if theme::is_monochrome() {
+        return Color::Reset;
+    }
+    theme::Theme::global()
+        .tool_content_bg_override(tool_name)
+        .unwrap_or_else(tool_content_bg)
+}
+
+/// A blended tint as the terminal can show it. Without the 256-color
+/// palette the nearest named color is too far off, so no tint is used.
+fn background_tint(tint: Color) -> Color {
+    match color_support::ColorSupport::global().depth {
+        ColorDepth::Ansi16 => Color::Reset,
+        _ => color_support::adapt(tint),
     }
 }
 
@@ -102,6 +176,26 @@ mod tests {
         assert!(!is_light((40, 40, 40))); // dark grey
     }
 
+    #[test]
+    fn test_resolve_bg_respects_forced_mode() {
+        let dark = Some((20, 20, 20));
+        let light = Some((250, 250, 250));
+
+        assert_eq!(resolve_bg(dark, BackgroundMode::Auto), dark);
+        assert_eq!(resolve_bg(None, BackgroundMode::Auto), None);
+
+        // Forcing light ignores a detected dark bg and assumes white
+        assert_eq!(
+            resolve_bg(dark, BackgroundMode::Light),
+            Some(ASSUMED_LIGHT_BG)
+        );
+        assert_eq!(resolve_bg(light, BackgroundMode::Light), light);
+
+        // Forcing dark drops a detected light bg so dark fallbacks apply
+        assert_eq!(resolve_bg(light, BackgroundMode::Dark), None);
+        assert_eq!(resolve_bg(dark, BackgroundMode::Dark), dark);
+    }
+
     #[test]
     fn test_blend_dark_bg() {
         // On a dark background (0,0,0), blending white at 12% should give (30,30,30)
@@ -6,8 +6,10 @@ use crossterm::event::KeyEvent;
 use crossterm::event::KeyModifiers;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::{Modifier, Style};
 use ratatui::widgets::WidgetRef;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
 use std::cell::Ref;
 use std::cell::RefCell;
 use std::ops::Range;
@@ -15,10 +17,52 @@ use textwrap::Options;
 use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
+use super::theme::Theme;
+
 const WORD_SEPARATORS: &str = "`~!@#$%^&*()-=+[{]}\\|;:'\",.<>/?";
 
-fn is_word_separator(ch: char) -> bool {
-    WORD_SEPARATORS.contains(ch)
+/// Prose keeps hyphenated words together and splits on underscores.
+const PROSE_WORD_SEPARATORS: &str = "`~!@#$%^&*()_=+[{]}\\|;:'\",.<>/?";
+
+/// Which punctuation ends a word for word navigation and deletion.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WordSeparators {
+    /// `snake_case` is one word, `kebab-case` is two
+    #[default]
+    Code,
+    /// `kebab-case` is one word, `snake_case` is two
+    Prose,
+}
+
+impl WordSeparators {
+    fn is_separator(self, ch: char) -> bool {
+        match self {
+            WordSeparators::Code => WORD_SEPARATORS.contains(ch),
+            WordSeparators::Prose => PROSE_WORD_SEPARATORS.contains(ch),
+        }
+    }
+}
+
+/// How long lines are broken into rows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WrapMode {
+    /// Fill each row as far as it goes
+    #[default]
+    FirstFit,
+    /// Balance row lengths across a paragraph, which reads better for prose
+    OptimalFit,
+    /// One row per line; the view scrolls sideways to keep the cursor visible
+    NoWrap,
+}
+
+/// Case change applied by Alt+U, Alt+L and Alt+C.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordCase {
+    Upper,
+    Lower,
+    Capitalize,
 }
 
 /// On Windows, AltGr sends ALT+CONTROL together. Detect this to avoid
@@ -42,7 +86,7 @@ struct TextElement {
     range: Range<usize>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TextArea {
     text: String,
     cursor_pos: usize,
@@ -50,12 +94,23 @@ pub struct TextArea {
     preferred_col: Option<usize>,
     kill_buffer: String,
     elements: Vec<TextElement>,
+    word_separators: WordSeparators,
+    wrap_mode: WrapMode,
+    /// Columns scrolled off the left edge in [`WrapMode::NoWrap`]
+    scroll_x: Cell<usize>,
+    /// Other end of the region started with Ctrl+Space; the cursor is the
+    /// active end.
+    mark: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
 struct WrapCache {
     width: u16,
     lines: Vec<Range<usize>>,
+    /// Bytes of text wrapped since the cache was built, to check that edits
+    /// only rewrap what they touch
+    #[cfg(test)]
+    wrapped_bytes: usize,
 }
 
 impl TextArea {
@@ -67,16 +122,32 @@ impl TextArea {
             preferred_col: None,
             kill_buffer: String::new(),
             elements: Vec::new(),
+            word_separators: WordSeparators::default(),
+            wrap_mode: WrapMode::default(),
+            scroll_x: Cell::new(0),
+            mark: None,
         }
     }
 
+    pub fn set_word_separators(&mut self, word_separators: WordSeparators) {
+        self.word_separators = word_separators;
+    }
+
+    pub fn set_wrap_mode(&mut self, wrap_mode: WrapMode) {
+        self.wrap_mode = wrap_mode;
+        self.wrap_cache.replace(None);
+        self.scroll_x.set(0);
+    }
+
     pub fn clear(&mut self) {
         self.text.clear();
         self.cursor_pos = 0;
         self.wrap_cache.replace(None);
+        self.scroll_x.set(0);
         self.preferred_col = None;
         self.kill_buffer.clear();
         self.elements.clear();
+        self.mark = None;
     }
 
     pub fn text(&self) -> &str {
@@ -94,11 +165,12 @@ impl TextArea {
     pub fn insert_str_at(&mut self, pos: usize, text: &str) {
         let pos = self.clamp_pos_for_insertion(pos);
         self.text.insert_str(pos, text);
-        self.wrap_cache.replace(None);
+        self.update_wrap_cache(pos, 0, text.len());
         if pos <= self.cursor_pos {
             self.cursor_pos += text.len();
         }
         self.shift_elements(pos, 0, text.len());
+        self.shift_mark(pos, pos, text.len());
         self.preferred_col = None;
     }
 
@@ -108,10 +180,11 @@ impl TextArea {
         let start = self.clamp_pos_for_insertion(self.cursor_pos);
         // Insert raw text
         self.text.insert_str(start, text);
-        self.wrap_cache.replace(None);
+        self.update_wrap_cache(start, 0, text.len());
         let end = start + text.len();
         // Shift existing elements
         self.shift_elements(start, 0, text.len());
+        self.shift_mark(start, start, text.len());
         // Register the new element
         self.elements.push(TextElement { range: start..end });
         self.elements.sort_by_key(|e| e.range.start);
@@ -120,6 +193,11 @@ impl TextArea {
         self.preferred_col = None;
     }
 
+    /// Byte ranges of all elements, in text order.
+    pub fn element_ranges(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.elements.iter().map(|e| e.range.clone())
+    }
+
     /// Returns true if the textarea has any elements (paste placeholders, image indicators).
     pub fn has_elements(&self) -> bool {
         !self.elements.is_empty()
@@ -137,7 +215,7 @@ impl TextArea {
         let diff = inserted_len as isize - removed_len as isize;
 
         self.text.replace_range(start..end, text);
-        self.wrap_cache.replace(None);
+        self.update_wrap_cache(start, removed_len, inserted_len);
         self.preferred_col = None;
         self.update_elements_after_replace(start, end, inserted_len);
 
@@ -151,6 +229,7 @@ impl TextArea {
         .min(self.text.len());
 
         self.cursor_pos = self.clamp_pos_to_nearest_boundary(self.cursor_pos);
+        self.shift_mark(start, end, inserted_len);
     }
 
     pub fn cursor(&self) -> usize {
@@ -169,6 +248,31 @@ impl TextArea {
         self.wrapped_lines(width).len().max(1) as u16
     }
 
+    /// Number of logical lines, counting an empty line after a trailing newline.
+    pub fn line_count(&self) -> usize {
+        self.text.matches('\n').count() + 1
+    }
+
+    /// The 1-based logical line number for each wrapped row at `width`, set
+    /// only on the first row of each line; continuation rows get `None`.
+    pub fn row_line_numbers(&self, width: u16) -> Vec<Option<usize>> {
+        let lines = self.wrapped_lines(width.max(1));
+        let mut scanned = 0;
+        let mut line = 0;
+        let mut last = None;
+        lines
+            .iter()
+            .map(|range| {
+                let start = range.start.min(self.text.len());
+                line += self.text[scanned..start].matches('\n').count();
+                scanned = start;
+                let first_row = last != Some(line);
+                last = Some(line);
+                first_row.then_some(line + 1)
+            })
+            .collect()
+    }
+
     /// Compute the on-screen cursor position.
     pub fn cursor_position(&self, area: Rect) -> Option<(u16, u16)> {
         if area.width == 0 {
@@ -177,8 +281,31 @@ impl TextArea {
         let lines = self.wrapped_lines(area.width);
         let i = Self::wrapped_line_index_by_start(&lines, self.cursor_pos)?;
         let ls = &lines[i];
-        let col = self.text[ls.start..self.cursor_pos].width() as u16;
-        Some((area.x + col, area.y + i as u16))
+        let col = self.text[ls.start..self.cursor_pos].width() - self.scroll_x(&lines, area.width);
+        Some((area.x + col as u16, area.y + i as u16))
+    }
+
+    /// Columns scrolled off the left edge at `width`. In no-wrap mode the
+    /// offset follows the cursor just far enough to keep it in view;
+    /// wrapped rows never scroll.
+    fn scroll_x(&self, lines: &[Range<usize>], width: u16) -> usize {
+        if self.wrap_mode != WrapMode::NoWrap {
+            return 0;
+        }
+        let Some(row) = Self::wrapped_line_index_by_start(lines, self.cursor_pos) else {
+            return 0;
+        };
+        let col = self.text[lines[row].start..self.cursor_pos].width();
+        let width = width.max(1) as usize;
+        let mut scroll = self.scroll_x.get();
+        if col < scroll {
+            scroll = col;
+        } else if col >= scroll + width {
+            // Room for the cursor cell after the last character
+            scroll = col + 1 - width;
+        }
+        self.scroll_x.set(scroll);
+        scroll
     }
 
     pub fn input(&mut self, event: KeyEvent) {
@@ -272,8 +399,28 @@ impl TextArea {
                 modifiers: KeyModifiers::CONTROL,
                 ..
             } => {
-                self.delete_backward_word();
+                if self.mark.is_some() {
+                    self.kill_region();
+                } else {
+                    self.delete_backward_word();
+                }
+            }
+            // Terminals report Ctrl+Space either as a control-modified space or as NUL
+            KeyEvent {
+                code: KeyCode::Char(' '),
+                modifiers: KeyModifiers::CONTROL,
+                ..
             }
+            | KeyEvent {
+                code: KeyCode::Null,
+                ..
+            } => self.toggle_mark(),
+            // Ctrl+G cancels the region, as keyboard-quit does in Emacs
+            KeyEvent {
+                code: KeyCode::Char('g'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.mark = None,
             // Meta-b / Meta-f for word navigation
             KeyEvent {
                 code: KeyCode::Char('b'),
@@ -310,6 +457,31 @@ impl TextArea {
             } => {
                 self.yank();
             }
+            KeyEvent {
+                code: KeyCode::Char('t'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.transpose_chars(),
+            KeyEvent {
+                code: KeyCode::Char('t'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.transpose_words(),
+            KeyEvent {
+                code: KeyCode::Char('u'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.change_word_case(WordCase::Upper),
+            KeyEvent {
+                code: KeyCode::Char('l'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.change_word_case(WordCase::Lower),
+            KeyEvent {
+                code: KeyCode::Char('c'),
+                modifiers: KeyModifiers::ALT,
+                ..
+            } => self.change_word_case(WordCase::Capitalize),
             // Cursor movement
             KeyEvent {
                 code: KeyCode::Left,
@@ -426,6 +598,117 @@ impl TextArea {
         }
     }
 
+    /// Swap the characters before and at the cursor and move past both; at
+    /// the end of a line, swap the last two characters instead.
+    pub fn transpose_chars(&mut self) {
+        let bol = self.beginning_of_current_line();
+        if self.cursor_pos == bol {
+            return;
+        }
+        let mid = if self.cursor_pos == self.end_of_current_line() {
+            self.prev_atomic_boundary(self.cursor_pos)
+        } else {
+            self.cursor_pos
+        };
+        let start = self.prev_atomic_boundary(mid);
+        let end = self.next_atomic_boundary(mid);
+        if start < bol || start == mid || end == mid || self.touches_element(start..end) {
+            return;
+        }
+        let swapped = format!("{}{}", &self.text[mid..end], &self.text[start..mid]);
+        self.replace_range(start..end, &swapped);
+        self.set_cursor(end);
+    }
+
+    /// Swap the word before the cursor with the word after it and move past
+    /// both; at the end of the text, swap the last two words.
+    pub fn transpose_words(&mut self) {
+        let mut second_end = self.word_end_after(self.cursor_pos);
+        if self.text[self.cursor_pos..].trim().is_empty() {
+            second_end = self.word_end_after(self.word_start_before(self.cursor_pos));
+        }
+        let second_start = self.word_start_before(second_end);
+        let first_start = self.word_start_before(second_start);
+        let first_end = self.word_end_after(first_start);
+        if first_start == second_start
+            || first_end > second_start
+            || self.touches_element(first_start..second_end)
+        {
+            return;
+        }
+        let swapped = format!(
+            "{}{}{}",
+            &self.text[second_start..second_end],
+            &self.text[first_end..second_start],
+            &self.text[first_start..first_end],
+        );
+        self.replace_range(first_start..second_end, &swapped);
+        self.set_cursor(second_end);
+    }
+
+    /// Change the case of the text from the cursor to the end of the next
+    /// word and move past it. Paste placeholders are skipped over unchanged.
+    pub fn change_word_case(&mut self, case: WordCase) {
+        let start = self.cursor_pos;
+        let end = self.end_of_next_word();
+        if end <= start {
+            return;
+        }
+        if self.touches_element(start..end) {
+            self.set_cursor(end);
+            return;
+        }
+        let word = &self.text[start..end];
+        let changed = match case {
+            WordCase::Upper => word.to_uppercase(),
+            WordCase::Lower => word.to_lowercase(),
+            WordCase::Capitalize => {
+                let mut capitalized = String::with_capacity(word.len());
+                let mut seen_alphanumeric = false;
+                for ch in word.chars() {
+                    if !seen_alphanumeric && ch.is_alphanumeric() {
+                        seen_alphanumeric = true;
+                        capitalized.extend(ch.to_uppercase());
+                    } else {
+                        capitalized.extend(ch.to_lowercase());
+                    }
+                }
+                capitalized
+            }
+        };
+        self.replace_range(start..end, &changed);
+        self.set_cursor(start + changed.len());
+    }
+
+    /// Set the mark at the cursor, or clear it if one is already set.
+    pub fn toggle_mark(&mut self) {
+        self.mark = match self.mark {
+            Some(_) => None,
+            None => Some(self.cursor_pos),
+        };
+    }
+
+    pub fn mark(&self) -> Option<usize> {
+        self.mark
+    }
+
+    /// Text between the mark and the cursor, widened to cover any element
+    /// it touches. `None` without a mark or when the region is empty.
+    pub fn region(&self) -> Option<Range<usize>> {
+        let mark = self.mark?;
+        let range = mark.min(self.cursor_pos)..mark.max(self.cursor_pos);
+        let range = self.expand_range_to_element_boundaries(range);
+        (range.start < range.end).then_some(range)
+    }
+
+    /// Cut the region into the kill buffer (for Ctrl+Y) and clear the mark.
+    pub fn kill_region(&mut self) {
+        if let Some(region) = self.region() {
+            self.kill_range(region);
+        }
+        self.mark = None;
+    }
+
     pub fn kill_to_end_of_line(&mut self) {
         let eol = self.end_of_current_line();
         let range = if self.cursor_pos == eol {
@@ -626,7 +909,16 @@ impl TextArea {
     // ####### Word Navigation #######
 
     fn beginning_of_previous_word(&self) -> usize {
-        let prefix = &self.text[..self.cursor_pos];
+        self.word_start_before(self.cursor_pos)
+    }
+
+    fn end_of_next_word(&self) -> usize {
+        self.word_end_after(self.cursor_pos)
+    }
+
+    /// Start of the word ending at or before `pos`.
+    fn word_start_before(&self, pos: usize) -> usize {
+        let prefix = &self.text[..pos];
         let Some((first_non_ws_idx, ch)) = prefix
             .char_indices()
             .rev()
@@ -634,10 +926,10 @@ impl TextArea {
         else {
             return 0;
         };
-        let is_separator = is_word_separator(ch);
+        let is_separator = self.word_separators.is_separator(ch);
         let mut start = first_non_ws_idx;
         for (idx, ch) in prefix[..first_non_ws_idx].char_indices().rev() {
-            if ch.is_whitespace() || is_word_separator(ch) != is_separator {
+            if ch.is_whitespace() || self.word_separators.is_separator(ch) != is_separator {
                 start = idx + ch.len_utf8();
                 break;
             }
@@ -646,20 +938,20 @@ impl TextArea {
         self.adjust_pos_out_of_elements(start, true)
     }
 
-    fn end_of_next_word(&self) -> usize {
-        let Some(first_non_ws) = self.text[self.cursor_pos..].find(|c: char| !c.is_whitespace())
-        else {
+    /// End of the word starting at or after `pos`.
+    fn word_end_after(&self, pos: usize) -> usize {
+        let Some(first_non_ws) = self.text[pos..].find(|c: char| !c.is_whitespace()) else {
             return self.text.len();
         };
-        let word_start = self.cursor_pos + first_non_ws;
+        let word_start = pos + first_non_ws;
         let mut iter = self.text[word_start..].char_indices();
         let Some((_, first_ch)) = iter.next() else {
             return word_start;
         };
-        let is_separator = is_word_separator(first_ch);
+        let is_separator = self.word_separators.is_separator(first_ch);
         let mut end = self.text.len();
         for (idx, ch) in iter {
-            if ch.is_whitespace() || is_word_separator(ch) != is_separator {
+            if ch.is_whitespace() || self.word_separators.is_separator(ch) != is_separator {
                 end = word_start + idx;
                 break;
             }
@@ -797,11 +1089,12 @@ impl TextArea {
                 None => true,
             };
             if needs_recalc {
-                let lines = wrap_ranges(
-                    &self.text,
-                    Options::new(width as usize).wrap_algorithm(textwrap::WrapAlgorithm::FirstFit),
-                );
-                *cache = Some(WrapCache { width, lines });
+                *cache = Some(WrapCache {
+                    width,
+                    lines: wrap_text(&self.text, width, self.wrap_mode),
+                    #[cfg(test)]
+                    wrapped_bytes: self.text.len(),
+                });
             }
         }
 
@@ -809,8 +1102,106 @@ impl TextArea {
         Ref::map(cache, |c| &c.as_ref().unwrap().lines)
     }
 
+    /// Patch the wrap cache after `removed` bytes at `start` were replaced by
+    /// `inserted` bytes. Rows before the edit and paragraphs after it are
+    /// reused; only the edited paragraph is rewrapped, starting one row
+    /// before the edit since a shorter word may now fit on that row.
+    /// Optimal fit balances whole paragraphs, so it rewraps the edited
+    /// paragraph in full; without wrapping the rows are just the lines.
+    fn update_wrap_cache(&self, start: usize, removed: usize, inserted: usize) {
+        let mut cache = self.wrap_cache.borrow_mut();
+        let Some(wrap) = cache.as_mut() else {
+            return;
+        };
+        let text = self.text.as_str();
+        if self.wrap_mode == WrapMode::NoWrap {
+            wrap.lines = line_ranges(text);
+            #[cfg(test)]
+            {
+                wrap.wrapped_bytes += text.len();
+            }
+            return;
+        }
+        let edit_end = start + inserted;
+
+        // Rewrapping must begin before the edited word, unless it is broken
+        // into row-sized pieces anyway, which keep their positions. Only look
+        // a row's worth of characters each way so long tokens stay cheap.
+        let limit = wrap.width as usize + 1;
+        let window_start = text[..start]
+            .char_indices()
+            .rev()
+            .nth(limit)
+            .map_or(0, |(i, _)| i);
+        let window_end = text[edit_end..]
+            .char_indices()
+            .nth(limit)
+            .map_or(text.len(), |(i, _)| edit_end + i);
+        let word_start = text[window_start..start]
+            .rfind([' ', '\n'])
+            .map(|i| window_start + i + 1);
+        let word_end = text[edit_end..window_end]
+            .find([' ', '\n'])
+            .map(|i| edit_end + i);
+        let word = word_start.unwrap_or(window_start)..word_end.unwrap_or(window_end);
+        let anchor = if self.wrap_mode == WrapMode::OptimalFit {
+            text[..start].rfind('\n').map_or(0, |i| i + 1)
+        } else if exceeds_width(&text[word.clone()], wrap.width as usize) {
+            start
+        } else if (word_start.is_some() || window_start == 0)
+            && (word_end.is_some() || window_end == text.len())
+        {
+            word.start
+        } else {
+            // Zero-width characters made the window too short to tell
+            0
+        };
+        let row = wrap
+            .lines
+            .partition_point(|line| line.start <= anchor)
+            .saturating_sub(1);
+        let resume_row = row.saturating_sub(1);
+        let resume = wrap.lines[resume_row].start;
+
+        let paragraph_end = text[edit_end..].find('\n').map(|i| edit_end + i);
+        let segment_end = paragraph_end.unwrap_or(text.len());
+        let rewrapped = wrap_ranges(
+            &text[resume..segment_end],
+            wrap_options(wrap.width, self.wrap_mode),
+        )
+        .into_iter()
+        .map(|line| line.start + resume..line.end + resume);
+
+        // Rows of later paragraphs only move by the size change
+        let following: Vec<Range<usize>> = match paragraph_end {
+            Some(newline) => {
+                let old_newline = newline - inserted + removed;
+                wrap.lines
+                    .iter()
+                    .filter(|line| line.start > old_newline)
+                    .map(|line| line.start + inserted - removed..line.end + inserted - removed)
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+
+        wrap.lines.truncate(resume_row);
+        wrap.lines.extend(rewrapped);
+        wrap.lines.extend(following);
+        #[cfg(test)]
+        {
+            wrap.wrapped_bytes += segment_end - resume;
+        }
+    }
+
     // ===== Element support =====
 
+    fn touches_element(&self, range: Range<usize>) -> bool {
+        self.elements
+            .iter()
+            .any(|e| e.range.start < range.end && range.start < e.range.end)
+    }
+
     fn find_element_containing(&self, pos: usize) -> Option<usize> {
         self.elements
             .iter()
@@ -912,18 +1303,35 @@ impl TextArea {
     fn update_elements_after_replace(&mut self, start: usize, end: usize, inserted_len: usize) {
         self.shift_elements(start, end.saturating_sub(start), inserted_len);
     }
+
+    /// Keep the mark on the same text after `start..end` was replaced by
+    /// `inserted_len` bytes. A mark inside the replaced text collapses to its
+    /// start; text inserted at the mark goes after it.
+    fn shift_mark(&mut self, start: usize, end: usize, inserted_len: usize) {
+        if let Some(mark) = self.mark {
+            let mark = if mark <= start {
+                mark
+            } else if mark <= end {
+                start
+            } else {
+                mark - (end - start) + inserted_len
+            };
+            self.mark = Some(self.clamp_pos_to_nearest_boundary(mark.min(self.text.len())));
+        }
+    }
 }
 
 /// Style used for highlighted element placeholders in the textarea.
 fn element_style() -> Style {
     Style::default()
-        .fg(Color::Cyan)
+        .fg(Theme::global().accent_fg)
         .add_modifier(Modifier::BOLD)
 }
 
 impl WidgetRef for &TextArea {
     fn render_ref(&self, area: Rect, buf: &mut Buffer) {
         let lines = self.wrapped_lines(area.width);
+        let scroll = self.scroll_x(&lines, area.width);
         for (row, idx) in (0..lines.len()).enumerate() {
             if row as u16 >= area.height {
                 break;
@@ -933,7 +1341,7 @@ impl WidgetRef for &TextArea {
             let line_range = r.start..r.end.saturating_sub(1);
             if let Some(text_slice) = self.text.get(line_range.clone()) {
                 // Draw the base line with default style.
-                buf.set_string(area.x, y, text_slice, Style::default());
+                set_scrolled_string(buf, area, y, text_slice, 0, scroll, Style::default());
 
                 // Overlay styled segments for elements that intersect this line.
                 for elem in &self.elements {
@@ -943,8 +1351,38 @@ impl WidgetRef for &TextArea {
                         continue;
                     }
                     if let Some(elem_slice) = self.text.get(overlap_start..overlap_end) {
-                        let col_offset = self.text[line_range.start..overlap_start].width() as u16;
-                        buf.set_string(area.x + col_offset, y, elem_slice, element_style());
+                        let col_offset = self.text[line_range.start..overlap_start].width();
+                        set_scrolled_string(
+                            buf,
+                            area,
+                            y,
+                            elem_slice,
+                            col_offset,
+                            scroll,
+                            element_style(),
+                        );
+                    }
+                }
+
+                // Highlight the part of the marked region on this line.
+                if let Some(region) = self.region() {
+                    let overlap_start = region.start.max(line_range.start);
+                    let overlap_end = region.end.min(line_range.end);
+                    if overlap_start < overlap_end {
+                        let col_start = self.text[line_range.start..overlap_start].width();
+                        let col_end = col_start + self.text[overlap_start..overlap_end].width();
+                        let visible_start = col_start.max(scroll) - scroll;
+                        let visible_end = col_end.max(scroll) - scroll;
+                        buf.set_style(
+                            Rect::new(
+                                area.x.saturating_add(visible_start as u16),
+                                y,
+                                (visible_end - visible_start) as u16,
+                                1,
+                            )
+                            .intersection(area),
+                            Style::default().add_modifier(Modifier::REVERSED),
+                        );
                     }
                 }
             }
@@ -952,6 +1390,77 @@ impl WidgetRef for &TextArea {
     }
 }
 
+/// Whether `text` is wider than `width` columns, without measuring all of it.
+fn exceeds_width(text: &str, width: usize) -> bool {
+    let mut total = 0;
+    text.chars().any(|ch| {
+        total += unicode_width::UnicodeWidthChar::width(ch).unwrap_or(0);
+        total > width
+    })
+}
+
+/// Draw `text`, which starts `col` columns into its row, with the first
+/// `scroll` columns of the row cut off. A wide character straddling the
+/// left edge is left out.
+fn set_scrolled_string(
+    buf: &mut Buffer,
+    area: Rect,
+    y: u16,
+    text: &str,
+    mut col: usize,
+    scroll: usize,
+    style: Style,
+) {
+    let mut rest = text;
+    while col < scroll {
+        let Some(ch) = rest.chars().next() else {
+            return;
+        };
+        col += unicode_width::UnicodeWidthChar::width(ch).unwrap_or(0);
+        rest = &rest[ch.len_utf8()..];
+    }
+    let x = area.x.saturating_add((col - scroll) as u16);
+    if x < area.right() {
+        buf.set_stringn(x, y, rest, (area.right() - x) as usize, style);
+    }
+}
+
+fn wrap_options(width: u16, mode: WrapMode) -> Options<'static> {
+    let algorithm = match mode {
+        WrapMode::OptimalFit => {
+            textwrap::WrapAlgorithm::OptimalFit(textwrap::wrap_algorithms::Penalties::new())
+        }
+        WrapMode::FirstFit | WrapMode::NoWrap => textwrap::WrapAlgorithm::FirstFit,
+    };
+    Options::new(width as usize).wrap_algorithm(algorithm)
+}
+
+/// Rows of `text` at `width` in `mode`.
+fn wrap_text(text: &str, width: u16, mode: WrapMode) -> Vec<Range<usize>> {
+    match mode {
+        WrapMode::NoWrap => line_ranges(text),
+        WrapMode::FirstFit | WrapMode::OptimalFit => wrap_ranges(text, wrap_options(width, mode)),
+    }
+}
+
+/// One row per line, in the same form as [`wrap_ranges`].
+fn line_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut start = 0;
+    text.split('\n')
+        .map(|line| {
+            let range = start..start + line.len() + 1;
+            start = range.end;
+            range
+        })
+        .collect()
+}
+
+/// Byte offset of `slice` within `text`, if it is a subslice of it.
+fn subslice_offset(text: &str, slice: &str) -> Option<usize> {
+    let start = (slice.as_ptr() as usize).checked_sub(text.as_ptr() as usize)?;
+    (start + slice.len() <= text.len()).then_some(start)
+}
+
 /// Compute byte ranges of wrapped lines using textwrap.
 /// Each range includes trailing whitespace and a sentinel +1 byte (matching codex convention).
 fn wrap_ranges<'a, O>(text: &str, width_or_options: O) -> Vec<Range<usize>>
@@ -960,26 +1469,36 @@ where
 {
     let opts = width_or_options.into();
     let mut lines: Vec<Range<usize>> = Vec::new();
-    for line in textwrap::wrap(text, opts).iter() {
-        match line {
-            std::borrow::Cow::Borrowed(slice) => {
-                let start = unsafe { slice.as_ptr().offset_from(text.as_ptr()) as usize };
-                let end = start + slice.len();
-                let trailing_spaces = text[end..].chars().take_while(|c| *c == ' ').count();
-                lines.push(start..end + trailing_spaces + 1);
-            }
-            std::borrow::Cow::Owned(_) => {
-                // textwrap may produce owned strings for certain edge cases;
-                // fall back to simple char-based ranges
-                let start = if let Some(prev) = lines.last() {
-                    prev.end
-                } else {
-                    0
-                };
-                let end = (start + line.len()).min(text.len());
-                lines.push(start..end + 1);
-            }
+    // Wrap paragraph by paragraph (as textwrap does internally) so every row
+    // can be located within its own paragraph
+    let mut paragraph_start = 0;
+    for paragraph in text.split('\n') {
+        let paragraph_end = paragraph_start + paragraph.len();
+        let mut pos = paragraph_start;
+        for (row, line) in textwrap::wrap(paragraph, &opts).iter().enumerate() {
+            let located = match line {
+                std::borrow::Cow::Borrowed(slice) => {
+                    subslice_offset(text, slice).map(|start| (start, slice.len()))
+                }
+                std::borrow::Cow::Owned(owned) => {
+                    let indent = if row == 0 {
+                        opts.initial_indent
+                    } else {
+                        opts.subsequent_indent
+                    };
+                    locate_owned_row(&text[pos..paragraph_end], owned, indent)
+                        .map(|(offset, len)| (pos + offset, len))
+                }
+            };
+            // Rows that can't be found (not expected) are kept empty at the
+            // current position rather than pointing at unrelated text
+            let (start, len) = located.unwrap_or((pos, 0));
+            let end = start + len;
+            let trailing_spaces = text[end..].chars().take_while(|c| *c == ' ').count();
+            lines.push(start..end + trailing_spaces + 1);
+            pos = end;
         }
+        paragraph_start = paragraph_end + 1;
     }
     // Ensure at least one line for empty text
     if lines.is_empty() {
@@ -988,6 +1507,21 @@ where
     lines
 }
 
+/// Find the text of an owned textwrap row in `rest` (the paragraph after the
+/// previous row). Owned rows carry indentation in front and may end with a
+/// hyphen that textwrap added when splitting a word; neither is in the text.
+/// Returns the offset into `rest` and length of the row's text.
+fn locate_owned_row(rest: &str, owned: &str, indent: &str) -> Option<(usize, usize)> {
+    let content = owned.strip_prefix(indent).unwrap_or(owned);
+    let candidates = [Some(content), content.strip_suffix('-')];
+    candidates.into_iter().flatten().find_map(|content| {
+        let skipped = rest.len() - rest.trim_start_matches(' ').len();
+        rest[skipped..]
+            .starts_with(content)
+            .then_some((skipped, content.len()))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1000,6 +1534,47 @@ mod tests {
         assert_eq!(ta.cursor(), 5);
     }
 
+    #[test]
+    fn test_word_navigation_follows_separator_preset() {
+        let alt = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::ALT);
+        let cases = [
+            (WordSeparators::Code, "snake_case", 0, 10),
+            (WordSeparators::Code, "kebab-case", 6, 5),
+            (WordSeparators::Prose, "snake_case", 6, 5),
+            (WordSeparators::Prose, "kebab-case", 0, 10),
+        ];
+        for (preset, text, word_start, first_word_end) in cases {
+            let mut ta = TextArea::new();
+            ta.set_word_separators(preset);
+            ta.insert_str(text);
+            ta.input(alt('b'));
+            assert_eq!(ta.cursor(), word_start, "{preset:?} Alt+B in {text}");
+
+            ta.set_cursor(0);
+            ta.input(alt('f'));
+            assert_eq!(ta.cursor(), first_word_end, "{preset:?} Alt+F in {text}");
+
+            ta.set_cursor(text.len());
+            ta.delete_backward_word();
+            assert_eq!(
+                ta.text(),
+                &text[..word_start],
+                "{preset:?} delete in {text}"
+            );
+
+            let mut ta = TextArea::new();
+            ta.set_word_separators(preset);
+            ta.insert_str(text);
+            ta.set_cursor(0);
+            ta.delete_forward_word();
+            assert_eq!(
+                ta.text(),
+                &text[first_word_end..],
+                "{preset:?} forward delete in {text}"
+            );
+        }
+    }
+
     #[test]
     fn test_clear() {
         let mut ta = TextArea::new();
@@ -1057,6 +1632,18 @@ mod tests {
         assert_eq!(pos, Some((5, 0)));
     }
 
+    #[test]
+    fn test_row_line_numbers_skip_continuation_rows() {
+        let mut ta = TextArea::new();
+        ta.insert_str("short\nthis line wraps around\n\nend\n");
+        assert_eq!(ta.line_count(), 5);
+        assert_eq!(
+            ta.row_line_numbers(10),
+            vec![Some(1), Some(2), None, None, Some(3), Some(4), Some(5)]
+        );
+        assert_eq!(TextArea::new().row_line_numbers(10), vec![Some(1)]);
+    }
+
     #[test]
     fn test_empty_desired_height() {
         let ta = TextArea::new();
@@ -1080,6 +1667,370 @@ mod tests {
         assert_eq!(ta.text(), "hello world");
     }
 
+    fn edited(text: &str, cursor: usize, key: KeyEvent) -> (String, usize) {
+        let mut ta = TextArea::new();
+        ta.insert_str(text);
+        ta.set_cursor(cursor);
+        ta.input(key);
+        (ta.text().to_string(), ta.cursor())
+    }
+
+    #[test]
+    fn test_transpose_chars() {
+        let ctrl_t = KeyEvent::new(KeyCode::Char('t'), KeyModifiers::CONTROL);
+        // Nothing before the cursor at the start of the buffer
+        assert_eq!(edited("abc", 0, ctrl_t), ("abc".to_string(), 0));
+        // Mid-line swaps around the cursor and moves right
+        assert_eq!(edited("abc", 1, ctrl_t), ("bac".to_string(), 2));
+        // End of line swaps the last two characters
+        assert_eq!(edited("abc", 3, ctrl_t), ("acb".to_string(), 3));
+        assert_eq!(edited("ab\ncd", 2, ctrl_t), ("ba\ncd".to_string(), 2));
+    }
+
+    #[test]
+    fn test_transpose_words() {
+        let alt_t = KeyEvent::new(KeyCode::Char('t'), KeyModifiers::ALT);
+        assert_eq!(
+            edited("foo bar baz", 0, alt_t),
+            ("foo bar baz".to_string(), 0)
+        );
+        assert_eq!(
+            edited("foo bar baz", 5, alt_t),
+            ("bar foo baz".to_string(), 7)
+        );
+        assert_eq!(
+            edited("foo bar baz", 11, alt_t),
+            ("foo baz bar".to_string(), 11)
+        );
+    }
+
+    #[test]
+    fn test_change_word_case() {
+        let alt = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::ALT);
+        assert_eq!(
+            edited("hello world", 0, alt('u')),
+            ("HELLO world".to_string(), 5)
+        );
+        assert_eq!(
+            edited("HELLO WORLD", 7, alt('l')),
+            ("HELLO World".to_string(), 11)
+        );
+        assert_eq!(
+            edited("hello WORLD", 5, alt('c')),
+            ("hello World".to_string(), 11)
+        );
+        assert_eq!(
+            edited("hello world", 11, alt('u')),
+            ("hello world".to_string(), 11)
+        );
+    }
+
+    #[test]
+    fn test_transpose_and_case_leave_elements_intact() {
+        let mut ta = TextArea::new();
+        ta.insert_str("x");
+        ta.insert_element("[IMG]");
+        let end = ta.cursor();
+        ta.transpose_chars();
+        assert_eq!((ta.text(), ta.cursor()), ("x[IMG]", end));
+
+        ta.set_cursor(1);
+        ta.change_word_case(WordCase::Lower);
+        assert_eq!((ta.text(), ta.cursor()), ("x[IMG]", end));
+    }
+
+    #[test]
+    fn test_mark_kill_region_and_yank() {
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        let mut ta = TextArea::new();
+        ta.insert_str("hello brave world");
+        ta.set_cursor(6);
+        ta.input(ctrl(' '));
+        ta.input(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::ALT));
+        assert_eq!(ta.region(), Some(6..11));
+
+        ta.input(ctrl('w'));
+        assert_eq!(ta.text(), "hello  world");
+        assert_eq!(ta.cursor(), 6);
+        assert_eq!(ta.mark(), None);
+
+        ta.move_cursor_to_end_of_line();
+        ta.insert_str(" ");
+        ta.input(ctrl('y'));
+        assert_eq!(ta.text(), "hello  world brave");
+
+        // Without a mark Ctrl+W still deletes the previous word
+        ta.input(ctrl('w'));
+        assert_eq!(ta.text(), "hello  world ");
+
+        // Ctrl+G drops the mark and leaves the text alone
+        ta.input(ctrl(' '));
+        ta.input(ctrl('g'));
+        assert_eq!(ta.mark(), None);
+        assert_eq!(ta.text(), "hello  world ");
+    }
+
+    #[test]
+    fn test_region_follows_edits_and_covers_elements() {
+        let mut ta = TextArea::new();
+        ta.insert_str("ab");
+        ta.toggle_mark();
+        ta.insert_element("[IMG]");
+        ta.insert_str("cd");
+        ta.set_cursor(0);
+        ta.insert_str("xy");
+        // The mark moved with the text inserted before it
+        assert_eq!(ta.mark(), Some(4));
+        assert_eq!(ta.region(), Some(2..4));
+
+        // A region ending inside an element widens to the whole element
+        ta.set_cursor(5);
+        assert_eq!(ta.cursor(), 4);
+        ta.move_cursor_right();
+        assert_eq!(ta.region(), Some(4..9));
+        ta.kill_region();
+        assert_eq!(ta.text(), "xyabcd");
+        assert!(!ta.has_elements());
+    }
+
+    #[test]
+    fn test_region_is_highlighted() {
+        let mut ta = TextArea::new();
+        ta.insert_str("abcdef");
+        ta.set_cursor(1);
+        ta.toggle_mark();
+        ta.set_cursor(4);
+
+        let area = Rect::new(0, 0, 10, 1);
+        let mut buf = Buffer::empty(area);
+        (&ta).render_ref(area, &mut buf);
+        let reversed: Vec<bool> = (0..6)
+            .map(|x| buf[(x, 0)].modifier.contains(Modifier::REVERSED))
+            .collect();
+        assert_eq!(reversed, vec![false, true, true, true, false, false]);
+    }
+
+    fn assert_wrap_cache_fresh(ta: &TextArea, width: u16) {
+        let cached = ta.wrapped_lines(width).clone();
+        assert_eq!(
+            cached,
+            wrap_text(ta.text(), width, ta.wrap_mode),
+            "stale wrap for {:?}",
+            ta.text()
+        );
+    }
+
+    #[test]
+    fn test_incremental_wrap_matches_full_rewrap() {
+        // Small deterministic generator so failures are reproducible
+        let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = move |bound: usize| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % bound as u64) as usize
+        };
+        let pieces = [
+            "a",
+            "bb",
+            " ",
+            "  ",
+            "\n",
+            "word ",
+            "longtokenwithoutspaces",
+            "é",
+            "界",
+        ];
+
+        let modes = [WrapMode::FirstFit, WrapMode::OptimalFit, WrapMode::NoWrap];
+        for (width, mode) in [1u16, 4, 7, 12]
+            .into_iter()
+            .flat_map(|width| modes.map(|mode| (width, mode)))
+        {
+            let mut ta = TextArea::new();
+            ta.set_wrap_mode(mode);
+            ta.insert_str("some initial text\nwith two paragraphs that wrap");
+            assert_wrap_cache_fresh(&ta, width);
+            for _ in 0..400 {
+                let len = ta.text().len();
+                let pos = ta.clamp_pos_to_char_boundary(next(len + 1));
+                if next(3) == 0 && len > 0 {
+                    let end = ta.clamp_pos_to_char_boundary(pos + next(8));
+                    ta.replace_range(pos..end, "");
+                } else {
+                    ta.insert_str_at(pos, pieces[next(pieces.len())]);
+                }
+                assert_wrap_cache_fresh(&ta, width);
+            }
+        }
+    }
+
+    #[test]
+    fn test_wrap_modes_break_rows_differently() {
+        let text = "To be, or not to be: that is the question\nnext";
+        let rows = |mode| {
+            let mut ta = TextArea::new();
+            ta.set_wrap_mode(mode);
+            ta.insert_str(text);
+            let rows: Vec<String> = ta
+                .wrapped_lines(10)
+                .iter()
+                .map(|range| text[range.start..range.end - 1].trim_end().to_string())
+                .collect();
+            rows
+        };
+        assert_eq!(
+            rows(WrapMode::FirstFit),
+            vec![
+                "To be, or",
+                "not to be:",
+                "that is",
+                "the",
+                "question",
+                "next"
+            ]
+        );
+        assert_eq!(
+            rows(WrapMode::OptimalFit),
+            vec![
+                "To be,",
+                "or not to",
+                "be: that",
+                "is the",
+                "question",
+                "next"
+            ]
+        );
+        assert_eq!(
+            rows(WrapMode::NoWrap),
+            vec!["To be, or not to be: that is the question", "next"]
+        );
+    }
+
+    #[test]
+    fn test_no_wrap_scrolls_to_keep_cursor_visible() {
+        let mut ta = TextArea::new();
+        ta.set_wrap_mode(WrapMode::NoWrap);
+        ta.insert_str("0123456789abcdef\nxy");
+        ta.set_cursor(16);
+        let area = Rect::new(0, 0, 6, 2);
+        let render = |ta: &TextArea| {
+            let mut buf = Buffer::empty(area);
+            ta.render_ref(area, &mut buf);
+            (0..2)
+                .map(|y| (0..6).map(|x| buf[(x, y)].symbol()).collect::<String>())
+                .collect::<Vec<_>>()
+        };
+
+        // The cursor after `f` sits in the last column
+        assert_eq!(ta.cursor_position(area), Some((5, 0)));
+        assert_eq!(render(&ta), vec!["bcdef ", "      "]);
+
+        // Moving left keeps the view until the cursor reaches its edge
+        ta.set_cursor(12);
+        assert_eq!(ta.cursor_position(area), Some((1, 0)));
+        ta.set_cursor(3);
+        assert_eq!(ta.cursor_position(area), Some((0, 0)));
+        assert_eq!(render(&ta), vec!["345678", "      "]);
+
+        // All rows scroll together, following the cursor to the short line
+        ta.move_cursor_down();
+        assert_eq!(ta.cursor(), 19);
+        assert_eq!(ta.cursor_position(area), Some((0, 1)));
+        ta.set_cursor(17);
+        assert_eq!(ta.cursor_position(area), Some((0, 1)));
+        assert_eq!(render(&ta), vec!["012345", "xy    "]);
+    }
+
+    #[test]
+    fn test_long_single_line_edits_rewrap_locally() {
+        let width = 80;
+        let mut ta = TextArea::new();
+        ta.insert_str(&"x".repeat(100_000));
+        let rows = ta.wrapped_lines(width).len();
+        assert_eq!(rows, 1250);
+
+        let wrapped_bytes = |ta: &TextArea| ta.wrap_cache.borrow().as_ref().unwrap().wrapped_bytes;
+        let before = wrapped_bytes(&ta);
+        for _ in 0..500 {
+            ta.insert_str("y");
+        }
+        ta.delete_backward(3);
+        // Typing at the end only rewraps the last couple of rows
+        let typed = wrapped_bytes(&ta) - before;
+        assert!(typed <= 501 * 3 * width as usize, "rewrapped {typed} bytes");
+
+        ta.set_cursor(99_000);
+        let before = wrapped_bytes(&ta);
+        ta.insert_str("z");
+        // In the middle of a long token the rows from the edit on change
+        let typed = wrapped_bytes(&ta) - before;
+        assert!(typed < 2_000, "rewrapped {typed} bytes");
+
+        for _ in 0..100 {
+            ta.move_cursor_up();
+        }
+        for _ in 0..100 {
+            ta.move_cursor_down();
+        }
+        assert_eq!(ta.cursor(), 99_001);
+        assert_wrap_cache_fresh(&ta, width);
+    }
+
+    #[test]
+    fn test_wrap_ranges_locate_owned_rows() {
+        let text = "hello world again";
+        let options = Options::new(8).initial_indent("> ").subsequent_indent("  ");
+        assert!(textwrap::wrap(text, &options)
+            .iter()
+            .all(|line| matches!(line, std::borrow::Cow::Owned(_))));
+        let rows: Vec<&str> = wrap_ranges(text, &options)
+            .into_iter()
+            .map(|range| text[range.start..range.end - 1].trim_end())
+            .collect();
+        assert_eq!(rows, vec!["hello", "world", "again"]);
+
+        // A hyphen added by textwrap is not part of the text
+        assert_eq!(
+            locate_owned_row("  splitting words", "split-", ""),
+            Some((2, 5))
+        );
+        assert_eq!(locate_owned_row("co-op", "co-", ""), Some((0, 3)));
+        assert_eq!(locate_owned_row("other", "split-", ""), None);
+    }
+
+    #[test]
+    fn test_cursor_and_rendered_rows_agree() {
+        let text = "non\u{a0}breaking\u{a0}content well-known re-wrapped 界界界界 end";
+        let mut ta = TextArea::new();
+        ta.insert_str(text);
+        let area = Rect::new(0, 0, 7, 20);
+        let mut buf = Buffer::empty(area);
+        (&ta).render_ref(area, &mut buf);
+
+        let ranges = ta.wrapped_lines(area.width).clone();
+        for (row, range) in ranges.iter().enumerate() {
+            let mut rendered = String::new();
+            let mut x = 0;
+            while x < area.width {
+                let symbol = buf[(x, row as u16)].symbol();
+                rendered.push_str(symbol);
+                x += symbol.width().max(1) as u16;
+            }
+            let expected = &text[range.start..(range.end - 1).min(text.len())];
+            assert_eq!(rendered.trim_end(), expected.trim_end(), "row {row}");
+        }
+
+        for (pos, ch) in text.char_indices() {
+            if ch == ' ' {
+                continue;
+            }
+            ta.set_cursor(pos);
+            let (x, y) = ta.cursor_position(area).unwrap();
+            assert_eq!(buf[(x, y)].symbol(), ch.to_string(), "cursor at byte {pos}");
+        }
+    }
+
     #[test]
     fn test_insert_element() {
         let mut ta = TextArea::new();
@@ -0,0 +1,711 @@
+//! Named colors for the terminal UI.
+//!
+//! Renderers read colors from the global [`Theme`] instead of hard-coding
+//! `Color` values, so alternative palettes can be selected via config.
+
+use ratatui::style::{Color, Style};
+use serde::{Deserialize, Serialize};
+#[cfg(not(test))]
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use unicode_width::UnicodeWidthStr;
+
+use super::color_support::ColorSupport;
+use super::config::TerminalConfig;
+use super::terminal_color;
+use crate::ui::ToolStatus;
+
+/// Built-in themes selectable via the `theme` key in `terminal.json`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThemeName {
+    #[default]
+    Default,
+    HighContrast,
+}
+
+/// Diff color presets selectable via `diff_style.preset` in `terminal.json`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiffStylePreset {
+    /// The theme's own diff colors, foreground only
+    #[default]
+    Default,
+    /// Soft green and red row backgrounds, like GitHub's diff view
+    Github,
+}
+
+/// `diff_style` in `terminal.json`: a preset plus optional overrides.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiffStyleConfig {
+    pub preset: DiffStylePreset,
+    /// Colors as names or `#rrggbb`; unset keeps the preset's color
+    pub insert_fg: Option<String>,
+    pub delete_fg: Option<String>,
+    pub insert_bg: Option<String>,
+    pub delete_bg: Option<String>,
+    /// Highlight changed lines with `insert_bg`/`delete_bg` instead of
+    /// coloring only their text
+    pub use_bg_highlight: Option<bool>,
+}
+
+/// Colors of inserted and deleted lines in diffs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffStyle {
+    pub insert_fg: Color,
+    pub delete_fg: Color,
+    /// Row backgrounds, used when `use_bg_highlight` is set
+    pub insert_bg: Color,
+    pub delete_bg: Color,
+    pub use_bg_highlight: bool,
+}
+
+impl DiffStyle {
+    /// GitHub-like background highlights for dark or light terminals.
+    pub const fn github(light_background: bool) -> Self {
+        if light_background {
+            Self {
+                insert_fg: Color::Rgb(17, 99, 41),
+                delete_fg: Color::Rgb(130, 7, 30),
+                insert_bg: Color::Rgb(218, 251, 225),
+                delete_bg: Color::Rgb(255, 235, 233),
+                use_bg_highlight: true,
+            }
+        } else {
+            Self {
+                insert_fg: Color::Rgb(126, 231, 135),
+                delete_fg: Color::Rgb(255, 161, 152),
+                insert_bg: Color::Rgb(18, 54, 32),
+                delete_bg: Color::Rgb(69, 22, 26),
+                use_bg_highlight: true,
+            }
+        }
+    }
+
+    /// Style for the text of an inserted or deleted line in a block drawn
+    /// on `bg`.
+    pub fn line_style(&self, insert: bool, bg: Color) -> Style {
+        let fg = if insert {
+            self.insert_fg
+        } else {
+            self.delete_fg
+        };
+        Style::default().fg(fg).bg(self.line_bg(insert, bg))
+    }
+
+    /// Row background of an inserted or deleted line in a block drawn on `bg`.
+    pub fn line_bg(&self, insert: bool, bg: Color) -> Color {
+        match (self.use_bg_highlight, insert) {
+            (false, _) => bg,
+            (true, true) => self.insert_bg,
+            (true, false) => self.delete_bg,
+        }
+    }
+}
+
+/// Colors used across the terminal UI.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub status_pending: Color,
+    pub status_running: Color,
+    pub status_success: Color,
+    pub status_error: Color,
+    /// Tool names in block headers
+    pub tool_name_fg: Color,
+    /// Project suffix and other de-emphasized metadata
+    pub muted_fg: Color,
+    /// Primary text drawn by widgets, such as commands and parameter values
+    pub text_fg: Color,
+    /// Supporting text: info messages, key hints and tool output
+    pub secondary_fg: Color,
+    /// Parameter names, inline code, path links and textarea elements
+    pub accent_fg: Color,
+    /// Welcome banner lettering and its subtitle
+    pub banner_fg: Color,
+    pub banner_subtitle_fg: Color,
+    /// Tool error status messages
+    pub tool_error_fg: Color,
+    /// Inserted and deleted lines in diffs
+    pub diff: DiffStyle,
+    pub diff_context: Color,
+    /// File paths shown under tool headers
+    pub path_fg: Color,
+    pub thinking_fg: Color,
+    /// Error messages in the status area
+    pub error_fg: Color,
+    /// Warnings in the status area
+    pub warning_fg: Color,
+    pub spinner_fg: Color,
+    pub rate_limit_fg: Color,
+    /// Spinner and status text while a failed request is retried
+    pub retry_fg: Color,
+    /// Gutter marker in front of user messages; its width sets the indent
+    /// of assistant content
+    pub user_marker: &'static str,
+    pub user_marker_fg: Color,
+    /// Gutter marker in front of system/instruction messages
+    pub system_marker: &'static str,
+    pub system_fg: Color,
+    /// Syntax colors for JSON tool output
+    pub json_key: Color,
+    pub json_string: Color,
+    pub json_number: Color,
+    /// `true`, `false` and `null`
+    pub json_literal: Color,
+    /// Background for tool content; `None` derives a tint from the terminal
+    /// background
+    pub tool_content_bg: Option<Color>,
+    /// Per-tool backgrounds that take precedence over `tool_content_bg`
+    pub tool_content_bg_overrides: Vec<(String, Color)>,
+}
+
+static DEFAULT_THEME: Theme = Theme::default_theme();
+
+static MONOCHROME_THEME: Theme = Theme::monochrome();
+
+static GLOBAL_THEME: OnceLock<Theme> = OnceLock::new();
+
+/// The monochrome palette with the configured user marker.
+static GLOBAL_MONOCHROME_THEME: OnceLock<Theme> = OnceLock::new();
+
+impl Theme {
+    /// The default palette (matches the original hard-coded colors).
+    pub const fn default_theme() -> Self {
+        Self {
+            status_pending: Color::Yellow,
+            status_running: Color::Blue,
+            status_success: Color::Green,
+            status_error: Color::Red,
+            tool_name_fg: Color::White,
+            muted_fg: Color::DarkGray,
+            text_fg: Color::White,
+            secondary_fg: Color::Gray,
+            accent_fg: Color::Cyan,
+            banner_fg: Color::Rgb(100, 140, 255),
+            banner_subtitle_fg: Color::Rgb(70, 100, 180),
+            tool_error_fg: Color::LightRed,
+            diff: DiffStyle {
+                insert_fg: Color::Green,
+                delete_fg: Color::Red,
+                insert_bg: Color::Rgb(18, 54, 32),
+                delete_bg: Color::Rgb(69, 22, 26),
+                use_bg_highlight: false,
+            },
+            diff_context: Color::Gray,
+            path_fg: Color::Gray,
+            thinking_fg: Color::DarkGray,
+            error_fg: Color::Red,
+            warning_fg: Color::Rgb(255, 176, 0),
+            spinner_fg: Color::Blue,
+            rate_limit_fg: Color::LightRed,
+            retry_fg: Color::Rgb(255, 176, 0),
+            user_marker: "› ",
+            user_marker_fg: Color::Cyan,
+            system_marker: "ℹ ",
+            system_fg: Color::DarkGray,
+            json_key: Color::Cyan,
+            json_string: Color::Green,
+            json_number: Color::Yellow,
+            json_literal: Color::Magenta,
+            tool_content_bg: None,
+            tool_content_bg_overrides: Vec::new(),
+        }
+    }
+
+    /// Brighter palette for low-contrast terminals or accessibility needs.
+    pub const fn high_contrast() -> Self {
+        Self {
+            status_pending: Color::LightYellow,
+            status_running: Color::LightCyan,
+            status_success: Color::LightGreen,
+            status_error: Color::LightRed,
+            tool_name_fg: Color::White,
+            muted_fg: Color::Gray,
+            text_fg: Color::White,
+            secondary_fg: Color::White,
+            accent_fg: Color::LightCyan,
+            banner_fg: Color::Rgb(100, 140, 255),
+            banner_subtitle_fg: Color::Rgb(70, 100, 180),
+            tool_error_fg: Color::LightRed,
+            diff: DiffStyle {
+                insert_fg: Color::LightGreen,
+                delete_fg: Color::LightRed,
+                insert_bg: Color::Rgb(0, 80, 0),
+                delete_bg: Color::Rgb(100, 0, 0),
+                use_bg_highlight: false,
+            },
+            diff_context: Color::White,
+            path_fg: Color::White,
+            thinking_fg: Color::Gray,
+            error_fg: Color::LightRed,
+            warning_fg: Color::LightYellow,
+            spinner_fg: Color::LightCyan,
+            rate_limit_fg: Color::LightYellow,
+            retry_fg: Color::Rgb(255, 191, 0),
+            user_marker: "› ",
+            user_marker_fg: Color::LightCyan,
+            system_marker: "ℹ ",
+            system_fg: Color::Gray,
+            json_key: Color::LightCyan,
+            json_string: Color::LightGreen,
+            json_number: Color::LightYellow,
+            json_literal: Color::LightMagenta,
+            tool_content_bg: None,
+            tool_content_bg_overrides: Vec::new(),
+        }
+    }
+
+    /// Palette for light terminal backgrounds, where gray and white text
+    /// from the default theme would be hard to read.
+    pub const fn light() -> Self {
+        Self {
+            status_pending: Color::Rgb(175, 115, 0),
+            status_running: Color::Rgb(0, 90, 190),
+            status_success: Color::Rgb(0, 130, 0),
+            status_error: Color::Rgb(190, 0, 0),
+            tool_name_fg: Color::Black,
+            muted_fg: Color::Rgb(100, 100, 100),
+            text_fg: Color::Black,
+            secondary_fg: Color::Rgb(70, 70, 70),
+            accent_fg: Color::Rgb(0, 110, 140),
+            banner_fg: Color::Rgb(60, 60, 160),
+            banner_subtitle_fg: Color::Rgb(100, 100, 180),
+            tool_error_fg: Color::Rgb(190, 0, 0),
+            diff: DiffStyle {
+                insert_fg: Color::Rgb(0, 120, 0),
+                delete_fg: Color::Rgb(170, 0, 0),
+                insert_bg: Color::Rgb(218, 251, 225),
+                delete_bg: Color::Rgb(255, 235, 233),
+                use_bg_highlight: false,
+            },
+            diff_context: Color::Rgb(70, 70, 70),
+            path_fg: Color::Rgb(70, 70, 70),
+            thinking_fg: Color::Rgb(110, 110, 110),
+            error_fg: Color::Rgb(190, 0, 0),
+            warning_fg: Color::Rgb(175, 110, 0),
+            spinner_fg: Color::Rgb(0, 90, 190),
+            rate_limit_fg: Color::Rgb(190, 0, 0),
+            retry_fg: Color::Rgb(175, 110, 0),
+            user_marker: "› ",
+            user_marker_fg: Color::Rgb(0, 120, 150),
+            system_marker: "ℹ ",
+            system_fg: Color::Rgb(110, 110, 110),
+            json_key: Color::Rgb(0, 110, 140),
+            json_string: Color::Rgb(0, 120, 0),
+            json_number: Color::Rgb(150, 90, 0),
+            json_literal: Color::Rgb(140, 0, 140),
+            tool_content_bg: None,
+            tool_content_bg_overrides: Vec::new(),
+        }
+    }
+
+    /// Every color resolves to the terminal default; only modifiers remain.
+    pub const fn monochrome() -> Self {
+        Self {
+            status_pending: Color::Reset,
+            status_running: Color::Reset,
+            status_success: Color::Reset,
+            status_error: Color::Reset,
+            tool_name_fg: Color::Reset,
+            muted_fg: Color::Reset,
+            text_fg: Color::Reset,
+            secondary_fg: Color::Reset,
+            accent_fg: Color::Reset,
+            banner_fg: Color::Reset,
+            banner_subtitle_fg: Color::Reset,
+            tool_error_fg: Color::Reset,
+            diff: DiffStyle {
+                insert_fg: Color::Reset,
+                delete_fg: Color::Reset,
+                insert_bg: Color::Reset,
+                delete_bg: Color::Reset,
+                use_bg_highlight: false,
+            },
+            diff_context: Color::Reset,
+            path_fg: Color::Reset,
+            thinking_fg: Color::Reset,
+            error_fg: Color::Reset,
+            warning_fg: Color::Reset,
+            spinner_fg: Color::Reset,
+            rate_limit_fg: Color::Reset,
+            retry_fg: Color::Reset,
+            user_marker: "› ",
+            user_marker_fg: Color::Reset,
+            system_marker: "ℹ ",
+            system_fg: Color::Reset,
+            json_key: Color::Reset,
+            json_string: Color::Reset,
+            json_number: Color::Reset,
+            json_literal: Color::Reset,
+            tool_content_bg: None,
+            tool_content_bg_overrides: Vec::new(),
+        }
+    }
+
+    /// Pick the palette for a theme name. The default theme switches to
+    /// [`Theme::light`] on light backgrounds.
+    pub fn from_name(name: ThemeName, light_background: bool) -> Self {
+        match name {
+            ThemeName::Default if light_background => Self::light(),
+            ThemeName::Default => Self::default_theme(),
+            ThemeName::HighContrast => Self::high_contrast(),
+        }
+    }
+
+    /// Apply the tool background settings from the terminal configuration.
+    /// Colors that fail to parse are logged and ignored.
+    pub fn with_tool_content_bg(mut self, config: &TerminalConfig) -> Self {
+        let parse = |value: &str| match value.parse::<Color>() {
+            Ok(color) => Some(color),
+            Err(_) => {
+                tracing::warn!("Ignoring invalid tool background color: {value}");
+                None
+            }
+        };
+        if let Some(color) = config.tool_content_bg.as_deref().and_then(parse) {
+            self.tool_content_bg = Some(color);
+        }
+        let mut overrides = config
+            .tool_content_bg_overrides
+            .iter()
+            .filter_map(|(tool, value)| parse(value).map(|color| (tool.clone(), color)))
+            .collect::<Vec<_>>();
+        overrides.sort_by(|a, b| a.0.cmp(&b.0));
+        self.tool_content_bg_overrides = overrides;
+        self
+    }
+
+    /// Apply the diff style settings from the terminal configuration on top
+    /// of the palette's diff colors. Colors that fail to parse are logged
+    /// and ignored.
+    pub fn with_diff_style(mut self, config: &TerminalConfig, light_background: bool) -> Self {
+        let style = &config.diff_style;
+        if style.preset == DiffStylePreset::Github {
+            self.diff = DiffStyle::github(light_background);
+        }
+        let parse = |value: &Option<String>| {
+            let value = value.as_deref()?;
+            value
+                .parse::<Color>()
+                .inspect_err(|_| tracing::warn!("Ignoring invalid diff color: {value}"))
+                .ok()
+        };
+        let diff = &mut self.diff;
+        for (color, value) in [
+            (&mut diff.insert_fg, &style.insert_fg),
+            (&mut diff.delete_fg, &style.delete_fg),
+            (&mut diff.insert_bg, &style.insert_bg),
+            (&mut diff.delete_bg, &style.delete_bg),
+        ] {
+            if let Some(parsed) = parse(value) {
+                *color = parsed;
+            }
+        }
+        if let Some(use_bg_highlight) = style.use_bg_highlight {
+            diff.use_bg_highlight = use_bg_highlight;
+        }
+        self
+    }
+
+    /// Apply the configured user prompt marker and color. The marker gets
+    /// a trailing space so messages never touch it; an invalid color is
+    /// logged and ignored.
+    pub fn with_user_prompt(mut self, config: &TerminalConfig) -> Self {
+        if let Some(prefix) = config.user_prompt.as_deref().map(str::trim_end) {
+            if !prefix.is_empty() {
+                // Leaked once at startup so the marker stays a `&'static str`
+                self.user_marker = Box::leak(format!("{prefix} ").into_boxed_str());
+            }
+        }
+        if let Some(value) = config.user_prompt_color.as_deref() {
+            match value.parse::<Color>() {
+                Ok(color) => self.user_marker_fg = color,
+                Err(_) => tracing::warn!("Ignoring invalid user prompt color: {value}"),
+            }
+        }
+        self
+    }
+
+    /// Display width of the user marker. Assistant text, thinking and the
+    /// continuation lines of user messages are indented by this much so
+    /// they line up after the marker.
+    pub fn gutter_width(&self) -> usize {
+        self.user_marker.width()
+    }
+
+    /// Blank indent as wide as the user marker.
+    pub fn gutter(&self) -> String {
+        " ".repeat(self.gutter_width())
+    }
+
+    /// Map the palette to colors the terminal can show.
+    pub fn adapted(mut self, support: &ColorSupport) -> Self {
+        self.status_pending = support.adapt(self.status_pending);
+        self.status_running = support.adapt(self.status_running);
+        self.status_success = support.adapt(self.status_success);
+        self.status_error = support.adapt(self.status_error);
+        self.tool_name_fg = support.adapt(self.tool_name_fg);
+        self.muted_fg = support.adapt(self.muted_fg);
+        self.text_fg = support.adapt(self.text_fg);
+        self.secondary_fg = support.adapt(self.secondary_fg);
+        self.accent_fg = support.adapt(self.accent_fg);
+        self.banner_fg = support.adapt(self.banner_fg);
+        self.banner_subtitle_fg = support.adapt(self.banner_subtitle_fg);
+        self.tool_error_fg = support.adapt(self.tool_error_fg);
+        self.diff.insert_fg = support.adapt(self.diff.insert_fg);
+        self.diff.delete_fg = support.adapt(self.diff.delete_fg);
+        self.diff.insert_bg = support.adapt(self.diff.insert_bg);
+        self.diff.delete_bg = support.adapt(self.diff.delete_bg);
+        self.diff_context = support.adapt(self.diff_context);
+        self.path_fg = support.adapt(self.path_fg);
+        self.thinking_fg = support.adapt(self.thinking_fg);
+        self.error_fg = support.adapt(self.error_fg);
+        self.warning_fg = support.adapt(self.warning_fg);
+        self.spinner_fg = support.adapt(self.spinner_fg);
+        self.rate_limit_fg = support.adapt(self.rate_limit_fg);
+        self.retry_fg = support.adapt(self.retry_fg);
+        self.user_marker_fg = support.adapt(self.user_marker_fg);
+        self.system_fg = support.adapt(self.system_fg);
+        self.json_key = support.adapt(self.json_key);
+        self.json_string = support.adapt(self.json_string);
+        self.json_number = support.adapt(self.json_number);
+        self.json_literal = support.adapt(self.json_literal);
+        self.tool_content_bg = self.tool_content_bg.map(|color| support.adapt(color));
+        for (_, color) in &mut self.tool_content_bg_overrides {
+            *color = support.adapt(*color);
+        }
+        self
+    }
+
+    /// Background override configured for a specific tool, if any.
+    pub fn tool_content_bg_override(&self, tool_name: &str) -> Option<Color> {
+        self.tool_content_bg_overrides
+            .iter()
+            .find(|(tool, _)| tool == tool_name)
+            .map(|(_, color)| *color)
+    }
+
+    /// Color for a tool status indicator.
+    pub fn status_color(&self, status: &ToolStatus) -> Color {
+        match status {
+            ToolStatus::Pending => self.status_pending,
+            ToolStatus::Running => self.status_running,
+            ToolStatus::Success => self.status_success,
+            ToolStatus::Error => self.status_error,
+        }
+    }
+
+    /// Install the global theme. Only the first call has an effect.
+    pub fn set_global(theme: Theme) {
+        let _ = GLOBAL_THEME.set(theme);
+    }
+
+    /// Retrieve the global theme, falling back to the default palette
+    /// when none has been installed (e.g. in tests). In monochrome mode
+    /// this is always the monochrome palette.
+    pub fn global() -> &'static Theme {
+        #[cfg(test)]
+        if let Some(theme) = TEST_THEME.with(|theme| theme.get()) {
+            return theme;
+        }
+        if is_monochrome() {
+            return GLOBAL_MONOCHROME_THEME.get().unwrap_or(&MONOCHROME_THEME);
+        }
+        GLOBAL_THEME.get().unwrap_or(&DEFAULT_THEME)
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::default_theme()
+    }
+}
+
+// Monochrome mode is per-thread in tests so toggling it cannot leak into
+// tests running in parallel.
+#[cfg(not(test))]
+static MONOCHROME: AtomicBool = AtomicBool::new(false);
+
+#[cfg(test)]
+thread_local! {
+    static MONOCHROME: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+// Tests that need a configured theme install it for their own thread.
+#[cfg(test)]
+thread_local! {
+    static TEST_THEME: std::cell::Cell<Option<&'static Theme>> = const { std::cell::Cell::new(None) };
+}
+
+/// Use `theme` as the global theme on the current test thread.
+#[cfg(test)]
+pub fn set_test_theme(theme: Theme) {
+    TEST_THEME.with(|slot| slot.set(Some(Box::leak(Box::new(theme)))));
+}
+
+/// Whether colors are suppressed (no-color mode).
+pub fn is_monochrome() -> bool {
+    #[cfg(not(test))]
+    {
+        MONOCHROME.load(Ordering::Relaxed)
+    }
+    #[cfg(test)]
+    {
+        MONOCHROME.with(|flag| flag.get())
+    }
+}
+
+/// Enable or disable no-color mode.
+pub fn set_monochrome(enabled: bool) {
+    #[cfg(not(test))]
+    MONOCHROME.store(enabled, Ordering::Relaxed);
+    #[cfg(test)]
+    MONOCHROME.with(|flag| flag.set(enabled));
+}
+
+/// `color` as it should reach the terminal: the terminal default in
+/// no-color mode, so colors that bypass the theme (e.g. from markdown
+/// rendering) are dropped as well.
+pub fn output_color(color: Color) -> Color {
+    if is_monochrome() {
+        Color::Reset
+    } else {
+        color
+    }
+}
+
+/// Flip no-color mode and return the new state.
+pub fn toggle_monochrome() -> bool {
+    let enabled = !is_monochrome();
+    set_monochrome(enabled);
+    enabled
+}
+
+/// Initialize the global theme from the terminal configuration, the
+/// detected background and the terminal's color support. Call after
+/// `terminal_color::init()`.
+pub fn init() {
+    let config = TerminalConfig::global();
+    let support = ColorSupport::global();
+    let light_background = terminal_color::is_light_background();
+    let theme = Theme::from_name(config.theme, light_background)
+        .with_tool_content_bg(config)
+        .with_diff_style(config, light_background)
+        .with_user_prompt(config)
+        .adapted(support);
+    let _ = GLOBAL_MONOCHROME_THEME.set(Theme {
+        user_marker: theme.user_marker,
+        ..Theme::monochrome()
+    });
+    Theme::set_global(theme);
+    set_monochrome(config.no_color || support.no_color);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::terminal::color_support::ColorDepth;
+
+    #[test]
+    fn test_default_theme_matches_original_status_colors() {
+        let theme = Theme::default_theme();
+        assert_eq!(theme.status_color(&ToolStatus::Pending), Color::Yellow);
+        assert_eq!(theme.status_color(&ToolStatus::Running), Color::Blue);
+        assert_eq!(theme.status_color(&ToolStatus::Success), Color::Green);
+        assert_eq!(theme.status_color(&ToolStatus::Error), Color::Red);
+    }
+
+    #[test]
+    fn test_monochrome_overrides_global_theme() {
+        set_monochrome(true);
+        assert_eq!(Theme::global(), &Theme::monochrome());
+        assert!(!toggle_monochrome());
+        assert_eq!(Theme::global(), &Theme::default_theme());
+    }
+
+    #[test]
+    fn test_from_name_selects_palette() {
+        assert_eq!(
+            Theme::from_name(ThemeName::Default, false),
+            Theme::default()
+        );
+        assert_eq!(Theme::from_name(ThemeName::Default, true), Theme::light());
+        assert_eq!(
+            Theme::from_name(ThemeName::HighContrast, true),
+            Theme::high_contrast()
+        );
+    }
+
+    #[test]
+    fn test_adapted_downgrades_rgb_colors() {
+        let support = ColorSupport {
+            no_color: false,
+            depth: ColorDepth::Ansi16,
+        };
+        let theme = Theme::light().adapted(&support);
+        assert_eq!(theme.status_error, Color::Red);
+        assert_eq!(theme.tool_name_fg, Color::Black);
+        assert_eq!(
+            Theme::default_theme().adapted(&ColorSupport::default()),
+            Theme::default_theme()
+        );
+    }
+
+    #[test]
+    fn test_tool_content_bg_overrides_from_config() {
+        let config: TerminalConfig = serde_json::from_str(
+            r##"{
+                "tool_content_bg": "#101820",
+                "tool_content_bg_overrides": {"execute_command": "blue", "edit": "not-a-color"}
+            }"##,
+        )
+        .unwrap();
+        let theme = Theme::default_theme().with_tool_content_bg(&config);
+
+        assert_eq!(theme.tool_content_bg, Some(Color::Rgb(16, 24, 32)));
+        assert_eq!(
+            theme.tool_content_bg_override("execute_command"),
+            Some(Color::Blue)
+        );
+        assert_eq!(theme.tool_content_bg_override("edit"), None);
+        assert_eq!(Theme::default_theme().tool_content_bg, None);
+    }
+
+    #[test]
+    fn test_user_prompt_from_config() {
+        let config: TerminalConfig =
+            serde_json::from_str(r##"{"user_prompt": ">>>", "user_prompt_color": "magenta"}"##)
+                .unwrap();
+        let theme = Theme::default_theme().with_user_prompt(&config);
+        assert_eq!(theme.user_marker, ">>> ");
+        assert_eq!(theme.user_marker_fg, Color::Magenta);
+        assert_eq!(theme.gutter(), "    ");
+
+        let unset = Theme::default_theme().with_user_prompt(&TerminalConfig::default());
+        assert_eq!(unset.user_marker, "› ");
+        assert_eq!(unset.gutter_width(), 2);
+    }
+
+    #[test]
+    fn test_diff_style_presets_and_overrides() {
+        let default = Theme::default_theme().with_diff_style(&TerminalConfig::default(), false);
+        assert_eq!(default.diff.insert_fg, Color::Green);
+        assert_eq!(
+            default.diff.line_style(true, Color::Black).bg,
+            Some(Color::Black)
+        );
+
+        let config: TerminalConfig = serde_json::from_str(
+            r##"{"diff_style": {"preset": "github", "delete_fg": "#ff0000", "insert_bg": "nope"}}"##,
+        )
+        .unwrap();
+        let github = Theme::default_theme().with_diff_style(&config, true);
+        assert_eq!(github.diff.delete_fg, Color::Rgb(255, 0, 0));
+        assert_eq!(github.diff.insert_bg, DiffStyle::github(true).insert_bg);
+        assert_eq!(
+            github.diff.line_style(false, Color::Black).bg,
+            Some(DiffStyle::github(true).delete_bg)
+        );
+    }
+}
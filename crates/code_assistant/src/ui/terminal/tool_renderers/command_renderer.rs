@@ -7,11 +7,15 @@ use ratatui::prelude::*;
 use ratatui::style::{Color, Modifier, Style};
 
 use super::{
-    push_error_history_line, render_error_line, render_tool_header, tool_header_line, ToolRenderer,
+    push_error_history_line, render_error_line, render_tool_header, tool_header_line, tool_indent,
+    tool_indent_str, truncate_to_width, ToolRenderer,
 };
 use crate::ui::terminal::message::ToolUseBlock;
+use crate::ui::terminal::redact::redact;
 use crate::ui::terminal::terminal_color;
+use crate::ui::terminal::theme::Theme;
 use crate::ui::ToolStatus;
+use unicode_width::UnicodeWidthStr;
 
 /// Expand tab characters to spaces (4-space tab stops).
 fn expand_tabs(text: &str) -> String {
@@ -35,15 +39,74 @@ fn expand_tabs(text: &str) -> String {
     result
 }
 
+/// Marker shown above the output when older lines were dropped.
+fn truncation_marker(tool_block: &ToolUseBlock) -> Option<String> {
+    if !tool_block.is_output_truncated() {
+        return None;
+    }
+    let shown = tool_block
+        .output
+        .as_deref()
+        .map_or(0, |o| o.lines().count());
+    Some(format!("… output truncated, showing last {shown} lines"))
+}
+
+/// Prefix of rows continuing a soft-wrapped output line.
+const CONTINUATION_MARKER: &str = "↳ ";
+
+/// Soft-wrap an output line at word boundaries to `width` columns. Rows
+/// after the first are returned without their `CONTINUATION_MARKER`, which
+/// callers render separately in a dim style.
+fn wrap_output_line(line: &str, width: usize) -> Vec<String> {
+    let expanded = expand_tabs(line);
+    // Leave room for the marker plus at least one character
+    let width = width.max(CONTINUATION_MARKER.width() + 1);
+    let options = textwrap::Options::new(width)
+        .subsequent_indent(CONTINUATION_MARKER)
+        .wrap_algorithm(textwrap::WrapAlgorithm::FirstFit);
+    textwrap::wrap(&expanded, options)
+        .into_iter()
+        .enumerate()
+        .map(|(i, row)| {
+            if i == 0 {
+                row.into_owned()
+            } else {
+                row.strip_prefix(CONTINUATION_MARKER)
+                    .unwrap_or(&row)
+                    .to_string()
+            }
+        })
+        .collect()
+}
+
+fn continuation_marker_style(bg: Color) -> Style {
+    Style::default()
+        .fg(Theme::global().muted_fg)
+        .add_modifier(Modifier::DIM)
+        .bg(bg)
+}
+
+fn truncation_marker_style(bg: Color) -> Style {
+    Style::default()
+        .fg(Theme::global().muted_fg)
+        .add_modifier(Modifier::ITALIC)
+        .bg(bg)
+}
+
 /// Renderer for the `execute_command` tool.
 pub struct CommandToolRenderer;
 
 impl ToolRenderer for CommandToolRenderer {
+    fn name(&self) -> &'static str {
+        "command"
+    }
+
     fn supported_tools(&self) -> &'static [&'static str] {
         &["execute_command"]
     }
 
     fn render(&self, tool_block: &ToolUseBlock, area: Rect, buf: &mut Buffer) {
+        let indent = tool_indent();
         if area.height < 1 {
             return;
         }
@@ -53,66 +116,89 @@ impl ToolRenderer for CommandToolRenderer {
         // Command line
         if let Some(cmd) = tool_block.parameters.get("command_line") {
             if y < area.y + area.height {
-                let bg = terminal_color::tool_content_bg();
-                let row_width = area.width.saturating_sub(2) as usize;
+                let bg = terminal_color::tool_content_bg_for(&tool_block.name);
+                let row_width = area.width.saturating_sub(indent) as usize;
                 buf.set_string(
-                    area.x + 2,
+                    area.x + indent,
                     y,
                     " ".repeat(row_width),
                     Style::default().bg(bg),
                 );
                 buf.set_string(
-                    area.x + 2,
+                    area.x + indent,
                     y,
                     "$ ",
                     Style::default()
-                        .fg(Color::DarkGray)
+                        .fg(Theme::global().muted_fg)
                         .add_modifier(Modifier::BOLD)
                         .bg(bg),
                 );
-                let max_cmd_len = row_width.saturating_sub(2);
-                let display = if cmd.value.len() > max_cmd_len {
-                    &cmd.value[..max_cmd_len]
-                } else {
-                    cmd.value.as_str()
-                };
+                let display = truncate_to_width(&redact(&cmd.value), row_width.saturating_sub(2));
                 buf.set_string(
-                    area.x + 4,
+                    area.x + indent + 2,
                     y,
                     display,
-                    Style::default().fg(Color::White).bg(bg),
+                    Style::default().fg(Theme::global().text_fg).bg(bg),
                 );
                 y += 1;
             }
         }
 
         // Terminal output
-        if let Some(ref output) = tool_block.output {
+        if let Some(output) = tool_block.output.as_deref().map(redact) {
             if !output.is_empty() {
-                let bg = terminal_color::tool_content_bg();
-                let row_width = area.width.saturating_sub(2) as usize;
-                for line in output.lines() {
+                let bg = terminal_color::tool_content_bg_for(&tool_block.name);
+                let row_width = area.width.saturating_sub(indent) as usize;
+                if let Some(marker) = truncation_marker(tool_block) {
+                    if y < area.y + area.height {
+                        buf.set_string(
+                            area.x + indent,
+                            y,
+                            " ".repeat(row_width),
+                            Style::default().bg(bg),
+                        );
+                        buf.set_stringn(
+                            area.x + indent,
+                            y,
+                            &marker,
+                            row_width,
+                            truncation_marker_style(bg),
+                        );
+                        y += 1;
+                    }
+                }
+                let rows = output
+                    .lines()
+                    .flat_map(|line| wrap_output_line(line, row_width).into_iter().enumerate());
+                for (row_idx, row) in rows {
                     if y >= area.y + area.height {
                         break;
                     }
                     // Fill background across full row width
                     buf.set_string(
-                        area.x + 2,
+                        area.x + indent,
                         y,
                         " ".repeat(row_width),
                         Style::default().bg(bg),
                     );
-                    let expanded = expand_tabs(line);
-                    let display = if expanded.len() > row_width {
-                        &expanded[..row_width]
-                    } else {
-                        expanded.as_str()
-                    };
-                    buf.set_string(
-                        area.x + 2,
+                    let mut x = area.x + indent;
+                    if row_idx > 0 {
+                        x = buf
+                            .set_stringn(
+                                x,
+                                y,
+                                CONTINUATION_MARKER,
+                                row_width,
+                                continuation_marker_style(bg),
+                            )
+                            .0;
+                    }
+                    buf.set_stringn(
+                        x,
                         y,
-                        display,
-                        Style::default().fg(Color::Gray).bg(bg),
+                        &row,
+                        row_width.saturating_sub((x - area.x - indent) as usize),
+                        Style::default().fg(Theme::global().secondary_fg).bg(bg),
                     );
                     y += 1;
                 }
@@ -122,7 +208,8 @@ impl ToolRenderer for CommandToolRenderer {
         render_error_line(tool_block, area, buf, y);
     }
 
-    fn calculate_height(&self, tool_block: &ToolUseBlock, _width: u16) -> u16 {
+    fn calculate_height(&self, tool_block: &ToolUseBlock, width: u16) -> u16 {
+        let indent = tool_indent();
         let mut height: u16 = 1; // header
 
         // Command line
@@ -131,9 +218,17 @@ impl ToolRenderer for CommandToolRenderer {
         }
 
         // Terminal output
-        if let Some(ref output) = tool_block.output {
+        if let Some(output) = tool_block.output.as_deref().map(redact) {
             if !output.is_empty() {
-                height += output.lines().count() as u16;
+                let row_width = width.saturating_sub(indent) as usize;
+                let rows: usize = output
+                    .lines()
+                    .map(|line| wrap_output_line(line, row_width).len())
+                    .sum();
+                height = height.saturating_add(rows as u16);
+                if tool_block.is_output_truncated() {
+                    height += 1;
+                }
             }
         }
 
@@ -143,9 +238,10 @@ impl ToolRenderer for CommandToolRenderer {
         height
     }
 
-    fn render_history_lines(&self, tool_block: &ToolUseBlock) -> Vec<Line<'static>> {
+    fn render_history_lines(&self, tool_block: &ToolUseBlock, width: u16) -> Vec<Line<'static>> {
+        let indent = tool_indent();
         let mut lines = vec![tool_header_line(tool_block)];
-        let bg = terminal_color::tool_content_bg();
+        let bg = terminal_color::tool_content_bg_for(&tool_block.name);
         let bg_style = Style::default().bg(bg);
 
         // Command line
@@ -153,29 +249,49 @@ impl ToolRenderer for CommandToolRenderer {
             lines.push(
                 Line::from(vec![
                     Span::styled(
-                        "  $ ",
+                        format!("{}$ ", tool_indent_str()),
                         Style::default()
-                            .fg(Color::DarkGray)
+                            .fg(Theme::global().muted_fg)
                             .add_modifier(Modifier::BOLD)
                             .bg(bg),
                     ),
-                    Span::styled(cmd.value.clone(), Style::default().fg(Color::White).bg(bg)),
+                    Span::styled(
+                        redact(&cmd.value),
+                        Style::default().fg(Theme::global().text_fg).bg(bg),
+                    ),
                 ])
                 .style(bg_style),
             );
         }
 
         // Terminal output
-        if let Some(ref output) = tool_block.output {
-            for line in output.lines() {
+        if let Some(output) = tool_block.output.as_deref().map(redact) {
+            if let Some(marker) = truncation_marker(tool_block) {
                 lines.push(
                     Line::from(vec![Span::styled(
-                        format!("  {}", expand_tabs(line)),
-                        Style::default().fg(Color::Gray).bg(bg),
+                        format!("{}{marker}", tool_indent_str()),
+                        truncation_marker_style(bg),
                     )])
                     .style(bg_style),
                 );
             }
+            let row_width = width.saturating_sub(indent) as usize;
+            for line in output.lines() {
+                for (row_idx, row) in wrap_output_line(line, row_width).into_iter().enumerate() {
+                    let mut spans = vec![Span::styled(tool_indent_str(), bg_style)];
+                    if row_idx > 0 {
+                        spans.push(Span::styled(
+                            CONTINUATION_MARKER,
+                            continuation_marker_style(bg),
+                        ));
+                    }
+                    spans.push(Span::styled(
+                        row,
+                        Style::default().fg(Theme::global().secondary_fg).bg(bg),
+                    ));
+                    lines.push(Line::from(spans).style(bg_style));
+                }
+            }
         }
 
         push_error_history_line(tool_block, &mut lines);
@@ -186,7 +302,7 @@ impl ToolRenderer for CommandToolRenderer {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ui::terminal::message::ParameterValue;
+    use crate::ui::terminal::message::{OutputLimit, ParameterValue};
     use indexmap::IndexMap;
 
     fn make_tool(params: &[(&str, &str)], output: Option<&str>) -> ToolUseBlock {
@@ -201,6 +317,13 @@ mod tests {
             status: ToolStatus::Success,
             status_message: None,
             output: output.map(|s| s.to_string()),
+            truncated_lines: 0,
+            truncated_first_line: false,
+            awaiting_approval: false,
+            progress: None,
+            duration: None,
+            unchanged: false,
+            pending_cr: false,
         }
     }
 
@@ -223,6 +346,116 @@ mod tests {
         assert_eq!(renderer.calculate_height(&tool, 80), 5);
     }
 
+    #[test]
+    fn test_long_output_lines_wrap_with_continuation_marker() {
+        let renderer = CommandToolRenderer;
+        let tool = make_tool(
+            &[("command_line", "cargo build")],
+            Some("warning: unused variable in function main\nok"),
+        );
+        // Rows are 20 columns after the 2-column indent
+        assert_eq!(
+            wrap_output_line("warning: unused variable in function main", 20),
+            vec!["warning: unused", "variable in", "function main"]
+        );
+        // 1 header + 1 command + 3 wrapped rows + 1 short line = 6
+        assert_eq!(renderer.calculate_height(&tool, 22), 6);
+
+        let lines = renderer.render_history_lines(&tool, 22);
+        let text =
+            |line: &Line| -> String { line.spans.iter().map(|s| s.content.as_ref()).collect() };
+        assert_eq!(text(&lines[2]), "  warning: unused");
+        assert_eq!(text(&lines[3]), "  ↳ variable in");
+        assert_eq!(text(&lines[4]), "  ↳ function main");
+        assert_eq!(text(&lines[5]), "  ok");
+        assert!(lines[3].spans[1].style.add_modifier.contains(Modifier::DIM));
+
+        let area = Rect::new(0, 0, 22, 6);
+        let mut buf = Buffer::empty(area);
+        renderer.render(&tool, area, &mut buf);
+        let row = |y: u16| -> String {
+            (0..area.width)
+                .map(|x| buf.cell((x, y)).unwrap().symbol())
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        };
+        assert_eq!(row(2), "  warning: unused");
+        assert_eq!(row(3), "  ↳ variable in");
+        assert_eq!(row(5), "  ok");
+    }
+
+    #[test]
+    fn test_truncated_output_shows_marker() {
+        let renderer = CommandToolRenderer;
+        let mut tool = make_tool(&[("command_line", "make")], None);
+        let limit = OutputLimit {
+            max_bytes: 1024,
+            max_lines: 3,
+        };
+        for i in 1..=5 {
+            tool.append_output(&format!("line {i}\n"), limit);
+        }
+        assert_eq!(tool.output.as_deref(), Some("line 3\nline 4\nline 5\n"));
+        assert_eq!(tool.truncated_lines, 2);
+
+        // 1 header + 1 command + 1 marker + 3 output lines = 6
+        assert_eq!(renderer.calculate_height(&tool, 80), 6);
+        let lines = renderer.render_history_lines(&tool, 80);
+        let marker: String = lines[2].spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(marker, "  … output truncated, showing last 3 lines");
+    }
+
+    #[test]
+    fn test_progress_output_redraws_its_line() {
+        let renderer = CommandToolRenderer;
+        let mut tool = make_tool(&[("command_line", "curl -O")], None);
+        let limit = OutputLimit {
+            max_bytes: 1024,
+            max_lines: 100,
+        };
+        for chunk in [
+            "Resolving\r",
+            "\n",
+            "Downloading 50%\r",
+            "Downloading 100%\r\n",
+        ] {
+            tool.append_output(chunk, limit);
+        }
+        assert_eq!(
+            tool.output.as_deref(),
+            Some("Resolving\nDownloading 100%\n")
+        );
+
+        let lines = renderer.render_history_lines(&tool, 80);
+        let text: Vec<String> = lines
+            .iter()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect();
+        assert!(text.iter().any(|line| line.contains("Downloading 100%")));
+        assert!(!text.iter().any(|line| line.contains("50%")));
+    }
+
+    #[test]
+    fn test_output_byte_limit_keeps_tail() {
+        let mut tool = make_tool(&[], None);
+        let limit = OutputLimit {
+            max_bytes: 8,
+            max_lines: 100,
+        };
+        tool.append_output("aaaa\nbbbb\ncccc", limit);
+        assert_eq!(tool.output.as_deref(), Some("cccc"));
+        assert_eq!(tool.truncated_lines, 2);
+
+        // A single line longer than the cap keeps its last bytes, which
+        // still counts as truncated
+        tool.set_output(Some("é".repeat(10)), limit);
+        assert_eq!(tool.output.as_deref(), Some("éééé"));
+        assert_eq!(tool.truncated_lines, 0);
+        assert!(tool.is_output_truncated());
+        assert!(truncation_marker(&tool).is_some());
+    }
+
     #[test]
     fn test_height_with_error() {
         let renderer = CommandToolRenderer;
@@ -2,21 +2,39 @@
 //!
 //! Shows only the tool name, project, and key identifiers (paths, patterns,
 //! URLs) — never the file contents, search results, or full tool output.
+//! Listing tools go further and collapse onto a single summary line.
 
 use ratatui::prelude::*;
-use ratatui::style::{Color, Modifier, Style};
+use ratatui::style::{Modifier, Style};
+use unicode_width::UnicodeWidthStr;
 
 use super::{
-    push_error_history_line, render_error_line, render_tool_header, tool_header_line, ToolRenderer,
+    get_project_suffix, push_error_history_line, render_error_line, render_tool_header,
+    status_color, status_symbol, tool_header_line, tool_indent, tool_indent_str, truncate_to_width,
+    ToolRenderer,
 };
 use crate::ui::terminal::message::ToolUseBlock;
+use crate::ui::terminal::theme::Theme;
 use crate::ui::ToolStatus;
 
+/// Tools whose parameters are summarized on the header line instead of
+/// being listed below it.
+const SUMMARY_TOOLS: &[&str] = &["read_files", "list_files", "glob_files", "list_projects"];
+
+/// A failed call keeps the full layout, so each parameter shows above the error.
+fn is_summary_tool(tool_block: &ToolUseBlock) -> bool {
+    tool_block.status != ToolStatus::Error && SUMMARY_TOOLS.contains(&tool_block.name.as_str())
+}
+
 /// Renderer for read/explore tools: read_files, list_files, list_projects,
 /// search_files, glob_files, web_search, web_fetch.
 pub struct CompactToolRenderer;
 
 impl ToolRenderer for CompactToolRenderer {
+    fn name(&self) -> &'static str {
+        "compact"
+    }
+
     fn supported_tools(&self) -> &'static [&'static str] {
         &[
             "read_files",
@@ -30,10 +48,18 @@ impl ToolRenderer for CompactToolRenderer {
     }
 
     fn render(&self, tool_block: &ToolUseBlock, area: Rect, buf: &mut Buffer) {
+        let indent = tool_indent();
         if area.height < 1 {
             return;
         }
 
+        if is_summary_tool(tool_block) {
+            buf.set_line(area.x, area.y, &summary_line(tool_block), area.width);
+            render_error_line(tool_block, area, buf, area.y + 1);
+            return;
+        }
+
+        let theme = Theme::global();
         let mut y = render_tool_header(tool_block, area, buf, area.y);
 
         for line in compact_lines(tool_block) {
@@ -42,35 +68,44 @@ impl ToolRenderer for CompactToolRenderer {
             }
             match line {
                 CompactLine::Item(text) => {
-                    buf.set_string(area.x + 2, y, "- ", Style::default().fg(Color::DarkGray));
-                    let max_len = area.width.saturating_sub(4) as usize;
-                    let display = if text.len() > max_len {
-                        &text[..max_len]
-                    } else {
-                        text.as_str()
-                    };
-                    buf.set_string(area.x + 4, y, display, Style::default().fg(Color::Gray));
+                    buf.set_string(
+                        area.x + indent,
+                        y,
+                        "- ",
+                        Style::default().fg(theme.muted_fg),
+                    );
+                    let display =
+                        truncate_to_width(&text, area.width.saturating_sub(indent + 2) as usize);
+                    buf.set_string(
+                        area.x + indent + 2,
+                        y,
+                        display,
+                        Style::default().fg(theme.path_fg),
+                    );
                 }
                 CompactLine::KeyValue(key, value) => {
-                    let key_len = key.len() as u16;
-                    buf.set_string(area.x + 2, y, &key, Style::default().fg(Color::Cyan));
+                    let key_len = key.width() as u16;
                     buf.set_string(
-                        area.x + 2 + key_len,
+                        area.x + indent,
+                        y,
+                        &key,
+                        Style::default().fg(theme.accent_fg),
+                    );
+                    buf.set_string(
+                        area.x + indent + key_len,
                         y,
                         ": ",
-                        Style::default().fg(Color::White),
+                        Style::default().fg(theme.tool_name_fg),
+                    );
+                    let display = truncate_to_width(
+                        &value,
+                        area.width.saturating_sub(indent + 2 + key_len) as usize,
                     );
-                    let max_len = area.width.saturating_sub(4 + key_len) as usize;
-                    let display = if value.len() > max_len {
-                        &value[..max_len]
-                    } else {
-                        value.as_str()
-                    };
                     buf.set_string(
-                        area.x + 4 + key_len,
+                        area.x + indent + 2 + key_len,
                         y,
                         display,
-                        Style::default().fg(Color::Gray),
+                        Style::default().fg(theme.path_fg),
                     );
                 }
             }
@@ -82,33 +117,45 @@ impl ToolRenderer for CompactToolRenderer {
 
     fn calculate_height(&self, tool_block: &ToolUseBlock, _width: u16) -> u16 {
         let mut height: u16 = 1; // header line
-        height += compact_lines(tool_block).len() as u16;
+        if !is_summary_tool(tool_block) {
+            height += compact_lines(tool_block).len() as u16;
+        }
         if tool_block.status == ToolStatus::Error && tool_block.status_message.is_some() {
             height += 1;
         }
         height
     }
 
-    fn render_history_lines(&self, tool_block: &ToolUseBlock) -> Vec<Line<'static>> {
+    fn render_history_lines(&self, tool_block: &ToolUseBlock, _width: u16) -> Vec<Line<'static>> {
+        if is_summary_tool(tool_block) {
+            let mut lines = vec![summary_line(tool_block)];
+            push_error_history_line(tool_block, &mut lines);
+            return lines;
+        }
+
+        let theme = Theme::global();
         let mut lines = vec![tool_header_line(tool_block)];
 
         for compact in compact_lines(tool_block) {
             match compact {
                 CompactLine::Item(text) => {
                     lines.push(Line::from(vec![
-                        Span::styled("  - ", Style::default().fg(Color::DarkGray)),
-                        Span::styled(text, Style::default().fg(Color::Gray)),
+                        Span::raw(tool_indent_str()),
+                        Span::styled("- ", Style::default().fg(theme.muted_fg)),
+                        Span::styled(text, Style::default().fg(theme.path_fg)),
                     ]));
                 }
                 CompactLine::KeyValue(key, value) => {
                     lines.push(Line::from(vec![
-                        Span::raw("  "),
+                        Span::raw(tool_indent_str()),
                         Span::styled(
                             key,
-                            Style::default().fg(Color::Cyan).add_modifier(Modifier::DIM),
+                            Style::default()
+                                .fg(theme.accent_fg)
+                                .add_modifier(Modifier::DIM),
                         ),
-                        Span::styled(": ", Style::default().fg(Color::White)),
-                        Span::styled(value, Style::default().fg(Color::Gray)),
+                        Span::styled(": ", Style::default().fg(theme.tool_name_fg)),
+                        Span::styled(value, Style::default().fg(theme.path_fg)),
                     ]));
                 }
             }
@@ -130,6 +177,45 @@ enum CompactLine {
     KeyValue(String, String),
 }
 
+/// Join a tool's key parameters into one comma-separated string, e.g.
+/// `src/main.rs, src/lib.rs` for `read_files`.
+pub fn summarize_parameters(tool_block: &ToolUseBlock) -> String {
+    compact_lines(tool_block)
+        .into_iter()
+        .map(|line| match line {
+            CompactLine::Item(text) | CompactLine::KeyValue(_, text) => text,
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// `● tool_name summary [project]` on a single line.
+fn summary_line(tool_block: &ToolUseBlock) -> Line<'static> {
+    let theme = Theme::global();
+    let mut spans = vec![
+        Span::styled(
+            format!("{} ", status_symbol(&tool_block.status)),
+            Style::default().fg(status_color(&tool_block.status)),
+        ),
+        Span::styled(
+            tool_block.name.clone(),
+            Style::default()
+                .fg(theme.tool_name_fg)
+                .add_modifier(Modifier::BOLD),
+        ),
+    ];
+    let summary = summarize_parameters(tool_block);
+    if !summary.is_empty() {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(summary, Style::default().fg(theme.path_fg)));
+    }
+    let project = get_project_suffix(tool_block);
+    if !project.is_empty() {
+        spans.push(Span::styled(project, Style::default().fg(theme.muted_fg)));
+    }
+    Line::from(spans)
+}
+
 /// Extract the compact display items for a given tool block.
 fn compact_lines(tool_block: &ToolUseBlock) -> Vec<CompactLine> {
     let mut out = Vec::new();
@@ -216,6 +302,13 @@ mod tests {
             status: ToolStatus::Success,
             status_message: None,
             output: None,
+            truncated_lines: 0,
+            truncated_first_line: false,
+            awaiting_approval: false,
+            progress: None,
+            duration: None,
+            unchanged: false,
+            pending_cr: false,
         }
     }
 
@@ -273,20 +366,71 @@ mod tests {
     fn test_height_matches_lines() {
         let renderer = CompactToolRenderer;
         let tool = make_tool(
-            "read_files",
-            &[("paths", "a.rs\nb.rs\nc.rs"), ("project", "my-proj")],
+            "search_files",
+            &[
+                ("pattern", "fn main"),
+                ("path", "src/"),
+                ("project", "my-proj"),
+            ],
         );
-        // 1 header + 3 items = 4
-        assert_eq!(renderer.calculate_height(&tool, 80), 4);
+        // 1 header + 2 items = 3
+        assert_eq!(renderer.calculate_height(&tool, 80), 3);
     }
 
     #[test]
     fn test_height_with_error() {
         let renderer = CompactToolRenderer;
-        let mut tool = make_tool("read_files", &[("paths", "a.rs")]);
+        let mut tool = make_tool("search_files", &[("pattern", "fn main")]);
         tool.status = ToolStatus::Error;
-        tool.status_message = Some("File not found".to_string());
+        tool.status_message = Some("Invalid regex".to_string());
         // 1 header + 1 item + 1 error = 3
         assert_eq!(renderer.calculate_height(&tool, 80), 3);
     }
+
+    #[test]
+    fn test_long_value_is_truncated_at_the_area_edge() {
+        let renderer = CompactToolRenderer;
+        let tool = make_tool("web_search", &[("query", &"ratatui ".repeat(10))]);
+        let area = Rect::new(0, 0, 30, 2);
+        let mut buf = Buffer::empty(area);
+        renderer.render(&tool, area, &mut buf);
+        assert_eq!(buf[(area.width - 1, 1)].symbol(), "…");
+    }
+
+    #[test]
+    fn test_summary_tool_renders_one_line() {
+        let renderer = CompactToolRenderer;
+        let mut tool = make_tool(
+            "read_files",
+            &[("paths", "src/main.rs\nsrc/lib.rs"), ("project", "my-proj")],
+        );
+        assert_eq!(summarize_parameters(&tool), "src/main.rs, src/lib.rs");
+        assert_eq!(renderer.calculate_height(&tool, 80), 1);
+
+        let lines = renderer.render_history_lines(&tool, 80);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(
+            lines[0].to_string(),
+            "● read_files src/main.rs, src/lib.rs [my-proj]"
+        );
+
+        let area = Rect::new(0, 0, 60, 1);
+        let mut buf = Buffer::empty(area);
+        renderer.render(&tool, area, &mut buf);
+        let row: String = (0..area.width)
+            .map(|x| buf[(x, 0)].symbol().to_string())
+            .collect();
+        assert_eq!(
+            row.trim_end(),
+            "● read_files src/main.rs, src/lib.rs [my-proj]"
+        );
+
+        // A failure falls back to the full layout: header, 2 paths, error
+        tool.status = ToolStatus::Error;
+        tool.status_message = Some("File not found".to_string());
+        assert_eq!(renderer.calculate_height(&tool, 80), 4);
+        let lines = renderer.render_history_lines(&tool, 80);
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].to_string().ends_with("- src/main.rs"));
+    }
 }
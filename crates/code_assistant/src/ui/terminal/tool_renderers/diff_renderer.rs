@@ -7,35 +7,56 @@ use ratatui::prelude::*;
 use ratatui::style::{Color, Modifier, Style};
 use similar::{ChangeTag, TextDiff};
 
+use unicode_width::UnicodeWidthStr;
+
 use super::{
-    push_error_history_line, render_error_line, render_tool_header, tool_header_line, ToolRenderer,
+    push_error_history_line, render_error_line, render_tool_header, tool_header_line,
+    tool_header_width, tool_indent, tool_indent_str, ToolRenderer,
 };
 use crate::ui::terminal::message::ToolUseBlock;
 use crate::ui::terminal::terminal_color;
+use crate::ui::terminal::theme::Theme;
 use crate::ui::ToolStatus;
 
 /// Renderer for write/edit tools: edit, write_file, replace_in_file.
 pub struct DiffToolRenderer;
 
 impl ToolRenderer for DiffToolRenderer {
+    fn name(&self) -> &'static str {
+        "diff"
+    }
+
     fn supported_tools(&self) -> &'static [&'static str] {
         &["edit", "write_file", "replace_in_file"]
     }
 
     fn render(&self, tool_block: &ToolUseBlock, area: Rect, buf: &mut Buffer) {
+        let indent = tool_indent();
         if area.height < 1 {
             return;
         }
 
+        let diff_lines = generate_tool_diff_lines(tool_block);
         let mut y = render_tool_header(tool_block, area, buf, area.y);
+        if let Some(stat) = count_changes(&diff_lines) {
+            // After the header text, on the last row it drew
+            let x = area.x.saturating_add(tool_header_width(tool_block));
+            if x < area.x + area.width {
+                buf.set_line(
+                    x,
+                    y - 1,
+                    &Line::from(diff_stat_spans(stat)),
+                    area.x + area.width - x,
+                );
+            }
+        }
 
         // File path line
         y = render_file_path(tool_block, area, buf, y);
 
         // Diff body
-        let diff_lines = generate_tool_diff_lines(tool_block);
-        let bg = terminal_color::tool_content_bg();
-        y = render_diff_to_buffer(&diff_lines, area, buf, area.x + 2, y, bg);
+        let bg = terminal_color::tool_content_bg_for(&tool_block.name);
+        y = render_diff_to_buffer(&diff_lines, area, buf, area.x + indent, y, bg);
 
         render_error_line(tool_block, area, buf, y);
     }
@@ -44,7 +65,7 @@ impl ToolRenderer for DiffToolRenderer {
         let mut height: u16 = 1; // header
 
         // File path
-        if get_file_path(tool_block).is_some() {
+        if file_path(tool_block).is_some() {
             height += 1;
         }
 
@@ -57,20 +78,25 @@ impl ToolRenderer for DiffToolRenderer {
         height
     }
 
-    fn render_history_lines(&self, tool_block: &ToolUseBlock) -> Vec<Line<'static>> {
-        let mut lines = vec![tool_header_line(tool_block)];
+    fn render_history_lines(&self, tool_block: &ToolUseBlock, _width: u16) -> Vec<Line<'static>> {
+        let mut header = tool_header_line(tool_block);
+        if let Some(stat) = diff_stat(tool_block) {
+            header.spans.extend(diff_stat_spans(stat));
+        }
+        let mut lines = vec![header];
 
         // File path
-        if let Some(path) = get_file_path(tool_block) {
+        if let Some(path) = file_path(tool_block) {
             lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled(path, Style::default().fg(Color::Gray)),
+                Span::raw(tool_indent_str()),
+                Span::styled(path, Style::default().fg(Theme::global().path_fg)),
             ]));
         }
 
         // Diff
         let diff_lines = generate_tool_diff_lines(tool_block);
-        render_diff_to_history_lines(&diff_lines, &mut lines);
+        let bg = terminal_color::tool_content_bg_for(&tool_block.name);
+        render_diff_to_history_lines(&diff_lines, bg, &mut lines);
 
         push_error_history_line(tool_block, &mut lines);
         lines
@@ -82,10 +108,59 @@ impl ToolRenderer for DiffToolRenderer {
 // ---------------------------------------------------------------------------
 
 pub enum DiffLine {
-    Context { line_num: usize, text: String },
-    Insert { line_num: usize, text: String },
-    Delete { line_num: usize, text: String },
+    Context {
+        line_num: usize,
+        text: String,
+    },
+    Insert {
+        line_num: usize,
+        text: String,
+    },
+    Delete {
+        line_num: usize,
+        text: String,
+    },
     HunkSeparator,
+    /// The write left the file as it was
+    NoChanges,
+    /// Content with null bytes, shown as a size instead of a line diff
+    Binary {
+        bytes: usize,
+    },
+    /// Parameters still streaming in; the diff is computed once they're complete
+    Streaming {
+        bytes: usize,
+    },
+}
+
+impl DiffLine {
+    /// Dim note shown in place of a diff for the sentinel variants.
+    pub fn note(&self) -> Option<String> {
+        match self {
+            DiffLine::NoChanges => Some("(no changes)".to_string()),
+            DiffLine::Binary { bytes } => Some(format!("(binary file, {bytes} bytes)")),
+            DiffLine::Streaming { bytes } => Some(format!("(streaming… {bytes} bytes)")),
+            _ => None,
+        }
+    }
+}
+
+/// A placeholder while any of `params` is still streaming, so the diff isn't
+/// recomputed (and its height doesn't jump) on every chunk.
+fn streaming_placeholder(tool_block: &ToolUseBlock, params: &[&str]) -> Option<Vec<DiffLine>> {
+    let streaming = params
+        .iter()
+        .filter_map(|name| tool_block.parameters.get(*name));
+    if streaming.clone().all(|param| param.complete) {
+        return None;
+    }
+    let bytes = streaming.map(|param| param.value.len()).sum();
+    Some(vec![DiffLine::Streaming { bytes }])
+}
+
+/// Null bytes don't occur in text files, so treat them as a binary marker.
+fn is_probably_binary(content: &str) -> bool {
+    content.contains('\0')
 }
 
 // ---------------------------------------------------------------------------
@@ -93,7 +168,7 @@ pub enum DiffLine {
 // ---------------------------------------------------------------------------
 
 /// Produce the appropriate diff lines for a tool block based on its name.
-fn generate_tool_diff_lines(tool_block: &ToolUseBlock) -> Vec<DiffLine> {
+pub fn generate_tool_diff_lines(tool_block: &ToolUseBlock) -> Vec<DiffLine> {
     match tool_block.name.as_str() {
         "edit" => {
             let old = tool_block
@@ -109,7 +184,28 @@ fn generate_tool_diff_lines(tool_block: &ToolUseBlock) -> Vec<DiffLine> {
             if old.is_empty() && new.is_empty() {
                 return Vec::new();
             }
-            generate_diff_lines(old, new)
+            if let Some(placeholder) = streaming_placeholder(tool_block, &["old_text", "new_text"])
+            {
+                return placeholder;
+            }
+            if old == new {
+                return vec![DiffLine::NoChanges];
+            }
+            if is_probably_binary(old) || is_probably_binary(new) {
+                return vec![DiffLine::Binary { bytes: new.len() }];
+            }
+            let mut lines = generate_diff_lines(old, new, DEFAULT_DIFF_CONTEXT_LINES);
+            // Number lines as in the file when the backend says where the
+            // edited text starts; otherwise they count from 1
+            if let Some(start_line) = tool_block
+                .parameters
+                .get("start_line")
+                .and_then(|p| p.value.trim().parse::<usize>().ok())
+                .filter(|&start_line| start_line > 0)
+            {
+                offset_line_numbers(&mut lines, start_line - 1);
+            }
+            lines
         }
         "replace_in_file" => {
             let diff = tool_block
@@ -120,6 +216,9 @@ fn generate_tool_diff_lines(tool_block: &ToolUseBlock) -> Vec<DiffLine> {
             if diff.is_empty() {
                 return Vec::new();
             }
+            if let Some(placeholder) = streaming_placeholder(tool_block, &["diff"]) {
+                return placeholder;
+            }
             generate_search_replace_diff_lines(diff)
         }
         "write_file" => {
@@ -128,55 +227,124 @@ fn generate_tool_diff_lines(tool_block: &ToolUseBlock) -> Vec<DiffLine> {
                 .get("content")
                 .map(|p| p.value.as_str())
                 .unwrap_or("");
+            if is_write_unchanged(tool_block) {
+                return vec![DiffLine::NoChanges];
+            }
             if content.is_empty() {
                 return Vec::new();
             }
+            if let Some(placeholder) = streaming_placeholder(tool_block, &["content"]) {
+                return placeholder;
+            }
+            if is_probably_binary(content) {
+                return vec![DiffLine::Binary {
+                    bytes: content.len(),
+                }];
+            }
             generate_write_file_diff_lines(content)
         }
         _ => Vec::new(),
     }
 }
 
+/// Inserted and deleted line counts for a tool's diff, or `None` while there
+/// are no changes to count (e.g. parameters are still streaming in).
+pub fn diff_stat(tool_block: &ToolUseBlock) -> Option<(usize, usize)> {
+    count_changes(&generate_tool_diff_lines(tool_block))
+}
+
+fn count_changes(diff_lines: &[DiffLine]) -> Option<(usize, usize)> {
+    let (inserted, deleted) =
+        diff_lines
+            .iter()
+            .fold((0, 0), |(inserted, deleted), line| match line {
+                DiffLine::Insert { .. } => (inserted + 1, deleted),
+                DiffLine::Delete { .. } => (inserted, deleted + 1),
+                _ => (inserted, deleted),
+            });
+    (inserted + deleted > 0).then_some((inserted, deleted))
+}
+
+/// The ` (+12 −3)` suffix appended to diff tool headers.
+fn diff_stat_spans((inserted, deleted): (usize, usize)) -> Vec<Span<'static>> {
+    let theme = Theme::global();
+    let muted = Style::default().fg(theme.muted_fg);
+    vec![
+        Span::styled(" (", muted),
+        Span::styled(
+            format!("+{inserted}"),
+            Style::default().fg(theme.diff.insert_fg),
+        ),
+        Span::styled(" ", muted),
+        Span::styled(
+            format!("−{deleted}"),
+            Style::default().fg(theme.diff.delete_fg),
+        ),
+        Span::styled(")", muted),
+    ]
+}
+
+/// Unchanged lines shown around each change in edit diffs.
+pub const DEFAULT_DIFF_CONTEXT_LINES: usize = 3;
+
+/// Whether `write_file` reported that the file already had this content.
+fn is_write_unchanged(tool_block: &ToolUseBlock) -> bool {
+    tool_block.status == ToolStatus::Success && tool_block.unchanged
+}
+
 /// Generate diff lines from old/new text using the `similar` crate.
-pub fn generate_diff_lines(old_text: &str, new_text: &str) -> Vec<DiffLine> {
+///
+/// Changes are grouped into hunks with up to `context_lines` unchanged lines
+/// around them; longer unchanged runs are collapsed into a
+/// `DiffLine::HunkSeparator`. Line numbers refer to the full texts.
+pub fn generate_diff_lines(old_text: &str, new_text: &str, context_lines: usize) -> Vec<DiffLine> {
     let diff = TextDiff::configure()
         .newline_terminated(true)
         .diff_lines(old_text, new_text);
 
     let mut lines = Vec::new();
-    let mut old_ln: usize = 1;
-    let mut new_ln: usize = 1;
-
-    for change in diff.iter_all_changes() {
-        let text = change.value().trim_end_matches('\n').to_string();
-        match change.tag() {
-            ChangeTag::Equal => {
-                lines.push(DiffLine::Context {
-                    line_num: new_ln,
-                    text,
-                });
-                old_ln += 1;
-                new_ln += 1;
-            }
-            ChangeTag::Delete => {
-                lines.push(DiffLine::Delete {
-                    line_num: old_ln,
-                    text,
-                });
-                old_ln += 1;
-            }
-            ChangeTag::Insert => {
-                lines.push(DiffLine::Insert {
-                    line_num: new_ln,
-                    text,
+    for (hunk_idx, hunk) in diff.grouped_ops(context_lines).iter().enumerate() {
+        if hunk_idx > 0 {
+            lines.push(DiffLine::HunkSeparator);
+        }
+        for op in hunk {
+            for change in diff.iter_changes(op) {
+                let text = change.value().trim_end_matches('\n').to_string();
+                // `similar` indices are 0-based positions in the full texts
+                let old_ln = change.old_index().map_or(0, |i| i + 1);
+                let new_ln = change.new_index().map_or(0, |i| i + 1);
+                lines.push(match change.tag() {
+                    ChangeTag::Equal => DiffLine::Context {
+                        line_num: new_ln,
+                        text,
+                    },
+                    ChangeTag::Delete => DiffLine::Delete {
+                        line_num: old_ln,
+                        text,
+                    },
+                    ChangeTag::Insert => DiffLine::Insert {
+                        line_num: new_ln,
+                        text,
+                    },
                 });
-                new_ln += 1;
             }
         }
     }
     lines
 }
 
+/// Shift the line numbers of `lines` down by `offset`.
+fn offset_line_numbers(lines: &mut [DiffLine], offset: usize) {
+    for line in lines {
+        if let DiffLine::Context { line_num, .. }
+        | DiffLine::Insert { line_num, .. }
+        | DiffLine::Delete { line_num, .. } = line
+        {
+            *line_num += offset;
+        }
+    }
+}
+
 /// Parse the `<<<<<<< SEARCH` / `=======` / `>>>>>>> REPLACE` format used by
 /// `replace_in_file` and emit diff lines.
 pub fn generate_search_replace_diff_lines(diff_param: &str) -> Vec<DiffLine> {
@@ -270,7 +438,8 @@ fn expand_tabs(text: &str) -> String {
     result
 }
 
-fn get_file_path(tool_block: &ToolUseBlock) -> Option<String> {
+/// The file an edit tool writes to, once its path parameter is known.
+pub fn file_path(tool_block: &ToolUseBlock) -> Option<String> {
     tool_block
         .parameters
         .get("file_path")
@@ -280,11 +449,17 @@ fn get_file_path(tool_block: &ToolUseBlock) -> Option<String> {
 }
 
 fn render_file_path(tool_block: &ToolUseBlock, area: Rect, buf: &mut Buffer, y: u16) -> u16 {
+    let indent = tool_indent();
     if y >= area.y + area.height {
         return y;
     }
-    if let Some(path) = get_file_path(tool_block) {
-        buf.set_string(area.x + 2, y, &path, Style::default().fg(Color::Gray));
+    if let Some(path) = file_path(tool_block) {
+        buf.set_string(
+            area.x + indent,
+            y,
+            &path,
+            Style::default().fg(Theme::global().path_fg),
+        );
         y + 1
     } else {
         y
@@ -306,7 +481,10 @@ fn max_line_number(diff_lines: &[DiffLine]) -> usize {
             DiffLine::Context { line_num, .. }
             | DiffLine::Insert { line_num, .. }
             | DiffLine::Delete { line_num, .. } => Some(*line_num),
-            DiffLine::HunkSeparator => None,
+            DiffLine::HunkSeparator
+            | DiffLine::NoChanges
+            | DiffLine::Binary { .. }
+            | DiffLine::Streaming { .. } => None,
         })
         .max()
         .unwrap_or(0)
@@ -321,6 +499,7 @@ pub fn render_diff_to_buffer(
     mut y: u16,
     bg: Color,
 ) -> u16 {
+    let theme = Theme::global();
     let max_ln = max_line_number(diff_lines);
     let gw = line_number_width(max_ln);
 
@@ -335,6 +514,15 @@ pub fn render_diff_to_buffer(
         buf.set_string(x, y, " ".repeat(row_width as usize), bg_style);
 
         match diff_line {
+            DiffLine::NoChanges | DiffLine::Binary { .. } | DiffLine::Streaming { .. } => {
+                let note = diff_line.note().unwrap_or_default();
+                buf.set_string(
+                    x,
+                    y,
+                    &note,
+                    Style::default().add_modifier(Modifier::DIM).bg(bg),
+                );
+            }
             DiffLine::HunkSeparator => {
                 let spacer = format!("{:width$} ", "", width = gw);
                 buf.set_string(
@@ -344,7 +532,7 @@ pub fn render_diff_to_buffer(
                     Style::default().add_modifier(Modifier::DIM).bg(bg),
                 );
                 buf.set_string(
-                    x + spacer.len() as u16,
+                    x + spacer.width() as u16,
                     y,
                     "⋮",
                     Style::default().add_modifier(Modifier::DIM).bg(bg),
@@ -360,29 +548,14 @@ pub fn render_diff_to_buffer(
                 );
                 let content = format!(" {}", expand_tabs(text));
                 buf.set_string(
-                    x + gutter.len() as u16,
-                    y,
-                    &content,
-                    Style::default().fg(Color::Gray).bg(bg),
-                );
-            }
-            DiffLine::Insert { line_num, text } => {
-                let gutter = format!("{:>width$} ", line_num, width = gw);
-                buf.set_string(
-                    x,
-                    y,
-                    &gutter,
-                    Style::default().add_modifier(Modifier::DIM).bg(bg),
-                );
-                let content = format!("+{}", expand_tabs(text));
-                buf.set_string(
-                    x + gutter.len() as u16,
+                    x + gutter.width() as u16,
                     y,
                     &content,
-                    Style::default().fg(Color::Green).bg(bg),
+                    Style::default().fg(theme.diff_context).bg(bg),
                 );
             }
-            DiffLine::Delete { line_num, text } => {
+            DiffLine::Insert { line_num, text } | DiffLine::Delete { line_num, text } => {
+                let insert = matches!(diff_line, DiffLine::Insert { .. });
                 let gutter = format!("{:>width$} ", line_num, width = gw);
                 buf.set_string(
                     x,
@@ -390,13 +563,18 @@ pub fn render_diff_to_buffer(
                     &gutter,
                     Style::default().add_modifier(Modifier::DIM).bg(bg),
                 );
-                let content = format!("-{}", expand_tabs(text));
+                // With background highlights the rest of the row is tinted too
+                let content_x = x + gutter.width() as u16;
+                let line_bg = theme.diff.line_bg(insert, bg);
                 buf.set_string(
-                    x + gutter.len() as u16,
+                    content_x,
                     y,
-                    &content,
-                    Style::default().fg(Color::Red).bg(bg),
+                    " ".repeat(row_width.saturating_sub(gutter.width() as u16) as usize),
+                    Style::default().bg(line_bg),
                 );
+                let sign = if insert { '+' } else { '-' };
+                let content = format!("{sign}{}", expand_tabs(text));
+                buf.set_string(content_x, y, &content, theme.diff.line_style(insert, bg));
             }
         }
         y += 1;
@@ -405,51 +583,59 @@ pub fn render_diff_to_buffer(
 }
 
 /// Produce styled Lines for scrollback history.
-pub fn render_diff_to_history_lines(diff_lines: &[DiffLine], lines: &mut Vec<Line<'static>>) {
+pub fn render_diff_to_history_lines(
+    diff_lines: &[DiffLine],
+    bg: Color,
+    lines: &mut Vec<Line<'static>>,
+) {
     let max_ln = max_line_number(diff_lines);
     let gw = line_number_width(max_ln);
-    let bg = terminal_color::tool_content_bg();
+    let theme = Theme::global();
     let bg_style = Style::default().bg(bg);
+    let indent = tool_indent_str();
 
     for diff_line in diff_lines {
         let line = match diff_line {
+            DiffLine::NoChanges | DiffLine::Binary { .. } | DiffLine::Streaming { .. } => {
+                Line::from(Span::styled(
+                    format!("{indent}{}", diff_line.note().unwrap_or_default()),
+                    Style::default().add_modifier(Modifier::DIM).bg(bg),
+                ))
+            }
             DiffLine::HunkSeparator => Line::from(vec![
                 Span::styled(
-                    format!("  {:width$} ", "", width = gw),
+                    format!("{indent}{:width$} ", "", width = gw),
                     Style::default().add_modifier(Modifier::DIM).bg(bg),
                 ),
                 Span::styled("⋮", Style::default().add_modifier(Modifier::DIM).bg(bg)),
             ]),
             DiffLine::Context { line_num, text } => Line::from(vec![
                 Span::styled(
-                    format!("  {:>width$} ", line_num, width = gw),
+                    format!("{indent}{:>width$} ", line_num, width = gw),
                     Style::default().add_modifier(Modifier::DIM).bg(bg),
                 ),
                 Span::styled(
                     format!(" {}", expand_tabs(text)),
-                    Style::default().fg(Color::Gray).bg(bg),
-                ),
-            ]),
-            DiffLine::Insert { line_num, text } => Line::from(vec![
-                Span::styled(
-                    format!("  {:>width$} ", line_num, width = gw),
-                    Style::default().add_modifier(Modifier::DIM).bg(bg),
-                ),
-                Span::styled(
-                    format!("+{}", expand_tabs(text)),
-                    Style::default().fg(Color::Green).bg(bg),
-                ),
-            ]),
-            DiffLine::Delete { line_num, text } => Line::from(vec![
-                Span::styled(
-                    format!("  {:>width$} ", line_num, width = gw),
-                    Style::default().add_modifier(Modifier::DIM).bg(bg),
-                ),
-                Span::styled(
-                    format!("-{}", expand_tabs(text)),
-                    Style::default().fg(Color::Red).bg(bg),
+                    Style::default().fg(theme.diff_context).bg(bg),
                 ),
             ]),
+            DiffLine::Insert { line_num, text } | DiffLine::Delete { line_num, text } => {
+                let insert = matches!(diff_line, DiffLine::Insert { .. });
+                let sign = if insert { '+' } else { '-' };
+                let line = Line::from(vec![
+                    Span::styled(
+                        format!("{indent}{:>width$} ", line_num, width = gw),
+                        Style::default().add_modifier(Modifier::DIM).bg(bg),
+                    ),
+                    Span::styled(
+                        format!("{sign}{}", expand_tabs(text)),
+                        theme.diff.line_style(insert, bg),
+                    ),
+                ]);
+                // The row fill beyond the text takes the highlight color
+                lines.push(line.style(Style::default().bg(theme.diff.line_bg(insert, bg))));
+                continue;
+            }
         };
         // Setting bg on the Line style causes history_insert to fill the
         // entire terminal row with the background colour (via ClearType::UntilNewLine).
@@ -475,12 +661,51 @@ mod tests {
             status: ToolStatus::Success,
             status_message: None,
             output: None,
+            truncated_lines: 0,
+            truncated_first_line: false,
+            awaiting_approval: false,
+            progress: None,
+            duration: None,
+            unchanged: false,
+            pending_cr: false,
+        }
+    }
+
+    #[test]
+    fn test_monochrome_diff_has_no_colors() {
+        crate::ui::terminal::theme::set_monochrome(true);
+        let tool = make_tool(
+            "edit",
+            &[
+                ("path", "src/main.rs"),
+                ("old_text", "hello\nworld"),
+                ("new_text", "hello\nearth"),
+            ],
+        );
+        let area = Rect::new(0, 0, 40, 8);
+        let mut buf = Buffer::empty(area);
+        DiffToolRenderer.render(&tool, area, &mut buf);
+        crate::ui::terminal::theme::set_monochrome(false);
+
+        for y in 0..area.height {
+            for x in 0..area.width {
+                let cell = buf.cell((x, y)).unwrap();
+                assert_eq!(cell.fg, Color::Reset, "fg at ({x}, {y})");
+                assert_eq!(cell.bg, Color::Reset, "bg at ({x}, {y})");
+            }
         }
+        // Structural modifiers survive
+        let gutter = buf.cell((2, 2)).unwrap();
+        assert!(gutter.modifier.contains(Modifier::DIM));
     }
 
     #[test]
     fn test_edit_diff_lines() {
-        let lines = generate_diff_lines("hello\nworld\n", "hello\nearth\n");
+        let lines = generate_diff_lines(
+            "hello\nworld\n",
+            "hello\nearth\n",
+            DEFAULT_DIFF_CONTEXT_LINES,
+        );
         assert_eq!(lines.len(), 3); // context + delete + insert
         match &lines[0] {
             DiffLine::Context { line_num, text } => {
@@ -499,6 +724,114 @@ mod tests {
         }
     }
 
+    /// Compact `(kind, line_num)` view of diff lines for assertions.
+    fn diff_shape(lines: &[DiffLine]) -> Vec<(char, usize)> {
+        lines
+            .iter()
+            .map(|line| match line {
+                DiffLine::Context { line_num, .. } => (' ', *line_num),
+                DiffLine::Insert { line_num, .. } => ('+', *line_num),
+                DiffLine::Delete { line_num, .. } => ('-', *line_num),
+                DiffLine::HunkSeparator => ('@', 0),
+                DiffLine::NoChanges | DiffLine::Binary { .. } | DiffLine::Streaming { .. } => {
+                    ('=', 0)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_edit_diff_collapses_unchanged_lines_into_hunks() {
+        let old: String = (1..=30).map(|i| format!("line {i}\n")).collect();
+        let new = old
+            .replace("line 5\n", "line five\n")
+            .replace("line 25\n", "line twenty-five\n");
+
+        let lines = generate_diff_lines(&old, &new, 2);
+        assert_eq!(
+            diff_shape(&lines),
+            vec![
+                (' ', 3),
+                (' ', 4),
+                ('-', 5),
+                ('+', 5),
+                (' ', 6),
+                (' ', 7),
+                ('@', 0),
+                (' ', 23),
+                (' ', 24),
+                ('-', 25),
+                ('+', 25),
+                (' ', 26),
+                (' ', 27),
+            ]
+        );
+        match &lines[7] {
+            DiffLine::Context { text, .. } => assert_eq!(text, "line 23"),
+            _ => panic!("expected Context"),
+        }
+    }
+
+    #[test]
+    fn test_edit_diff_line_numbers_follow_inserted_lines() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let new = "a\nnew 1\nnew 2\nb\nc\nd\ne\nf\ng\nh\ni\nj changed\n";
+
+        let lines = generate_diff_lines(old, new, 1);
+        assert_eq!(
+            diff_shape(&lines),
+            vec![
+                (' ', 1),
+                ('+', 2),
+                ('+', 3),
+                (' ', 4),
+                ('@', 0),
+                (' ', 11),
+                ('-', 10),
+                ('+', 12),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_edit_diff_line_numbers_start_at_start_line() {
+        let params = [("old_text", "a\nb\n"), ("new_text", "a\nB\n")];
+        let tool = make_tool("edit", &params);
+        assert_eq!(
+            diff_shape(&generate_tool_diff_lines(&tool)),
+            vec![(' ', 1), ('-', 2), ('+', 2)]
+        );
+
+        let mut with_start = params.to_vec();
+        with_start.push(("start_line", "999"));
+        let lines = generate_tool_diff_lines(&make_tool("edit", &with_start));
+        assert_eq!(
+            diff_shape(&lines),
+            vec![(' ', 999), ('-', 1000), ('+', 1000)]
+        );
+        // The gutter widens to fit the file's line numbers
+        assert_eq!(line_number_width(max_line_number(&lines)), 4);
+
+        with_start.pop();
+        with_start.push(("start_line", "unknown"));
+        assert_eq!(
+            diff_shape(&generate_tool_diff_lines(&make_tool("edit", &with_start))),
+            vec![(' ', 1), ('-', 2), ('+', 2)]
+        );
+    }
+
+    #[test]
+    fn test_edit_diff_merges_nearby_changes_into_one_hunk() {
+        let old = "a\nb\nc\nd\ne\n";
+        let new = "A\nb\nc\nd\nE\n";
+
+        let lines = generate_diff_lines(old, new, DEFAULT_DIFF_CONTEXT_LINES);
+        assert!(!lines
+            .iter()
+            .any(|line| matches!(line, DiffLine::HunkSeparator)));
+        assert_eq!(lines.len(), 7);
+    }
+
     #[test]
     fn test_search_replace_diff_lines() {
         let diff = "<<<<<<< SEARCH\nold line 1\nold line 2\n=======\nnew line 1\n>>>>>>> REPLACE";
@@ -554,6 +887,90 @@ mod tests {
         assert_eq!(renderer.calculate_height(&tool, 80), 5);
     }
 
+    #[test]
+    fn test_no_op_edit_renders_no_changes_note() {
+        let tool = make_tool(
+            "edit",
+            &[
+                ("file_path", "src/main.rs"),
+                ("old_text", "same\n"),
+                ("new_text", "same\n"),
+            ],
+        );
+        assert!(matches!(
+            generate_tool_diff_lines(&tool).as_slice(),
+            [DiffLine::NoChanges]
+        ));
+        // header + path + note
+        assert_eq!(DiffToolRenderer.calculate_height(&tool, 80), 3);
+
+        let lines = DiffToolRenderer.render_history_lines(&tool, 80);
+        let note: String = lines[2].spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(note.trim(), "(no changes)");
+        assert!(lines[2].spans[0].style.add_modifier.contains(Modifier::DIM));
+    }
+
+    #[test]
+    fn test_unchanged_write_file_renders_no_changes_note() {
+        let mut tool = make_tool(
+            "write_file",
+            &[("path", "README.md"), ("content", "# Title\n")],
+        );
+        tool.unchanged = true;
+        assert!(matches!(
+            generate_tool_diff_lines(&tool).as_slice(),
+            [DiffLine::NoChanges]
+        ));
+
+        tool.unchanged = false;
+        assert!(matches!(
+            generate_tool_diff_lines(&tool).as_slice(),
+            [DiffLine::Insert { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_binary_content_renders_size_instead_of_lines() {
+        let tool = make_tool(
+            "write_file",
+            &[("path", "logo.bin"), ("content", "PNG\0\0\nIHDR\0")],
+        );
+        let diff_lines = generate_tool_diff_lines(&tool);
+        assert!(matches!(
+            diff_lines.as_slice(),
+            [DiffLine::Binary { bytes: 11 }]
+        ));
+        assert_eq!(
+            diff_lines[0].note().as_deref(),
+            Some("(binary file, 11 bytes)")
+        );
+    }
+
+    #[test]
+    fn test_streaming_parameters_render_placeholder_until_complete() {
+        let mut tool = ToolUseBlock::new("edit".to_string(), "test-id".to_string());
+        tool.add_or_update_parameter("path".to_string(), "src/main.rs".to_string());
+        tool.add_or_update_parameter("old_text".to_string(), "let a = 1;".to_string());
+        tool.add_or_update_parameter("new_text".to_string(), "let a".to_string());
+        let diff_lines = generate_tool_diff_lines(&tool);
+        assert!(matches!(
+            diff_lines.as_slice(),
+            [DiffLine::Streaming { bytes: 15 }]
+        ));
+        assert_eq!(
+            diff_lines[0].note().as_deref(),
+            Some("(streaming… 15 bytes)")
+        );
+        assert_eq!(DiffToolRenderer.calculate_height(&tool, 80), 3);
+
+        tool.add_or_update_parameter("new_text".to_string(), " = 2;".to_string());
+        tool.complete_parameters();
+        assert!(matches!(
+            generate_tool_diff_lines(&tool).as_slice(),
+            [DiffLine::Delete { .. }, DiffLine::Insert { .. }]
+        ));
+    }
+
     #[test]
     fn test_height_write_file() {
         let renderer = DiffToolRenderer;
@@ -564,4 +981,34 @@ mod tests {
         // 1 header + 1 file path + 2 insert lines = 4
         assert_eq!(renderer.calculate_height(&tool, 80), 4);
     }
+
+    #[test]
+    fn test_diff_stat_appended_to_header() {
+        let mut tool = make_tool(
+            "edit",
+            &[
+                ("file_path", "src/main.rs"),
+                ("old_text", "a\nb\nc\n"),
+                ("new_text", "a\nB\nc\nd\n"),
+            ],
+        );
+        assert_eq!(diff_stat(&tool), Some((2, 1)));
+        let lines = DiffToolRenderer.render_history_lines(&tool, 80);
+        assert_eq!(lines[0].to_string(), "● edit (+2 −1)");
+
+        let area = Rect::new(0, 0, 40, 6);
+        let mut buf = Buffer::empty(area);
+        DiffToolRenderer.render(&tool, area, &mut buf);
+        let header: String = (0..area.width)
+            .map(|x| buf[(x, 0)].symbol().to_string())
+            .collect();
+        assert_eq!(header.trim_end(), "● edit (+2 −1)");
+
+        // Nothing to count while the parameters are still streaming in
+        tool.parameters.clear();
+        tool.status = ToolStatus::Pending;
+        assert_eq!(diff_stat(&tool), None);
+        let lines = DiffToolRenderer.render_history_lines(&tool, 80);
+        assert_eq!(lines[0].to_string(), "● edit");
+    }
 }
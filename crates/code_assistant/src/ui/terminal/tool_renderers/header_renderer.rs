@@ -0,0 +1,44 @@
+//! Header-only renderer used for every tool while compact tool rendering is
+//! toggled on (Ctrl+O).
+//!
+//! Shows the `● tool_name [project]` line and, for failed calls, the error
+//! message — never parameters or output.
+
+use ratatui::prelude::*;
+
+use super::{
+    push_error_history_line, render_error_line, render_tool_header, tool_header_line, ToolRenderer,
+};
+use crate::ui::terminal::message::ToolUseBlock;
+use crate::ui::ToolStatus;
+
+pub struct HeaderToolRenderer;
+
+impl ToolRenderer for HeaderToolRenderer {
+    fn name(&self) -> &'static str {
+        "header"
+    }
+
+    fn supported_tools(&self) -> &'static [&'static str] {
+        // Not registered by name; selected for all tools in compact mode
+        &[]
+    }
+
+    fn render(&self, tool_block: &ToolUseBlock, area: Rect, buf: &mut Buffer) {
+        if area.height == 0 {
+            return;
+        }
+        let y = render_tool_header(tool_block, area, buf, area.y);
+        render_error_line(tool_block, area, buf, y);
+    }
+
+    fn calculate_height(&self, tool_block: &ToolUseBlock, _width: u16) -> u16 {
+        1 + u16::from(tool_block.status == ToolStatus::Error && tool_block.status_message.is_some())
+    }
+
+    fn render_history_lines(&self, tool_block: &ToolUseBlock, _width: u16) -> Vec<Line<'static>> {
+        let mut lines = vec![tool_header_line(tool_block)];
+        push_error_history_line(tool_block, &mut lines);
+        lines
+    }
+}
@@ -0,0 +1,204 @@
+//! Pretty-printed, syntax-colored JSON for tool output.
+//!
+//! The generic tool rendering uses this when a tool's output parses as a JSON
+//! object or array. Containers nested deeper than [`MAX_JSON_DEPTH`] collapse
+//! to a one-line summary, long strings are shortened, and the result is capped
+//! at [`MAX_JSON_LINES`] so large payloads don't flood the transcript.
+
+use ratatui::prelude::*;
+use ratatui::style::Style;
+use serde_json::Value;
+
+use crate::ui::terminal::theme::Theme;
+
+/// Nesting level past which objects and arrays are collapsed.
+pub const MAX_JSON_DEPTH: usize = 3;
+
+/// Maximum number of lines produced for one output.
+pub const MAX_JSON_LINES: usize = 40;
+
+/// Strings longer than this are cut off with an ellipsis.
+const MAX_JSON_STRING_CHARS: usize = 120;
+
+const INDENT: &str = "  ";
+
+/// Render JSON tool output as styled lines, or `None` if the output is not a
+/// JSON object or array (callers then show it as plain text).
+pub fn json_output_lines(output: &str) -> Option<Vec<Line<'static>>> {
+    let value: Value = serde_json::from_str(output.trim()).ok()?;
+    if !(value.is_object() || value.is_array()) {
+        return None;
+    }
+
+    let mut lines = Vec::new();
+    push_value(&value, 0, Vec::new(), false, &mut lines);
+
+    if lines.len() > MAX_JSON_LINES {
+        let hidden = lines.len() - (MAX_JSON_LINES - 1);
+        lines.truncate(MAX_JSON_LINES - 1);
+        lines.push(Line::styled(
+            format!("… {hidden} more lines"),
+            Style::default().fg(Theme::global().muted_fg),
+        ));
+    }
+    Some(lines)
+}
+
+/// Push the lines for `value`, starting with `prefix` (indent and key) on the
+/// first line.
+fn push_value(
+    value: &Value,
+    depth: usize,
+    prefix: Vec<Span<'static>>,
+    comma: bool,
+    lines: &mut Vec<Line<'static>>,
+) {
+    let theme = Theme::global();
+    let muted = Style::default().fg(theme.muted_fg);
+    let close = |bracket: &str| {
+        let mut text = bracket.to_string();
+        if comma {
+            text.push(',');
+        }
+        text
+    };
+
+    let (open, close_bracket, children): (&str, &str, Vec<(Option<&String>, &Value)>) = match value
+    {
+        Value::Object(map) => ("{", "}", map.iter().map(|(k, v)| (Some(k), v)).collect()),
+        Value::Array(items) => ("[", "]", items.iter().map(|v| (None, v)).collect()),
+        scalar => {
+            let mut spans = prefix;
+            spans.push(scalar_span(scalar));
+            if comma {
+                spans.push(Span::styled(",", muted));
+            }
+            lines.push(Line::from(spans));
+            return;
+        }
+    };
+
+    let mut spans = prefix;
+    if children.is_empty() {
+        spans.push(Span::styled(
+            close(&format!("{open}{close_bracket}")),
+            muted,
+        ));
+        lines.push(Line::from(spans));
+        return;
+    }
+    if depth >= MAX_JSON_DEPTH {
+        let count = children.len();
+        let summary = match (value, count) {
+            (Value::Object(_), 1) => "{… 1 key}".to_string(),
+            (Value::Object(_), _) => format!("{{… {count} keys}}"),
+            (_, 1) => "[… 1 item]".to_string(),
+            _ => format!("[… {count} items]"),
+        };
+        spans.push(Span::styled(close(&summary), muted));
+        lines.push(Line::from(spans));
+        return;
+    }
+
+    spans.push(Span::styled(open.to_string(), muted));
+    lines.push(Line::from(spans));
+
+    let indent = INDENT.repeat(depth + 1);
+    let last = children.len() - 1;
+    for (index, (key, child)) in children.into_iter().enumerate() {
+        let mut prefix = vec![Span::raw(indent.clone())];
+        if let Some(key) = key {
+            prefix.push(Span::styled(
+                quote(key),
+                Style::default().fg(theme.json_key),
+            ));
+            prefix.push(Span::styled(": ", muted));
+        }
+        push_value(child, depth + 1, prefix, index < last, lines);
+    }
+
+    lines.push(Line::from(vec![
+        Span::raw(INDENT.repeat(depth)),
+        Span::styled(close(close_bracket), muted),
+    ]));
+}
+
+fn scalar_span(value: &Value) -> Span<'static> {
+    let theme = Theme::global();
+    match value {
+        Value::String(text) => {
+            let text = if text.chars().count() > MAX_JSON_STRING_CHARS {
+                let cut: String = text.chars().take(MAX_JSON_STRING_CHARS).collect();
+                format!("{cut}…")
+            } else {
+                text.clone()
+            };
+            Span::styled(quote(&text), Style::default().fg(theme.json_string))
+        }
+        Value::Number(number) => {
+            Span::styled(number.to_string(), Style::default().fg(theme.json_number))
+        }
+        other => Span::styled(other.to_string(), Style::default().fg(theme.json_literal)),
+    }
+}
+
+/// Quote a string the way JSON would, escaping control characters and quotes.
+fn quote(text: &str) -> String {
+    Value::String(text.to_string()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(lines: &[Line]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_pretty_prints_and_colors_json() {
+        let lines = json_output_lines(r#"{"count":2,"name":"x","ok":true,"tags":["a"]}"#).unwrap();
+        assert_eq!(
+            plain(&lines),
+            vec![
+                "{",
+                r#"  "count": 2,"#,
+                r#"  "name": "x","#,
+                r#"  "ok": true,"#,
+                r#"  "tags": ["#,
+                r#"    "a""#,
+                "  ]",
+                "}",
+            ]
+        );
+        let theme = Theme::global();
+        assert_eq!(lines[1].spans[1].style.fg, Some(theme.json_key));
+        assert_eq!(lines[1].spans[3].style.fg, Some(theme.json_number));
+        assert_eq!(lines[2].spans[3].style.fg, Some(theme.json_string));
+        assert_eq!(lines[3].spans[3].style.fg, Some(theme.json_literal));
+    }
+
+    #[test]
+    fn test_deep_nesting_collapses() {
+        let lines = json_output_lines(r#"{"a":{"b":{"c":{"d":1,"e":2}},"f":[[1,2,3]]}}"#).unwrap();
+        let text = plain(&lines);
+        assert!(text.contains(&r#"      "c": {… 2 keys}"#.to_string()));
+        assert!(text.contains(&"      [… 3 items]".to_string()));
+    }
+
+    #[test]
+    fn test_large_payload_is_capped() {
+        let items: Vec<String> = (0..100).map(|i| i.to_string()).collect();
+        let lines = json_output_lines(&format!("[{}]", items.join(","))).unwrap();
+        assert_eq!(lines.len(), MAX_JSON_LINES);
+        assert_eq!(lines.last().unwrap().to_string(), "… 63 more lines");
+    }
+
+    #[test]
+    fn test_non_json_and_scalars_fall_back() {
+        assert!(json_output_lines("plain text output").is_none());
+        assert!(json_output_lines("{ not json").is_none());
+        assert!(json_output_lines("42").is_none());
+        assert!(json_output_lines("\"text\"").is_none());
+    }
+}
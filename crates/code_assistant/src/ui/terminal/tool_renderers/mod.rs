@@ -6,14 +6,22 @@
 pub mod command_renderer;
 pub mod compact_renderer;
 pub mod diff_renderer;
+pub mod header_renderer;
+pub mod json_output;
 
 use std::collections::HashMap;
 use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
 use ratatui::prelude::*;
 use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
+use super::config::TerminalConfig;
 use super::message::ToolUseBlock;
+use super::theme::Theme;
 use crate::ui::ToolStatus;
 
 /// Trait for custom tool block renderers.
@@ -21,6 +29,9 @@ use crate::ui::ToolStatus;
 /// Implementations handle rendering for one or more tool names, covering
 /// both the live viewport (ratatui Buffer) and scrollback history (Line items).
 pub trait ToolRenderer: Send + Sync {
+    /// Short name of the renderer, shown by `/tools`.
+    fn name(&self) -> &'static str;
+
     /// Which tool names this renderer handles.
     fn supported_tools(&self) -> &'static [&'static str];
 
@@ -30,8 +41,8 @@ pub trait ToolRenderer: Send + Sync {
     /// Calculate the height (in rows) needed for this tool block.
     fn calculate_height(&self, tool_block: &ToolUseBlock, width: u16) -> u16;
 
-    /// Produce styled Lines for scrollback history.
-    fn render_history_lines(&self, tool_block: &ToolUseBlock) -> Vec<Line<'static>>;
+    /// Produce styled Lines for scrollback history at the given terminal width.
+    fn render_history_lines(&self, tool_block: &ToolUseBlock, width: u16) -> Vec<Line<'static>>;
 }
 
 // ---------------------------------------------------------------------------
@@ -64,6 +75,37 @@ impl ToolRendererRegistry {
         self.renderers.get(tool_name).cloned()
     }
 
+    /// Names of all tools with a registered renderer, sorted.
+    pub fn registered_tools(&self) -> Vec<String> {
+        let mut tools: Vec<String> = self.renderers.keys().cloned().collect();
+        tools.sort();
+        tools
+    }
+
+    /// Registered tools grouped by the name of their renderer, both sorted.
+    pub fn tools_by_renderer(&self) -> Vec<(&'static str, Vec<String>)> {
+        let mut groups: Vec<(&'static str, Vec<String>)> = Vec::new();
+        for tool_name in self.registered_tools() {
+            let renderer = self.renderers[&tool_name].name();
+            match groups.iter_mut().find(|(name, _)| *name == renderer) {
+                Some((_, tools)) => tools.push(tool_name),
+                None => groups.push((renderer, vec![tool_name])),
+            }
+        }
+        groups.sort_by_key(|(name, _)| *name);
+        groups
+    }
+
+    /// Info text for `/tools`: each renderer with the tools it handles.
+    pub fn tools_text(&self) -> String {
+        let mut text = String::from("Tool renderers:\n");
+        for (renderer, tools) in self.tools_by_renderer() {
+            text.push_str(&format!("  {renderer}: {}\n", tools.join(", ")));
+        }
+        text.push_str("Other tools use the generic rendering.");
+        text
+    }
+
     /// Install the global singleton.
     pub fn set_global(registry: ToolRendererRegistry) {
         let _ = GLOBAL_REGISTRY.set(Arc::new(registry));
@@ -75,19 +117,151 @@ impl ToolRendererRegistry {
     }
 }
 
+/// How much of each tool block is shown, toggled for all tools with Ctrl+O.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ToolDetail {
+    /// Each tool's own renderer
+    #[default]
+    Full,
+    /// Only the header line (and error message) of every tool
+    Compact,
+}
+
+impl ToolDetail {
+    pub fn toggled(self) -> Self {
+        match self {
+            ToolDetail::Full => ToolDetail::Compact,
+            ToolDetail::Compact => ToolDetail::Full,
+        }
+    }
+}
+
+/// The renderer to use for `tool_name` at `detail`, or `None` for the
+/// generic fallback rendering.
+pub fn renderer_for(tool_name: &str, detail: ToolDetail) -> Option<Arc<dyn ToolRenderer>> {
+    match detail {
+        ToolDetail::Compact => Some(Arc::new(header_renderer::HeaderToolRenderer)),
+        ToolDetail::Full => ToolRendererRegistry::global()?.get(tool_name),
+    }
+}
+
+/// Info text for `/tools` from the global registry.
+pub fn tools_info() -> String {
+    match ToolRendererRegistry::global() {
+        Some(registry) => registry.tools_text(),
+        None => "No tool renderers registered.".to_string(),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Shared helpers used by multiple renderers
 // ---------------------------------------------------------------------------
 
+/// Cut `text` to at most `max_width` columns, marking the cut with `…`.
+/// Whole grapheme clusters are kept, so this is safe for any UTF-8 input.
+pub fn truncate_to_width(text: &str, max_width: usize) -> String {
+    if text.width() <= max_width {
+        return text.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+    let mut truncated = String::new();
+    let mut used = 0;
+    for grapheme in text.graphemes(true) {
+        let grapheme_width = grapheme.width();
+        if used + grapheme_width + 1 > max_width {
+            break;
+        }
+        truncated.push_str(grapheme);
+        used += grapheme_width;
+    }
+    truncated.push('…');
+    truncated
+}
+
+/// How the ` [project]` suffix in tool headers is shown.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectSuffixStyle {
+    /// Project values that get no suffix, e.g. the default project's name
+    pub hidden: Vec<String>,
+    /// Suffix text, with `{}` replaced by the project
+    pub format: String,
+    /// Show only the last path component when the project is a path
+    pub basename: bool,
+}
+
+impl Default for ProjectSuffixStyle {
+    fn default() -> Self {
+        Self {
+            hidden: vec![String::new(), ".".to_string(), "unknown".to_string()],
+            format: "[{}]".to_string(),
+            basename: false,
+        }
+    }
+}
+
+impl ProjectSuffixStyle {
+    /// Whether `project` names a project worth showing.
+    pub fn is_visible(&self, project: &str) -> bool {
+        !self.hidden.iter().any(|hidden| hidden == project)
+    }
+
+    /// ` [project]` for a visible project, else empty.
+    pub fn suffix(&self, project: &str) -> String {
+        if !self.is_visible(project) {
+            return String::new();
+        }
+        let shown = if self.basename {
+            std::path::Path::new(project)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or(project)
+        } else {
+            project
+        };
+        format!(" {}", self.format.replace("{}", shown))
+    }
+}
+
+/// The tool's `project` parameter, unless it is one of the hidden values.
+pub fn tool_project(tool_block: &ToolUseBlock) -> Option<&str> {
+    let project = tool_block.parameters.get("project")?.value.as_str();
+    TerminalConfig::global()
+        .project_suffix
+        .is_visible(project)
+        .then_some(project)
+}
+
 /// Return ` [project]` if a meaningful project parameter is present, else empty.
 pub fn get_project_suffix(tool_block: &ToolUseBlock) -> String {
-    if let Some(project_param) = tool_block.parameters.get("project") {
-        let val = &project_param.value;
-        if !val.is_empty() && val != "." && val != "unknown" {
-            return format!(" [{}]", val);
-        }
+    tool_block
+        .parameters
+        .get("project")
+        .map(|project| {
+            TerminalConfig::global()
+                .project_suffix
+                .suffix(&project.value)
+        })
+        .unwrap_or_default()
+}
+
+/// Tools finishing faster than this get no elapsed-time badge.
+const MIN_BADGE_DURATION: Duration = Duration::from_millis(100);
+
+/// `1.3s` or `2m 5s` for the scrollback header; `None` for near-instant
+/// tools, which would only add noise.
+pub fn format_tool_duration(duration: Duration) -> Option<String> {
+    if duration < MIN_BADGE_DURATION {
+        return None;
     }
-    String::new()
+    let secs = duration.as_secs();
+    Some(if secs < 60 {
+        format!("{:.1}s", duration.as_secs_f64())
+    } else {
+        format!("{}m {}s", secs / 60, secs % 60)
+    })
 }
 
 /// Status symbol for a tool block.
@@ -97,36 +271,51 @@ pub fn status_symbol(_status: &ToolStatus) -> &'static str {
 
 /// Status color for a tool block.
 pub fn status_color(status: &ToolStatus) -> Color {
-    match status {
-        ToolStatus::Pending => Color::Yellow,
-        ToolStatus::Running => Color::Blue,
-        ToolStatus::Success => Color::Green,
-        ToolStatus::Error => Color::Red,
-    }
+    Theme::global().status_color(status)
+}
+
+/// Columns from a tool block's left edge to its content. The status symbol
+/// sits in the same gutter as the user marker, so tool content lines up
+/// with message text.
+pub fn tool_indent() -> u16 {
+    Theme::global().gutter_width() as u16
+}
+
+/// Blank [`tool_indent`] for the start of scrollback lines.
+pub fn tool_indent_str() -> String {
+    Theme::global().gutter()
+}
+
+/// Columns the header drawn by [`render_tool_header`] takes.
+pub fn tool_header_width(tool_block: &ToolUseBlock) -> u16 {
+    let width = tool_block.name.width() + get_project_suffix(tool_block).width();
+    tool_indent().saturating_add(width as u16)
 }
 
 /// Render the standard `● tool_name [project]` header line into a Buffer.
 /// Returns the y position of the next row.
 pub fn render_tool_header(tool_block: &ToolUseBlock, area: Rect, buf: &mut Buffer, y: u16) -> u16 {
+    let indent = tool_indent();
+    let theme = Theme::global();
     let color = status_color(&tool_block.status);
     let symbol = status_symbol(&tool_block.status);
     let project = get_project_suffix(tool_block);
 
     buf.set_string(area.x, y, symbol, Style::default().fg(color));
     buf.set_string(
-        area.x + 2,
+        area.x + indent,
         y,
         &tool_block.name,
         Style::default()
-            .fg(Color::White)
+            .fg(theme.tool_name_fg)
             .add_modifier(Modifier::BOLD),
     );
     if !project.is_empty() {
         buf.set_string(
-            area.x + 2 + tool_block.name.len() as u16,
+            area.x + indent + tool_block.name.width() as u16,
             y,
             &project,
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.muted_fg),
         );
     }
     y + 1
@@ -134,36 +323,50 @@ pub fn render_tool_header(tool_block: &ToolUseBlock, area: Rect, buf: &mut Buffe
 
 /// Produce a styled `● tool_name [project]` Line for scrollback history.
 pub fn tool_header_line(tool_block: &ToolUseBlock) -> Line<'static> {
+    let theme = Theme::global();
     let color = status_color(&tool_block.status);
     let project = get_project_suffix(tool_block);
 
     let mut spans = vec![
-        Span::styled("● ", Style::default().fg(color)),
+        Span::styled(
+            format!("●{}", &tool_indent_str()[1..]),
+            Style::default().fg(color),
+        ),
         Span::styled(
             tool_block.name.clone(),
             Style::default()
-                .fg(Color::White)
+                .fg(theme.tool_name_fg)
                 .add_modifier(Modifier::BOLD),
         ),
     ];
     if !project.is_empty() {
-        spans.push(Span::styled(project, Style::default().fg(Color::DarkGray)));
+        spans.push(Span::styled(project, Style::default().fg(theme.muted_fg)));
+    }
+    if let Some(elapsed) = tool_block.duration.and_then(format_tool_duration) {
+        spans.push(Span::styled(
+            format!(" · {elapsed}"),
+            Style::default()
+                .fg(theme.muted_fg)
+                .add_modifier(Modifier::DIM),
+        ));
     }
     Line::from(spans)
 }
 
 /// Render an error status message (if any) into a Buffer. Returns the next y.
 pub fn render_error_line(tool_block: &ToolUseBlock, area: Rect, buf: &mut Buffer, y: u16) -> u16 {
+    let indent = tool_indent();
     if tool_block.status == ToolStatus::Error {
         if let Some(ref message) = tool_block.status_message {
             if y < area.y + area.height {
-                let max_len = area.width.saturating_sub(2) as usize;
-                let display = if message.len() > max_len {
-                    &message[..max_len]
-                } else {
-                    message.as_str()
-                };
-                buf.set_string(area.x + 2, y, display, Style::default().fg(Color::LightRed));
+                let display =
+                    truncate_to_width(message, area.width.saturating_sub(indent) as usize);
+                buf.set_string(
+                    area.x + indent,
+                    y,
+                    display,
+                    Style::default().fg(Theme::global().tool_error_fg),
+                );
                 return y + 1;
             }
         }
@@ -176,8 +379,8 @@ pub fn push_error_history_line(tool_block: &ToolUseBlock, lines: &mut Vec<Line<'
     if tool_block.status == ToolStatus::Error {
         if let Some(ref message) = tool_block.status_message {
             lines.push(Line::styled(
-                format!("  {message}"),
-                Style::default().fg(Color::LightRed),
+                format!("{}{message}", tool_indent_str()),
+                Style::default().fg(Theme::global().tool_error_fg),
             ));
         }
     }
@@ -195,3 +398,123 @@ pub fn init_registry() {
     registry.register(Arc::new(command_renderer::CommandToolRenderer));
     ToolRendererRegistry::set_global(registry);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registered_tools_grouped_by_renderer() {
+        let mut registry = ToolRendererRegistry::new();
+        registry.register(Arc::new(diff_renderer::DiffToolRenderer));
+        registry.register(Arc::new(command_renderer::CommandToolRenderer));
+        assert_eq!(
+            registry.registered_tools(),
+            ["edit", "execute_command", "replace_in_file", "write_file"]
+        );
+        assert_eq!(
+            registry.tools_text(),
+            "Tool renderers:\n  command: execute_command\n  diff: edit, replace_in_file, write_file\nOther tools use the generic rendering."
+        );
+    }
+
+    #[test]
+    fn test_truncate_to_width_keeps_graphemes_whole() {
+        assert_eq!(truncate_to_width("short", 10), "short");
+        assert_eq!(truncate_to_width("abcdef", 4), "abc…");
+        assert_eq!(truncate_to_width("日本語のエラー", 7), "日本語…");
+        // `é` as e + combining accent stays together
+        assert_eq!(truncate_to_width("cafe\u{301}latte", 6), "cafe\u{301}l…");
+        assert_eq!(truncate_to_width("abc", 0), "");
+    }
+
+    #[test]
+    fn test_header_line_shows_elapsed_badge() {
+        let header_text = |tool: &ToolUseBlock| -> String {
+            tool_header_line(tool)
+                .spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect()
+        };
+        let mut tool = ToolUseBlock::new("read_files".to_string(), "tool-1".to_string());
+        tool.status = ToolStatus::Success;
+        assert_eq!(header_text(&tool), "● read_files");
+
+        tool.duration = Some(Duration::from_millis(40));
+        assert_eq!(header_text(&tool), "● read_files");
+
+        tool.duration = Some(Duration::from_millis(1340));
+        assert_eq!(header_text(&tool), "● read_files · 1.3s");
+        let badge = tool_header_line(&tool).spans.last().unwrap().clone();
+        assert!(badge.style.add_modifier.contains(Modifier::DIM));
+
+        assert_eq!(
+            format_tool_duration(Duration::from_secs(125)).as_deref(),
+            Some("2m 5s")
+        );
+    }
+
+    #[test]
+    fn test_header_places_project_suffix_by_display_width() {
+        let mut tool_block = ToolUseBlock::new("読み込み".to_string(), "tool-1".to_string());
+        tool_block.parameters.insert(
+            "project".to_string(),
+            crate::ui::terminal::message::ParameterValue::new("app".to_string()),
+        );
+
+        let area = Rect::new(0, 0, 30, 1);
+        let mut buf = Buffer::empty(area);
+        assert_eq!(render_tool_header(&tool_block, area, &mut buf, 0), 1);
+
+        // Four wide characters take eight columns, so the suffix starts at 2 + 8
+        assert_eq!(buf[(10, 0)].symbol(), " ");
+        assert_eq!(buf[(11, 0)].symbol(), "[");
+        let row: String = (0..area.width)
+            .map(|x| buf[(x, 0)].symbol().to_string())
+            .collect();
+        assert_eq!(row.trim_end(), "● 読 み 込 み  [app]");
+    }
+
+    #[test]
+    fn test_project_suffix_defaults() {
+        let style = ProjectSuffixStyle::default();
+        assert_eq!(style.suffix("code-assistant"), " [code-assistant]");
+        assert_eq!(style.suffix(""), "");
+        assert_eq!(style.suffix("."), "");
+        assert_eq!(style.suffix("unknown"), "");
+        assert_eq!(style.suffix("~/src/app"), " [~/src/app]");
+    }
+
+    #[test]
+    fn test_project_suffix_custom_hidden_values_and_basename() {
+        let style = ProjectSuffixStyle {
+            hidden: vec!["default".to_string()],
+            format: "({})".to_string(),
+            basename: true,
+        };
+        assert_eq!(style.suffix("default"), "");
+        // Only the configured values are hidden
+        assert_eq!(style.suffix("unknown"), " (unknown)");
+        assert_eq!(style.suffix("/home/me/src/app"), " (app)");
+        assert_eq!(style.suffix("/home/me/src/app/"), " (app)");
+        assert_eq!(style.suffix("app"), " (app)");
+    }
+
+    #[test]
+    fn test_error_line_truncates_multibyte_message() {
+        let mut tool_block = ToolUseBlock::new("read_files".to_string(), "tool-1".to_string());
+        tool_block.status = ToolStatus::Error;
+        tool_block.status_message = Some("ファイルが見つかりません: ß/ü/é".repeat(4));
+
+        let area = Rect::new(0, 0, 20, 1);
+        let mut buf = Buffer::empty(area);
+        assert_eq!(render_error_line(&tool_block, area, &mut buf, 0), 1);
+
+        // Wide characters cover two cells; the second one is left blank
+        let row: String = (0..area.width)
+            .map(|x| buf[(x, 0)].symbol().to_string())
+            .collect();
+        assert_eq!(row.trim_end(), "  フ ァ イ ル が 見 つ か …");
+    }
+}
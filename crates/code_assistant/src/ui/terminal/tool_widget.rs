@@ -1,8 +1,13 @@
 use crate::ui::ToolStatus;
 use ratatui::prelude::*;
+use unicode_width::UnicodeWidthStr;
 
 use super::message::ToolUseBlock;
-use super::tool_renderers::ToolRendererRegistry;
+use super::theme::Theme;
+use super::tool_renderers::json_output::json_output_lines;
+use super::tool_renderers::{
+    renderer_for, tool_indent, tool_indent_str, truncate_to_width, ToolDetail,
+};
 
 /// Custom ratatui widget for rendering tool use blocks.
 ///
@@ -11,11 +16,12 @@ use super::tool_renderers::ToolRendererRegistry;
 /// without a custom renderer (e.g. `spawn_agent`, `delete_files`).
 pub struct ToolWidget<'a> {
     tool_block: &'a ToolUseBlock,
+    detail: ToolDetail,
 }
 
 impl<'a> ToolWidget<'a> {
-    pub fn new(tool_block: &'a ToolUseBlock) -> Self {
-        Self { tool_block }
+    pub fn new(tool_block: &'a ToolUseBlock, detail: ToolDetail) -> Self {
+        Self { tool_block, detail }
     }
 
     fn get_status_symbol(&self) -> &'static str {
@@ -23,12 +29,7 @@ impl<'a> ToolWidget<'a> {
     }
 
     fn get_status_color(&self) -> Color {
-        match self.tool_block.status {
-            ToolStatus::Pending => Color::Yellow,
-            ToolStatus::Running => Color::Blue,
-            ToolStatus::Success => Color::Green,
-            ToolStatus::Error => Color::Red,
-        }
+        Theme::global().status_color(&self.tool_block.status)
     }
 }
 
@@ -38,12 +39,15 @@ impl<'a> Widget for ToolWidget<'a> {
             return;
         }
 
+        if let Some(fraction) = self.tool_block.visible_progress() {
+            self.render_with_progress(fraction, area, buf);
+            return;
+        }
+
         // Try a registered renderer first.
-        if let Some(registry) = ToolRendererRegistry::global() {
-            if let Some(renderer) = registry.get(&self.tool_block.name) {
-                renderer.render(self.tool_block, area, buf);
-                return;
-            }
+        if let Some(renderer) = renderer_for(&self.tool_block.name, self.detail) {
+            renderer.render(self.tool_block, area, buf);
+            return;
         }
 
         // ── Fallback: generic rendering ──────────────────────────────────
@@ -52,8 +56,43 @@ impl<'a> Widget for ToolWidget<'a> {
 }
 
 impl<'a> ToolWidget<'a> {
+    /// Render the block with a progress bar line inserted under its header.
+    /// The block is drawn off-screen first so every renderer gets the line
+    /// without knowing about it.
+    fn render_with_progress(&self, fraction: f32, area: Rect, buf: &mut Buffer) {
+        let body = Rect {
+            height: area.height.saturating_sub(1),
+            ..area
+        };
+        let mut scratch = Buffer::empty(body);
+        let tool_block = ToolUseBlock {
+            progress: None,
+            ..self.tool_block.clone()
+        };
+        ToolWidget::new(&tool_block, self.detail).render(body, &mut scratch);
+
+        for y in body.top()..body.bottom() {
+            let target_y = if y == body.top() { y } else { y + 1 };
+            for x in body.left()..body.right() {
+                buf[(x, target_y)] = scratch[(x, y)].clone();
+            }
+        }
+        if area.height > 1 {
+            let bar_width = area.width.saturating_sub(7).min(PROGRESS_BAR_WIDTH);
+            let line = Line::from(vec![
+                Span::raw(tool_indent_str()),
+                Span::styled(
+                    render_progress_bar(fraction, bar_width),
+                    Style::default().fg(Theme::global().status_running),
+                ),
+            ]);
+            buf.set_line(area.x, area.y + 1, &line, area.width);
+        }
+    }
+
     /// Generic fallback rendering for tools without a custom renderer.
     fn render_fallback(&self, area: Rect, buf: &mut Buffer) {
+        let indent = tool_indent();
         let (regular_params, fullwidth_params): (Vec<_>, Vec<_>) = self
             .tool_block
             .parameters
@@ -74,11 +113,11 @@ impl<'a> ToolWidget<'a> {
             Style::default().fg(status_color),
         );
         buf.set_string(
-            area.x + 2,
+            area.x + indent,
             current_y,
             &self.tool_block.name,
             Style::default()
-                .fg(Color::White)
+                .fg(Theme::global().tool_name_fg)
                 .add_modifier(Modifier::BOLD),
         );
         current_y += 1;
@@ -93,22 +132,22 @@ impl<'a> ToolWidget<'a> {
             }
 
             buf.set_string(
-                area.x + 2,
+                area.x + indent,
                 current_y,
                 name,
-                Style::default().fg(Color::Cyan),
+                Style::default().fg(Theme::global().accent_fg),
             );
             buf.set_string(
-                area.x + 2 + name.len() as u16,
+                area.x + indent + name.width() as u16,
                 current_y,
                 ": ",
-                Style::default().fg(Color::White),
+                Style::default().fg(Theme::global().text_fg),
             );
             buf.set_string(
-                area.x + 2 + name.len() as u16 + 2,
+                area.x + indent + name.width() as u16 + 2,
                 current_y,
                 param.get_display_value(),
-                Style::default().fg(Color::Gray),
+                Style::default().fg(Theme::global().secondary_fg),
             );
             current_y += 1;
         }
@@ -123,11 +162,11 @@ impl<'a> ToolWidget<'a> {
             }
 
             buf.set_string(
-                area.x + 2,
+                area.x + indent,
                 current_y,
                 name,
                 Style::default()
-                    .fg(Color::Cyan)
+                    .fg(Theme::global().accent_fg)
                     .add_modifier(Modifier::BOLD),
             );
             current_y += 1;
@@ -137,10 +176,10 @@ impl<'a> ToolWidget<'a> {
                     break;
                 }
                 buf.set_string(
-                    area.x + 4,
+                    area.x + indent + 2,
                     current_y,
                     line,
-                    Style::default().fg(Color::White),
+                    Style::default().fg(Theme::global().text_fg),
                 );
                 current_y += 1;
             }
@@ -149,16 +188,13 @@ impl<'a> ToolWidget<'a> {
         // Error status message
         if let Some(ref message) = self.tool_block.status_message {
             if self.tool_block.status == ToolStatus::Error && current_y < area.y + area.height {
-                let display_text = if message.len() > area.width as usize {
-                    &message[..area.width as usize]
-                } else {
-                    message
-                };
+                let display_text =
+                    truncate_to_width(message, area.width.saturating_sub(indent) as usize);
                 buf.set_string(
-                    area.x + 2,
+                    area.x + indent,
                     current_y,
                     display_text,
-                    Style::default().fg(Color::LightRed),
+                    Style::default().fg(Theme::global().tool_error_fg),
                 );
                 current_y += 1;
             }
@@ -175,14 +211,17 @@ impl<'a> ToolWidget<'a> {
                             break;
                         }
 
+                        let theme = Theme::global();
                         let (sym, color) = match tool.status {
                             crate::agent::sub_agent::SubAgentToolStatus::Running => {
-                                ("●", Color::Blue)
+                                ("●", theme.status_running)
                             }
                             crate::agent::sub_agent::SubAgentToolStatus::Success => {
-                                ("●", Color::Green)
+                                ("●", theme.status_success)
+                            }
+                            crate::agent::sub_agent::SubAgentToolStatus::Error => {
+                                ("●", theme.status_error)
                             }
-                            crate::agent::sub_agent::SubAgentToolStatus::Error => ("●", Color::Red),
                         };
 
                         let display_text = tool
@@ -194,18 +233,13 @@ impl<'a> ToolWidget<'a> {
                             .unwrap_or_else(|| tool.name.replace('_', " "));
 
                         let full_text = format!("{sym} {display_text}");
-                        let truncated = if full_text.len() > (area.width.saturating_sub(4)) as usize
-                        {
-                            format!(
-                                "{}...",
-                                &full_text[..(area.width.saturating_sub(7)) as usize]
-                            )
-                        } else {
-                            full_text
-                        };
+                        let truncated = truncate_to_width(
+                            &full_text,
+                            area.width.saturating_sub(indent + 2) as usize,
+                        );
 
                         buf.set_string(
-                            area.x + 2,
+                            area.x + indent,
                             current_y,
                             &truncated,
                             Style::default().fg(color),
@@ -216,10 +250,10 @@ impl<'a> ToolWidget<'a> {
                     if sub_agent_output.cancelled == Some(true) && current_y < area.y + area.height
                     {
                         buf.set_string(
-                            area.x + 2,
+                            area.x + indent,
                             current_y,
                             "Sub-agent cancelled",
-                            Style::default().fg(Color::Yellow),
+                            Style::default().fg(Theme::global().status_pending),
                         );
                         current_y += 1;
                     }
@@ -228,28 +262,38 @@ impl<'a> ToolWidget<'a> {
                         if current_y < area.y + area.height {
                             let error_text = format!("Error: {error}");
                             buf.set_string(
-                                area.x + 2,
+                                area.x + indent,
                                 current_y,
                                 &error_text,
-                                Style::default().fg(Color::Red),
+                                Style::default().fg(Theme::global().status_error),
                             );
                         }
                     }
+                } else if let Some(json_lines) = json_output_lines(output) {
+                    for line in json_lines {
+                        if current_y >= area.y + area.height {
+                            break;
+                        }
+                        buf.set_line(
+                            area.x + indent,
+                            current_y,
+                            &line,
+                            area.width.saturating_sub(indent),
+                        );
+                        current_y += 1;
+                    }
                 } else {
                     for line in output.lines() {
                         if current_y >= area.y + area.height {
                             break;
                         }
-                        let truncated = if line.len() > (area.width.saturating_sub(4)) as usize {
-                            format!("{}...", &line[..(area.width.saturating_sub(7)) as usize])
-                        } else {
-                            line.to_string()
-                        };
+                        let truncated =
+                            truncate_to_width(line, area.width.saturating_sub(indent + 2) as usize);
                         buf.set_string(
-                            area.x + 2,
+                            area.x + indent,
                             current_y,
                             &truncated,
-                            Style::default().fg(Color::Gray),
+                            Style::default().fg(Theme::global().secondary_fg),
                         );
                         current_y += 1;
                     }
@@ -263,6 +307,22 @@ impl<'a> ToolWidget<'a> {
 // Helpers used by the fallback path and by message.rs height calculation
 // ---------------------------------------------------------------------------
 
+/// Widest progress bar drawn under a running tool.
+const PROGRESS_BAR_WIDTH: u16 = 20;
+
+/// `████░░░░ 60%` with a bar `width` cells wide.
+pub fn render_progress_bar(fraction: f32, width: u16) -> String {
+    let fraction = fraction.clamp(0.0, 1.0);
+    let filled = (fraction * width as f32).round() as usize;
+    let empty = width as usize - filled;
+    format!(
+        "{}{} {:.0}%",
+        "█".repeat(filled),
+        "░".repeat(empty),
+        fraction * 100.0
+    )
+}
+
 /// Check if a parameter should be rendered full-width.
 pub(super) fn is_full_width_parameter(tool_name: &str, param_name: &str) -> bool {
     match (tool_name, param_name) {
@@ -273,6 +333,15 @@ pub(super) fn is_full_width_parameter(tool_name: &str, param_name: &str) -> bool
     }
 }
 
+/// JSON output lines for the fallback path. Sub-agent progress is also JSON
+/// but gets its own rendering, so it is excluded here.
+pub(super) fn fallback_json_output(output: &str) -> Option<Vec<Line<'static>>> {
+    if crate::agent::sub_agent::SubAgentOutput::from_json(output).is_some() {
+        return None;
+    }
+    json_output_lines(output)
+}
+
 /// Check if a parameter should be hidden from display.
 pub(super) fn should_hide_parameter(tool_name: &str, param_name: &str, param_value: &str) -> bool {
     match (tool_name, param_name) {
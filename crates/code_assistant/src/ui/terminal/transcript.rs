@@ -1,26 +1,204 @@
 use ratatui::{
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
 };
 
-use super::message::{LiveMessage, MessageBlock};
-use super::streaming::markdown_stream::render_markdown_lines;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::ops::Range;
+use std::time::Duration;
+use unicode_width::UnicodeWidthStr;
+
+use super::config::TerminalConfig;
+use super::history_insert::HistoryRow;
+use super::inline_image;
+use super::message::{LiveMessage, MessageBlock, MessageUsage, ToolUseBlock};
+use super::path_links::highlight_path_refs;
+use super::streaming::markdown_stream::render_markdown_with_footnotes;
 use super::terminal_color;
-use super::tool_renderers::ToolRendererRegistry;
+use super::theme::Theme;
+use super::tool_renderers::{renderer_for, tool_header_line, tool_indent_str, ToolDetail};
+use super::tool_widget::fallback_json_output;
 use crate::ui::ToolStatus;
 
+/// A thinking block that was sent to scrollback, kept so it can be
+/// re-emitted expanded or collapsed on demand.
+#[derive(Debug, Clone)]
+pub struct ThinkingUnit {
+    /// Styled, indented history lines of the full thinking content
+    pub lines: Vec<Line<'static>>,
+    pub expanded: bool,
+}
+
+/// How the model's thinking shows up in the transcript.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ThinkingVisibility {
+    /// Not shown; only a `(thought for Ns)` note marks where it happened
+    Hidden,
+    /// A one-line summary that can be expanded with Ctrl+T
+    #[default]
+    Collapsed,
+    /// Shown in full as it streams
+    Full,
+}
+
+/// What goes between an assistant turn and the next user message in
+/// scrollback.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TurnSeparator {
+    /// A blank line
+    #[default]
+    BlankLine,
+    /// A blank line and a dim full-width rule
+    Rule,
+    /// Nothing; the user message's background padding is the only break
+    None,
+}
+
+impl TurnSeparator {
+    /// The separator lines for a terminal `width` columns wide.
+    pub fn lines(self, width: u16) -> Vec<Line<'static>> {
+        match self {
+            Self::BlankLine => vec![Line::from("")],
+            Self::Rule => vec![
+                Line::from(""),
+                Line::styled(
+                    "─".repeat(width as usize),
+                    Style::default()
+                        .fg(Theme::global().muted_fg)
+                        .add_modifier(Modifier::DIM),
+                ),
+            ],
+            Self::None => Vec::new(),
+        }
+    }
+}
+
+fn thinking_style() -> Style {
+    Style::default()
+        .fg(Theme::global().thinking_fg)
+        .add_modifier(Modifier::DIM)
+        .add_modifier(Modifier::ITALIC)
+}
+
+/// `  12.3k in · 456 out · $0.0123` under a finished response.
+pub fn usage_footer_line(usage: MessageUsage) -> Line<'static> {
+    Line::from(vec![
+        Span::raw(Theme::global().gutter()),
+        Span::styled(
+            usage.summary(),
+            Style::default()
+                .fg(Theme::global().muted_fg)
+                .add_modifier(Modifier::DIM),
+        ),
+    ])
+}
+
+/// One-line `(thought for Ns)` note left in place of hidden thinking.
+pub fn thought_for_line(elapsed: Duration) -> Line<'static> {
+    let seconds = elapsed.as_secs();
+    let duration = if seconds < 60 {
+        format!("{seconds}s")
+    } else {
+        format!("{}m{}s", seconds / 60, seconds % 60)
+    };
+    Line::from(vec![
+        Span::raw(Theme::global().gutter()),
+        Span::styled(format!("(thought for {duration})"), thinking_style()),
+    ])
+}
+
+/// One-line `▸ Thinking (N lines)` summary (or `▾` header when expanded).
+pub fn thinking_summary_line(line_count: usize, expanded: bool) -> Line<'static> {
+    let marker = if expanded { "▾" } else { "▸" };
+    let noun = if line_count == 1 { "line" } else { "lines" };
+    Line::from(vec![
+        Span::raw(Theme::global().gutter()),
+        Span::styled(
+            format!("{marker} Thinking ({line_count} {noun})"),
+            thinking_style(),
+        ),
+    ])
+}
+
+/// Append a block's rows with a single blank line before them, unless the
+/// previous block already ends with one (e.g. UserText includes a trailing
+/// blank).
+fn push_block_rows(rows: &mut Vec<HistoryRow>, block_rows: Vec<HistoryRow>) {
+    if block_rows.is_empty() {
+        return;
+    }
+    let prev_is_blank = rows.last().is_none_or(|row| {
+        row.as_line()
+            .is_some_and(|line| line.spans.iter().all(|span| span.content.is_empty()))
+    });
+    if !prev_is_blank {
+        rows.push(HistoryRow::Text(Line::from("")));
+    }
+    rows.extend(block_rows);
+}
+
+/// Header of a tool call that repeats the one before it, with a `×N` badge
+/// instead of the body that is already visible above.
+fn repeated_tool_line(tool: &ToolUseBlock, count: usize) -> Line<'static> {
+    let mut line = tool_header_line(tool);
+    line.spans.push(Span::styled(
+        format!(" ×{count}"),
+        Style::default().fg(Theme::global().muted_fg),
+    ));
+    line
+}
+
+/// Whether `block` ends a run of repeated tool calls (any visible text).
+fn breaks_tool_run(block: &MessageBlock) -> bool {
+    match block {
+        MessageBlock::ToolUse(_) => false,
+        MessageBlock::PlainText(text) => !text.content.trim().is_empty(),
+        MessageBlock::Thinking(thinking) => !thinking.content.trim().is_empty(),
+        MessageBlock::UserText(_) | MessageBlock::SystemText(_) | MessageBlock::Image(_) => true,
+    }
+}
+
+/// Which scrollback rendering of a committed message is wanted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryKind {
+    /// Every block; thinking as configured
+    Full {
+        thinking: ThinkingVisibility,
+        tools: ToolDetail,
+    },
+    /// Only blocks that were not streamed to scrollback (tools, user text)
+    NonStreamedOnly { tools: ToolDetail },
+}
+
+/// The messages of a transcript as plain data, for golden tests.
+#[cfg(test)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptSnapshot {
+    pub committed_messages: Vec<LiveMessage>,
+    pub active_message: Option<LiveMessage>,
+}
+
 pub struct TranscriptState {
     committed_messages: Vec<LiveMessage>,
+    /// Ids of the tools in finalized messages, kept when old messages are
+    /// trimmed so that late updates for them are still recognized as stale
+    committed_tool_ids: HashSet<String>,
     committed_rendered_count: usize,
     active_message: Option<LiveMessage>,
+    thinking_units: Vec<ThinkingUnit>,
 }
 
 impl TranscriptState {
     pub fn new() -> Self {
         Self {
             committed_messages: Vec::new(),
+            committed_tool_ids: HashSet::new(),
             committed_rendered_count: 0,
             active_message: None,
+            thinking_units: Vec::new(),
         }
     }
 
@@ -39,120 +217,281 @@ impl TranscriptState {
 
     pub fn finalize_active_if_content(&mut self) {
         if let Some(mut current_message) = self.active_message.take() {
-            current_message.finalized = true;
+            current_message.finalize();
             if current_message.has_content() {
-                self.committed_messages.push(current_message);
+                self.commit(current_message);
             }
         }
     }
 
     pub fn push_committed_message(&mut self, mut message: LiveMessage) {
-        message.finalized = true;
+        message.finalize();
+        self.commit(message);
+    }
+
+    fn commit(&mut self, message: LiveMessage) {
+        for block in &message.blocks {
+            if let MessageBlock::ToolUse(tool) = block {
+                self.committed_tool_ids.insert(tool.id.clone());
+            }
+        }
         self.committed_messages.push(message);
     }
 
+    /// Replace the transcript with `messages`, all committed and none of
+    /// them sent to scrollback yet.
+    pub fn load_from(&mut self, messages: Vec<LiveMessage>) {
+        self.clear();
+        for message in messages {
+            self.push_committed_message(message);
+        }
+    }
+
     pub fn clear(&mut self) {
         self.committed_messages.clear();
+        self.committed_tool_ids.clear();
         self.committed_rendered_count = 0;
         self.active_message = None;
+        self.thinking_units.clear();
+    }
+
+    pub fn push_thinking_unit(&mut self, unit: ThinkingUnit) {
+        self.thinking_units.push(unit);
+    }
+
+    pub fn last_thinking_unit_mut(&mut self) -> Option<&mut ThinkingUnit> {
+        self.thinking_units.last_mut()
+    }
+
+    #[cfg(test)]
+    pub fn thinking_units(&self) -> &[ThinkingUnit] {
+        &self.thinking_units
     }
 
+    /// Copy the committed and active messages out of the transcript.
     #[cfg(test)]
+    pub fn snapshot(&self) -> TranscriptSnapshot {
+        TranscriptSnapshot {
+            committed_messages: self.committed_messages.clone(),
+            active_message: self.active_message.clone(),
+        }
+    }
+
+    /// Whether a finalized message contains the tool `tool_id`.
+    pub fn has_committed_tool(&self, tool_id: &str) -> bool {
+        self.committed_tool_ids.contains(tool_id)
+    }
+
     pub fn committed_messages(&self) -> &[LiveMessage] {
         &self.committed_messages
     }
 
-    #[cfg(test)]
-    pub fn committed_messages_mut(&mut self) -> &mut Vec<LiveMessage> {
-        &mut self.committed_messages
+    /// Indices of committed messages not yet sent to scrollback.
+    pub fn unrendered_committed_range(&self) -> Range<usize> {
+        self.committed_rendered_count..self.committed_messages.len()
     }
 
-    pub fn unrendered_committed_messages(&self) -> &[LiveMessage] {
-        &self.committed_messages[self.committed_rendered_count..]
+    /// Scrollback rows for the committed message at `index`. Each message is
+    /// rendered once, when it is flushed to scrollback, so nothing is cached.
+    pub fn committed_history_lines(
+        &self,
+        index: usize,
+        width: u16,
+        kind: HistoryKind,
+    ) -> Vec<HistoryRow> {
+        let Some(message) = self.committed_messages.get(index) else {
+            return Vec::new();
+        };
+        let repeats = self.tool_repeat_counts(index);
+        match kind {
+            HistoryKind::Full { thinking, tools } => {
+                Self::as_history_lines(message, width, thinking, tools, &repeats)
+            }
+            HistoryKind::NonStreamedOnly { tools } => {
+                Self::as_history_lines_non_streamed_only(message, width, tools, &repeats)
+            }
+        }
+    }
+
+    /// For each block of the committed message at `index`, how many identical
+    /// tool calls in a row end with it (1 for anything that is not a repeat).
+    /// Runs continue across messages and reset on any text in between.
+    fn tool_repeat_counts(&self, index: usize) -> Vec<usize> {
+        // Seed with the run of identical calls ending right before this message
+        let mut run: Option<(&ToolUseBlock, usize)> = None;
+        'seed: for message in self.committed_messages[..index].iter().rev() {
+            for block in message.blocks.iter().rev() {
+                match block {
+                    MessageBlock::ToolUse(tool) => match run {
+                        None => run = Some((tool, 1)),
+                        Some((latest, count)) if latest.is_repeat_of(tool) => {
+                            run = Some((latest, count + 1))
+                        }
+                        Some(_) => break 'seed,
+                    },
+                    block if breaks_tool_run(block) => break 'seed,
+                    _ => {}
+                }
+            }
+        }
+
+        self.committed_messages[index]
+            .blocks
+            .iter()
+            .map(|block| match block {
+                MessageBlock::ToolUse(tool) => {
+                    let count = match run {
+                        Some((previous, count)) if tool.is_repeat_of(previous) => count + 1,
+                        _ => 1,
+                    };
+                    run = Some((tool, count));
+                    count
+                }
+                block => {
+                    if breaks_tool_run(block) {
+                        run = None;
+                    }
+                    1
+                }
+            })
+            .collect()
     }
 
     pub fn mark_committed_as_rendered(&mut self) {
         self.committed_rendered_count = self.committed_messages.len();
     }
 
-    pub fn as_history_lines(message: &LiveMessage, width: u16) -> Vec<Line<'static>> {
-        let mut lines = Vec::new();
-        // Account for 2-char indent when computing render width
-        let render_width = if width > 2 {
-            Some((width - 2) as usize)
+    /// Drop the oldest committed messages beyond `max`, but only ones already
+    /// sent to scrollback; the terminal keeps showing them. 0 keeps everything.
+    pub fn trim_committed(&mut self, max: usize) {
+        if max == 0 {
+            return;
+        }
+        let excess = self.committed_messages.len().saturating_sub(max);
+        let dropped = excess.min(self.committed_rendered_count);
+        if dropped == 0 {
+            return;
+        }
+        self.committed_messages.drain(..dropped);
+        self.committed_rendered_count -= dropped;
+    }
+
+    /// Account for the gutter indent when computing markdown render width.
+    fn render_width(width: u16) -> Option<usize> {
+        let gutter = Theme::global().gutter_width() as u16;
+        if width > gutter {
+            Some((width - gutter) as usize)
         } else if width > 0 {
             Some(width as usize)
         } else {
             None
-        };
+        }
+    }
 
-        for block in &message.blocks {
-            let block_lines_start = lines.len();
+    /// Render thinking content as indented, dim/italic history lines.
+    pub fn thinking_history_lines(content: &str, width: u16) -> Vec<Line<'static>> {
+        render_markdown_with_footnotes(content, Self::render_width(width))
+            .into_iter()
+            .map(|line| {
+                let mut styled_spans: Vec<Span<'static>> =
+                    vec![Span::raw(Theme::global().gutter())];
+                styled_spans.extend(line.spans.into_iter().map(|span| {
+                    let style = span
+                        .style
+                        .fg(Theme::global().thinking_fg)
+                        .add_modifier(Modifier::DIM)
+                        .add_modifier(Modifier::ITALIC);
+                    Span::styled(span.content.to_string(), style)
+                }));
+                Line::from(styled_spans)
+            })
+            .collect()
+    }
 
+    /// Render a finalized message for scrollback. Thinking blocks are shown
+    /// in full, as a one-line summary or not at all, depending on `thinking`.
+    /// Tool blocks with a `tool_repeats` count above 1 collapse to their header.
+    pub fn as_history_lines(
+        message: &LiveMessage,
+        width: u16,
+        thinking: ThinkingVisibility,
+        tools: ToolDetail,
+        tool_repeats: &[usize],
+    ) -> Vec<HistoryRow> {
+        let mut rows = Vec::new();
+        let render_width = Self::render_width(width);
+
+        for (block_index, block) in message.blocks.iter().enumerate() {
+            let mut lines = Vec::new();
             match block {
                 MessageBlock::PlainText(text) => {
                     if text.content.is_empty() {
                         continue;
                     }
-                    for mut line in render_markdown_lines(&text.content, render_width) {
-                        line.spans.insert(0, Span::raw("  ".to_string()));
+                    let highlight = TerminalConfig::global().highlight_paths;
+                    for mut line in render_markdown_with_footnotes(&text.content, render_width) {
+                        if highlight {
+                            line = highlight_path_refs(line);
+                        }
+                        line.spans.insert(0, Span::raw(Theme::global().gutter()));
                         lines.push(line);
                     }
                 }
-                MessageBlock::Thinking(thinking) => {
-                    if thinking.content.trim().is_empty() {
+                MessageBlock::Thinking(block) => {
+                    if block.content.trim().is_empty() {
                         continue;
                     }
-                    let rendered = render_markdown_lines(&thinking.content, render_width);
-                    for line in rendered {
-                        let mut styled_spans: Vec<Span<'static>> =
-                            vec![Span::raw("  ".to_string())];
-                        styled_spans.extend(line.spans.into_iter().map(|span| {
-                            let mut style = span.style;
-                            style = style
-                                .fg(Color::DarkGray)
-                                .add_modifier(Modifier::DIM)
-                                .add_modifier(Modifier::ITALIC);
-                            Span::styled(span.content.to_string(), style)
-                        }));
-                        lines.push(Line::from(styled_spans));
+                    let thinking_lines = Self::thinking_history_lines(&block.content, width);
+                    match thinking {
+                        ThinkingVisibility::Hidden => {}
+                        ThinkingVisibility::Collapsed => {
+                            lines.push(thinking_summary_line(thinking_lines.len(), false));
+                        }
+                        ThinkingVisibility::Full => lines.extend(thinking_lines),
                     }
                 }
                 MessageBlock::UserText(text) => {
                     Self::push_user_text_history_lines(&text.content, width, &mut lines);
                 }
+                MessageBlock::SystemText(text) => {
+                    Self::push_system_text_history_lines(&text.content, width, &mut lines);
+                }
+                MessageBlock::Image(image) => {
+                    push_block_rows(&mut rows, vec![inline_image::history_row(image, width)]);
+                    continue;
+                }
                 MessageBlock::ToolUse(tool) => {
-                    Self::push_tool_history_lines(tool, &mut lines);
+                    let repeats = tool_repeats.get(block_index).copied().unwrap_or(1);
+                    if repeats > 1 {
+                        lines.push(repeated_tool_line(tool, repeats));
+                    } else {
+                        Self::push_tool_history_lines(tool, width, tools, &mut lines);
+                    }
                 }
             }
 
-            // Insert a single blank line between blocks, unless the previous
-            // block already ends with one (e.g. UserText includes a trailing blank).
-            if lines.len() > block_lines_start && block_lines_start > 0 {
-                let prev_is_blank = lines.get(block_lines_start - 1).is_some_and(|l| {
-                    l.spans.is_empty() || l.spans.iter().all(|s| s.content.is_empty())
-                });
-                if !prev_is_blank {
-                    lines.insert(block_lines_start, Line::from(""));
-                }
-            }
+            push_block_rows(&mut rows, lines.into_iter().map(HistoryRow::Text).collect());
         }
 
-        lines
+        let mut footer = Vec::new();
+        Self::push_usage_footer(message, &mut footer);
+        rows.extend(footer.into_iter().map(HistoryRow::Text));
+        rows
     }
 
-    /// Render only non-streamed blocks (ToolUse, UserText) to history lines.
+    /// Render only non-streamed blocks (ToolUse, UserText, SystemText, Image) to history rows.
     /// Used when PlainText/Thinking blocks were already progressively sent to
     /// scrollback during streaming.
     pub fn as_history_lines_non_streamed_only(
         message: &LiveMessage,
         width: u16,
-    ) -> Vec<Line<'static>> {
-        let mut lines = Vec::new();
-
-        for block in &message.blocks {
-            let block_lines_start = lines.len();
+        tools: ToolDetail,
+        tool_repeats: &[usize],
+    ) -> Vec<HistoryRow> {
+        let mut rows = Vec::new();
 
+        for (block_index, block) in message.blocks.iter().enumerate() {
+            let mut lines = Vec::new();
             match block {
                 MessageBlock::PlainText(_) | MessageBlock::Thinking(_) => {
                     // Already sent to scrollback during streaming — skip.
@@ -160,27 +499,43 @@ impl TranscriptState {
                 MessageBlock::UserText(text) => {
                     Self::push_user_text_history_lines(&text.content, width, &mut lines);
                 }
+                MessageBlock::SystemText(text) => {
+                    Self::push_system_text_history_lines(&text.content, width, &mut lines);
+                }
+                MessageBlock::Image(image) => {
+                    push_block_rows(&mut rows, vec![inline_image::history_row(image, width)]);
+                    continue;
+                }
                 MessageBlock::ToolUse(tool) => {
-                    Self::push_tool_history_lines(tool, &mut lines);
+                    let repeats = tool_repeats.get(block_index).copied().unwrap_or(1);
+                    if repeats > 1 {
+                        lines.push(repeated_tool_line(tool, repeats));
+                    } else {
+                        Self::push_tool_history_lines(tool, width, tools, &mut lines);
+                    }
                 }
             }
 
-            // Insert a single blank line between blocks, unless the previous
-            // block already ends with one.
-            if lines.len() > block_lines_start && block_lines_start > 0 {
-                let prev_is_blank = lines.get(block_lines_start - 1).is_some_and(|l| {
-                    l.spans.is_empty() || l.spans.iter().all(|s| s.content.is_empty())
-                });
-                if !prev_is_blank {
-                    lines.insert(block_lines_start, Line::from(""));
-                }
-            }
+            push_block_rows(&mut rows, lines.into_iter().map(HistoryRow::Text).collect());
         }
 
-        lines
+        let mut footer = Vec::new();
+        Self::push_usage_footer(message, &mut footer);
+        rows.extend(footer.into_iter().map(HistoryRow::Text));
+        rows
     }
 
-    /// Render a UserText block as history lines with "› " prefix, word wrapping,
+    /// Dim usage line under a response, when enabled and known.
+    fn push_usage_footer(message: &LiveMessage, lines: &mut Vec<Line<'static>>) {
+        if !TerminalConfig::global().show_usage {
+            return;
+        }
+        if let Some(usage) = message.usage {
+            lines.push(usage_footer_line(usage));
+        }
+    }
+
+    /// Render a UserText block as history lines with the user marker, word wrapping,
     /// and background color matching the composer input area.
     fn push_user_text_history_lines(content: &str, width: u16, lines: &mut Vec<Line<'static>>) {
         if content.is_empty() {
@@ -193,8 +548,8 @@ impl TranscriptState {
 
         // Helper: create a full-width background-filled line from spans
         let make_bg_line = |spans: Vec<Span<'static>>| -> Line<'static> {
-            // Calculate how many visible chars the spans occupy
-            let used: usize = spans.iter().map(|s| s.content.len()).sum();
+            // Calculate how many columns the spans occupy
+            let used: usize = spans.iter().map(|s| s.content.width()).sum();
             let mut all_spans = spans;
             if used < w {
                 all_spans.push(Span::styled(" ".repeat(w - used), bg_style));
@@ -207,19 +562,21 @@ impl TranscriptState {
         // Top padding line (full-width background)
         lines.push(make_bg_line(vec![]));
 
-        let wrap_width = if width > 3 {
-            // prefix "› " = 2, plus 1 right margin
-            (width - 3) as usize
+        let theme = Theme::global();
+        let gutter = theme.gutter_width();
+        // The marker's width, plus 1 right margin
+        let wrap_width = if w > gutter + 1 {
+            w - gutter - 1
         } else {
-            width.max(1) as usize
+            w.max(1)
         };
 
         let opts =
             textwrap::Options::new(wrap_width).wrap_algorithm(textwrap::WrapAlgorithm::FirstFit);
 
         let prefix_style = Style::default()
+            .fg(theme.user_marker_fg)
             .add_modifier(Modifier::BOLD)
-            .add_modifier(Modifier::DIM)
             .bg(bg);
 
         let mut is_first_visual_line = true;
@@ -227,9 +584,9 @@ impl TranscriptState {
             if logical_line.is_empty() {
                 let prefix = if is_first_visual_line {
                     is_first_visual_line = false;
-                    Span::styled("› ", prefix_style)
+                    Span::styled(theme.user_marker, prefix_style)
                 } else {
-                    Span::styled("  ", bg_style)
+                    Span::styled(theme.gutter(), bg_style)
                 };
                 lines.push(make_bg_line(vec![prefix]));
                 continue;
@@ -238,9 +595,9 @@ impl TranscriptState {
             for wrapped in textwrap::wrap(logical_line, &opts) {
                 let prefix = if is_first_visual_line {
                     is_first_visual_line = false;
-                    Span::styled("› ", prefix_style)
+                    Span::styled(theme.user_marker, prefix_style)
                 } else {
-                    Span::styled("  ", bg_style)
+                    Span::styled(theme.gutter(), bg_style)
                 };
                 lines.push(make_bg_line(vec![
                     prefix,
@@ -255,53 +612,248 @@ impl TranscriptState {
         lines.push(Line::from(""));
     }
 
+    /// Render a system/instruction block as dimmed markdown with the
+    /// theme's system marker in the gutter of its first line.
+    fn push_system_text_history_lines(content: &str, width: u16, lines: &mut Vec<Line<'static>>) {
+        let content = content.trim_matches('\n');
+        if content.trim().is_empty() {
+            return;
+        }
+
+        let theme = Theme::global();
+        let text_style = Style::default()
+            .fg(theme.system_fg)
+            .add_modifier(Modifier::DIM);
+        for (i, line) in render_markdown_with_footnotes(content, Self::render_width(width))
+            .into_iter()
+            .enumerate()
+        {
+            let gutter = if i == 0 {
+                let padding = theme
+                    .gutter_width()
+                    .saturating_sub(theme.system_marker.width());
+                Span::styled(
+                    format!("{}{}", theme.system_marker, " ".repeat(padding)),
+                    Style::default().fg(theme.system_fg),
+                )
+            } else {
+                Span::raw(theme.gutter())
+            };
+            let mut spans = vec![gutter];
+            spans.extend(
+                line.spans.into_iter().map(|span| {
+                    Span::styled(span.content.to_string(), span.style.patch(text_style))
+                }),
+            );
+            lines.push(Line::from(spans));
+        }
+    }
+
     /// Render a ToolUse block as history lines with "● name" format.
     /// Dot at col 0, name at col 2 — aligned with user "› " prefix.
     fn push_tool_history_lines(
         tool: &super::message::ToolUseBlock,
+        width: u16,
+        detail: ToolDetail,
         lines: &mut Vec<Line<'static>>,
     ) {
         // Try a registered renderer first.
-        if let Some(registry) = ToolRendererRegistry::global() {
-            if let Some(renderer) = registry.get(&tool.name) {
-                lines.extend(renderer.render_history_lines(tool));
-                return;
-            }
+        if let Some(renderer) = renderer_for(&tool.name, detail) {
+            lines.extend(renderer.render_history_lines(tool, width));
+            return;
         }
 
         // Fallback: generic rendering
-        let status_color = match tool.status {
-            ToolStatus::Pending => Color::Yellow,
-            ToolStatus::Running => Color::Blue,
-            ToolStatus::Success => Color::Green,
-            ToolStatus::Error => Color::Red,
-        };
+        let theme = Theme::global();
+        let status_color = theme.status_color(&tool.status);
+        let gutter = tool_indent_str();
         lines.push(Line::from(vec![
-            Span::styled("● ", Style::default().fg(status_color)),
+            Span::styled(
+                format!("●{}", &gutter[1..]),
+                Style::default().fg(status_color),
+            ),
             Span::styled(
                 tool.name.clone(),
                 Style::default()
-                    .fg(Color::White)
+                    .fg(theme.tool_name_fg)
                     .add_modifier(Modifier::BOLD),
             ),
         ]));
         for (param_name, param_value) in &tool.parameters {
             for line in param_value.value.lines() {
-                lines.push(Line::from(format!("  {param_name}: {line}")));
+                lines.push(Line::from(format!("{gutter}{param_name}: {line}")));
             }
         }
         if let Some(status_message) = &tool.status_message {
             if tool.status == ToolStatus::Error {
                 lines.push(Line::styled(
-                    format!("  {status_message}"),
-                    Style::default().fg(Color::LightRed),
+                    format!("{gutter}{status_message}"),
+                    Style::default().fg(theme.tool_error_fg),
                 ));
             }
         }
         if let Some(output) = &tool.output {
-            for line in output.lines() {
-                lines.push(Line::from(format!("  {line}")));
+            if let Some(json_lines) = fallback_json_output(output) {
+                lines.extend(json_lines.into_iter().map(|line| {
+                    let mut spans = vec![Span::raw(gutter.clone())];
+                    spans.extend(line.spans);
+                    Line::from(spans)
+                }));
+            } else {
+                for line in output.lines() {
+                    lines.push(Line::from(format!("{gutter}{line}")));
+                }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::terminal::message::{ImageBlock, PlainTextBlock, ThinkingBlock};
+
+    #[test]
+    fn test_usage_footer_and_session_total() {
+        let mut total = MessageUsage::default();
+        total.add(MessageUsage {
+            prompt_tokens: 12_345,
+            completion_tokens: 678,
+            cost: None,
+        });
+        assert_eq!(total.summary(), "12.3k in · 678 out");
+
+        total.add(MessageUsage {
+            prompt_tokens: 2_000_000,
+            completion_tokens: 1_000,
+            cost: Some(0.0123),
+        });
+        assert_eq!(total.summary(), "2.0M in · 1.7k out · $0.0123");
+
+        let line = usage_footer_line(MessageUsage {
+            prompt_tokens: 10,
+            completion_tokens: 5,
+            cost: Some(1.5),
+        });
+        let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, "  10 in · 5 out · $1.50");
+        assert!(line.spans[1].style.add_modifier.contains(Modifier::DIM));
+    }
+
+    #[test]
+    fn test_trim_committed_drops_only_rendered_messages() {
+        let text_message = |content: &str| {
+            let mut block = PlainTextBlock::new();
+            block.content = content.to_string();
+            let mut message = LiveMessage::new();
+            message.add_block(MessageBlock::PlainText(block));
+            message
+        };
+        let mut transcript = TranscriptState::new();
+        for i in 0..4 {
+            let mut message = text_message(&format!("old {i}"));
+            let tool = ToolUseBlock::new("read_files".to_string(), format!("tool-{i}"));
+            message.add_block(MessageBlock::ToolUse(tool));
+            transcript.push_committed_message(message);
+        }
+        transcript.mark_committed_as_rendered();
+        transcript.push_committed_message(text_message("new"));
+
+        transcript.trim_committed(2);
+        // The unrendered message is kept even though it is over the cap
+        let contents: Vec<String> = transcript
+            .committed_messages()
+            .iter()
+            .map(|message| match &message.blocks[0] {
+                MessageBlock::PlainText(block) => block.content.clone(),
+                _ => String::new(),
+            })
+            .collect();
+        assert_eq!(contents, ["old 3", "new"]);
+        assert_eq!(transcript.unrendered_committed_range(), 1..2);
+        // Tools of trimmed messages still count as finalized
+        assert!(transcript.has_committed_tool("tool-0"));
+        assert!(!transcript.has_committed_tool("tool-9"));
+
+        transcript.mark_committed_as_rendered();
+        transcript.trim_committed(0);
+        assert_eq!(transcript.committed_messages().len(), 2);
+        transcript.trim_committed(1);
+        assert_eq!(transcript.committed_messages().len(), 1);
+        assert_eq!(transcript.unrendered_committed_range(), 1..1);
+    }
+
+    #[test]
+    fn test_images_show_placeholder_without_graphics_support() {
+        let mut text = PlainTextBlock::new();
+        text.content = "What is this?".to_string();
+        let mut message = LiveMessage::new();
+        message.add_block(MessageBlock::UserText(text));
+        message.add_block(MessageBlock::Image(ImageBlock {
+            media_type: "image/png".to_string(),
+            data: "iVBORw0KGgo=".to_string(),
+            width: Some(640),
+            height: Some(480),
+        }));
+
+        let lines = TranscriptState::as_history_lines(
+            &message,
+            80,
+            ThinkingVisibility::Full,
+            ToolDetail::Full,
+            &[],
+        );
+        // The placeholder is plain text, not an image row
+        let text: Vec<String> = lines
+            .iter()
+            .map(|row| row.as_line().unwrap().to_string())
+            .collect();
+        assert_eq!(
+            text.last().map(String::as_str),
+            Some("  [image 640x480 (image/png)]")
+        );
+    }
+
+    #[test]
+    fn test_snapshot_round_trips_through_json() {
+        let mut transcript = TranscriptState::new();
+        transcript.start_active_message();
+        let message = transcript.active_message_mut().unwrap();
+        let mut thinking = ThinkingBlock::new();
+        thinking.content = "Look at the file first".to_string();
+        message.add_block(MessageBlock::Thinking(thinking));
+        let mut tool = ToolUseBlock::new("read_files".to_string(), "tool-1".to_string());
+        tool.add_or_update_parameter("project".to_string(), "demo".to_string());
+        tool.add_or_update_parameter("paths".to_string(), "src/main.rs".to_string());
+        tool.status = ToolStatus::Success;
+        tool.output = Some("fn main() {}".to_string());
+        message.add_block(MessageBlock::ToolUse(tool));
+        transcript.start_active_message();
+        let mut text = PlainTextBlock::new();
+        text.content = "Done".to_string();
+        transcript
+            .active_message_mut()
+            .unwrap()
+            .add_block(MessageBlock::PlainText(text));
+
+        let text = serde_json::to_string(&transcript.snapshot()).unwrap();
+        // Parameters keep their streaming order
+        assert!(
+            text.contains(r#""parameters":{"project":"demo","paths":"src/main.rs"}"#),
+            "{text}"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+        let tool = &json["committed_messages"][0]["blocks"][1];
+        assert_eq!(tool["type"], "tool_use");
+        assert_eq!(tool["status"], "success");
+        assert_eq!(json["active_message"]["blocks"][0]["content"], "Done");
+
+        let restored: TranscriptSnapshot = serde_json::from_str(&text).unwrap();
+        let MessageBlock::ToolUse(tool) = &restored.committed_messages[0].blocks[1] else {
+            panic!("expected a tool block");
+        };
+        assert_eq!(tool.status, ToolStatus::Success);
+        assert_eq!(serde_json::to_string(&restored).unwrap(), text);
+    }
+}
@@ -0,0 +1,288 @@
+//! Human-readable markdown transcript of a session, appended as messages
+//! are committed to scrollback.
+
+use std::path::PathBuf;
+
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use super::config::TerminalConfig;
+use super::message::{LiveMessage, MessageBlock, ToolUseBlock};
+use super::redact::redact;
+use super::tool_renderers::diff_renderer::{generate_tool_diff_lines, DiffLine};
+use super::tool_widget::should_hide_parameter;
+use crate::persistence::FileSessionPersistence;
+use crate::ui::ToolStatus;
+
+/// Appends rendered messages to a transcript file from a background task,
+/// so file I/O never blocks the render loop.
+#[derive(Debug, Clone)]
+pub struct TranscriptWriter {
+    session_id: String,
+    tx: mpsc::UnboundedSender<String>,
+}
+
+impl TranscriptWriter {
+    /// Start a writer for the transcript of `session_id` when
+    /// `save_transcript` is enabled. Messages are appended, so resuming a
+    /// session continues its transcript.
+    pub fn for_session(session_id: &str) -> Option<Self> {
+        if !TerminalConfig::global().save_transcript {
+            return None;
+        }
+        let path = match FileSessionPersistence::new().transcript_file_path(session_id) {
+            Ok(path) => path,
+            Err(err) => {
+                warn!("Transcript disabled: {err:#}");
+                return None;
+            }
+        };
+        Some(Self::spawn(session_id, path))
+    }
+
+    /// Start the writer task appending to `path`.
+    fn spawn(session_id: &str, path: PathBuf) -> Self {
+        let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+        tokio::spawn(async move {
+            let opened = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .await;
+            let mut file = match opened {
+                Ok(file) => file,
+                Err(err) => {
+                    warn!("Failed to open transcript {}: {err}", path.display());
+                    return;
+                }
+            };
+            while let Some(chunk) = rx.recv().await {
+                let result = async {
+                    file.write_all(chunk.as_bytes()).await?;
+                    file.flush().await
+                }
+                .await;
+                if let Err(err) = result {
+                    warn!("Failed to write transcript {}: {err}", path.display());
+                    return;
+                }
+            }
+        });
+        Self {
+            session_id: session_id.to_string(),
+            tx,
+        }
+    }
+
+    /// The session whose transcript this writes.
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Queue a committed message for appending.
+    pub fn append_message(&self, message: &LiveMessage) {
+        let markdown = redact(&message_to_markdown(message));
+        if !markdown.is_empty() {
+            // The task only stops on I/O errors, which it has already logged
+            let _ = self.tx.send(markdown);
+        }
+    }
+}
+
+/// Render a message as markdown, one section per block.
+pub fn message_to_markdown(message: &LiveMessage) -> String {
+    let mut out = String::new();
+    for block in &message.blocks {
+        if !block.has_content() {
+            continue;
+        }
+        match block {
+            MessageBlock::UserText(text) => {
+                out.push_str("### User\n\n");
+                out.push_str(text.content.trim_end());
+                out.push_str("\n\n");
+            }
+            MessageBlock::PlainText(text) => {
+                out.push_str(text.content.trim_end());
+                out.push_str("\n\n");
+            }
+            MessageBlock::SystemText(text) => {
+                for line in text.content.trim_matches('\n').lines() {
+                    out.push_str("> ");
+                    out.push_str(line);
+                    out.push('\n');
+                }
+                out.push('\n');
+            }
+            MessageBlock::Thinking(thinking) => {
+                out.push_str("<details>\n<summary>Thinking</summary>\n\n");
+                out.push_str(thinking.content.trim_end());
+                out.push_str("\n\n</details>\n\n");
+            }
+            MessageBlock::ToolUse(tool) => push_tool_markdown(tool, &mut out),
+            MessageBlock::Image(image) => {
+                out.push_str(&image.placeholder());
+                out.push_str("\n\n");
+            }
+        }
+    }
+    out
+}
+
+fn status_label(status: ToolStatus) -> &'static str {
+    match status {
+        ToolStatus::Pending => "pending",
+        ToolStatus::Running => "running",
+        ToolStatus::Success => "success",
+        ToolStatus::Error => "error",
+    }
+}
+
+/// A backtick fence longer than any backtick run inside `content`.
+fn fence_for(content: &str) -> String {
+    let longest_run = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest_run.max(2) + 1)
+}
+
+fn push_fenced(out: &mut String, info: &str, body: &str) {
+    let fence = fence_for(body);
+    out.push_str(&format!("{fence}{info}\n{body}\n{fence}\n\n"));
+}
+
+fn push_tool_markdown(tool: &ToolUseBlock, out: &mut String) {
+    out.push_str(&format!(
+        "**Tool:** `{}` ({})\n\n",
+        tool.name,
+        status_label(tool.status)
+    ));
+
+    let diff_lines = generate_tool_diff_lines(tool);
+    let mut summary = Vec::new();
+    for (name, param) in &tool.parameters {
+        if should_hide_parameter(&tool.name, name, &param.value) {
+            continue;
+        }
+        // The diff below already shows the edited content
+        if !diff_lines.is_empty() && name != "path" {
+            continue;
+        }
+        summary.push(format!("{name}: {}", param.get_display_value()));
+    }
+    if let Some(message) = tool.status_message.as_deref() {
+        if tool.status == ToolStatus::Error {
+            summary.push(format!("error: {message}"));
+        }
+    }
+    if !summary.is_empty() {
+        push_fenced(out, "", &summary.join("\n"));
+    }
+
+    if !diff_lines.is_empty() {
+        let diff = diff_lines
+            .iter()
+            .map(|line| match line {
+                DiffLine::Context { text, .. } => format!(" {text}"),
+                DiffLine::Insert { text, .. } => format!("+{text}"),
+                DiffLine::Delete { text, .. } => format!("-{text}"),
+                DiffLine::HunkSeparator => "@@".to_string(),
+                DiffLine::NoChanges | DiffLine::Binary { .. } | DiffLine::Streaming { .. } => {
+                    line.note().unwrap_or_default()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        push_fenced(out, "diff", &diff);
+    }
+
+    if let Some(output) = tool.output.as_deref() {
+        if !output.trim().is_empty() {
+            push_fenced(out, "", output.trim_end());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::terminal::message::{PlainTextBlock, ThinkingBlock};
+
+    fn message_with(blocks: Vec<MessageBlock>) -> LiveMessage {
+        let mut message = LiveMessage::new();
+        for block in blocks {
+            message.add_block(block);
+        }
+        message
+    }
+
+    #[test]
+    fn test_text_and_thinking_markdown() {
+        let mut user = PlainTextBlock::new();
+        user.content = "Fix the bug".to_string();
+        let mut thinking = ThinkingBlock::new();
+        thinking.content = "Looking at the code".to_string();
+        let mut text = PlainTextBlock::new();
+        text.content = "Done.".to_string();
+
+        let markdown = message_to_markdown(&message_with(vec![
+            MessageBlock::UserText(user),
+            MessageBlock::Thinking(thinking),
+            MessageBlock::PlainText(text),
+        ]));
+        assert_eq!(
+            markdown,
+            "### User\n\nFix the bug\n\n\
+             <details>\n<summary>Thinking</summary>\n\nLooking at the code\n\n</details>\n\n\
+             Done.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_edit_tool_renders_diff_block() {
+        let mut tool = ToolUseBlock::new("edit".to_string(), "t1".to_string());
+        tool.add_or_update_parameter("path".to_string(), "src/main.rs".to_string());
+        tool.add_or_update_parameter("old_text".to_string(), "let a = 1;".to_string());
+        tool.add_or_update_parameter("new_text".to_string(), "let a = 2;".to_string());
+        tool.complete_parameters();
+        tool.status = ToolStatus::Success;
+
+        let markdown = message_to_markdown(&message_with(vec![MessageBlock::ToolUse(tool)]));
+        assert!(markdown.starts_with("**Tool:** `edit` (success)\n\n"));
+        assert!(markdown.contains("```\npath: src/main.rs\n```"));
+        assert!(markdown.contains("```diff\n-let a = 1;\n+let a = 2;\n```"));
+        assert!(!markdown.contains("old_text"));
+    }
+
+    #[test]
+    fn test_fence_outgrows_backticks_in_output() {
+        let mut tool = ToolUseBlock::new("execute_command".to_string(), "t1".to_string());
+        tool.output = Some("```\ninner\n```".to_string());
+
+        let markdown = message_to_markdown(&message_with(vec![MessageBlock::ToolUse(tool)]));
+        assert!(markdown.contains("````\n```\ninner\n```\n````"));
+    }
+
+    #[tokio::test]
+    async fn test_writer_appends_messages_to_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.md");
+        std::fs::write(&path, "Earlier\n\n").unwrap();
+        let writer = TranscriptWriter::spawn("session", path.clone());
+
+        let mut text = PlainTextBlock::new();
+        text.content = "Hello".to_string();
+        let message = message_with(vec![MessageBlock::PlainText(text)]);
+        writer.append_message(&message);
+        writer.append_message(&message);
+
+        let mut content = String::new();
+        for _ in 0..50 {
+            content = std::fs::read_to_string(&path).unwrap_or_default();
+            if content.len() >= "Earlier\n\nHello\n\nHello\n\n".len() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(content, "Earlier\n\nHello\n\nHello\n\n");
+    }
+}
@@ -8,9 +8,16 @@
 use std::io;
 use std::io::stdout;
 use std::io::Stdout;
+use std::io::Write;
 use std::panic;
+use std::path::PathBuf;
 
-use crossterm::event::EnableBracketedPaste;
+use base64::Engine;
+use crossterm::cursor::MoveTo;
+use crossterm::event::{
+    DisableBracketedPaste, DisableFocusChange, EnableBracketedPaste, EnableFocusChange,
+};
+use crossterm::terminal::{Clear, ClearType};
 use crossterm::SynchronizedUpdate;
 use ratatui::backend::Backend;
 use ratatui::backend::CrosstermBackend;
@@ -20,10 +27,24 @@ use ratatui::crossterm::terminal::enable_raw_mode;
 use ratatui::layout::Offset;
 use ratatui::layout::Rect;
 use ratatui::layout::Size;
-use ratatui::text::Line;
+
+use serde::{Deserialize, Serialize};
 
 use super::custom_terminal;
 use super::custom_terminal::Terminal as CustomTerminal;
+use super::history_insert::HistoryRow;
+
+/// How to signal a finished turn while the terminal window is unfocused.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TurnNotification {
+    #[default]
+    Off,
+    /// Ring the terminal bell
+    Bell,
+    /// OSC 9 desktop notification (iTerm2, WezTerm, kitty, ...)
+    Desktop,
+}
 
 /// Type alias for the terminal type used in this application.
 pub type Terminal = CustomTerminal<CrosstermBackend<Stdout>>;
@@ -34,11 +55,24 @@ pub fn init() -> io::Result<Tui> {
     // Uses OSC 11 to detect the actual bg color for composer overlay blending.
     super::terminal_color::init();
 
+    // Read NO_COLOR / COLORTERM / TERM before the theme adapts to them.
+    super::color_support::init();
+
+    // Detect Kitty / iTerm2 graphics support for inline images.
+    super::inline_image::init();
+
+    // Load the color theme selected in the terminal config.
+    super::theme::init();
+
     // Initialize tool renderer registry for custom tool block display.
     super::tool_renderers::init_registry();
 
     enable_raw_mode()?;
+    // Bracketed paste delivers a paste as one Event::Paste instead of keys,
+    // so its newlines don't submit and its indentation is kept
     let _ = execute!(stdout(), EnableBracketedPaste);
+    // Focus reports dim the composer and gate turn notifications
+    let _ = execute!(stdout(), EnableFocusChange);
 
     set_panic_hook();
 
@@ -49,10 +83,31 @@ pub fn init() -> io::Result<Tui> {
 
 /// Restore terminal state.
 pub fn restore() -> io::Result<()> {
+    let _ = execute!(stdout(), DisableBracketedPaste, DisableFocusChange);
     disable_raw_mode()?;
     Ok(())
 }
 
+/// Tell the user a turn has finished, using the configured mechanism.
+pub fn notify_turn_complete(mode: TurnNotification) -> io::Result<()> {
+    let mut out = stdout();
+    match mode {
+        TurnNotification::Off => return Ok(()),
+        TurnNotification::Bell => out.write_all(b"\x07")?,
+        TurnNotification::Desktop => out.write_all(b"\x1b]9;code-assistant: turn complete\x07")?,
+    }
+    out.flush()
+}
+
+/// Ask the terminal to set the clipboard (OSC 52). Terminals that don't
+/// support it ignore the sequence.
+pub fn copy_via_osc52(text: &str) -> io::Result<()> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let mut out = stdout();
+    write!(out, "\x1b]52;c;{encoded}\x07")?;
+    out.flush()
+}
+
 fn set_panic_hook() {
     let hook = panic::take_hook();
     panic::set_hook(Box::new(move |panic_info| {
@@ -66,22 +121,31 @@ fn set_panic_hook() {
 /// `SynchronizedUpdate` block for flicker-free output.
 pub struct Tui {
     pub terminal: Terminal,
-    pending_history_lines: Vec<Line<'static>>,
+    pending_history_rows: Vec<HistoryRow>,
+    /// Project root for hyperlinking highlighted paths in history
+    path_link_root: Option<PathBuf>,
 }
 
 impl Tui {
     pub fn new(terminal: Terminal) -> Self {
         Self {
             terminal,
-            pending_history_lines: vec![],
+            pending_history_rows: vec![],
+            path_link_root: None,
         }
     }
 
-    /// Buffer history lines for insertion in the next `draw()` call.
-    /// Lines are not written to the terminal immediately -- they are inserted
+    /// Write highlighted path references to scrollback as OSC 8 hyperlinks
+    /// to the files under `root`.
+    pub fn set_path_link_root(&mut self, root: PathBuf) {
+        self.path_link_root = Some(root);
+    }
+
+    /// Buffer history rows for insertion in the next `draw()` call.
+    /// Rows are not written to the terminal immediately -- they are inserted
     /// atomically together with the viewport rendering inside `draw()`.
-    pub fn insert_history_lines(&mut self, lines: Vec<Line<'static>>) {
-        self.pending_history_lines.extend(lines);
+    pub fn insert_history_lines(&mut self, rows: Vec<HistoryRow>) {
+        self.pending_history_rows.extend(rows);
     }
 
     /// Draw a frame to the terminal. All operations happen inside a single
@@ -124,10 +188,11 @@ impl Tui {
                 terminal.set_viewport_area(area);
             }
 
-            if !self.pending_history_lines.is_empty() {
+            if !self.pending_history_rows.is_empty() {
                 super::history_insert::insert_history_lines(
                     terminal,
-                    std::mem::take(&mut self.pending_history_lines),
+                    std::mem::take(&mut self.pending_history_rows),
+                    self.path_link_root.as_deref(),
                 )?;
             }
 
@@ -158,6 +223,34 @@ impl Tui {
         Ok(None)
     }
 
+    /// Wipe the screen and the terminal's scrollback and move the viewport
+    /// back to the top row. The next `draw()` repaints it from scratch.
+    pub fn clear_scrollback(&mut self) -> io::Result<()> {
+        self.pending_history_rows.clear();
+        execute!(
+            self.terminal.backend_mut(),
+            Clear(ClearType::All),
+            Clear(ClearType::Purge),
+            MoveTo(0, 0)
+        )?;
+        let mut area = self.terminal.viewport_area;
+        area.y = 0;
+        self.terminal.set_viewport_area(area);
+        self.terminal.set_cursor_position((0, 0))?;
+        self.terminal.clear()
+    }
+
+    /// Hand the terminal to another program (e.g. an editor) while `run`
+    /// executes, then take it back. The next `draw()` repaints the viewport.
+    pub fn run_external<R>(&mut self, run: impl FnOnce() -> R) -> io::Result<R> {
+        restore()?;
+        let result = run();
+        enable_raw_mode()?;
+        let _ = execute!(stdout(), EnableBracketedPaste, EnableFocusChange);
+        self.terminal.clear()?;
+        Ok(result)
+    }
+
     /// Get the current terminal screen size.
     pub fn size(&self) -> io::Result<Size> {
         self.terminal.size()
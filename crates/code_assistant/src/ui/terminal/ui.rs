@@ -4,11 +4,15 @@ use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc,
 };
+use std::time::Instant;
 use tokio::sync::{watch, Mutex};
 use tracing::{debug, warn};
 
+use super::config::TerminalConfig;
+use super::message::{ImageBlock, MessageUsage};
 use super::renderer::ProductionTerminalRenderer;
-use super::state::AppState;
+use super::state::{AppState, PendingToolApproval};
+use crate::ui::backend::BackendEvent;
 
 #[derive(Clone)]
 pub struct TerminalUI {
@@ -17,6 +21,7 @@ pub struct TerminalUI {
     pub cancel_flag: Arc<AtomicBool>,
     pub renderer: Arc<Mutex<Option<Arc<Mutex<ProductionTerminalRenderer>>>>>,
     event_sender: Arc<std::sync::Mutex<Option<async_channel::Sender<UiEvent>>>>,
+    backend_event_sender: Arc<std::sync::Mutex<Option<async_channel::Sender<BackendEvent>>>>,
 }
 
 impl TerminalUI {
@@ -27,6 +32,7 @@ impl TerminalUI {
             cancel_flag: Arc::new(AtomicBool::new(false)),
             renderer: Arc::new(Mutex::new(None)),
             event_sender: Arc::new(std::sync::Mutex::new(None)),
+            backend_event_sender: Arc::new(std::sync::Mutex::new(None)),
         }
     }
 
@@ -48,7 +54,7 @@ impl TerminalUI {
     }
 
     /// Trigger a redraw
-    async fn trigger_redraw(&self) {
+    pub async fn trigger_redraw(&self) {
         if let Some(tx) = self.redraw_tx.lock().await.as_ref() {
             let _ = tx.send(());
         }
@@ -62,6 +68,39 @@ impl TerminalUI {
             .expect("event_sender lock poisoned") = Some(sender);
     }
 
+    /// Set the sender used to answer the backend directly, e.g. to skip a
+    /// tool approval whose session is going away
+    pub fn set_backend_event_sender(&self, sender: async_channel::Sender<BackendEvent>) {
+        *self
+            .backend_event_sender
+            .lock()
+            .expect("backend_event_sender lock poisoned") = Some(sender);
+    }
+
+    /// Skip the tool call waiting for approval, if any, so the agent that
+    /// asked does not wait on a prompt that is no longer shown.
+    fn reject_pending_tool_approval(&self, state: &mut AppState) {
+        let Some(PendingToolApproval {
+            session_id,
+            tool_id,
+        }) = state.pending_tool_approval.take()
+        else {
+            return;
+        };
+        let guard = self
+            .backend_event_sender
+            .lock()
+            .expect("backend_event_sender lock poisoned");
+        if let Some(sender) = guard.as_ref() {
+            if let Err(err) = sender.try_send(BackendEvent::RejectTool {
+                session_id,
+                tool_id,
+            }) {
+                warn!("Failed to reject pending tool approval: {}", err);
+            }
+        }
+    }
+
     /// Helper to push an event to the queue.
     /// Uses synchronous `try_send` on an unbounded channel to guarantee FIFO
     /// ordering.  The previous implementation spawned a Tokio task per event,
@@ -88,21 +127,32 @@ impl UserInterface for TerminalUI {
                 session_id,
                 tool_results,
             } => {
-                let mut state = self.app_state.lock().await;
                 debug!("Setting messages for session {:?}", session_id);
+                {
+                    let mut state = self.app_state.lock().await;
+                    if let Some(session_id) = &session_id {
+                        if state.current_session_id.as_ref() != Some(session_id) {
+                            self.reject_pending_tool_approval(&mut state);
+                            state.set_plan(None);
+                            state.session_usage = None;
+                        }
+                        state.current_session_id = Some(session_id.clone());
+                    }
 
-                if let Some(session_id) = session_id {
-                    if state.current_session_id.as_ref() != Some(&session_id) {
-                        state.set_plan(None);
+                    // Update tool statuses from tool results
+                    for tool_result in tool_results {
+                        state
+                            .tool_statuses
+                            .insert(tool_result.tool_id, tool_result.status);
                     }
-                    state.current_session_id = Some(session_id);
                 }
 
-                // Update tool statuses from tool results
-                for tool_result in tool_results {
-                    state
-                        .tool_statuses
-                        .insert(tool_result.tool_id, tool_result.status);
+                // The draw loop locks the renderer before the state, so the
+                // state lock must be released before taking the renderer
+                if let Some(session_id) = session_id {
+                    if let Some(renderer) = self.renderer.lock().await.as_ref() {
+                        renderer.lock().await.switch_transcript(&session_id);
+                    }
                 }
             }
 
@@ -143,9 +193,16 @@ impl UserInterface for TerminalUI {
                 );
                 if let Some(current_session_id) = &state.current_session_id {
                     if current_session_id == &session_id {
+                        let was_busy = state.activity_state.as_ref().is_some_and(|state| {
+                            !matches!(state, crate::session::instance::SessionActivityState::Idle)
+                        });
                         state.update_activity_state(Some(activity_state));
                         if is_idle {
                             self.cancel_flag.store(false, Ordering::SeqCst);
+                            state.requeue_steering_message();
+                            if was_busy {
+                                state.note_turn_completed();
+                            }
                         }
                     }
                 }
@@ -168,21 +225,55 @@ impl UserInterface for TerminalUI {
                 status,
                 message,
                 output,
+                duration,
+                unchanged,
             } => {
                 debug!("Updating tool status for {}: {:?}", tool_id, status);
                 {
                     let mut state = self.app_state.lock().await;
                     state.tool_statuses.insert(tool_id.clone(), status);
+                    if state
+                        .pending_tool_approval
+                        .as_ref()
+                        .is_some_and(|pending| pending.tool_id == tool_id)
+                    {
+                        state.pending_tool_approval = None;
+                    }
                 }
 
                 // Update tool status in renderer - can now update any tool in current message
                 if let Some(renderer) = self.renderer.lock().await.as_ref() {
                     let mut renderer_guard = renderer.lock().await;
                     renderer_guard.update_tool_status(&tool_id, status, message, output);
+                    if let Some(duration) = duration {
+                        renderer_guard.set_tool_duration(&tool_id, duration);
+                    }
+                    if unchanged {
+                        renderer_guard.mark_tool_unchanged(&tool_id);
+                    }
+                }
+            }
+            UiEvent::RequestToolApproval { tool_id } => {
+                debug!("Tool {} awaits approval", tool_id);
+                {
+                    let mut state = self.app_state.lock().await;
+                    // The proxy only forwards events while its session is the
+                    // connected one, so the request comes from the current session
+                    if let Some(session_id) = state.current_session_id.clone() {
+                        state.pending_tool_approval = Some(PendingToolApproval {
+                            session_id,
+                            tool_id: tool_id.clone(),
+                        });
+                    }
+                }
+                if let Some(renderer) = self.renderer.lock().await.as_ref() {
+                    let mut renderer_guard = renderer.lock().await;
+                    renderer_guard.set_tool_awaiting_approval(&tool_id, true);
                 }
             }
             UiEvent::ClearMessages => {
                 debug!("Clearing messages");
+                self.reject_pending_tool_approval(&mut *self.app_state.lock().await);
                 // Clear all messages in renderer
                 if let Some(renderer) = self.renderer.lock().await.as_ref() {
                     let mut renderer_guard = renderer.lock().await;
@@ -197,33 +288,39 @@ impl UserInterface for TerminalUI {
             } => {
                 debug!("Displaying user input: {}", content);
 
+                // Queued messages are delivered together as this input
+                {
+                    let mut state = self.app_state.lock().await;
+                    state.queued_messages.clear();
+                    state.steering_message = None;
+                    state.note_change(Instant::now());
+                }
+
                 // Add user message
                 if let Some(renderer) = self.renderer.lock().await.as_ref() {
                     let mut renderer_guard = renderer.lock().await;
                     // Clear any existing error when user sends a message
                     renderer_guard.clear_error();
-                    // Build combined content with attachment info merged in
+                    // Build combined content with attachment info merged in;
+                    // images follow the text as image blocks
                     let mut display_content = content.clone();
+                    let mut images = Vec::new();
                     let attachment_lines: Vec<String> = attachments
                         .iter()
-                        .map(|attachment| match attachment {
+                        .filter_map(|attachment| match attachment {
                             crate::persistence::DraftAttachment::Text { .. } => {
-                                "[text attachment]".to_string()
+                                Some("[text attachment]".to_string())
                             }
                             crate::persistence::DraftAttachment::Image {
+                                content,
                                 mime_type,
-                                width,
-                                height,
                                 ..
                             } => {
-                                let dims = match (width, height) {
-                                    (Some(w), Some(h)) => format!("{w}x{h} "),
-                                    _ => String::new(),
-                                };
-                                format!("[image {dims}({mime_type})]")
+                                images.push(ImageBlock::new(mime_type.clone(), content.clone()));
+                                None
                             }
                             crate::persistence::DraftAttachment::File { filename, .. } => {
-                                format!("[file ({filename})]")
+                                Some(format!("[file ({filename})]"))
                             }
                         })
                         .collect();
@@ -234,7 +331,7 @@ impl UserInterface for TerminalUI {
                             display_content.push_str(line);
                         }
                     }
-                    let _ = renderer_guard.add_user_message(&display_content);
+                    let _ = renderer_guard.add_user_message_with_images(&display_content, images);
                 }
             }
             UiEvent::DisplayCompactionSummary { summary } => {
@@ -244,6 +341,7 @@ impl UserInterface for TerminalUI {
                     let formatted = format!("\n\n[conversation compacted]\n{summary}\n",);
                     let _ = renderer_guard.add_instruction_message(&formatted);
                 }
+                self.app_state.lock().await.note_change(Instant::now());
             }
             UiEvent::StreamingStarted(request_id) => {
                 debug!("Streaming started for request {}", request_id);
@@ -255,6 +353,8 @@ impl UserInterface for TerminalUI {
                     // Clear any existing error when new operation starts
                     renderer_guard.clear_error();
                 }
+                // The previous message was finalized into the transcript
+                self.app_state.lock().await.note_change(Instant::now());
             }
             UiEvent::AppendToTextBlock { content } => {
                 debug!("Appending to text block: '{content}'");
@@ -272,6 +372,13 @@ impl UserInterface for TerminalUI {
                     renderer_guard.queue_thinking_delta(content);
                 }
             }
+            UiEvent::AddImage { media_type, data } => {
+                debug!("Adding image: {}", media_type);
+                if let Some(renderer) = self.renderer.lock().await.as_ref() {
+                    let mut renderer_guard = renderer.lock().await;
+                    renderer_guard.add_image_block(ImageBlock::new(media_type, data));
+                }
+            }
             UiEvent::StartTool { name, id } => {
                 debug!("Starting tool: {} ({})", name, id);
 
@@ -295,10 +402,13 @@ impl UserInterface for TerminalUI {
                 }
             }
 
-            UiEvent::EndTool { id: _ } => {
-                // EndTool just marks the end of parameter streaming
-                // The actual status comes later via UpdateToolStatus
-                // For now, we don't change the status here - wait for UpdateToolStatus
+            UiEvent::EndTool { id } => {
+                // EndTool marks the end of parameter streaming; the status
+                // comes later via UpdateToolStatus
+                if let Some(renderer) = self.renderer.lock().await.as_ref() {
+                    let mut renderer_guard = renderer.lock().await;
+                    renderer_guard.complete_tool_parameters(&id);
+                }
             }
             UiEvent::AppendToolOutput { tool_id, chunk } => {
                 // Accumulate streaming output into the tool block (used by execute_command)
@@ -307,6 +417,28 @@ impl UserInterface for TerminalUI {
                     renderer_guard.append_tool_output(&tool_id, &chunk);
                 }
             }
+            UiEvent::MessageUsage {
+                prompt_tokens,
+                completion_tokens,
+                cost,
+            } => {
+                let usage = MessageUsage {
+                    prompt_tokens,
+                    completion_tokens,
+                    cost,
+                };
+                self.app_state.lock().await.add_session_usage(usage);
+                if let Some(renderer) = self.renderer.lock().await.as_ref() {
+                    let mut renderer_guard = renderer.lock().await;
+                    renderer_guard.set_message_usage(usage);
+                }
+            }
+            UiEvent::ToolProgress { tool_id, fraction } => {
+                if let Some(renderer) = self.renderer.lock().await.as_ref() {
+                    let mut renderer_guard = renderer.lock().await;
+                    renderer_guard.set_tool_progress(&tool_id, fraction);
+                }
+            }
             UiEvent::HiddenToolCompleted => {
                 // Mark that a hidden tool completed - renderer handles paragraph breaks
                 if let Some(renderer) = self.renderer.lock().await.as_ref() {
@@ -325,6 +457,7 @@ impl UserInterface for TerminalUI {
                 );
 
                 self.cancel_flag.store(false, Ordering::SeqCst);
+                self.app_state.lock().await.note_change(Instant::now());
 
                 if let Some(renderer) = self.renderer.lock().await.as_ref() {
                     let mut renderer_guard = renderer.lock().await;
@@ -334,6 +467,16 @@ impl UserInterface for TerminalUI {
                 // Don't finalize the message yet - keep it live for tool status updates
                 // It will be finalized when the next StreamingStarted event arrives
             }
+            UiEvent::UpdateCurrentModel { model_name } => {
+                debug!("Updating current model: {}", model_name);
+                let mut state = self.app_state.lock().await;
+                state.update_current_model(Some(model_name));
+            }
+            UiEvent::UpdateSandboxPolicy { policy } => {
+                debug!("Updating sandbox policy: {:?}", policy);
+                let mut state = self.app_state.lock().await;
+                state.update_sandbox_policy(Some(policy));
+            }
             UiEvent::DisplayError { message } => {
                 debug!("Displaying error: {}", message);
                 // Set error in renderer
@@ -342,6 +485,20 @@ impl UserInterface for TerminalUI {
                     renderer_guard.set_error(message);
                 }
             }
+            UiEvent::DisplayWarning { message } => {
+                debug!("Displaying warning: {}", message);
+                if let Some(renderer) = self.renderer.lock().await.as_ref() {
+                    let mut renderer_guard = renderer.lock().await;
+                    renderer_guard.set_warning(message);
+                }
+            }
+            UiEvent::DisplayErrorDetailed { info } => {
+                debug!("Displaying error: {:?}", info);
+                if let Some(renderer) = self.renderer.lock().await.as_ref() {
+                    let mut renderer_guard = renderer.lock().await;
+                    renderer_guard.set_error_detailed(info);
+                }
+            }
             UiEvent::ClearError => {
                 debug!("Clearing error");
                 // Clear error in renderer
@@ -350,6 +507,19 @@ impl UserInterface for TerminalUI {
                     renderer_guard.clear_error();
                 }
             }
+            UiEvent::RetryAttempt { attempt, max } => {
+                debug!("Retrying request (attempt {}/{})", attempt, max);
+                if let Some(renderer) = self.renderer.lock().await.as_ref() {
+                    let mut renderer_guard = renderer.lock().await;
+                    renderer_guard.show_retry_spinner(attempt, max);
+                }
+            }
+            UiEvent::Phase { label } => {
+                if let Some(renderer) = self.renderer.lock().await.as_ref() {
+                    let mut renderer_guard = renderer.lock().await;
+                    renderer_guard.set_spinner_phase(label);
+                }
+            }
             // Resource events - logged for debugging, can be extended for features like "follow mode"
             UiEvent::ResourceLoaded { project, path } => {
                 tracing::trace!(
@@ -549,6 +719,17 @@ impl UserInterface for TerminalUI {
         });
     }
 
+    fn notify_retry_attempt(&self, attempt: u32, max: u32) {
+        self.push_event(UiEvent::RetryAttempt { attempt, max });
+    }
+
+    fn requires_tool_approval(&self, tool_name: &str) -> bool {
+        TerminalConfig::global()
+            .approve_tools
+            .iter()
+            .any(|tool| tool == tool_name)
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
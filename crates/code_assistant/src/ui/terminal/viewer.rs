@@ -0,0 +1,101 @@
+//! Read-only viewer for saved sessions (`--view <SESSION_ID>`).
+//!
+//! The session is loaded straight from persistence and shown as committed
+//! history; no backend is started and the composer takes no input.
+
+use anyhow::{anyhow, Result};
+use crossterm::cursor::MoveTo;
+use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyModifiers};
+use futures::StreamExt;
+
+use super::composer::StatusBarInfo;
+use super::renderer::ProductionTerminalRenderer;
+use super::textarea::TextArea;
+use super::tui;
+use crate::persistence::FileSessionPersistence;
+use crate::session::instance::SessionInstance;
+
+/// Show the saved session `session_id` until the user quits. Nothing is
+/// sent anywhere: the history goes to scrollback once, and the viewport
+/// only holds the disabled composer and the status bar.
+pub async fn run(session_id: &str) -> Result<()> {
+    let session = FileSessionPersistence::new()
+        .load_chat_session(session_id)?
+        .ok_or_else(|| anyhow!("Session not found: {session_id}"))?;
+    let session_name = session.name.clone();
+    let tool_syntax = session.config.tool_syntax;
+    let instance = SessionInstance::new(session);
+    let messages = instance.convert_messages_to_ui_data(tool_syntax)?;
+    let tool_results = instance.convert_tool_executions_to_ui_data()?;
+
+    let mut renderer = ProductionTerminalRenderer::new()?;
+    renderer.set_read_only(true);
+    renderer.load_session(&messages, &tool_results);
+    renderer.set_status_bar(StatusBarInfo {
+        session_name: Some(session_name),
+        tool_syntax: Some(tool_syntax),
+        ..StatusBarInfo::default()
+    });
+
+    let mut tui = tui::init()?;
+    let loop_result = view_loop(&mut renderer, &mut tui).await;
+    let cleanup_result = tui::restore();
+    loop_result?;
+    cleanup_result?;
+    Ok(())
+}
+
+async fn view_loop(renderer: &mut ProductionTerminalRenderer, tui: &mut tui::Tui) -> Result<()> {
+    let textarea = TextArea::new();
+    let mut event_stream = EventStream::new();
+    let mut needs_redraw = true;
+
+    loop {
+        if needs_redraw {
+            let screen_size = tui.size()?;
+            renderer.prepare(screen_size.width, screen_size.height);
+            let pending_lines = renderer.drain_pending_history_rows();
+            if !pending_lines.is_empty() {
+                tui.insert_history_lines(pending_lines);
+            }
+            let desired_height = renderer.desired_viewport_height(&textarea, screen_size.width);
+            tui.draw(desired_height, |frame| renderer.paint(frame, &textarea))?;
+            needs_redraw = false;
+        }
+
+        match event_stream.next().await {
+            Some(Ok(Event::Key(key_event))) if is_quit_key(key_event) => break,
+            Some(Ok(Event::Resize(_, _))) => needs_redraw = true,
+            Some(Ok(_)) => {}
+            Some(Err(e)) => return Err(e.into()),
+            None => break,
+        }
+    }
+
+    let viewport = tui.terminal.viewport_area;
+    crossterm::execute!(std::io::stdout(), MoveTo(0, viewport.bottom()))?;
+    Ok(())
+}
+
+fn is_quit_key(key_event: KeyEvent) -> bool {
+    match key_event.code {
+        KeyCode::Char('c') => key_event.modifiers.contains(KeyModifiers::CONTROL),
+        KeyCode::Char('q') | KeyCode::Esc => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quit_keys() {
+        let key = |code, modifiers| KeyEvent::new(code, modifiers);
+        assert!(is_quit_key(key(KeyCode::Char('q'), KeyModifiers::NONE)));
+        assert!(is_quit_key(key(KeyCode::Esc, KeyModifiers::NONE)));
+        assert!(is_quit_key(key(KeyCode::Char('c'), KeyModifiers::CONTROL)));
+        assert!(!is_quit_key(key(KeyCode::Char('c'), KeyModifiers::NONE)));
+        assert!(!is_quit_key(key(KeyCode::Enter, KeyModifiers::NONE)));
+    }
+}
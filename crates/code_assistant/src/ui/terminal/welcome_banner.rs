@@ -1,9 +1,9 @@
 use ratatui::{
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
 };
 
-use super::terminal_color;
+use super::theme::Theme;
 
 /// 5-row bitmap font for each letter in "code".
 /// '#' = filled pixel, ' ' = empty. Each letter is rendered at 2x horizontal scale.
@@ -58,11 +58,12 @@ fn render_banner() -> Vec<String> {
 pub fn welcome_banner_lines(project_path: &str, is_temporary: bool) -> Vec<Line<'static>> {
     let mut lines = Vec::new();
 
-    let accent = banner_accent_color();
-    let dim_accent = banner_dim_color();
+    let theme = Theme::global();
+    let accent = theme.banner_fg;
+    let dim_accent = theme.banner_subtitle_fg;
     let banner_style = Style::default().fg(accent);
     let dim_style = Style::default()
-        .fg(Color::DarkGray)
+        .fg(theme.muted_fg)
         .add_modifier(Modifier::DIM);
 
     // Empty line before banner
@@ -101,28 +102,6 @@ pub fn welcome_banner_lines(project_path: &str, is_temporary: bool) -> Vec<Line<
     lines
 }
 
-/// Accent color for the banner, adapts to light/dark terminal backgrounds.
-fn banner_accent_color() -> Color {
-    match terminal_color::terminal_bg() {
-        Some(bg) if is_light(bg) => Color::Rgb(60, 60, 160),
-        _ => Color::Rgb(100, 140, 255),
-    }
-}
-
-/// Dimmer accent for the "assistant" subtitle.
-fn banner_dim_color() -> Color {
-    match terminal_color::terminal_bg() {
-        Some(bg) if is_light(bg) => Color::Rgb(100, 100, 180),
-        _ => Color::Rgb(70, 100, 180),
-    }
-}
-
-fn is_light(bg: (u8, u8, u8)) -> bool {
-    let (r, g, b) = bg;
-    let y = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
-    y > 128.0
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
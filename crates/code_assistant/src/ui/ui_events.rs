@@ -24,6 +24,86 @@ pub struct ToolResultData {
     pub status: ToolStatus,
     pub message: Option<String>,
     pub output: Option<String>,
+    /// The tool succeeded without changing anything
+    pub unchanged: bool,
+}
+
+/// An error with what is known about its cause, for UIs that can show more
+/// than a line of text
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorInfo {
+    pub title: String,
+    pub message: String,
+    /// Set when an LLM API request failed; other errors are known only by
+    /// their message
+    pub api: Option<ApiErrorInfo>,
+}
+
+/// How an LLM API request failed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiErrorInfo {
+    /// Kind of API failure, e.g. `rate_limit` or `overloaded`
+    pub code: String,
+    /// Label of the LLM provider the request went to
+    pub provider: Option<String>,
+    /// Sending the same request again may succeed
+    pub retryable: bool,
+}
+
+impl ErrorInfo {
+    /// An error known only by its message.
+    pub fn plain(message: impl Into<String>) -> Self {
+        Self {
+            title: "Error".to_string(),
+            message: message.into(),
+            api: None,
+        }
+    }
+
+    /// Describe `error`, classifying LLM API failures found in its chain.
+    /// Other errors become a plain `title: error` message.
+    pub fn from_error(title: &str, error: &anyhow::Error) -> Self {
+        let Some(api_error) = llm::ApiError::find_in(error) else {
+            return Self::plain(format!("{title}: {error}"));
+        };
+
+        let (title, code, retryable, message) = match api_error {
+            llm::ApiError::RateLimit(msg) => ("Rate limited", "rate_limit", true, msg),
+            llm::ApiError::Authentication(msg) => {
+                ("Authentication failed", "authentication", false, msg)
+            }
+            llm::ApiError::InvalidRequest(msg) => {
+                ("Invalid request", "invalid_request", false, msg)
+            }
+            llm::ApiError::ServiceError(msg) => ("Service error", "service_error", true, msg),
+            llm::ApiError::Overloaded(msg) => ("Service overloaded", "overloaded", true, msg),
+            llm::ApiError::NetworkError(msg) => ("Network error", "network", true, msg),
+            llm::ApiError::Unknown(msg) => ("Request failed", "unknown", false, msg),
+        };
+        Self {
+            title: title.to_string(),
+            message: message.clone(),
+            api: Some(ApiErrorInfo {
+                code: code.to_string(),
+                provider: None,
+                retryable,
+            }),
+        }
+    }
+
+    /// Whether sending the same request again may succeed.
+    pub fn is_retryable(&self) -> bool {
+        self.api.as_ref().is_some_and(|api| api.retryable)
+    }
+}
+
+impl std::fmt::Display for ErrorInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.api {
+            Some(_) => write!(f, "{}: {}", self.title, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
 }
 
 /// Events for UI updates from the agent thread
@@ -56,6 +136,10 @@ pub enum UiEvent {
         status: ToolStatus,
         message: Option<String>,
         output: Option<String>,
+        /// How long the tool ran; only set once it has finished
+        duration: Option<std::time::Duration>,
+        /// The tool succeeded without changing anything
+        unchanged: bool,
     },
 
     /// End a tool invocation
@@ -66,6 +150,8 @@ pub enum UiEvent {
     AddImage { media_type: String, data: String },
     /// Append streaming tool output
     AppendToolOutput { tool_id: String, chunk: String },
+    /// Determinate progress (0.0 to 1.0) of a running tool
+    ToolProgress { tool_id: String, fraction: f32 },
     /// Update the session plan display
     UpdatePlan { plan: PlanState },
     /// Set all messages at once (for session loading, clears existing)
@@ -76,6 +162,14 @@ pub enum UiEvent {
     },
     /// Streaming started for a request
     StreamingStarted(u64),
+    /// Token usage of a finished LLM response, sent before `StreamingStopped`
+    MessageUsage {
+        /// Input tokens, including cache reads and writes
+        prompt_tokens: u32,
+        completion_tokens: u32,
+        /// Estimated cost in dollars, if the model has pricing configured
+        cost: Option<f64>,
+    },
     /// Streaming stopped for a request
     StreamingStopped {
         id: u64,
@@ -117,8 +211,17 @@ pub enum UiEvent {
     UpdatePendingMessage { message: Option<String> },
     /// Display an error message to the user
     DisplayError { message: String },
+    /// Display an error with its cause and whether retrying may help
+    DisplayErrorDetailed { info: ErrorInfo },
     /// Clear the current error display
     ClearError,
+    /// Display a non-error notice, e.g. a fallback to another model
+    DisplayWarning { message: String },
+    /// A failed LLM request is being retried after a backoff delay
+    RetryAttempt { attempt: u32, max: u32 },
+    /// What the agent is busy with, e.g. `Running execute_command`; `None`
+    /// once that work is done
+    Phase { label: Option<String> },
     /// Start a new reasoning summary item
     StartReasoningSummaryItem,
     /// Append delta content to the current reasoning summary item
@@ -132,6 +235,8 @@ pub enum UiEvent {
 
     /// Cancel a running sub-agent by its tool id
     CancelSubAgent { tool_id: String },
+    /// A gated tool is waiting for the user to approve or skip it
+    RequestToolApproval { tool_id: String },
 
     // === Session Branching Events ===
     /// Request to start editing a message (creates a branch point)
@@ -194,3 +299,29 @@ pub enum UiEvent {
     /// A file was deleted by a tool
     ResourceDeleted { project: String, path: PathBuf },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_info_classifies_api_errors() {
+        // As providers report it, behind the rate limit context
+        let error = anyhow::Error::new(llm::ApiErrorContext::<()> {
+            error: llm::ApiError::Overloaded("try later".to_string()),
+            rate_limits: None,
+        })
+        .context("LLM request failed");
+        let info = ErrorInfo::from_error("Agent error", &error);
+        assert_eq!(info.title, "Service overloaded");
+        assert_eq!(info.message, "try later");
+        assert_eq!(info.api.as_ref().unwrap().code, "overloaded");
+        assert!(info.is_retryable());
+
+        // Other errors read as they did before they were classified
+        let info = ErrorInfo::from_error("Agent error", &anyhow::anyhow!("disk full"));
+        assert_eq!(info, ErrorInfo::plain("Agent error: disk full"));
+        assert_eq!(info.to_string(), "Agent error: disk full");
+        assert!(!info.is_retryable());
+    }
+}
@@ -230,7 +230,7 @@ impl MessageConverter for DefaultMessageConverter {
 
 /// Rate limit information extracted from response headers
 #[derive(Debug, Default)]
-struct AnthropicRateLimitInfo {
+pub(crate) struct AnthropicRateLimitInfo {
     requests_limit: Option<u32>,
     requests_remaining: Option<u32>,
     requests_reset: Option<DateTime<Utc>>,
@@ -703,7 +703,8 @@ impl AnthropicClient {
 
         if let Some(callback) = streaming_callback {
             // Use the common PlaybackChunkStream and existing streaming processing logic
-            let mut chunk_stream = PlaybackChunkStream::new(session.chunks.clone(), playback.fast);
+            let mut chunk_stream = PlaybackChunkStream::new(session.chunks.clone(), playback.fast)
+                .with_control(playback.control.clone());
             let rate_limits = AnthropicRateLimitInfo::default();
 
             // Use the same streaming processing logic, but without recording
@@ -2,9 +2,10 @@ use crate::aicore::{AiCoreAnthropicClient, AiCoreApiType, AiCoreOpenAIClient, Ai
 use crate::auth::TokenManager;
 use crate::provider_config::{ConfigurationSystem, ModelConfig, ProviderConfig};
 use crate::{
-    recording::PlaybackState, AnthropicClient, CerebrasClient, GroqClient, LLMProvider,
-    MinimaxClient, MistralAiClient, MoonshotClient, OllamaClient, OpenAIClient,
-    OpenAIResponsesClient, OpenRouterClient, VertexClient, ZaiClient,
+    recording::{PlaybackControl, PlaybackState},
+    AnthropicClient, CerebrasClient, GroqClient, LLMProvider, MinimaxClient, MistralAiClient,
+    MoonshotClient, OllamaClient, OpenAIClient, OpenAIResponsesClient, OpenRouterClient,
+    VertexClient, ZaiClient,
 };
 use anyhow::{Context, Result};
 use clap::ValueEnum;
@@ -209,11 +210,14 @@ pub struct LLMClientConfig {
     pub fast_playback: bool,
 }
 
-/// Create an LLM client using the new model-based configuration system
+/// Create an LLM client using the new model-based configuration system.
+/// A playback from `playback_path` follows the pause, step and speed
+/// settings of `playback_control`.
 pub async fn create_llm_client_from_model(
     model_name: &str,
     playback_path: Option<PathBuf>,
     fast_playback: bool,
+    playback_control: PlaybackControl,
     record_path: Option<PathBuf>,
 ) -> Result<Box<dyn LLMProvider>> {
     let config_system = ConfigurationSystem::load()?;
@@ -224,6 +228,7 @@ pub async fn create_llm_client_from_model(
         provider_config,
         playback_path,
         fast_playback,
+        playback_control,
         record_path,
     )
     .await
@@ -235,11 +240,12 @@ pub async fn create_llm_client_from_configs(
     provider_config: &ProviderConfig,
     playback_path: Option<PathBuf>,
     fast_playback: bool,
+    playback_control: PlaybackControl,
     record_path_override: Option<PathBuf>,
 ) -> Result<Box<dyn LLMProvider>> {
     // Build optional playback state once
     let playback_state = if let Some(path) = &playback_path {
-        let state = PlaybackState::from_file(path, fast_playback)?;
+        let state = PlaybackState::from_file(path, fast_playback)?.with_control(playback_control);
         if state.session_count() == 0 {
             return Err(anyhow::anyhow!("Recording file contains no sessions"));
         }
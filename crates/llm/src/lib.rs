@@ -73,6 +73,8 @@ pub enum StreamingChunk {
     RateLimit { seconds_remaining: u64 },
     /// Clear rate limit notification
     RateLimitClear,
+    /// A failed request is about to be retried (1-based attempt out of `max`)
+    RetryAttempt { attempt: u32, max: u32 },
     /// Indicates that streaming from the LLM has completed
     StreamingComplete,
     /// OpenAI reasoning summary started a new item
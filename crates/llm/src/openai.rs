@@ -210,7 +210,7 @@ struct OpenAIFunctionDelta {
 
 /// Rate limit information extracted from response headers
 #[derive(Debug)]
-struct OpenAIRateLimitInfo {
+pub(crate) struct OpenAIRateLimitInfo {
     requests_limit: Option<u32>,
     requests_remaining: Option<u32>,
     requests_reset: Option<Duration>,
@@ -418,7 +418,7 @@ struct StreamEvent {
 
 /// Rate limit information from response headers
 #[derive(Debug, Default)]
-struct ResponsesRateLimitInfo {
+pub(crate) struct ResponsesRateLimitInfo {
     retry_after: Option<Duration>,
 }
 
@@ -800,7 +800,8 @@ impl OpenAIResponsesClient {
 
         if let Some(callback) = streaming_callback {
             // Create a playback chunk stream
-            let mut chunk_stream = PlaybackChunkStream::new(session.chunks.clone(), playback.fast);
+            let mut chunk_stream = PlaybackChunkStream::new(session.chunks.clone(), playback.fast)
+                .with_control(playback.control.clone());
             let rate_limits = ResponsesRateLimitInfo::default();
 
             // Use the same streaming processing logic
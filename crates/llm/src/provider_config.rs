@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+use crate::Usage;
+
 /// Configuration for a single provider instance
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProviderConfig {
@@ -28,6 +30,39 @@ pub struct ModelConfig {
     pub config: serde_json::Value,
     /// Maximum context window supported by the model (token count)
     pub context_token_limit: u32,
+    /// Token prices, used to estimate the cost of each response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pricing: Option<ModelPricing>,
+}
+
+/// Prices in dollars per million tokens. Cache prices default to the input
+/// price when not given.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub input: f64,
+    pub output: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read: Option<f64>,
+}
+
+impl ModelPricing {
+    /// Estimated cost in dollars of a request with the given usage.
+    pub fn cost(&self, usage: &Usage) -> f64 {
+        let tokens = |count: u32, price: f64| f64::from(count) * price;
+        (tokens(usage.input_tokens, self.input)
+            + tokens(usage.output_tokens, self.output)
+            + tokens(
+                usage.cache_creation_input_tokens,
+                self.cache_write.unwrap_or(self.input),
+            )
+            + tokens(
+                usage.cache_read_input_tokens,
+                self.cache_read.unwrap_or(self.input),
+            ))
+            / 1_000_000.0
+    }
 }
 
 /// Configuration for all models (model_display_name -> ModelConfig)
@@ -369,6 +404,36 @@ mod tests {
     use super::*;
     use std::env;
 
+    #[test]
+    fn test_model_pricing_cost() {
+        let model: ModelConfig = serde_json::from_value(serde_json::json!({
+            "provider": "anthropic-main",
+            "id": "claude-sonnet-4-5",
+            "config": {},
+            "context_token_limit": 200000,
+            "pricing": {"input": 3.0, "output": 15.0, "cache_read": 0.3}
+        }))
+        .unwrap();
+        let pricing = model.pricing.unwrap();
+        let usage = Usage {
+            input_tokens: 1_000_000,
+            output_tokens: 100_000,
+            cache_creation_input_tokens: 10_000,
+            cache_read_input_tokens: 1_000_000,
+        };
+        // 3.0 + 1.5 + 0.03 (cache writes at the input price) + 0.3
+        assert!((pricing.cost(&usage) - 4.83).abs() < 1e-9);
+
+        let model: ModelConfig = serde_json::from_value(serde_json::json!({
+            "provider": "openai-main",
+            "id": "gpt-4.1",
+            "config": {},
+            "context_token_limit": 128000
+        }))
+        .unwrap();
+        assert_eq!(model.pricing, None);
+    }
+
     #[test]
     fn test_env_var_substitution() {
         // Set a test environment variable
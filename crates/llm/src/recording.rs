@@ -130,12 +130,117 @@ impl APIRecorder {
     }
 }
 
+/// Slowest playback speed multiplier
+pub const MIN_PLAYBACK_SPEED: f32 = 0.25;
+/// Fastest playback speed multiplier
+pub const MAX_PLAYBACK_SPEED: f32 = 16.0;
+
+/// Interactive adjustment of a running playback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaybackAction {
+    /// Pause, or resume a paused playback
+    TogglePause,
+    /// Release one chunk; pauses a running playback first
+    Step,
+    /// Double the speed
+    Faster,
+    /// Halve the speed
+    Slower,
+}
+
+/// Current pause state and speed of a playback
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlaybackStatus {
+    pub paused: bool,
+    pub speed: f32,
+}
+
+impl Default for PlaybackStatus {
+    fn default() -> Self {
+        Self {
+            paused: false,
+            speed: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct PlaybackControlState {
+    status: PlaybackStatus,
+    /// Chunks to release while paused
+    steps: usize,
+}
+
+/// Pause, step and speed settings shared between playback streams and the
+/// UI that drives them. Cloning shares the same settings.
+#[derive(Debug, Clone, Default)]
+pub struct PlaybackControl {
+    state: Arc<Mutex<PlaybackControlState>>,
+    changed: Arc<tokio::sync::Notify>,
+}
+
+impl PlaybackControl {
+    /// Apply `action` and return the resulting status
+    pub fn apply(&self, action: PlaybackAction) -> PlaybackStatus {
+        let status = {
+            let mut state = self.state.lock().unwrap();
+            match action {
+                PlaybackAction::TogglePause => {
+                    state.status.paused = !state.status.paused;
+                    state.steps = 0;
+                }
+                PlaybackAction::Step => {
+                    state.status.paused = true;
+                    state.steps += 1;
+                }
+                PlaybackAction::Faster => {
+                    state.status.speed = (state.status.speed * 2.0).min(MAX_PLAYBACK_SPEED);
+                }
+                PlaybackAction::Slower => {
+                    state.status.speed = (state.status.speed / 2.0).max(MIN_PLAYBACK_SPEED);
+                }
+            }
+            state.status
+        };
+        self.changed.notify_waiters();
+        status
+    }
+
+    pub fn status(&self) -> PlaybackStatus {
+        self.state.lock().unwrap().status
+    }
+
+    /// Wait until the next chunk may be delivered: right away unless
+    /// paused, otherwise until resumed or stepped. Returns true when a
+    /// step released the chunk.
+    pub async fn wait_for_turn(&self) -> bool {
+        loop {
+            // Register for changes before looking, so none is missed
+            let changed = self.changed.notified();
+            tokio::pin!(changed);
+            changed.as_mut().enable();
+            {
+                let mut state = self.state.lock().unwrap();
+                if !state.status.paused {
+                    return false;
+                }
+                if state.steps > 0 {
+                    state.steps -= 1;
+                    return true;
+                }
+            }
+            changed.await;
+        }
+    }
+}
+
 /// Provider-agnostic playback state shared by providers
 #[derive(Clone)]
 pub struct PlaybackState {
     sessions: Arc<Vec<RecordingSession>>, // immutable list
     index: Arc<Mutex<usize>>,             // current session index
     pub fast: bool,
+    pub control: PlaybackControl,
 }
 
 impl PlaybackState {
@@ -150,9 +255,16 @@ impl PlaybackState {
             sessions: Arc::new(sessions),
             index: Arc::new(Mutex::new(0)),
             fast,
+            control: PlaybackControl::default(),
         })
     }
 
+    /// Share `control` with the streams of this playback
+    pub fn with_control(mut self, control: PlaybackControl) -> Self {
+        self.control = control;
+        self
+    }
+
     pub fn session_count(&self) -> usize {
         self.sessions.len()
     }
@@ -213,4 +325,45 @@ mod tests {
         // Cleanup
         let _ = fs::remove_file(&tmp_path);
     }
+
+    #[test]
+    fn playback_control_pauses_steps_and_clamps_speed() {
+        let control = PlaybackControl::default();
+        assert_eq!(control.status(), PlaybackStatus::default());
+
+        assert!(control.apply(PlaybackAction::TogglePause).paused);
+        assert!(!control.apply(PlaybackAction::TogglePause).paused);
+
+        for _ in 0..10 {
+            control.apply(PlaybackAction::Faster);
+        }
+        assert_eq!(control.status().speed, MAX_PLAYBACK_SPEED);
+        for _ in 0..10 {
+            control.apply(PlaybackAction::Slower);
+        }
+        assert_eq!(control.status().speed, MIN_PLAYBACK_SPEED);
+
+        // Stepping a running playback pauses it
+        let status = control.apply(PlaybackAction::Step);
+        assert!(status.paused);
+    }
+
+    #[tokio::test]
+    async fn playback_control_releases_one_chunk_per_step() {
+        let control = PlaybackControl::default();
+        assert!(!control.wait_for_turn().await);
+
+        control.apply(PlaybackAction::Step);
+        assert!(control.wait_for_turn().await);
+
+        // Paused with no steps left: blocks until resumed
+        let waiter = tokio::spawn({
+            let control = control.clone();
+            async move { control.wait_for_turn().await }
+        });
+        tokio::task::yield_now().await;
+        assert!(!waiter.is_finished());
+        control.apply(PlaybackAction::TogglePause);
+        assert!(!waiter.await.unwrap());
+    }
 }
@@ -4,11 +4,12 @@
 //! from LLM providers, supporting both real HTTP responses and recorded
 //! playback with identical processing logic.
 
-use crate::recording::RecordedChunk;
+use crate::recording::{PlaybackControl, RecordedChunk};
 use anyhow::Result;
 use async_trait::async_trait;
 use reqwest::Response;
-use std::time::{Duration, Instant};
+use std::time::Duration;
+use tokio::time::Instant;
 
 /// Trait for streaming chunk sources (real HTTP response or recorded playback)
 ///
@@ -45,8 +46,11 @@ impl ChunkStream for HttpChunkStream {
 pub struct PlaybackChunkStream {
     chunks: Vec<RecordedChunk>,
     current_index: usize,
-    start_time: Instant,
+    /// When the previous chunk was due and its offset in the recording;
+    /// later chunks are scheduled from here so delays don't add up
+    previous: (Instant, Duration),
     fast_mode: bool,
+    control: PlaybackControl,
 }
 
 impl PlaybackChunkStream {
@@ -54,10 +58,35 @@ impl PlaybackChunkStream {
         Self {
             chunks,
             current_index: 0,
-            start_time: Instant::now(),
+            previous: (Instant::now(), Duration::ZERO),
             fast_mode,
+            control: PlaybackControl::default(),
         }
     }
+
+    /// Follow the pause, step and speed settings of `control`
+    pub fn with_control(mut self, control: PlaybackControl) -> Self {
+        self.control = control;
+        self
+    }
+
+    /// Offset of chunk `index` in the recording
+    fn recorded_offset(&self, index: usize) -> Duration {
+        if self.fast_mode {
+            // Fast playback - small delay to simulate streaming (~60fps)
+            Duration::from_millis(17) * index as u32
+        } else {
+            Duration::from_millis(self.chunks[index].timestamp_ms)
+        }
+    }
+
+    /// When chunk `index` is due: the recorded gap after the previous
+    /// chunk, scaled by the current speed
+    fn deadline(&self, index: usize) -> Instant {
+        let (previous_at, previous_offset) = self.previous;
+        let gap = self.recorded_offset(index).saturating_sub(previous_offset);
+        previous_at + gap.div_f64(f64::from(self.control.status().speed))
+    }
 }
 
 #[async_trait]
@@ -67,25 +96,56 @@ impl ChunkStream for PlaybackChunkStream {
             return Ok(None);
         }
 
-        let chunk = &self.chunks[self.current_index];
-
-        // Handle timing - either fast playback or respect original timing
-        if !self.fast_mode {
-            let elapsed = self.start_time.elapsed();
-            let expected_time = Duration::from_millis(chunk.timestamp_ms);
-
-            if elapsed < expected_time {
-                let sleep_duration = expected_time - elapsed;
-                tokio::time::sleep(sleep_duration).await;
+        // A step delivers the chunk right away; otherwise wait until it is
+        // due and hold it if playback was paused meanwhile. A held chunk
+        // restarts the schedule, so the pause isn't made up afterwards.
+        let mut due = None;
+        if !self.control.wait_for_turn().await {
+            let deadline = self.deadline(self.current_index);
+            tokio::time::sleep_until(deadline).await;
+            let held = self.control.status().paused;
+            self.control.wait_for_turn().await;
+            if !held {
+                due = Some(deadline);
             }
-        } else {
-            // Fast playback - small delay to simulate streaming
-            tokio::time::sleep(Duration::from_millis(17)).await; // ~60fps
         }
 
+        self.previous = (
+            due.unwrap_or_else(Instant::now),
+            self.recorded_offset(self.current_index),
+        );
+        let chunk = &self.chunks[self.current_index];
         let line = format!("{}\n", chunk.data);
         self.current_index += 1;
 
         Ok(Some(line.into_bytes()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recording::PlaybackAction;
+
+    fn chunk(timestamp_ms: u64) -> RecordedChunk {
+        RecordedChunk {
+            data: String::new(),
+            timestamp_ms,
+        }
+    }
+
+    #[test]
+    fn playback_deadline_counts_from_when_the_previous_chunk_was_due() {
+        let control = PlaybackControl::default();
+        let mut stream = PlaybackChunkStream::new(vec![chunk(0), chunk(100), chunk(300)], false)
+            .with_control(control.clone());
+        let due = Instant::now() - Duration::from_secs(1);
+        stream.previous = (due, Duration::from_millis(100));
+
+        // Late delivery of the previous chunk doesn't push this one back
+        assert_eq!(stream.deadline(2), due + Duration::from_millis(200));
+
+        control.apply(PlaybackAction::Faster);
+        assert_eq!(stream.deadline(2), due + Duration::from_millis(100));
+    }
+}
@@ -284,6 +284,30 @@ pub enum ApiError {
     Unknown(String),
 }
 
+impl ApiError {
+    /// The API error behind `error`, looking through its context chain and
+    /// the rate limit wrappers providers put around it.
+    pub fn find_in(error: &anyhow::Error) -> Option<&ApiError> {
+        error.chain().find_map(|cause| {
+            cause
+                .downcast_ref::<ApiError>()
+                .or_else(|| context_error::<()>(cause))
+                .or_else(|| context_error::<crate::anthropic::AnthropicRateLimitInfo>(cause))
+                .or_else(|| context_error::<crate::openai::OpenAIRateLimitInfo>(cause))
+                .or_else(|| context_error::<crate::openai_responses::ResponsesRateLimitInfo>(cause))
+                .or_else(|| context_error::<crate::vertex::VertexRateLimitInfo>(cause))
+        })
+    }
+}
+
+fn context_error<'a, T: std::fmt::Debug + 'static>(
+    cause: &'a (dyn std::error::Error + 'static),
+) -> Option<&'a ApiError> {
+    cause
+        .downcast_ref::<ApiErrorContext<T>>()
+        .map(|context| &context.error)
+}
+
 /// Context wrapper for API errors that includes rate limit information
 #[derive(Debug, thiserror::Error)]
 #[error("{error}")]
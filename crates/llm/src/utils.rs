@@ -141,6 +141,12 @@ pub async fn handle_retryable_error<
                         max_retries,
                         delay.as_secs()
                     );
+                    if let Some(callback) = streaming_callback {
+                        let _ = callback(&StreamingChunk::RetryAttempt {
+                            attempt: attempts,
+                            max: max_retries,
+                        });
+                    }
                     sleep(delay).await;
                     return true;
                 }
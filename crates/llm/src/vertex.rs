@@ -213,7 +213,7 @@ struct VertexFunctionResponse {
 
 /// Rate limit information extracted from response headers
 #[derive(Debug)]
-struct VertexRateLimitInfo {
+pub(crate) struct VertexRateLimitInfo {
     // TODO: Add actual rate limit fields once we know what headers Vertex AI uses
     requests_remaining: Option<u32>,
     #[allow(dead_code)]